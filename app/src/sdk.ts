@@ -19,12 +19,22 @@ import IDL from "./multistake.json";
 import {
   PoolConfig,
   PoolInfo,
+  PoolSizeTier,
   TokenInfo,
+  decodePoolItem,
+  POOL_HEADER_SIZE,
+  POOL_ITEM_SIZE,
+  poolAccountSize,
+  poolTierCapacity,
 } from "./types";
-import { createAssociatedTokenAccountInstruction } from "@solana/spl-token";
+import {
+  NATIVE_MINT,
+  createAssociatedTokenAccountInstruction,
+  createAssociatedTokenAccountIdempotentInstruction,
+} from "@solana/spl-token";
 
 /**
- * AnySwap SDK - 单币质押系统
+ * MultiStake SDK - 单币质押系统
  */
 export class MultiStakeSDK {
   private program: Program<Multistake>;
@@ -48,6 +58,22 @@ export class MultiStakeSDK {
     return new MultiStakeSDK(program, provider);
   }
 
+  /**
+   * 读取 Pool 账户：头部字段 + 紧跟其后的 capacity 个 item（tokens）
+   */
+  async fetchPool(pool: PublicKey) {
+    const info = await this.provider.connection.getAccountInfo(pool);
+    if (!info) {
+      throw new Error(`Pool account not found: ${pool.toBase58()}`);
+    }
+    const header = this.program.coder.accounts.decode("pool", info.data);
+    const tokens = Array.from({ length: header.capacity }, (_, i) => {
+      const offset = POOL_HEADER_SIZE + i * POOL_ITEM_SIZE;
+      return decodePoolItem(info.data.subarray(offset, offset + POOL_ITEM_SIZE));
+    });
+    return { ...header, tokens };
+  }
+
   /**
    * 获取 Program 实例
    */
@@ -63,15 +89,27 @@ export class MultiStakeSDK {
   }
 
   /**
-   * 派生 Pool Authority PDA
+   * 派生 Pool PDA ["pool", main_token_mint, admin]
    */
-  derivePoolAuthority(pool: PublicKey): [PublicKey, number] {
+  derivePool(mainTokenMint: PublicKey, admin: PublicKey): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
-      [new TextEncoder().encode("anyswap_authority"), pool.toBytes()],
+      [new TextEncoder().encode("pool"), mainTokenMint.toBytes(), admin.toBytes()],
       this.program.programId
     );
   }
 
+  /**
+   * 派生 Pool Authority PDA
+   * @param seedVersion pool.authoritySeedVersion，新建 pool 为 0（不附加版本字节，与已部署 pool 一致）
+   */
+  derivePoolAuthority(pool: PublicKey, seedVersion: number = 0): [PublicKey, number] {
+    const seeds = [new TextEncoder().encode("anyswap_authority"), pool.toBytes()];
+    if (seedVersion > 0) {
+      seeds.push(Uint8Array.of(seedVersion));
+    }
+    return PublicKey.findProgramAddressSync(seeds, this.program.programId);
+  }
+
   /**
    * 派生 Pool Vault PDA
    */
@@ -82,49 +120,210 @@ export class MultiStakeSDK {
     );
   }
 
+  /**
+   * 派生手续费 Vault PDA
+   */
+  deriveFeeVault(pool: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [new TextEncoder().encode("fee_vault"), pool.toBytes()],
+      this.program.programId
+    );
+  }
+
+  /**
+   * 派生主币的 Pool 注册表 PDA
+   */
+  derivePoolRegistry(mainTokenMint: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [new TextEncoder().encode("pool_registry"), mainTokenMint.toBytes()],
+      this.program.programId
+    );
+  }
+
+  /**
+   * 派生 LP mint PDA ["lp_mint", pool, increment_count (u16 le)]
+   */
+  deriveLpMint(pool: PublicKey, incrementCount: number): [PublicKey, number] {
+    const countSeed = new Uint8Array(2);
+    new DataView(countSeed.buffer).setUint16(0, incrementCount, true);
+    return PublicKey.findProgramAddressSync(
+      [new TextEncoder().encode("lp_mint"), pool.toBytes(), countSeed],
+      this.program.programId
+    );
+  }
+
+  /**
+   * 派生质押代理授权 PDA
+   */
+  deriveDelegation(pool: PublicKey, owner: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [new TextEncoder().encode("delegation"), pool.toBytes(), owner.toBytes()],
+      this.program.programId
+    );
+  }
+
+  /**
+   * 派生全局程序配置 PDA
+   */
+  deriveProgramConfig(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [new TextEncoder().encode("program_config")],
+      this.program.programId
+    );
+  }
+
+  /**
+   * 派生质押类型的兑换率来源 PDA
+   */
+  deriveItemRateSource(pool: PublicKey, lpMint: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [new TextEncoder().encode("item_rate_source"), pool.toBytes(), lpMint.toBytes()],
+      this.program.programId
+    );
+  }
+
+  /**
+   * 构造 stake / unstake 需要的兑换率来源账户：与 itemIndex 同一主币下每个配置了兑换率来源的
+   * item 一组 [item_rate_source, source]，须放在 remaining_accounts 最前面
+   */
+  async resolveRateSourceAccounts(pool: PublicKey, itemIndex: number) {
+    const poolAccount = await this.fetchPool(pool);
+    const items = poolAccount.tokens.slice(0, poolAccount.tokenCount);
+    const assetIndex = items[itemIndex].mainAssetIndex;
+    const accounts = [];
+    for (const item of items) {
+      if (item.rateWeightEnabled === 0 || item.mainAssetIndex !== assetIndex) {
+        continue;
+      }
+      const [itemRateSource] = this.deriveItemRateSource(pool, item.mintAccount);
+      const rateSource = await this.program.account.itemRateSource.fetch(itemRateSource);
+      accounts.push(
+        { pubkey: itemRateSource, isSigner: false, isWritable: false },
+        { pubkey: rateSource.source, isSigner: false, isWritable: false }
+      );
+    }
+    return accounts;
+  }
+
+  /**
+   * 全局配置设置了协议分成时返回协议金库在该主币下的 ATA，否则返回 null
+   */
+  async resolveProtocolTreasuryToken(mainTokenMint: PublicKey): Promise<PublicKey | null> {
+    const [programConfig] = this.deriveProgramConfig();
+    const config = await this.program.account.programConfig.fetchNullable(programConfig);
+    if (!config || config.protocolFeeShareBps === 0) {
+      return null;
+    }
+    return await getAssociatedTokenAddress(mainTokenMint, config.protocolTreasury, true);
+  }
+
+  /**
+   * 创建全局程序配置（须由程序升级权限调用）
+   */
+  async initializeConfig(
+    protocolTreasury: PublicKey,
+    protocolFeeShareBps: number
+  ): Promise<string> {
+    const programData = PublicKey.findProgramAddressSync(
+      [this.program.programId.toBytes()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    )[0];
+
+    return await this.program.methods
+      .initializeConfig(protocolTreasury, protocolFeeShareBps)
+      .accountsPartial({
+        program: this.program.programId,
+        programData,
+        authority: this.provider.publicKey,
+      })
+      .rpc();
+  }
+
+  /**
+   * 更新全局程序配置，传入 newAuthority 时移交配置管理员
+   */
+  async updateConfig(
+    protocolTreasury: PublicKey,
+    protocolFeeShareBps: number,
+    newAuthority: PublicKey | null = null
+  ): Promise<string> {
+    return await this.program.methods
+      .updateConfig(protocolTreasury, protocolFeeShareBps, newAuthority)
+      .accountsPartial({ authority: this.provider.publicKey })
+      .rpc();
+  }
+
   /**
    * 创建 Pool
    * @param mainTokenMint 主币 mint 地址
    * @param admin Pool 管理员
    * @param payer 支付账户
    * @param config Pool 配置
+   * @param sizeTier 容量档位（默认 Small，item 槽位不足时可通过 growPool 扩容）
    * @returns Pool 公钥和交易签名
    */
   async createPool(
     mainTokenMint: PublicKey,
-    config: PoolConfig = { feeNumerator: 3, feeDenominator: 1000 }
+    config: PoolConfig = { stakeFeeBps: 30, unstakeFeeBps: 30, swapFeeBps: 30, protocolFeeBps: 10_000 },
+    sizeTier: PoolSizeTier = { small: {} }
   ): Promise<{ pool: PublicKey; signature: string }> {
-    const pool = Keypair.generate();
     const wallet = this.provider.publicKey;
-    const [poolAuthority] = this.derivePoolAuthority(pool.publicKey);
-    const [poolVault] = this.derivePoolVault(pool.publicKey);
-
-    const poolSize = 24704;
-    const lamports = await this.provider.connection.getMinimumBalanceForRentExemption(poolSize);
-
-    const createPoolAccountIx = SystemProgram.createAccount({
-      fromPubkey: wallet,
-      newAccountPubkey: pool.publicKey,
-      lamports,
-      space: poolSize,
-      programId: this.program.programId,
-    });
+    const [pool] = this.derivePool(mainTokenMint, wallet);
+    const [poolAuthority] = this.derivePoolAuthority(pool);
+    const [poolVault] = this.derivePoolVault(pool);
+    const [feeVault] = this.deriveFeeVault(pool);
+    const [poolRegistry] = this.derivePoolRegistry(mainTokenMint);
+
+    // 较大档位的 Pool 账户超过单条指令可分配的 10KB，先分多次调用 allocate_pool
+    const poolSize = poolAccountSize(poolTierCapacity(sizeTier));
+    const allocateIx = await this.program.methods
+      .allocatePool(sizeTier)
+      .accountsPartial({
+        pool,
+        mainTokenMint,
+        admin: wallet,
+        payer: wallet,
+      })
+      .instruction();
+    const allocateIxs = Array.from({ length: Math.ceil(poolSize / 10240) }, () => allocateIx);
 
     const signature = await this.program.methods
-      .createPool(new BN(config.feeNumerator), new BN(config.feeDenominator))
+      .createPool(config, sizeTier)
       .accountsPartial({
-        pool: pool.publicKey,
+        pool,
         poolAuthority,
         mainTokenMint,
         poolVault,
+        feeVault,
+        poolRegistry,
+        admin: wallet,
+        payer: wallet,
+      })
+      .preInstructions(allocateIxs)
+      .rpc();
+
+    return { pool, signature };
+  }
+
+  /**
+   * 增加 Pool 的 item 容量
+   * @param pool Pool 公钥
+   * @param additional 新增的 item 槽位数量（单次最多 75 个）
+   * @returns 交易签名
+   */
+  async growPool(pool: PublicKey, additional: number): Promise<string> {
+    const wallet = this.provider.publicKey;
+
+    const signature = await this.program.methods
+      .growPool(additional)
+      .accountsPartial({
+        pool,
         admin: wallet,
         payer: wallet,
       })
-      .preInstructions([createPoolAccountIx])
-      .signers([pool])
       .rpc();
 
-    return { pool: pool.publicKey, signature };
+    return signature;
   }
 
   /**
@@ -135,23 +334,24 @@ export class MultiStakeSDK {
   async addTokenToPool(
     pool: PublicKey
   ): Promise<{ lpMint: PublicKey; signature: string }> {
-    const lpMint = Keypair.generate();
     const wallet = this.provider.publicKey;
-    const [poolAuthority] = this.derivePoolAuthority(pool);
+    const poolAccount = await this.program.account.pool.fetch(pool);
+    const [poolAuthority] = this.derivePoolAuthority(pool, poolAccount.authoritySeedVersion);
+    const [lpMint] = this.deriveLpMint(pool, poolAccount.incrementCount);
 
     const signature = await this.program.methods
       .addTokenToPool()
       .accountsPartial({
         pool,
         poolAuthority,
-        lpMint: lpMint.publicKey,
+        lpMint,
         admin: wallet,
         payer: wallet,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
-      .signers([lpMint])
       .rpc();
 
-    return { lpMint: lpMint.publicKey, signature };
+    return { lpMint, signature };
   }
 
   /**
@@ -166,12 +366,17 @@ export class MultiStakeSDK {
     lpMint: PublicKey,
     admin: Keypair
   ): Promise<string> {
+    const poolAccount = await this.program.account.pool.fetch(pool);
+    const [poolAuthority] = this.derivePoolAuthority(pool, poolAccount.authoritySeedVersion);
+
     const signature = await this.program.methods
       .removeTokenFromPool()
-      .accounts({
+      .accountsPartial({
         pool,
+        poolAuthority,
         lpMint,
         admin: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([admin])
       .rpc();
@@ -211,7 +416,8 @@ export class MultiStakeSDK {
     pool: PublicKey,
     itemIndex: number,
     lpMint: PublicKey,
-    amount: BN
+    amount: BN,
+    whitelistProof: number[][] = []
   ): Promise<string> {
     const wallet = this.provider.publicKey;
     const [poolVault] = this.derivePoolVault(pool);
@@ -225,6 +431,14 @@ export class MultiStakeSDK {
 
     const userMainToken = await getAssociatedTokenAddress(mainTokenMint, wallet);
 
+    // 新建 pool 的手续费进入 fee_vault；旧 pool 没有 fee_vault
+    const poolAccount = await this.program.account.pool.fetch(pool);
+    const feeVault = poolAccount.feeVault.equals(PublicKey.default)
+      ? null
+      : poolAccount.feeVault;
+
+    const protocolTreasuryToken = await this.resolveProtocolTreasuryToken(mainTokenMint);
+
     // Get or create user's LP token account
     const userLpToken = await getAssociatedTokenAddress(lpMint, wallet);
 
@@ -258,15 +472,21 @@ export class MultiStakeSDK {
     }
 
     const signature = await this.program.methods
-      .stake(itemIndex, amountBN)
+      .stake(itemIndex, amountBN, null, null, whitelistProof)
       .accountsPartial({
         pool,
         poolVault,
+        feeVault,
+        protocolTreasuryToken,
         lpMint,
         userMainToken,
         userLpToken,
         user: wallet,
+        mainTokenMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mainTokenProgram: TOKEN_PROGRAM_ID,
       })
+      .remainingAccounts(await this.resolveRateSourceAccounts(pool, itemIndex))
       .preInstructions(preInstructions)
       .rpc();
 
@@ -280,14 +500,18 @@ export class MultiStakeSDK {
     pool: PublicKey,
     itemIndex: number,
     lpMint: PublicKey,
-    lpAmount: BN
+    lpAmount: BN,
+    minRedeemAmount: BN = new BN(0)
   ): Promise<string> {
     const wallet = this.provider.publicKey;
     const [poolVault] = this.derivePoolVault(pool);
 
     // Get pool info to get main token mint
-    const poolInfo = await this.getPoolInfo(pool);
-    const mainTokenMint = poolInfo.poolMint;
+    const poolAccount = await this.program.account.pool.fetch(pool);
+    const mainTokenMint = poolAccount.poolMint;
+    const feeVault = poolAccount.feeVault.equals(PublicKey.default)
+      ? null
+      : poolAccount.feeVault;
 
     const userMainToken = await getAssociatedTokenAddress(
       mainTokenMint,
@@ -299,26 +523,226 @@ export class MultiStakeSDK {
       wallet
     );
 
+    const protocolTreasuryToken = await this.resolveProtocolTreasuryToken(mainTokenMint);
+
     const signature = await this.program.methods
-      .unstake(itemIndex, lpAmount)
+      .unstake(itemIndex, lpAmount, minRedeemAmount, null)
       .accountsPartial({
         pool,
         poolVault,
+        feeVault,
+        protocolTreasuryToken,
         lpMint,
         userLpToken,
         userMainToken,
         user: wallet,
+        mainTokenMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mainTokenProgram: TOKEN_PROGRAM_ID,
       })
+      .remainingAccounts(await this.resolveRateSourceAccounts(pool, itemIndex))
       .rpc();
 
     return signature;
   }
 
+  /**
+   * 向主币 vault 捐赠主币，立即按兑换率分配给现有 LP 持有人（单资产 pool）
+   */
+  async donateToPool(pool: PublicKey, amount: BN): Promise<string> {
+    const wallet = this.provider.publicKey;
+    const [poolVault] = this.derivePoolVault(pool);
+    const poolAccount = await this.program.account.pool.fetch(pool);
+    const mainTokenMint = poolAccount.poolMint;
+
+    const donorToken = await getAssociatedTokenAddress(
+      mainTokenMint,
+      wallet
+    );
+
+    return await this.program.methods
+      .donateToPool(0, amount)
+      .accountsPartial({
+        pool,
+        poolVault,
+        mainTokenMint,
+        donorToken,
+        donor: wallet,
+        mainTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  /**
+   * 设置质押代理，permissions 为权限位（stake = 1 / unstake = 2 / claim = 4）
+   * 代理 stake / unstake 前还需对主币账户和 LP 账户 approve delegation PDA
+   */
+  async setDelegate(
+    pool: PublicKey,
+    delegate: PublicKey,
+    permissions: number
+  ): Promise<string> {
+    const wallet = this.provider.publicKey;
+    const [delegation] = this.deriveDelegation(pool, wallet);
+
+    return await this.program.methods
+      .setDelegate(delegate, permissions)
+      .accountsPartial({ pool, delegation, owner: wallet })
+      .rpc();
+  }
+
+  /**
+   * 撤销质押代理
+   */
+  async revokeDelegate(pool: PublicKey): Promise<string> {
+    const wallet = this.provider.publicKey;
+    const [delegation] = this.deriveDelegation(pool, wallet);
+
+    return await this.program.methods
+      .revokeDelegate()
+      .accountsPartial({ pool, delegation, owner: wallet })
+      .rpc();
+  }
+
+  /**
+   * 使用原生 SOL 质押（pool 主币为 wSOL），指令内自动包装并在用完后关闭 wSOL 账户
+   */
+  async stakeSol(
+    pool: PublicKey,
+    itemIndex: number,
+    lpMint: PublicKey,
+    lamports: BN,
+    whitelistProof: number[][] = []
+  ): Promise<string> {
+    const wallet = this.provider.publicKey;
+    const [poolVault] = this.derivePoolVault(pool);
+    const poolAccount = await this.program.account.pool.fetch(pool);
+    const feeVault = poolAccount.feeVault.equals(PublicKey.default)
+      ? null
+      : poolAccount.feeVault;
+
+    const userWsol = await getAssociatedTokenAddress(NATIVE_MINT, wallet);
+    const userLpToken = await getAssociatedTokenAddress(lpMint, wallet);
+
+    return await this.program.methods
+      .stakeSol(itemIndex, lamports, null, null, whitelistProof)
+      .accountsPartial({
+        stake: {
+          pool,
+          poolVault,
+          feeVault,
+          lpMint,
+          userMainToken: userWsol,
+          userLpToken,
+          user: wallet,
+          mainTokenMint: NATIVE_MINT,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          mainTokenProgram: TOKEN_PROGRAM_ID,
+        },
+      })
+      .preInstructions([
+        createAssociatedTokenAccountIdempotentInstruction(wallet, userWsol, wallet, NATIVE_MINT),
+        createAssociatedTokenAccountIdempotentInstruction(wallet, userLpToken, wallet, lpMint),
+      ])
+      .rpc();
+  }
+
+  /**
+   * 销毁 LP 凭证赎回为原生 SOL（pool 主币为 wSOL），赎回后关闭 wSOL 账户
+   */
+  async unstakeSol(
+    pool: PublicKey,
+    itemIndex: number,
+    lpMint: PublicKey,
+    lpAmount: BN,
+    minRedeemAmount: BN = new BN(0)
+  ): Promise<string> {
+    const wallet = this.provider.publicKey;
+    const [poolVault] = this.derivePoolVault(pool);
+    const poolAccount = await this.program.account.pool.fetch(pool);
+    const feeVault = poolAccount.feeVault.equals(PublicKey.default)
+      ? null
+      : poolAccount.feeVault;
+    const userWsol = await getAssociatedTokenAddress(NATIVE_MINT, wallet);
+    const userLpToken = await getAssociatedTokenAddress(lpMint, wallet);
+
+    return await this.program.methods
+      .unstakeSol(itemIndex, lpAmount, minRedeemAmount, null)
+      .accountsPartial({
+        unstake: {
+          pool,
+          poolVault,
+          feeVault,
+          lpMint,
+          userLpToken,
+          userMainToken: userWsol,
+          user: wallet,
+          mainTokenMint: NATIVE_MINT,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          mainTokenProgram: TOKEN_PROGRAM_ID,
+        },
+      })
+      .preInstructions([
+        createAssociatedTokenAccountIdempotentInstruction(wallet, userWsol, wallet, NATIVE_MINT),
+      ])
+      .rpc();
+  }
+
+  /**
+   * 报价 stake：模拟 quote_stake 并读取链上计算的手续费与可得 LP
+   */
+  async quoteStake(
+    pool: PublicKey,
+    itemIndex: number,
+    amount: BN
+  ): Promise<{ feeAmount: BN; lpAmount: BN; feeLpAmount: BN }> {
+    const [poolVault] = this.derivePoolVault(pool);
+    const result = await this.program.methods
+      .quoteStake(itemIndex, amount)
+      .accountsPartial({ pool, poolVault })
+      .simulate();
+    const [feeAmount, lpAmount, feeLpAmount] = this.readReturnedU64s(result.raw, 3);
+    return { feeAmount, lpAmount, feeLpAmount };
+  }
+
+  /**
+   * 报价 unstake：模拟 quote_unstake 并读取链上计算的赎回金额
+   * 配置了 oracle 的 item 需要通过 program 直接传入 itemOracle 与 priceFeed
+   */
+  async quoteUnstake(
+    pool: PublicKey,
+    itemIndex: number,
+    lpAmount: BN
+  ): Promise<{ redeemAmount: BN; feeAmount: BN; amountAfterFee: BN }> {
+    const [poolVault] = this.derivePoolVault(pool);
+    const result = await this.program.methods
+      .quoteUnstake(itemIndex, lpAmount)
+      .accountsPartial({ pool, poolVault, itemOracle: null, priceFeed: null })
+      .simulate();
+    const [redeemAmount, feeAmount, amountAfterFee] = this.readReturnedU64s(result.raw, 3);
+    return { redeemAmount, feeAmount, amountAfterFee };
+  }
+
+  /**
+   * 从模拟日志中解析本程序的 return data（连续的小端 u64）
+   */
+  private readReturnedU64s(logs: readonly string[], count: number): BN[] {
+    const prefix = `Program return: ${this.program.programId.toBase58()} `;
+    const line = logs.find((log) => log.startsWith(prefix));
+    if (!line) {
+      throw new Error("No return data in simulation logs");
+    }
+    const data = Buffer.from(line.slice(prefix.length), "base64");
+    return Array.from({ length: count }, (_, i) =>
+      new BN(data.subarray(i * 8, i * 8 + 8), "le")
+    );
+  }
+
   /**
    * 获取 Pool 信息
    */
   async getPoolInfo(pool: PublicKey): Promise<PoolInfo & { items: TokenInfo[] }> {
-    const poolAccount = await this.program.account.pool.fetch(pool);
+    const poolAccount = await this.fetchPool(pool);
     let poolItems: TokenInfo[] = [];
     for (let i = 0; i < poolAccount.tokenCount; i++) {
       const token = poolAccount.tokens[i];
@@ -335,8 +759,7 @@ export class MultiStakeSDK {
       poolVault: poolAccount.poolVault,
       poolMint: poolAccount.poolMint,
       tokenCount: poolAccount.tokenCount,
-      feeNumerator: poolAccount.feeNumerator,
-      feeDenominator: poolAccount.feeDenominator,
+      feeConfig: poolAccount.feeConfig,
       items: poolItems,
     };
   }
@@ -348,6 +771,15 @@ export class MultiStakeSDK {
     const poolInfo = await this.getPoolInfo(pool);
     return poolInfo.items.map((item) => item.mintAccount);
   }
+
+  /**
+   * 通过注册表获取以 mainTokenMint 为主币的所有 Pool
+   */
+  async getPoolsByMint(mainTokenMint: PublicKey): Promise<PublicKey[]> {
+    const [poolRegistry] = this.derivePoolRegistry(mainTokenMint);
+    const registry = await this.program.account.poolRegistry.fetchNullable(poolRegistry);
+    return registry ? registry.pools : [];
+  }
 }
 
 export type { PoolInfo, TokenInfo };
\ No newline at end of file
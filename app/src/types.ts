@@ -5,8 +5,64 @@ import { BN } from "@coral-xyz/anchor";
  * Pool 配置
  */
 export interface PoolConfig {
-  feeNumerator: number;
-  feeDenominator: number;
+  /** stake 手续费（bps） */
+  stakeFeeBps: number;
+  /** unstake 手续费（bps） */
+  unstakeFeeBps: number;
+  /** 质押类型之间转换的手续费（bps） */
+  swapFeeBps: number;
+  /** 手续费中归协议的比例（bps），其余留在 vault 中归 LP 持有人 */
+  protocolFeeBps: number;
+}
+
+/**
+ * Pool 容量档位：Small 16 / Medium 128 / Large 512 个质押类型，决定账户大小和租金
+ */
+export type PoolSizeTier = { small: {} } | { medium: {} } | { large: {} };
+
+/** 各容量档位的 item 槽位数量 */
+export function poolTierCapacity(tier: PoolSizeTier): number {
+  if ("small" in tier) return 16;
+  if ("medium" in tier) return 128;
+  return 512;
+}
+
+/** Pool 账户头部大小（8 discriminator + 1848） */
+export const POOL_HEADER_SIZE = 1856;
+
+/** 单个 item 的大小 */
+export const POOL_ITEM_SIZE = 136;
+
+/** 容纳 capacity 个 item 的 Pool 账户大小 */
+export function poolAccountSize(capacity: number): number {
+  return POOL_HEADER_SIZE + capacity * POOL_ITEM_SIZE;
+}
+
+/**
+ * 按 PoolItem 的 zero_copy 布局解码单个 item（item 数组不在 Pool 头部中，IDL 不包含该类型）
+ */
+export function decodePoolItem(data: Buffer) {
+  const u64 = (offset: number) => new BN(data.subarray(offset, offset + 8), "le");
+  const i64 = (offset: number) => new BN(data.subarray(offset, offset + 8), "le").fromTwos(64);
+  return {
+    mintAccount: new PublicKey(data.subarray(0, 32)),
+    mintAmount: u64(32),
+    weight: u64(40),
+    lastWeightUpdateSlot: u64(48),
+    mainAssetIndex: data[56],
+    oracleEnabled: data[57],
+    active: data[58],
+    rateWeightEnabled: data[59],
+    rewardPerShare: Array.from(data.subarray(64, 80)),
+    weightDecay: {
+      startWeight: u64(80),
+      target: u64(88),
+      startTs: i64(96),
+      endTs: i64(104),
+    },
+    name: Array.from(data.subarray(112, 128)),
+    symbol: Array.from(data.subarray(128, 136)),
+  };
 }
 
 /**
@@ -17,8 +73,7 @@ export interface PoolInfo {
   poolVault: PublicKey;
   poolMint: PublicKey;
   tokenCount: number;
-  feeNumerator: BN;
-  feeDenominator: BN;
+  feeConfig: PoolConfig;
 }
 
 /**
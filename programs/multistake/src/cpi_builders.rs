@@ -0,0 +1,303 @@
+//! 供其他 Anchor 程序 CPI 调用 stake / unstake 的辅助接口
+//!
+//! 启用 `cpi` feature 后导出。Anchor 生成的 `crate::cpi::accounts::*` 要求调用方逐个填写
+//! 全部可选账户，这里提供按必需账户构造、可选账户按需追加的构造器，以及相关 PDA 的推导函数。
+//!
+//! 指令本身不依赖交易的 fee payer：`user` 只需是本次调用的签名者（调用方程序可用 PDA 签名）。
+//! 需要注意 `stake_record` 和 `referral_account` 不存在时由 `user` 支付租金创建，
+//! 以携带数据的 PDA 作为 `user` 时应先由其他付款方通过 `open_stake_record` 创建质押记录，且不应传入 `referral_account`。
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::get_return_data;
+use crate::error::ErrorCode;
+use crate::state::delegation::DELEGATION_SEED;
+use crate::state::program_config::PROGRAM_CONFIG_SEED;
+use crate::state::{authority_seed_suffix, POOL_AUTHORITY_SEED};
+
+/// 推导 pool authority PDA（seed_version 取 pool 当前的 authority_seed_version）
+pub fn pool_authority_address(pool: &Pubkey, seed_version: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        &[POOL_AUTHORITY_SEED, pool.as_ref(), authority_seed_suffix(seed_version)],
+        &crate::ID,
+    ).0
+}
+
+/// 推导用户在某个质押类型上的质押记录 PDA（按质押类型的 LP mint 绑定）
+pub fn stake_record_address(pool: &Pubkey, user: &Pubkey, lp_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"stake_record", pool.as_ref(), user.as_ref(), lp_mint.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// 推导委托授权 PDA
+pub fn delegation_address(pool: &Pubkey, owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[DELEGATION_SEED, pool.as_ref(), owner.as_ref()], &crate::ID).0
+}
+
+/// 推导 treasury LP 账户 PDA
+pub fn treasury_lp_address(pool: &Pubkey, lp_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"treasury_lp", pool.as_ref(), lp_mint.as_ref()], &crate::ID).0
+}
+
+/// 推导质押类型的 oracle 配置 PDA
+pub fn item_oracle_address(pool: &Pubkey, lp_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"item_oracle", pool.as_ref(), lp_mint.as_ref()], &crate::ID).0
+}
+
+/// 推导全局程序配置 PDA（未初始化时也须传入该地址）
+pub fn program_config_address() -> Pubkey {
+    Pubkey::find_program_address(&[PROGRAM_CONFIG_SEED], &crate::ID).0
+}
+
+/// stake 的 CPI 账户构造器
+pub struct StakeCpiBuilder<'info> {
+    accounts: crate::cpi::accounts::Stake<'info>,
+    remaining_accounts: Vec<AccountInfo<'info>>,
+}
+
+impl<'info> StakeCpiBuilder<'info> {
+    /// 以必需账户构造，可选账户默认不传
+    /// stake_record 为 stake_record_address 推导的质押记录 PDA
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        pool: AccountInfo<'info>,
+        pool_authority: AccountInfo<'info>,
+        pool_vault: AccountInfo<'info>,
+        main_token_mint: AccountInfo<'info>,
+        lp_mint: AccountInfo<'info>,
+        user_main_token: AccountInfo<'info>,
+        user_lp_token: AccountInfo<'info>,
+        user: AccountInfo<'info>,
+        stake_record: AccountInfo<'info>,
+        program_config: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+        main_token_program: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+    ) -> Self {
+        Self {
+            accounts: crate::cpi::accounts::Stake {
+                pool,
+                pool_authority,
+                pool_vault,
+                main_token_mint,
+                lp_mint,
+                user_main_token,
+                user_lp_token,
+                user,
+                delegate: None,
+                delegation: None,
+                stake_record,
+                treasury_lp_token: None,
+                referral: None,
+                referrer_fee_account: None,
+                referral_account: None,
+                fee_vault: None,
+                program_config,
+                protocol_treasury_token: None,
+                instructions_sysvar: None,
+                token_program,
+                main_token_program,
+                system_program,
+            },
+            remaining_accounts: Vec::new(),
+        }
+    }
+
+    /// 以委托人身份代 owner 质押
+    pub fn delegate(mut self, delegate: AccountInfo<'info>, delegation: AccountInfo<'info>) -> Self {
+        self.accounts.delegate = Some(delegate);
+        self.accounts.delegation = Some(delegation);
+        self
+    }
+
+    /// pool 启用 fee_in_lp 时必须传入
+    pub fn treasury_lp_token(mut self, treasury_lp_token: AccountInfo<'info>) -> Self {
+        self.accounts.treasury_lp_token = Some(treasury_lp_token);
+        self
+    }
+
+    /// 使用推荐码质押
+    pub fn referral(
+        mut self,
+        referral: AccountInfo<'info>,
+        referrer_fee_account: AccountInfo<'info>,
+    ) -> Self {
+        self.accounts.referral = Some(referral);
+        self.accounts.referrer_fee_account = Some(referrer_fee_account);
+        self
+    }
+
+    /// 传入推荐关系账户（不存在时由 user 支付租金创建）
+    pub fn referral_account(mut self, referral_account: AccountInfo<'info>) -> Self {
+        self.accounts.referral_account = Some(referral_account);
+        self
+    }
+
+    /// 传入协议手续费账户
+    pub fn fee_vault(mut self, fee_vault: AccountInfo<'info>) -> Self {
+        self.accounts.fee_vault = Some(fee_vault);
+        self
+    }
+
+    /// 传入协议金库的主币账户（全局配置设置了协议分成时必须）
+    pub fn protocol_treasury_token(mut self, protocol_treasury_token: AccountInfo<'info>) -> Self {
+        self.accounts.protocol_treasury_token = Some(protocol_treasury_token);
+        self
+    }
+
+    /// 传入 instructions sysvar（pool 启用调用方白名单时必须）
+    pub fn instructions_sysvar(mut self, instructions_sysvar: AccountInfo<'info>) -> Self {
+        self.accounts.instructions_sysvar = Some(instructions_sysvar);
+        self
+    }
+
+    /// 追加 remaining accounts（兑换率来源账户对在前，之后为 transfer hook 需要的额外账户）
+    pub fn remaining_accounts(mut self, accounts: Vec<AccountInfo<'info>>) -> Self {
+        self.remaining_accounts = accounts;
+        self
+    }
+
+    /// 发起 CPI；user 为调用方 PDA 时通过 signer_seeds 签名
+    /// 返回铸造给 user 的 LP 数量
+    #[allow(clippy::too_many_arguments)]
+    pub fn invoke_signed(
+        self,
+        program: AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+        item_index: u16,
+        stake_amount: u64,
+        tag: Option<[u8; 32]>,
+        referrer: Option<Pubkey>,
+        whitelist_proof: Vec<[u8; 32]>,
+    ) -> Result<u64> {
+        let ctx = CpiContext::new_with_signer(program, self.accounts, signer_seeds)
+            .with_remaining_accounts(self.remaining_accounts);
+        crate::cpi::stake(ctx, item_index, stake_amount, tag, referrer, whitelist_proof)?;
+        read_return_amount()
+    }
+}
+
+/// unstake 的 CPI 账户构造器
+pub struct UnstakeCpiBuilder<'info> {
+    accounts: crate::cpi::accounts::Unstake<'info>,
+    remaining_accounts: Vec<AccountInfo<'info>>,
+}
+
+impl<'info> UnstakeCpiBuilder<'info> {
+    /// 以必需账户构造，可选账户默认不传
+    /// stake_record 为 stake_record_address 推导的质押记录 PDA
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        pool: AccountInfo<'info>,
+        pool_authority: AccountInfo<'info>,
+        pool_vault: AccountInfo<'info>,
+        main_token_mint: AccountInfo<'info>,
+        lp_mint: AccountInfo<'info>,
+        user_lp_token: AccountInfo<'info>,
+        user_main_token: AccountInfo<'info>,
+        user: AccountInfo<'info>,
+        stake_record: AccountInfo<'info>,
+        program_config: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+        main_token_program: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+    ) -> Self {
+        Self {
+            accounts: crate::cpi::accounts::Unstake {
+                pool,
+                pool_authority,
+                pool_vault,
+                main_token_mint,
+                lp_mint,
+                user_lp_token,
+                user_main_token,
+                user,
+                delegate: None,
+                delegation: None,
+                stake_record,
+                item_oracle: None,
+                price_feed: None,
+                priority_redeemer: None,
+                fee_vault: None,
+                program_config,
+                protocol_treasury_token: None,
+                instructions_sysvar: None,
+                token_program,
+                main_token_program,
+                system_program,
+            },
+            remaining_accounts: Vec::new(),
+        }
+    }
+
+    /// 以委托人身份代 owner 赎回
+    pub fn delegate(mut self, delegate: AccountInfo<'info>, delegation: AccountInfo<'info>) -> Self {
+        self.accounts.delegate = Some(delegate);
+        self.accounts.delegation = Some(delegation);
+        self
+    }
+
+    /// item 配置了 oracle 时必须传入
+    pub fn oracle(mut self, item_oracle: AccountInfo<'info>, price_feed: AccountInfo<'info>) -> Self {
+        self.accounts.item_oracle = Some(item_oracle);
+        self.accounts.price_feed = Some(price_feed);
+        self
+    }
+
+    /// 传入优先赎回授权
+    pub fn priority_redeemer(mut self, priority_redeemer: AccountInfo<'info>) -> Self {
+        self.accounts.priority_redeemer = Some(priority_redeemer);
+        self
+    }
+
+    /// 传入协议手续费账户
+    pub fn fee_vault(mut self, fee_vault: AccountInfo<'info>) -> Self {
+        self.accounts.fee_vault = Some(fee_vault);
+        self
+    }
+
+    /// 传入协议金库的主币账户（全局配置设置了协议分成时必须）
+    pub fn protocol_treasury_token(mut self, protocol_treasury_token: AccountInfo<'info>) -> Self {
+        self.accounts.protocol_treasury_token = Some(protocol_treasury_token);
+        self
+    }
+
+    /// 传入 instructions sysvar（pool 启用调用方白名单时必须）
+    pub fn instructions_sysvar(mut self, instructions_sysvar: AccountInfo<'info>) -> Self {
+        self.accounts.instructions_sysvar = Some(instructions_sysvar);
+        self
+    }
+
+    /// 追加 remaining accounts（兑换率来源账户对在前，之后为 transfer hook 需要的额外账户）
+    pub fn remaining_accounts(mut self, accounts: Vec<AccountInfo<'info>>) -> Self {
+        self.remaining_accounts = accounts;
+        self
+    }
+
+    /// 发起 CPI；user 为调用方 PDA 时通过 signer_seeds 签名
+    /// 返回转给 user 的主币数量（扣除手续费后）
+    pub fn invoke_signed(
+        self,
+        program: AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+        item_index: u16,
+        lp_amount: u64,
+        min_redeem_amount: u64,
+        tag: Option<[u8; 32]>,
+    ) -> Result<u64> {
+        let ctx = CpiContext::new_with_signer(program, self.accounts, signer_seeds)
+            .with_remaining_accounts(self.remaining_accounts);
+        crate::cpi::unstake(ctx, item_index, lp_amount, min_redeem_amount, tag)?;
+        read_return_amount()
+    }
+}
+
+/// 读取 stake / unstake 写入的 return data（Borsh 编码的 u64）
+/// 只接受本程序设置的 return data
+pub fn read_return_amount() -> Result<u64> {
+    let (program_id, data) = get_return_data().ok_or(ErrorCode::MissingReturnData)?;
+    require_keys_eq!(program_id, crate::ID, ErrorCode::MissingReturnData);
+    Ok(u64::try_from_slice(&data)?)
+}
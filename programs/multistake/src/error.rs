@@ -28,5 +28,168 @@ pub enum ErrorCode {
     SameTokenSwap,
     #[msg("无效的管理员")]
     InvalidAdmin,
+    #[msg("无效的账户大小")]
+    InvalidAccountSize,
+    #[msg("Pool vault 地址不匹配")]
+    InvalidPoolVault,
+    #[msg("时间锁尚未到期")]
+    TimelockNotElapsed,
+    #[msg("没有待执行的操作")]
+    NoPendingAction,
+    #[msg("调用方程序未被允许")]
+    UnauthorizedCaller,
+    #[msg("无效的调用方策略")]
+    InvalidCallerPolicy,
+    #[msg("weight 修改过于频繁，冷却期未结束")]
+    WeightUpdateCooldown,
+    #[msg("无效的主币资产索引")]
+    InvalidAssetIndex,
+    #[msg("质押类型仍有未赎回的 LP")]
+    ItemHasOutstandingLp,
+    #[msg("无效的 oracle 账户")]
+    InvalidOracleAccount,
+    #[msg("无效的 oracle 价格")]
+    InvalidOraclePrice,
+    #[msg("oracle 价格已过期")]
+    StaleOraclePrice,
+    #[msg("检测到脱锚，暂停赎回")]
+    DepegDetected,
+    #[msg("无效的 oracle 配置")]
+    InvalidOracleConfig,
+    #[msg("当前未处于退出受限状态，请使用普通赎回")]
+    ExitNotRestricted,
+    #[msg("无效的费率配置")]
+    InvalidFeeConfig,
+    #[msg("预览完成：仅用于模拟，交易不会生效")]
+    PreviewOnly,
+    #[msg("无效的推荐码")]
+    InvalidReferralCode,
+    #[msg("无效的推荐人收款账户")]
+    InvalidReferralAccount,
+    #[msg("没有可收割的奖励")]
+    NothingToHarvest,
+    #[msg("当前 epoch 已记录过汇率")]
+    RateAlreadySampled,
+    #[msg("无效的 oracle 签名")]
+    InvalidOracleSignature,
+    #[msg("无权取消该操作")]
+    InvalidGuardian,
+    #[msg("无效的时间锁配置")]
+    InvalidTimelockConfig,
+    #[msg("无效的紧凑 weight 编码")]
+    InvalidPackedWeights,
+    #[msg("vault 余额低于最小值")]
+    VaultBalanceBelowMinimum,
+    #[msg("无效的兑换率保护配置")]
+    InvalidRateGuardConfig,
+    #[msg("质押记录不可转让")]
+    PositionNotTransferable,
+    #[msg("无效的新所有者")]
+    InvalidNewOwner,
+    #[msg("无效的质押策略配置")]
+    InvalidStakeStrategyConfig,
+    #[msg("策略质押账户仍在使用中")]
+    StrategyStakeActive,
+    #[msg("超出策略委托上限")]
+    StrategyDelegationExceeded,
+    #[msg("当前窗口的赎回额度已用尽")]
+    RedemptionRateLimited,
+    #[msg("无效的赎回限速配置")]
+    InvalidRedemptionLimit,
+    #[msg("赎回数量低于最小值（滑点过大）")]
+    SlippageExceeded,
+    #[msg("没有待接受的管理员提议或签名者不是被提议的管理员")]
+    InvalidPendingAdmin,
+    #[msg("Pool 已暂停")]
+    PoolPaused,
+    #[msg("Pool 启用了赎回冷却期，请使用 request_unstake")]
+    UnstakeCooldownRequired,
+    #[msg("赎回凭证尚未解锁")]
+    UnstakeTicketLocked,
+    #[msg("无效的赎回冷却期")]
+    InvalidCooldown,
+    #[msg("无效的奖励排放配置")]
+    InvalidEmissionConfig,
+    #[msg("费率超过上限")]
+    FeeTooHigh,
+    #[msg("Pool 未启用 oracle weight 更新")]
+    OracleWeightsDisabled,
+    #[msg("距离上次刷新 weight 未超过最小间隔")]
+    CrankTooEarly,
+    #[msg("无效的 crank 配置")]
+    InvalidCrankConfig,
+    #[msg("weight 变化幅度超过上限")]
+    WeightChangeTooLarge,
+    #[msg("无效的 weight 变化幅度上限")]
+    InvalidWeightChangeLimit,
+    #[msg("Pool 已启用时间锁，该操作必须通过 queue_action 执行")]
+    TimelockRequired,
+    #[msg("管理员签名数不足")]
+    InsufficientAdminSignatures,
+    #[msg("无效的共同管理员")]
+    InvalidAdminSigner,
+    #[msg("无效的管理员签名门限")]
+    InvalidAdminThreshold,
+    #[msg("Pool 仍有质押类型或资金，无法关闭")]
+    PoolNotEmpty,
+    #[msg("该质押类型已停用，只允许赎回")]
+    ItemInactive,
+    #[msg("Pool 记账不变量校验失败")]
+    InvariantViolated,
+    #[msg("没有可领取的推荐返佣")]
+    NoReferralFees,
+    #[msg("用户不在质押白名单中")]
+    NotWhitelisted,
+    #[msg("启用白名单时 merkle 根不能为空")]
+    InvalidWhitelistRoot,
+    #[msg("金额过小：低于最小质押数量或赎回金额为 0")]
+    DustAmount,
+    #[msg("Pool 已在注册表中")]
+    PoolAlreadyRegistered,
+    #[msg("Pool 注册表已满")]
+    PoolRegistryFull,
+    #[msg("Vault 余额变化与预期不符")]
+    VaultBalanceMismatch,
+    #[msg("该主币资产没有未赎回的 LP，捐赠无人受益")]
+    NoOutstandingLp,
+    #[msg("Pool 已启用 weight 历史，必须传入 WeightHistory 账户")]
+    WeightHistoryRequired,
+    #[msg("锁定时长低于最低锁仓档位")]
+    InvalidLockDuration,
+    #[msg("锁仓头寸尚未到期")]
+    PositionLocked,
+    #[msg("质押记录未开启自动复投")]
+    AutoCompoundDisabled,
+    #[msg("代理未获授权执行该操作")]
+    DelegateNotAuthorized,
+    #[msg("无效的代理权限位")]
+    InvalidDelegatePermissions,
+    #[msg("主币、LP 和奖励币不能通过 rescue_tokens 提取")]
+    MintNotRescuable,
+    #[msg("救援提取的来源账户必须由 pool authority 持有")]
+    InvalidRescueSource,
+    #[msg("无效的主币迁移：新 mint 必须不同，兑换比例不能为 0，且不能有未领取的赎回、返佣或委托质押")]
+    InvalidMintMigration,
+    #[msg("新主币 vault 的余额低于按兑换比例折算的旧 vault 余额")]
+    MintMigrationUnderfunded,
+    #[msg("快照 epoch 与当前 epoch 不一致")]
+    InvalidSnapshotEpoch,
+    #[msg("缺少本程序的 return data")]
+    MissingReturnData,
+    #[msg("协议金库账户无效")]
+    InvalidProtocolTreasury,
+    #[msg("无权管理全局程序配置")]
+    InvalidConfigAuthority,
+    #[msg("weight 超出允许范围")]
+    WeightOutOfBounds,
+    #[msg("weight 缩放比例无效或无法整除")]
+    InvalidWeightRescale,
+    #[msg("weight 衰减计划无效")]
+    InvalidWeightSchedule,
+    #[msg("兑换率来源账户无效或缺失")]
+    InvalidRateSource,
+    #[msg("item 名称或符号过长")]
+    InvalidItemMetadata,
+    #[msg("账户布局版本不匹配，需先迁移")]
+    WrongAccountVersion,
 }
-
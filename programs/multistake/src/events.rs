@@ -0,0 +1,142 @@
+//! 链上事件定义
+//! 通过 emit! 写入程序日志，链下索引服务可以按 IDL 类型化解析，无需解析 msg! 文本
+
+use anchor_lang::prelude::*;
+use crate::state::FeeConfig;
+
+/// Pool 创建
+#[event]
+pub struct PoolCreatedEvent {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub main_token_mint: Pubkey,
+    pub pool_vault: Pubkey,
+    pub fee_config: FeeConfig,
+}
+
+/// Pool 关闭
+#[event]
+pub struct PoolClosedEvent {
+    pub pool: Pubkey,
+    pub recipient: Pubkey,
+}
+
+/// 质押类型添加
+#[event]
+pub struct TokenAddedEvent {
+    pub pool: Pubkey,
+    pub item_index: u16,
+    pub lp_mint: Pubkey,
+    pub weight: u64,
+}
+
+/// 质押类型移除
+/// moved_from: 被移动到 item_index 位置的原最后一个 item 的索引（未移动时为 None）
+#[event]
+pub struct TokenRemovedEvent {
+    pub pool: Pubkey,
+    pub item_index: u16,
+    pub lp_mint: Pubkey,
+    pub moved_from: Option<u16>,
+}
+
+/// 单个 item 的 weight 修改
+#[event]
+pub struct WeightModifiedEvent {
+    pub pool: Pubkey,
+    pub item_index: u16,
+    pub lp_mint: Pubkey,
+    pub old_weight: u64,
+    pub new_weight: u64,
+    pub slot: u64,
+}
+
+/// 质押
+#[event]
+pub struct StakeEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub item_index: u16,
+    pub amount: u64,
+    pub fee: u64,
+    pub referral_amount: u64,
+    /// 通过 referrer 参数指定的推荐人（返佣记入其 ReferralAccount）
+    pub referrer: Option<Pubkey>,
+    pub lp_minted: u64,
+    pub fee_lp_minted: u64,
+    pub tag: Option<[u8; 32]>,
+}
+
+/// 赎回
+#[event]
+pub struct UnstakeEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub item_index: u16,
+    pub lp_burned: u64,
+    pub redeem_amount: u64,
+    pub fee: u64,
+    /// 手续费中转入 fee_vault 的部分
+    pub protocol_fee: u64,
+    pub amount_after_fee: u64,
+    pub tag: Option<[u8; 32]>,
+}
+
+/// vault 实际余额与记账余额不一致（外部捐赠或短缺），在 stake / unstake 记账前发出
+#[event]
+pub struct VaultReconciledEvent {
+    pub pool: Pubkey,
+    pub asset_index: u8,
+    pub tracked_balance: u64,
+    /// vault 实际余额（含委托部分、扣除待领取赎回）
+    pub actual_balance: u64,
+}
+
+/// 向 vault 捐赠主币，按兑换率分配给现有 LP 持有人
+#[event]
+pub struct DonationEvent {
+    pub pool: Pubkey,
+    pub donor: Pubkey,
+    pub asset_index: u8,
+    pub amount: u64,
+    /// vault 实际到账数量（扣除 TransferFee 扩展手续费）
+    pub received: u64,
+}
+
+/// 锁仓质押
+#[event]
+pub struct LockedStakeEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub item_index: u16,
+    pub amount: u64,
+    pub fee: u64,
+    pub lp_amount: u64,
+    /// 本次锁仓按档位获得的加成数量
+    pub boost_amount: u64,
+    pub unlock_timestamp: i64,
+}
+
+/// 赎回到期的锁仓头寸
+#[event]
+pub struct LockedUnstakeEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub item_index: u16,
+    pub lp_amount: u64,
+    pub boost_amount: u64,
+    pub redeem_amount: u64,
+    pub fee: u64,
+    pub amount_after_fee: u64,
+}
+
+/// 奖励复投为 LP（harvest_for 自动复投或 compound_rewards）
+#[event]
+pub struct RewardsCompoundedEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub item_index: u16,
+    /// 转入 pool_vault 的奖励数量
+    pub reward: u64,
+    pub lp_minted: u64,
+}
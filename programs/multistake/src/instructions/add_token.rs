@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token};
-use crate::state::Pool;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::state::{MintIndex, Pool, DEFAULT_ITEM_WEIGHT, LP_MINT_DECIMALS, POOL_AUTHORITY_SEED};
 use crate::error::ErrorCode;
+use crate::events::TokenAddedEvent;
 
 /// 添加质押类型到 pool
 /// 自动创建新的 LP mint，权限归属于 pool authority
@@ -13,20 +14,42 @@ pub struct AddTokenToPool<'info> {
     /// Pool authority PDA - LP mint 的 authority
     /// CHECK: PDA derived from pool key
     #[account(
-        seeds = [b"anyswap_authority", pool.key().as_ref()],
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
         bump
     )]
     pub pool_authority: AccountInfo<'info>,
 
     /// LP mint - 自动创建，权限归属于 pool_authority
-    /// 使用 increment_count 作为 seed 确保唯一性（只增不减）
+    /// PDA ["lp_mint", pool, increment_count (u16 le)]，increment_count 只增不减，确保唯一性
+    /// 推导 seed 时检查 pool 容量，pool 已满时不会创建 mint
     #[account(
         init,
         payer = payer,
-        mint::decimals = 9,
+        seeds = [
+            b"lp_mint",
+            pool.key().as_ref(),
+            &pool.load()?.next_lp_mint_seed()?[..],
+        ],
+        bump,
+        mint::decimals = LP_MINT_DECIMALS,
         mint::authority = pool_authority,
+        mint::token_program = token_program,
     )]
-    pub lp_mint: Account<'info, Mint>,
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    /// LP mint → item 索引映射 PDA
+    #[account(
+        init,
+        payer = payer,
+        space = MintIndex::space(),
+        seeds = [b"mint_index", pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump
+    )]
+    pub mint_index: Account<'info, MintIndex>,
 
     /// Pool 管理员 - 必须签名
     pub admin: Signer<'info>,
@@ -35,28 +58,34 @@ pub struct AddTokenToPool<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// LP mint 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 /// 添加质押类型到 pool
 ///
-/// 自动创建 LP mint（由 Anchor 处理）
+/// 自动创建 LP mint PDA（由 Anchor 处理），客户端可根据 pool.increment_count 推导地址
+/// LP mint 在传入的 token_program 下创建（Token 或 Token-2022）
 /// weight 默认为 10^8 (100,000,000)
 /// mint_amount 初始为 0
 pub fn add_token_to_pool(ctx: Context<AddTokenToPool>) -> Result<()> {
-    let pool = &mut ctx.accounts.pool.load_mut()?;
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
 
     // 验证管理员权限
-    pool.verify_admin(&ctx.accounts.admin.key())?;
-
-    // 默认权重：10^8 = 100,000,000
-    const DEFAULT_WEIGHT: u64 = 100_000_000;
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
 
     // 添加质押类型（LP mint 和默认 weight）
     let lp_mint_key = ctx.accounts.lp_mint.key();
-    let index = pool.add_token(&lp_mint_key, DEFAULT_WEIGHT)?;
+    let index = pool.add_token(&lp_mint_key, DEFAULT_ITEM_WEIGHT)?;
+
+    // 记录 mint → index 映射
+    let mint_index = &mut ctx.accounts.mint_index;
+    mint_index.pool = ctx.accounts.pool.key();
+    mint_index.lp_mint = lp_mint_key;
+    mint_index.item_index = index as u16;
+    mint_index.bump = ctx.bumps.mint_index;
 
     // increment_count 递增（只增不减）
     pool.increment_count = pool.increment_count
@@ -64,7 +93,13 @@ pub fn add_token_to_pool(ctx: Context<AddTokenToPool>) -> Result<()> {
         .ok_or(ErrorCode::MathOverflow)?;
 
     msg!("Staking type added: index: {}, lp_mint: {}, weight: {}, mint_amount: 0",
-         index, lp_mint_key, DEFAULT_WEIGHT);
+         index, lp_mint_key, DEFAULT_ITEM_WEIGHT);
+    emit!(TokenAddedEvent {
+        pool: ctx.accounts.pool.key(),
+        item_index: index as u16,
+        lp_mint: lp_mint_key,
+        weight: DEFAULT_ITEM_WEIGHT,
+    });
 
     Ok(())
 }
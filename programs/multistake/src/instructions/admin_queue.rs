@@ -0,0 +1,519 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenInterface};
+use crate::state::{AdminAction, AdminTimelock, MintIndex, PendingAction, Pool, WeightChange, WeightHistory, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::events::{TokenRemovedEvent, WeightModifiedEvent};
+use crate::instructions::remove_token::revoke_lp_mint_authority;
+use crate::instructions::rescue::rescue_token_account;
+use crate::instructions::weight_history::record_weight_change;
+use crate::transfer::transfer_main_token;
+
+/// 配置管理操作时间锁
+#[derive(Accounts)]
+pub struct ConfigureTimelock<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 时间锁配置 PDA
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = AdminTimelock::space(),
+        seeds = [b"timelock", pool.key().as_ref()],
+        bump
+    )]
+    pub timelock: Account<'info, AdminTimelock>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 配置管理操作时间锁
+/// guardian: 可以取消待执行操作的地址，启用时间锁后只能通过 queue_action（SetGuardian）更换
+/// delay_slots: 入队后至少等待的 slot 数，创建后只能增大
+/// delay_slots 大于 0 时 pool 启用时间锁：费率、weight、移除质押类型、解除暂停、救援提取、
+/// 紧急赎回配置、调用方策略、白名单只能通过队列执行；oracle / 兑换率来源配置、策略委托等
+/// 需要额外账户的操作在启用时间锁后不能再直接执行
+pub fn configure_timelock(
+    ctx: Context<ConfigureTimelock>,
+    guardian: Pubkey,
+    delay_slots: u64,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let timelock = &mut ctx.accounts.timelock;
+    // 防止管理员先缩短延迟再绕过时间锁
+    require!(
+        delay_slots >= timelock.delay_slots,
+        ErrorCode::InvalidTimelockConfig
+    );
+    // 防止管理员直接换掉 guardian 后执行本应被取消的操作
+    if pool.admin_timelock_enabled != 0 {
+        require!(timelock.guardian == guardian, ErrorCode::TimelockRequired);
+    }
+
+    timelock.pool = ctx.accounts.pool.key();
+    timelock.guardian = guardian;
+    timelock.delay_slots = delay_slots;
+    timelock.bump = ctx.bumps.timelock;
+    if delay_slots > 0 {
+        pool.admin_timelock_enabled = 1;
+    }
+
+    msg!("Timelock configured: pool: {}, guardian: {}, delay_slots: {}",
+         timelock.pool,
+         guardian,
+         delay_slots);
+
+    Ok(())
+}
+
+/// 将管理操作加入队列
+#[derive(Accounts)]
+#[instruction(action: AdminAction)]
+pub struct QueueAction<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 时间锁配置
+    #[account(
+        mut,
+        seeds = [b"timelock", pool.key().as_ref()],
+        bump = timelock.bump,
+    )]
+    pub timelock: Account<'info, AdminTimelock>,
+
+    /// 待执行操作 PDA
+    #[account(
+        init,
+        payer = admin,
+        space = PendingAction::space(&action),
+        seeds = [
+            b"pending_action",
+            pool.key().as_ref(),
+            &timelock.next_action_id.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+
+    /// Pool 管理员 - 必须签名，并支付租金
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 将管理操作加入队列，delay_slots 之后才能执行
+pub fn queue_action(ctx: Context<QueueAction>, action: AdminAction) -> Result<()> {
+    action.validate()?;
+
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let timelock = &mut ctx.accounts.timelock;
+    let action_id = timelock.next_action_id;
+    let execute_after_slot = Clock::get()?.slot
+        .checked_add(timelock.delay_slots)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let pending = &mut ctx.accounts.pending_action;
+    pending.pool = ctx.accounts.pool.key();
+    pending.action_id = action_id;
+    pending.execute_after_slot = execute_after_slot;
+    pending.bump = ctx.bumps.pending_action;
+    pending.action = action;
+
+    timelock.next_action_id = action_id
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    msg!("Action queued: pool: {}, action_id: {}, execute_after_slot: {}, action: {:?}",
+         pending.pool,
+         action_id,
+         execute_after_slot,
+         pending.action);
+
+    Ok(())
+}
+
+/// 执行到期的管理操作
+#[derive(Accounts)]
+pub struct ExecuteAction<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 待执行操作 - 执行后关闭，租金退还给 admin
+    #[account(
+        mut,
+        has_one = pool,
+        close = admin,
+        seeds = [
+            b"pending_action",
+            pool.key().as_ref(),
+            &pending_action.action_id.to_le_bytes(),
+        ],
+        bump = pending_action.bump,
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+
+    /// Pool authority PDA - 紧急提取和放弃 LP mint 权限时签名
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 紧急提取的主币资产 vault - EmergencyWithdraw 时必须提供，且与入队时指定的 asset_index 对应
+    #[account(mut)]
+    pub pool_vault: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 紧急提取的主币 Mint - EmergencyWithdraw 时必须提供，与 pool_vault 一致
+    pub vault_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// 紧急提取的目标账户 - 必须与入队时指定的一致
+    #[account(mut)]
+    pub destination: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 紧急提取主币所属的 token 程序（Token 或 Token-2022）- EmergencyWithdraw 时必须提供
+    pub main_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// 被移除 item 的 LP mint - RemoveToken 时必须提供，mint authority 会被设置为 None
+    #[account(mut)]
+    pub lp_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// LP mint 所属的 token 程序（Token 或 Token-2022）- RemoveToken 时必须提供
+    pub lp_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// 被移除 item 的 mint → index 映射（RemoveToken 可选）- 关闭后租金退还给 admin
+    #[account(
+        mut,
+        has_one = pool,
+        close = admin,
+        seeds = [b"mint_index", pool.key().as_ref(), mint_index.lp_mint.as_ref()],
+        bump = mint_index.bump,
+    )]
+    pub mint_index: Option<Account<'info, MintIndex>>,
+
+    /// 被移动到空位的最后一个 item 的映射（RemoveToken 可选）
+    #[account(
+        mut,
+        has_one = pool,
+        seeds = [b"mint_index", pool.key().as_ref(), moved_mint_index.lp_mint.as_ref()],
+        bump = moved_mint_index.bump,
+    )]
+    pub moved_mint_index: Option<Account<'info, MintIndex>>,
+
+    /// weight 修改历史 - pool 启用 weight 历史后执行 SetWeights 时必须提供
+    #[account(
+        mut,
+        seeds = [b"weight_history", pool.key().as_ref()],
+        bump = weight_history.bump,
+    )]
+    pub weight_history: Option<Box<Account<'info, WeightHistory>>>,
+
+    /// 时间锁配置 - SetGuardian 时必须提供
+    #[account(
+        mut,
+        seeds = [b"timelock", pool.key().as_ref()],
+        bump = timelock.bump,
+    )]
+    pub timelock: Option<Account<'info, AdminTimelock>>,
+
+    /// 救援提取的来源账户 - RescueTokens 时必须提供，且与入队时指定的一致
+    #[account(mut)]
+    pub rescue_source: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 救援提取的 mint - RescueTokens 时必须提供
+    pub rescue_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// 救援提取的目标账户 - RescueTokens 时必须提供，且与入队时指定的一致
+    #[account(mut)]
+    pub rescue_destination: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 积分奖励 vault 地址 - RescueTokens 时必须提供
+    /// CHECK: 地址由 seeds 约束，只读取 token 账户的 mint 字段
+    #[account(seeds = [b"reward_vault", pool.key().as_ref()], bump)]
+    pub reward_vault: Option<UncheckedAccount<'info>>,
+
+    /// 救援提取 mint 所属的 token 程序 - RescueTokens 时必须提供
+    pub rescue_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Pool 管理员 - 必须签名
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+/// 执行到期的管理操作
+/// remaining_accounts: 共同管理员签名者；EmergencyWithdraw / RescueTokens 的 mint 启用 TransferHook 时
+/// 追加 hook 需要的额外账户
+pub fn execute_action<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteAction<'info>>,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let pending = &ctx.accounts.pending_action;
+    let clock = Clock::get()?;
+    pending.verify_ready(clock.slot)?;
+
+    // mint → index 映射只用于 RemoveToken，防止其他操作误关闭映射账户
+    if !matches!(pending.action, AdminAction::RemoveToken { .. }) {
+        require!(
+            ctx.accounts.mint_index.is_none() && ctx.accounts.moved_mint_index.is_none(),
+            ErrorCode::InvalidTokenMint
+        );
+    }
+
+    match &pending.action {
+        AdminAction::SetFee { fee_config } => {
+            fee_config.validate()?;
+            pool.set_fee(*fee_config);
+            pool.clear_pending_fee();
+        }
+        AdminAction::SetWeights { weights } => {
+            require!(
+                weights.len() == pool.get_token_count(),
+                ErrorCode::InvalidTokenCount
+            );
+            // 结算奖励排放（weight 变化前）
+            pool.accrue_emission(clock.unix_timestamp)?;
+            let cooldown_slots = pool.get_weight_update_cooldown_slots();
+            let max_change_bps = pool.get_max_weight_change_bps();
+            for (index, weight) in weights.iter().enumerate() {
+                let token = pool.get_token(index)
+                    .ok_or(ErrorCode::InvalidTokenIndex)?;
+                token.verify_weight_cooldown(clock.slot, cooldown_slots)?;
+                token.verify_weight_change(*weight, max_change_bps)?;
+                let old_weight = token.get_weight();
+                let lp_mint = *token.mint_pubkey();
+                pool.update_item_weight(index, *weight, clock.slot)?;
+                record_weight_change(pool, ctx.accounts.weight_history.as_mut(), WeightChange {
+                    unix_timestamp: clock.unix_timestamp,
+                    item_index: index as u16,
+                    old_weight,
+                    new_weight: *weight,
+                    authority: ctx.accounts.admin.key(),
+                })?;
+                emit!(WeightModifiedEvent {
+                    pool: ctx.accounts.pool.key(),
+                    item_index: index as u16,
+                    lp_mint,
+                    old_weight,
+                    new_weight: *weight,
+                    slot: clock.slot,
+                });
+            }
+        }
+        AdminAction::EmergencyWithdraw { asset_index, amount, destination } => {
+            let pool_vault = ctx.accounts.pool_vault.as_ref()
+                .ok_or(ErrorCode::InvalidPoolVault)?;
+            require!(
+                pool_vault.key() == pool.get_asset_vault(*asset_index)?,
+                ErrorCode::InvalidPoolVault
+            );
+            let vault_mint = ctx.accounts.vault_mint.as_ref()
+                .ok_or(ErrorCode::InvalidTokenMint)?;
+            require!(vault_mint.key() == pool_vault.mint, ErrorCode::InvalidTokenMint);
+            let destination_account = ctx.accounts.destination.as_ref()
+                .ok_or(ErrorCode::InvalidPoolVault)?;
+            require!(
+                destination_account.key() == *destination,
+                ErrorCode::InvalidPoolVault
+            );
+            let main_token_program = ctx.accounts.main_token_program.as_ref()
+                .ok_or(ErrorCode::InvalidTokenMint)?;
+
+            // 先扣减 vault 记账余额，提取后的余额不计入兑换率
+            let tracked_balance = pool.get_tracked_balance(*asset_index)?.saturating_sub(*amount);
+            pool.set_tracked_balance(*asset_index, tracked_balance)?;
+
+            let pool_key = ctx.accounts.pool.key();
+            let bump = ctx.bumps.pool_authority;
+            let authority_seed = pool.get_authority_seed();
+            let seeds = &[
+                POOL_AUTHORITY_SEED,
+                pool_key.as_ref(),
+                authority_seed,
+                &[bump],
+            ];
+            let signer = &[&seeds[..]];
+
+            transfer_main_token(
+                &main_token_program.to_account_info(),
+                &pool_vault.to_account_info(),
+                &vault_mint.to_account_info(),
+                &destination_account.to_account_info(),
+                &ctx.accounts.pool_authority,
+                ctx.remaining_accounts,
+                *amount,
+                vault_mint.decimals,
+                signer,
+            )?;
+        }
+        AdminAction::RemoveToken { lp_mint } => {
+            let token_index = pool.find_token_index(lp_mint)
+                .ok_or(ErrorCode::InvalidTokenMint)?;
+            let lp_mint_account = ctx.accounts.lp_mint.as_ref()
+                .ok_or(ErrorCode::InvalidTokenMint)?;
+            require!(lp_mint_account.key() == *lp_mint, ErrorCode::InvalidTokenMint);
+            let lp_token_program = ctx.accounts.lp_token_program.as_ref()
+                .ok_or(ErrorCode::InvalidTokenMint)?;
+            if let Some(mint_index) = ctx.accounts.mint_index.as_ref() {
+                require!(mint_index.lp_mint == *lp_mint, ErrorCode::InvalidTokenMint);
+            }
+
+            let moved_from = pool.remove_token(token_index)?;
+
+            // 更新被移动 item 的映射
+            if let (Some(_), Some(moved_mint_index)) = (moved_from, ctx.accounts.moved_mint_index.as_mut()) {
+                require!(
+                    moved_mint_index.lp_mint == pool.tokens[token_index].mint_account,
+                    ErrorCode::InvalidTokenMint
+                );
+                moved_mint_index.item_index = token_index as u16;
+            }
+
+            let pool_key = ctx.accounts.pool.key();
+            let bump = ctx.bumps.pool_authority;
+            let authority_seed = pool.get_authority_seed();
+            let seeds = &[
+                POOL_AUTHORITY_SEED,
+                pool_key.as_ref(),
+                authority_seed,
+                &[bump],
+            ];
+            revoke_lp_mint_authority(
+                &lp_token_program.to_account_info(),
+                lp_mint_account,
+                &ctx.accounts.pool_authority,
+                &[&seeds[..]],
+            )?;
+
+            emit!(TokenRemovedEvent {
+                pool: ctx.accounts.pool.key(),
+                item_index: token_index as u16,
+                lp_mint: *lp_mint,
+                moved_from: moved_from.map(|index| index as u16),
+            });
+        }
+        AdminAction::SetPause { paused, withdraw_only } => {
+            pool.set_paused(*paused, *withdraw_only);
+        }
+        AdminAction::SetEmergencyConfig { exit_restricted, penalty_bps } => {
+            pool.set_emergency_config(*exit_restricted, *penalty_bps)?;
+        }
+        AdminAction::SetCallerPolicy { mode, programs } => {
+            pool.caller_allowlist.set(*mode, programs)?;
+        }
+        AdminAction::SetWhitelist { whitelist_root, permissioned } => {
+            pool.set_whitelist(*whitelist_root, *permissioned)?;
+        }
+        AdminAction::SetGuardian { guardian } => {
+            let timelock = ctx.accounts.timelock.as_mut()
+                .ok_or(ErrorCode::InvalidTimelockConfig)?;
+            timelock.guardian = *guardian;
+        }
+        AdminAction::RescueTokens { source, amount, destination } => {
+            let source_account = ctx.accounts.rescue_source.as_ref()
+                .ok_or(ErrorCode::InvalidRescueSource)?;
+            let destination_account = ctx.accounts.rescue_destination.as_ref()
+                .ok_or(ErrorCode::InvalidRescueSource)?;
+            require!(
+                source_account.key() == *source && destination_account.key() == *destination,
+                ErrorCode::InvalidRescueSource
+            );
+            let mint = ctx.accounts.rescue_mint.as_ref()
+                .ok_or(ErrorCode::InvalidTokenMint)?;
+            let reward_vault = ctx.accounts.reward_vault.as_ref()
+                .ok_or(ErrorCode::InvalidRescueSource)?;
+            let rescue_token_program = ctx.accounts.rescue_token_program.as_ref()
+                .ok_or(ErrorCode::InvalidTokenMint)?;
+
+            rescue_token_account(
+                pool,
+                &ctx.accounts.pool.key(),
+                &ctx.accounts.pool_authority,
+                ctx.bumps.pool_authority,
+                source_account,
+                mint,
+                destination_account,
+                reward_vault,
+                &rescue_token_program.to_account_info(),
+                ctx.remaining_accounts,
+                *amount,
+            )?;
+        }
+    }
+
+    msg!("Action executed: pool: {}, action_id: {}, action: {:?}",
+         ctx.accounts.pool.key(),
+         pending.action_id,
+         pending.action);
+
+    Ok(())
+}
+
+/// 取消待执行的管理操作
+#[derive(Accounts)]
+pub struct CancelAction<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 时间锁配置
+    #[account(
+        seeds = [b"timelock", pool.key().as_ref()],
+        bump = timelock.bump,
+    )]
+    pub timelock: Account<'info, AdminTimelock>,
+
+    /// 待取消的操作 - 关闭后租金退还给 authority
+    #[account(
+        mut,
+        has_one = pool,
+        close = authority,
+        seeds = [
+            b"pending_action",
+            pool.key().as_ref(),
+            &pending_action.action_id.to_le_bytes(),
+        ],
+        bump = pending_action.bump,
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+
+    /// guardian 或 admin
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// 取消待执行的管理操作（guardian 或 admin）
+pub fn cancel_action(ctx: Context<CancelAction>) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == ctx.accounts.timelock.guardian || authority == pool.admin,
+        ErrorCode::InvalidGuardian
+    );
+
+    msg!("Action cancelled: pool: {}, action_id: {}, by: {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.pending_action.action_id,
+         authority);
+
+    Ok(())
+}
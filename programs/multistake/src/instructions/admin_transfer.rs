@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+
+/// 提议新的 Pool 管理员
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 当前 Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 提议新的 Pool 管理员
+/// new_admin: 新管理员地址，需由新管理员签名 accept_admin 后才生效
+/// 传入 Pubkey::default() 表示撤销尚未接受的提议
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    pool.propose_admin(&new_admin);
+
+    msg!("Admin proposed: pool: {}, admin: {}, pending_admin: {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.admin.key(),
+         new_admin);
+
+    Ok(())
+}
+
+/// 接受 Pool 管理员交接
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 被提议的新管理员 - 必须签名，证明持有新密钥
+    pub new_admin: Signer<'info>,
+}
+
+/// 接受 Pool 管理员交接
+/// 签名者必须与 pending_admin 一致，成功后 admin 更新并清空 pending_admin
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    let old_admin = pool.admin;
+
+    pool.accept_admin(&ctx.accounts.new_admin.key())?;
+
+    msg!("Admin accepted: pool: {}, old_admin: {}, new_admin: {}",
+         ctx.accounts.pool.key(),
+         old_admin,
+         ctx.accounts.new_admin.key());
+
+    Ok(())
+}
+
+/// 管理共同管理员与签名门限
+#[derive(Accounts)]
+pub struct ManageAdminSigners<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名，其余管理员签名通过 remaining_accounts 传入
+    pub admin: Signer<'info>,
+}
+
+/// 登记共同管理员
+/// signer: 新的共同管理员地址，最多 MAX_ADMIN_SIGNERS 个
+pub fn add_admin_signer(ctx: Context<ManageAdminSigners>, signer: Pubkey) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    pool.add_admin_signer(&signer)?;
+
+    msg!("Admin signer added: pool: {}, signer: {}, signers: {}, threshold: {}",
+         ctx.accounts.pool.key(),
+         signer,
+         pool.admin_signer_count,
+         pool.admin_threshold);
+
+    Ok(())
+}
+
+/// 移除共同管理员
+/// 移除后管理员总数不能低于签名门限，需要时先调用 set_admin_threshold 降低门限
+pub fn remove_admin_signer(ctx: Context<ManageAdminSigners>, signer: Pubkey) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    pool.remove_admin_signer(&signer)?;
+
+    msg!("Admin signer removed: pool: {}, signer: {}, signers: {}, threshold: {}",
+         ctx.accounts.pool.key(),
+         signer,
+         pool.admin_signer_count,
+         pool.admin_threshold);
+
+    Ok(())
+}
+
+/// 设置管理操作所需的最少管理员签名数
+/// threshold: 1 ≤ threshold ≤ 主管理员 + 共同管理员数量
+pub fn set_admin_threshold(ctx: Context<ManageAdminSigners>, threshold: u8) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let old_threshold = pool.admin_threshold;
+    pool.set_admin_threshold(threshold)?;
+
+    msg!("Admin threshold updated: pool: {}, old: {}, new: {}",
+         ctx.accounts.pool.key(),
+         old_threshold,
+         threshold);
+
+    Ok(())
+}
+
+/// 设置 weight 操作员
+/// operator: 新的操作员，Pubkey::default() 表示撤销
+/// 操作员只能调用 modify_token_weight，适合交给 oracle 机器人持有的低权限密钥
+pub fn set_weight_operator(ctx: Context<ManageAdminSigners>, operator: Pubkey) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let old_operator = pool.weight_operator;
+    pool.set_weight_operator(&operator);
+
+    msg!("Weight operator updated: pool: {}, old: {}, new: {}",
+         ctx.accounts.pool.key(),
+         old_operator,
+         operator);
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::associated_token::{self, AssociatedToken, Create};
+use anchor_spl::token::{self, InitializeMint2, Mint, MintTo, Token, TokenAccount};
+use crate::state::{authority_seed_suffix, FeeConfig, Pool, DEFAULT_ITEM_WEIGHT, INITIAL_AUTHORITY_SEED_VERSION, LP_MINT_DECIMALS, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+
+/// 测试 pool 使用的主币 / LP 精度
+const BOOTSTRAP_DECIMALS: u8 = 9;
+
+/// 一次性创建测试 pool（仅 localnet-bootstrap feature）
+#[derive(Accounts)]
+pub struct BootstrapTestPool<'info> {
+    /// 由客户端在同一交易中预先分配（与 create_pool 相同）
+    #[account(zero)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            authority_seed_suffix(INITIAL_AUTHORITY_SEED_VERSION),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 测试主币 Mint - mint authority 为 admin，测试中可以继续增发
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"bootstrap_mint", pool.key().as_ref()],
+        bump,
+        mint::decimals = BOOTSTRAP_DECIMALS,
+        mint::authority = admin,
+    )]
+    pub main_token_mint: Box<Account<'info, Mint>>,
+
+    /// Pool 的主币 Vault
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pool_vault", pool.key().as_ref()],
+        bump,
+        token::mint = main_token_mint,
+        token::authority = pool_authority
+    )]
+    pub pool_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pool 管理员
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 一次性创建测试 pool、若干 item 和已注资的用户账户
+/// item_count: 创建的质押类型数量
+/// fund_amount: 给每个用户铸造的主币数量
+/// remaining_accounts（均可写）:
+/// - 前 item_count 个：LP mint PDA ["bootstrap_lp", pool, [i]]（未初始化）
+/// - 之后每两个一组：[user_wallet, user_main_token_ata]（ATA 未创建）
+pub fn bootstrap_test_pool<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BootstrapTestPool<'info>>,
+    fee_config: FeeConfig,
+    item_count: u8,
+    fund_amount: u64,
+) -> Result<()> {
+    fee_config.validate()?;
+
+    let item_count = item_count as usize;
+    require!(
+        ctx.remaining_accounts.len() >= item_count
+            && (ctx.remaining_accounts.len() - item_count) % 2 == 0,
+        ErrorCode::InvalidTokenCount
+    );
+    let (lp_mints, users) = ctx.remaining_accounts.split_at(item_count);
+
+    let pool_key = ctx.accounts.pool.key();
+    // 容量按客户端分配的账户大小计算
+    let capacity = Pool::capacity_for_space(ctx.accounts.pool.as_ref().data_len());
+    let pool = &mut Pool::load_init(&ctx.accounts.pool, capacity)?;
+    pool.initialize(
+        &ctx.accounts.admin.key(),
+        &ctx.accounts.pool_vault.key(),
+        &ctx.accounts.main_token_mint.key(),
+        fee_config,
+        capacity,
+    );
+    pool.main_token_decimals = BOOTSTRAP_DECIMALS;
+
+    // 1. 创建 LP mint 并注册为 item
+    let mint_space = Mint::LEN as u64;
+    let mint_lamports = ctx.accounts.rent.minimum_balance(Mint::LEN);
+    for (index, lp_mint) in lp_mints.iter().enumerate() {
+        let index_seed = [index as u8];
+        let (expected, bump) = Pubkey::find_program_address(
+            &[b"bootstrap_lp", pool_key.as_ref(), &index_seed],
+            ctx.program_id,
+        );
+        require!(lp_mint.key() == expected, ErrorCode::InvalidLpMint);
+
+        let seeds = &[b"bootstrap_lp", pool_key.as_ref(), &index_seed, &[bump]];
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: lp_mint.clone(),
+                },
+                &[&seeds[..]],
+            ),
+            mint_lamports,
+            mint_space,
+            &ctx.accounts.token_program.key(),
+        )?;
+        token::initialize_mint2(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeMint2 { mint: lp_mint.clone() },
+            ),
+            LP_MINT_DECIMALS,
+            &ctx.accounts.pool_authority.key(),
+            None,
+        )?;
+
+        pool.add_token(&expected, DEFAULT_ITEM_WEIGHT)?;
+        pool.increment_count = pool.increment_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    // 2. 创建用户主币 ATA 并注资
+    for pair in users.chunks(2) {
+        let (wallet, ata) = (&pair[0], &pair[1]);
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: ctx.accounts.payer.to_account_info(),
+                associated_token: ata.clone(),
+                authority: wallet.clone(),
+                mint: ctx.accounts.main_token_mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.main_token_mint.to_account_info(),
+                    to: ata.clone(),
+                    authority: ctx.accounts.admin.to_account_info(),
+                },
+            ),
+            fund_amount,
+        )?;
+    }
+
+    msg!("Test pool bootstrapped: pool: {}, main_token_mint: {}, items: {}, users: {}, fund_amount: {}",
+         pool_key,
+         ctx.accounts.main_token_mint.key(),
+         item_count,
+         users.len() / 2,
+         fund_amount);
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+
+/// 设置调用方策略
+#[derive(Accounts)]
+pub struct SetCallerPolicy<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置调用方策略
+/// mode: 0 = 不限制，1 = 只允许顶层直接调用，2 = 只允许白名单程序 CPI 调用
+/// programs: 白名单程序 ID（最多 4 个，mode = 2 时不能为空）
+pub fn set_caller_policy(
+    ctx: Context<SetCallerPolicy>,
+    mode: u8,
+    programs: Vec<Pubkey>,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后必须通过 queue_action 执行
+    pool.verify_direct_admin_action()?;
+
+    pool.caller_allowlist.set(mode, &programs)?;
+
+    msg!("Caller policy updated: pool: {}, mode: {}, programs: {:?}",
+         ctx.accounts.pool.key(),
+         mode,
+         programs);
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::state::{Pool, PoolCheckpoint};
+
+/// 生成 pool 记账状态检查点
+#[derive(Accounts)]
+pub struct Checkpoint<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 检查点 PDA - 首次调用时创建
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PoolCheckpoint::space(),
+        seeds = [b"checkpoint", pool.key().as_ref()],
+        bump
+    )]
+    pub checkpoint: Box<Account<'info, PoolCheckpoint>>,
+
+    /// 任何人都可以调用并支付创建费用
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 生成 pool 记账状态检查点（任何人都可以调用）
+/// 将所有 item 状态和 vault 记账余额哈希为摘要，连同 slot 写入检查点账户
+/// 审计方可按 PoolCheckpoint::compute_digest 的顺序用归档账户数据重算并比对
+pub fn checkpoint(ctx: Context<Checkpoint>) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    let pool_key = ctx.accounts.pool.key();
+    let slot = Clock::get()?.slot;
+    let digest = PoolCheckpoint::compute_digest(&pool_key, slot, &pool)?;
+
+    let checkpoint = &mut ctx.accounts.checkpoint;
+    if checkpoint.pool == Pubkey::default() {
+        checkpoint.pool = pool_key;
+        checkpoint.sequence = 0;
+        checkpoint.bump = ctx.bumps.checkpoint;
+    } else {
+        checkpoint.sequence = checkpoint.sequence.saturating_add(1);
+    }
+    checkpoint.slot = slot;
+    checkpoint.token_count = pool.get_token_count() as u16;
+    checkpoint.digest = digest;
+
+    msg!("Checkpoint: pool: {}, sequence: {}, slot: {}, token_count: {}, digest: {}",
+         pool_key,
+         checkpoint.sequence,
+         slot,
+         checkpoint.token_count,
+         crate::instructions::stake::format_tag(&digest));
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, TokenAccount, TokenInterface};
+use crate::state::{Pool, PoolRegistry, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::events::PoolClosedEvent;
+
+/// 关闭 Pool，回收 pool、pool vault 和 fee vault 的租金
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(mut, close = recipient)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// Pool 的主币 Vault - 余额必须为 0
+    #[account(
+        mut,
+        address = pool.load()?.pool_vault @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 手续费 vault - pool 记录了 fee_vault 时必须传入，余额必须为 0
+    #[account(
+        mut,
+        address = pool.load()?.fee_vault @ ErrorCode::InvalidFeeConfig,
+    )]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// 主币的 pool 注册表 - 必须传入，从中移除该 pool，避免注册表残留已关闭的 pool
+    /// 注册表上线前创建的 pool 所属主币尚无注册表时，需先调用 register_pool 创建
+    #[account(
+        mut,
+        seeds = [b"pool_registry", pool.load()?.pool_mint.as_ref()],
+        bump = pool_registry.bump,
+    )]
+    pub pool_registry: Box<Account<'info, PoolRegistry>>,
+
+    /// 接收回收租金的账户
+    /// CHECK: 仅接收 lamports
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 关闭 Pool
+/// 要求所有质押类型已移除（token_count = 0）、pool vault 和 fee vault 余额为 0、
+/// 没有委托给质押策略的资金、没有待领取的赎回和推荐返佣，且没有额外主币（额外主币 vault 无法移除，
+/// 关闭后其中的资产将无人可以取回）；关闭后 pool 账户和 vault 的租金退还给 recipient
+/// 不受时间锁限制：关闭时 pool 中已没有用户资产
+pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let (authority_seed, recorded_fee_vault) = {
+        let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+        // 验证管理员权限
+        pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+        require!(pool.token_count == 0, ErrorCode::PoolNotEmpty);
+        require!(pool.extra_asset_count == 0, ErrorCode::PoolNotEmpty);
+        require!(pool.strategy_delegated_amount == 0, ErrorCode::PoolNotEmpty);
+        require!(pool.pending_unstake_amount == 0, ErrorCode::PoolNotEmpty);
+        require!(pool.referral_fees_owed == 0, ErrorCode::PoolNotEmpty);
+        (pool.get_authority_seed(), pool.fee_vault)
+    };
+    require!(ctx.accounts.pool_vault.amount == 0, ErrorCode::PoolNotEmpty);
+
+    let bump = ctx.bumps.pool_authority;
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    // 1. 关闭 fee vault（旧 pool 没有 fee vault）
+    match &ctx.accounts.fee_vault {
+        Some(fee_vault) => {
+            require!(fee_vault.amount == 0, ErrorCode::PoolNotEmpty);
+            token_interface::close_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: fee_vault.to_account_info(),
+                    destination: ctx.accounts.recipient.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer,
+            ))?;
+        }
+        None => require!(
+            recorded_fee_vault == Pubkey::default(),
+            ErrorCode::InvalidFeeConfig
+        ),
+    }
+
+    // 2. 从注册表中移除（未登记的旧 pool 不在列表中）
+    ctx.accounts.pool_registry.unregister(&pool_key);
+
+    // 3. 关闭 pool vault，pool 账户由 close 约束关闭
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.pool_vault.to_account_info(),
+            destination: ctx.accounts.recipient.to_account_info(),
+            authority: ctx.accounts.pool_authority.to_account_info(),
+        },
+        signer,
+    ))?;
+
+    msg!("Pool closed: pool: {}, pool_vault: {}, recipient: {}",
+         pool_key,
+         ctx.accounts.pool_vault.key(),
+         ctx.accounts.recipient.key());
+    emit!(PoolClosedEvent {
+        pool: pool_key,
+        recipient: ctx.accounts.recipient.key(),
+    });
+
+    Ok(())
+}
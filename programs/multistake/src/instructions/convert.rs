@@ -0,0 +1,328 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, BurnChecked, MintToChecked, TokenInterface};
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+use crate::state::{ItemOracle, Pool, StakeRecord, LP_MINT_DECIMALS, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use super::rate_weight::apply_rate_weights;
+
+/// 将一个质押类型的 LP 转换为另一个质押类型的 LP
+#[derive(Accounts)]
+#[instruction(from_index: u16, to_index: u16)]
+pub struct ConvertLp<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - LP mint 的 authority
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 两个 item 共用的主币 Vault（只读，用于估值）
+    #[account(
+        address = Pool::load_versioned(&pool)?.get_item_vault(from_index as usize)? @ ErrorCode::InvalidPoolVault,
+        constraint = Pool::load_versioned(&pool)?.get_item_vault(to_index as usize)? == pool_vault.key()
+            @ ErrorCode::InvalidAssetIndex,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 转出的 LP mint（Token 或 Token-2022）
+    #[account(
+        mut,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::token_program = token_program,
+    )]
+    pub from_lp_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 转入的 LP mint（Token 或 Token-2022），由 pool authority 铸造
+    #[account(
+        mut,
+        mint::authority = pool_authority,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::token_program = token_program,
+    )]
+    pub to_lp_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 用户的转出 LP 账户（销毁来源）
+    #[account(
+        mut,
+        token::mint = from_lp_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_from_lp_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 用户的转入 LP 账户（铸造目标）
+    #[account(
+        mut,
+        token::mint = to_lp_mint,
+        token::token_program = token_program,
+    )]
+    pub user_to_lp_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 用户签名，首次转入时支付转入记录的租金
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// 转出 item 的 oracle 配置 - 转出 item 配置了 oracle 时必须提供
+    #[account(
+        seeds = [b"item_oracle", pool.key().as_ref(), from_lp_mint.key().as_ref()],
+        bump = item_oracle.bump,
+    )]
+    pub item_oracle: Option<Box<Account<'info, ItemOracle>>>,
+
+    /// oracle 价格账户 - 转出 item 配置了 oracle 时必须提供
+    /// CHECK: 与 item_oracle.price_feed 比对，并在读取时校验 owner
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// 转出 item 的质押记录 - 不存在时创建，结算积分和奖励后扣减 staked_amount
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeRecord::space(),
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            from_lp_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub from_stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// 转入 item 的质押记录 - 不存在时创建，结算积分和奖励后累加 staked_amount
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeRecord::space(),
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            to_lp_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub to_stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// Instructions sysvar - pool 启用调用方白名单时用于识别 CPI 调用方
+    /// CHECK: 地址约束为 instructions sysvar
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    /// LP mint 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// 将质押类型 A 的 LP 转换为质押类型 B 的 LP
+/// from_index / to_index: 转出 / 转入的质押类型索引（必须使用同一种主币）
+/// lp_amount: 转出的 LP 数量
+///
+/// 逻辑：
+/// 1. 按赎回公式计算 A 的 LP 价值（含脱锚调整），扣除手续费
+/// 2. 销毁 A 的 LP，按扣费后的价值以 B 的兑换率铸造 B 的 LP（与 stake 一致）
+/// 3. 主币不出入 vault，手续费留在 vault 中
+///
+/// remaining_accounts: 同一主币下每个配置了兑换率来源的 item 一组 [item_rate_source, source]
+pub fn convert_lp(
+    ctx: Context<ConvertLp>,
+    from_index: u16,
+    to_index: u16,
+    lp_amount: u64,
+) -> Result<()> {
+    convert(ctx, from_index, to_index, lp_amount, 0)
+}
+
+/// 将头寸从质押类型 A 迁移到质押类型 B（带滑点保护的 convert_lp）
+/// 只收取一次 swap_fee_bps 手续费，相比 unstake 再 stake 省去一次手续费和主币进出 vault
+/// min_lp_out: 滑点保护 - 铸造的 B 的 LP 少于该值时回滚
+/// 积分和奖励先按两个 item 各自的 weight 结算，再把 staked_amount 从 A 移到 B
+pub fn restake(
+    ctx: Context<ConvertLp>,
+    from_index: u16,
+    to_index: u16,
+    lp_amount: u64,
+    min_lp_out: u64,
+) -> Result<()> {
+    convert(ctx, from_index, to_index, lp_amount, min_lp_out)
+}
+
+fn convert(
+    ctx: Context<ConvertLp>,
+    from_index: u16,
+    to_index: u16,
+    lp_amount: u64,
+    min_lp_out: u64,
+) -> Result<()> {
+    require!(lp_amount > 0, ErrorCode::InvalidTokenCount);
+    require!(from_index != to_index, ErrorCode::InvalidTokenIndex);
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 暂停状态检查
+    pool.verify_stake_allowed()?;
+
+    // 验证调用方策略
+    pool.caller_allowlist.verify_caller(
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+    )?;
+
+    // 两个 item 必须属于同一主币资产（pool_vault 约束已保证共用 vault，这里显式校验）
+    let from_asset = pool.get_token(from_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?
+        .get_main_asset_index();
+    let to_asset = pool.get_token(to_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?
+        .get_main_asset_index();
+    require!(from_asset == to_asset, ErrorCode::InvalidAssetIndex);
+
+    // 结算奖励排放（LP 数量变化前），再按衰减计划更新 weight
+    let now = Clock::get()?.unix_timestamp;
+    pool.accrue_emission(now)?;
+    // from_index 和 to_index 属于同一主币，一次应用该主币下的全部计划
+    pool.apply_weight_schedules(from_index as usize, now)?;
+    // 按兑换率来源刷新 weight：刷新该主币下所有配置了兑换率来源的 item，from_index 和 to_index 都包含在内
+    apply_rate_weights(
+        pool,
+        &ctx.accounts.pool.key(),
+        from_index as usize,
+        ctx.remaining_accounts,
+        &Clock::get()?,
+    )?;
+
+    // 验证 LP mint 地址匹配
+    let from_item = pool.get_token(from_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    require!(
+        ctx.accounts.from_lp_mint.key() == *from_item.mint_pubkey(),
+        ErrorCode::InvalidTokenMint
+    );
+    let to_item = pool.get_token(to_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    // 停用的 item 不接受转入
+    to_item.verify_active()?;
+    require!(
+        ctx.accounts.to_lp_mint.key() == *to_item.mint_pubkey(),
+        ErrorCode::InvalidTokenMint
+    );
+
+    // 1. 按赎回公式估值
+    let value = pool.calculate_redeem_amount(
+        lp_amount,
+        from_index as usize,
+        ctx.accounts.pool_vault.amount,
+    )?;
+    let value = if from_item.has_oracle() {
+        let oracle = ctx.accounts.item_oracle.as_ref()
+            .ok_or(ErrorCode::InvalidOracleAccount)?;
+        let price_feed = ctx.accounts.price_feed.as_ref()
+            .ok_or(ErrorCode::InvalidOracleAccount)?;
+        oracle.adjust_redeem_amount(
+            price_feed,
+            Clock::get()?.unix_timestamp,
+            from_item.get_weight(),
+            value,
+        )?
+    } else {
+        value
+    };
+    let (fee_amount, minted_value) = pool.calculate_swap_fee(value)?;
+    require!(minted_value > 0, ErrorCode::InsufficientTokenAmount);
+
+    // A 的 LP 先出账，再把扣费后的价值视为一笔存入 B 的 stake（存入前 vault 不含该价值）
+    pool.sub_item_mint_amount(from_index as usize, lp_amount)?;
+    let vault_before_deposit = ctx.accounts.pool_vault.amount
+        .checked_sub(minted_value)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let minted_amount = pool.calculate_stake_lp_amount(
+        minted_value,
+        to_index as usize,
+        vault_before_deposit,
+    )?;
+    require!(minted_amount > 0, ErrorCode::InsufficientTokenAmount);
+    require!(minted_amount >= min_lp_out, ErrorCode::SlippageExceeded);
+
+    // 先完成全部记账，再执行外部 CPI
+    // 2. 更新 B 的 mint_amount
+    pool.add_item_mint_amount(to_index as usize, minted_amount)?;
+
+    // 3. 结算并迁移质押记录（首次转入时初始化 B 的记录）
+    let pool_key = ctx.accounts.pool.key();
+    let current_slot = Clock::get()?.slot;
+    let from_item = pool.get_token(from_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    let from_record = &mut ctx.accounts.from_stake_record;
+    if from_record.is_uninitialized() {
+        from_record.initialize(&pool_key, &ctx.accounts.user.key(), from_item.mint_pubkey(), ctx.bumps.from_stake_record, current_slot);
+    }
+    from_record.accrue_points(from_item.get_weight(), current_slot)?;
+    from_record.settle_rewards(from_item.get_reward_per_share())?;
+    from_record.sub_staked_amount(lp_amount);
+
+    let to_item = pool.get_token(to_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    let to_record = &mut ctx.accounts.to_stake_record;
+    if to_record.is_uninitialized() {
+        to_record.initialize(&pool_key, &ctx.accounts.user.key(), to_item.mint_pubkey(), ctx.bumps.to_stake_record, current_slot);
+    }
+    to_record.accrue_points(to_item.get_weight(), current_slot)?;
+    to_record.settle_rewards(to_item.get_reward_per_share())?;
+    to_record.add_staked_amount(minted_amount)?;
+
+    // 4. 销毁 A 的 LP
+    token_interface::burn_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            BurnChecked {
+                mint: ctx.accounts.from_lp_mint.to_account_info(),
+                from: ctx.accounts.user_from_lp_token.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        lp_amount,
+        LP_MINT_DECIMALS,
+    )?;
+
+    // 5. 铸造 B 的 LP
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    token_interface::mint_to_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintToChecked {
+                mint: ctx.accounts.to_lp_mint.to_account_info(),
+                to: ctx.accounts.user_to_lp_token.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer,
+        ),
+        minted_amount,
+        LP_MINT_DECIMALS,
+    )?;
+
+    msg!("LP converted: user: {}, from_index: {}, to_index: {}, lp_burned: {}, value: {}, fee: {}, lp_minted: {}",
+         ctx.accounts.user.key(),
+         from_index,
+         to_index,
+         lp_amount,
+         value,
+         fee_amount,
+         minted_amount);
+
+    Ok(())
+}
@@ -1,25 +1,139 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
-use crate::state::Pool;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use anchor_lang::system_program::{self, Allocate, Assign, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{authority_seed_suffix, FeeConfig, Pool, PoolRegistry, PoolSizeTier, INITIAL_AUTHORITY_SEED_VERSION, POOL_AUTHORITY_SEED, POOL_SEED};
+use crate::events::PoolCreatedEvent;
 use crate::error::ErrorCode;
 
+/// 分配 Pool PDA 的账户空间
+/// Medium / Large 档位的 Pool 账户超过 CPI 单次可分配的 10KB，需要在 create_pool 之前多次调用（可放在同一交易中）
+#[derive(Accounts)]
+pub struct AllocatePool<'info> {
+    /// 待分配的 Pool PDA
+    /// CHECK: 尚未初始化，由 create_pool 写入数据
+    #[account(
+        mut,
+        seeds = [POOL_SEED, main_token_mint.key().as_ref(), admin.key().as_ref()],
+        bump
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    /// 主币的 Mint 账户
+    pub main_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool 管理员 - PDA seeds 的一部分
+    pub admin: Signer<'info>,
+
+    /// 支付 Pool 账户租金
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 分配 Pool PDA 的账户空间
+/// size_tier: 容量档位，决定账户大小 Pool::space_for(size_tier.capacity()) 和租金，须与 create_pool 一致
+///
+/// 逻辑：
+/// 1. 账户不存在时，payer 一次性补足完整大小所需的租金，分配首个 10KB 并 assign 给本程序
+/// 2. 已分配时，每次再扩展最多 10KB，直到达到完整大小
+/// 3. 达到完整大小后调用 create_pool 初始化
+pub fn allocate_pool(ctx: Context<AllocatePool>, size_tier: PoolSizeTier) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let target_size = Pool::space_for(size_tier.capacity());
+    let current_size = pool_info.data_len();
+
+    let main_token_mint = ctx.accounts.main_token_mint.key();
+    let admin = ctx.accounts.admin.key();
+    let bump = ctx.bumps.pool;
+    let seeds = &[POOL_SEED, main_token_mint.as_ref(), admin.as_ref(), &[bump]];
+    let signer = &[&seeds[..]];
+
+    // payer 补足完整大小所需的租金（已有 lamports 的地址也可以分配）
+    let required_lamports = Rent::get()?.minimum_balance(target_size);
+    let lamports_delta = required_lamports.saturating_sub(pool_info.lamports());
+    if lamports_delta > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: pool_info.clone(),
+                },
+            ),
+            lamports_delta,
+        )?;
+    }
+
+    let new_size = if *pool_info.owner == system_program::ID {
+        require!(current_size == 0, ErrorCode::InvalidAccountSize);
+        let new_size = target_size.min(MAX_PERMITTED_DATA_INCREASE);
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Allocate { account_to_allocate: pool_info.clone() },
+                signer,
+            ),
+            new_size as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Assign { account_to_assign: pool_info.clone() },
+                signer,
+            ),
+            ctx.program_id,
+        )?;
+        new_size
+    } else {
+        // 只扩展尚未初始化的 pool，已初始化的 pool 通过 resize_pool 扩容
+        require!(
+            *pool_info.owner == *ctx.program_id
+                && current_size < target_size
+                && pool_info.try_borrow_data()?[..8] == [0u8; 8],
+            ErrorCode::InvalidAccountSize
+        );
+        let new_size = target_size.min(current_size + MAX_PERMITTED_DATA_INCREASE);
+        pool_info.resize(new_size)?;
+        new_size
+    };
+
+    msg!("Pool allocated: pool: {}, size: {}/{}, rent_paid: {}",
+         pool_info.key(),
+         new_size,
+         target_size,
+         lamports_delta);
+
+    Ok(())
+}
+
 /// 创建单币质押 Pool
-/// 每个 Pool 对应一种主币，支持多种质押类型（最多 512 种）
+/// 每个 Pool 对应一种主币，支持的质押类型数量由容量档位决定（最多 512 种）
 #[derive(Accounts)]
 pub struct CreatePool<'info> {
-    #[account(zero)]
+    /// Pool PDA - 每个 (主币, 管理员) 对应唯一的 pool，需先通过 allocate_pool 分配空间
+    #[account(
+        zero,
+        seeds = [POOL_SEED, main_token_mint.key().as_ref(), admin.key().as_ref()],
+        bump
+    )]
     pub pool: AccountLoader<'info, Pool>,
 
     /// Pool authority PDA - 用于管理 pool vault
     /// CHECK: 用于管理 pool vault
     #[account(
-        seeds = [b"anyswap_authority", pool.key().as_ref()],
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            authority_seed_suffix(INITIAL_AUTHORITY_SEED_VERSION),
+        ],
         bump
     )]
     pub pool_authority: AccountInfo<'info>,
 
-    /// 主币的 Mint 账户 - Pool 对应的币种
-    pub main_token_mint: Account<'info, Mint>,
+    /// 主币的 Mint 账户 - Pool 对应的币种（Token 或 Token-2022）
+    pub main_token_mint: InterfaceAccount<'info, Mint>,
 
     /// Pool 的主币 Vault - 存储所有质押的主币
     #[account(
@@ -28,9 +142,32 @@ pub struct CreatePool<'info> {
         seeds = [b"pool_vault", pool.key().as_ref()],
         bump,
         token::mint = main_token_mint,
-        token::authority = pool_authority
+        token::authority = pool_authority,
+        token::token_program = token_program,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 手续费 vault - 存储 stake 手续费，由管理员通过 withdraw_fees 提取
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"fee_vault", pool.key().as_ref()],
+        bump,
+        token::mint = main_token_mint,
+        token::authority = pool_authority,
+        token::token_program = token_program,
+    )]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 主币的 pool 注册表 - 不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PoolRegistry::space(),
+        seeds = [b"pool_registry", main_token_mint.key().as_ref()],
+        bump,
     )]
-    pub pool_vault: Box<Account<'info, TokenAccount>>,
+    pub pool_registry: Box<Account<'info, PoolRegistry>>,
 
     /// Pool 管理员 - 用于所有操作的权限控制
     pub admin: Signer<'info>,
@@ -39,41 +176,60 @@ pub struct CreatePool<'info> {
     pub payer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 /// 创建 Pool
-/// fee_numerator: 手续费分子
-/// fee_denominator: 手续费分母
-/// 例如：fee_numerator=3, fee_denominator=1000 表示 0.3% 手续费
+/// fee_config: 分路径手续费配置（bps）
+/// 例如：stake_fee_bps=30 表示 stake 收取 0.3% 手续费
+/// size_tier: 容量档位，账户须已通过 allocate_pool 按同一档位分配完整大小
 pub fn create_pool(
     ctx: Context<CreatePool>,
-    fee_numerator: u64,
-    fee_denominator: u64,
+    fee_config: FeeConfig,
+    size_tier: PoolSizeTier,
 ) -> Result<()> {
-    require!(fee_denominator > 0, ErrorCode::MathOverflow);
-    require!(fee_numerator <= fee_denominator, ErrorCode::MathOverflow);
-    
-    let pool = &mut ctx.accounts.pool.load_init()?;
-    pool.token_count = 0;
-    pool.increment_count = 0;
-    pool.padding = [0u8; 4];
-    pool.admin = ctx.accounts.admin.key();
-    pool.pool_vault = ctx.accounts.pool_vault.key();
-    pool.pool_mint = ctx.accounts.main_token_mint.key();
-    pool.fee_numerator = fee_numerator;
-    pool.fee_denominator = fee_denominator;
-
-    // 初始化所有质押类型 items 为零值（zero_copy 会自动处理）
-
-    msg!("Staking Pool created: pool: {}, main_token_mint: {}, pool_vault: {}, admin: {}, fee: {}/{}",
+    fee_config.validate()?;
+
+    let capacity = size_tier.capacity();
+    require!(
+        ctx.accounts.pool.as_ref().data_len() == Pool::space_for(capacity),
+        ErrorCode::InvalidAccountSize
+    );
+    let pool = &mut Pool::load_init(&ctx.accounts.pool, capacity)?;
+    pool.initialize(
+        &ctx.accounts.admin.key(),
+        &ctx.accounts.pool_vault.key(),
+        &ctx.accounts.main_token_mint.key(),
+        fee_config,
+        capacity,
+    );
+    pool.fee_vault = ctx.accounts.fee_vault.key();
+    pool.main_token_decimals = ctx.accounts.main_token_mint.decimals;
+
+    // 登记到主币的 pool 注册表
+    let registry = &mut ctx.accounts.pool_registry;
+    if registry.is_uninitialized() {
+        registry.main_token_mint = ctx.accounts.main_token_mint.key();
+        registry.bump = ctx.bumps.pool_registry;
+    }
+    registry.register(&ctx.accounts.pool.key())?;
+
+    msg!("Staking Pool created: pool: {}, main_token_mint: {}, pool_vault: {}, admin: {}, fee_config: {:?}, capacity: {}",
          ctx.accounts.pool.key(),
          ctx.accounts.main_token_mint.key(),
          ctx.accounts.pool_vault.key(),
          ctx.accounts.admin.key(),
-         fee_numerator,
-         fee_denominator);
+         fee_config,
+         capacity);
+    emit!(PoolCreatedEvent {
+        pool: ctx.accounts.pool.key(),
+        admin: ctx.accounts.admin.key(),
+        main_token_mint: ctx.accounts.main_token_mint.key(),
+        pool_vault: ctx.accounts.pool_vault.key(),
+        fee_config,
+    });
     Ok(())
 }
 
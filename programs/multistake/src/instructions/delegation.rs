@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use crate::state::delegation::{DELEGATE_ALL, DELEGATION_SEED};
+use crate::state::{Delegation, Pool};
+use crate::error::ErrorCode;
+
+/// 设置质押代理
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 代理授权 - 不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Delegation::space(),
+        seeds = [DELEGATION_SEED, pool.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    /// 质押记录所有者 - 必须签名，支付租金
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 设置质押代理
+/// delegate: 代理地址，覆盖之前登记的代理
+/// permissions: 权限位（DELEGATE_STAKE = 1 / DELEGATE_UNSTAKE = 2 / DELEGATE_CLAIM = 4）
+///
+/// 代理 stake / unstake 时由 delegation PDA 签名转出主币和销毁 LP，
+/// owner 需另行对主币账户和 LP 账户 approve delegation PDA，approve 的额度即代理可动用的上限
+pub fn set_delegate(
+    ctx: Context<SetDelegate>,
+    delegate: Pubkey,
+    permissions: u8,
+) -> Result<()> {
+    require!(
+        permissions != 0 && permissions & !DELEGATE_ALL == 0,
+        ErrorCode::InvalidDelegatePermissions
+    );
+    require!(
+        delegate != Pubkey::default() && delegate != ctx.accounts.owner.key(),
+        ErrorCode::InvalidDelegatePermissions
+    );
+
+    let delegation = &mut ctx.accounts.delegation;
+    delegation.pool = ctx.accounts.pool.key();
+    delegation.owner = ctx.accounts.owner.key();
+    delegation.delegate = delegate;
+    delegation.permissions = permissions;
+    delegation.bump = ctx.bumps.delegation;
+
+    msg!("Delegate set: pool: {}, owner: {}, delegate: {}, permissions: {}",
+         delegation.pool,
+         delegation.owner,
+         delegate,
+         permissions);
+
+    Ok(())
+}
+
+/// 撤销质押代理
+#[derive(Accounts)]
+pub struct RevokeDelegate<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 代理授权 - 关闭后租金退还给 owner
+    #[account(
+        mut,
+        close = owner,
+        seeds = [DELEGATION_SEED, pool.key().as_ref(), owner.key().as_ref()],
+        bump = delegation.bump,
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    /// 质押记录所有者 - 必须签名
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// 撤销质押代理
+/// token 账户上对 delegation PDA 的 approve 需 owner 另行 revoke
+pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+    msg!("Delegate revoked: pool: {}, owner: {}, delegate: {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.owner.key(),
+         ctx.accounts.delegation.delegate);
+
+    Ok(())
+}
+
+/// 验证 owner 本人签名，或由 delegation 登记的代理签名且拥有 permission 权限位
+/// delegation 账户需由调用方按 ["delegation", pool, owner] 约束
+/// 返回 true 表示代理操作，owner token 账户的转出和销毁需由 delegation PDA 签名
+pub(crate) fn verify_owner_or_delegate(
+    owner: &AccountInfo,
+    delegate: Option<&Signer>,
+    delegation: Option<&Account<Delegation>>,
+    permission: u8,
+) -> Result<bool> {
+    if owner.is_signer {
+        return Ok(false);
+    }
+    let delegate = delegate.ok_or(ErrorCode::DelegateNotAuthorized)?;
+    let delegation = delegation.ok_or(ErrorCode::DelegateNotAuthorized)?;
+    require!(delegation.owner == owner.key(), ErrorCode::DelegateNotAuthorized);
+    delegation.verify_permission(&delegate.key(), permission)?;
+
+    msg!("Delegated operation: owner: {}, delegate: {}, permission: {}",
+         owner.key(),
+         delegate.key(),
+         permission);
+
+    Ok(true)
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TokenInterface};
+use crate::state::Pool;
+use crate::error::ErrorCode;
+use crate::events::DonationEvent;
+use crate::transfer::{received_amount, transfer_main_token, verify_vault_inflow};
+
+/// 向主币 vault 捐赠主币
+#[derive(Accounts)]
+#[instruction(asset_index: u8)]
+pub struct DonateToPool<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 主币资产对应的 vault
+    #[account(
+        mut,
+        address = pool.load()?.get_asset_vault(asset_index)? @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 主币 Mint - Token 或 Token-2022
+    #[account(address = pool_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub main_token_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 捐赠方的主币账户
+    #[account(
+        mut,
+        token::mint = main_token_mint,
+        token::authority = donor,
+    )]
+    pub donor_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    pub donor: Signer<'info>,
+
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
+}
+
+/// 向 pool 捐赠主币（无需权限）
+///
+/// 捐赠立即计入记账余额，不受 max_donation_bps 限制，共享该 vault 的 LP 兑换率随之上升，
+/// 是向质押者分发收益的显式入口；直接转入 vault 的资金仍视为意外转账，按 max_donation_bps 逐步计入
+/// 主币启用 TransferHook 扩展时，hook 需要的额外账户通过 remaining_accounts 传入
+pub fn donate_to_pool<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DonateToPool<'info>>,
+    asset_index: u8,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidTokenCount);
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    let decimals = pool.verify_asset_decimals(asset_index, ctx.accounts.main_token_mint.decimals)?;
+    // 没有 LP 时捐赠会被下一个质押者独占
+    require!(pool.has_outstanding_lp(asset_index), ErrorCode::NoOutstandingLp);
+
+    let vault_balance = ctx.accounts.pool_vault.amount;
+    let effective_balance = pool.effective_vault_balance(asset_index, vault_balance)?;
+    let received = received_amount(&ctx.accounts.main_token_mint.to_account_info(), amount)?;
+
+    // 先更新记账余额，再转账
+    let tracked_balance = effective_balance
+        .checked_add(received)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.set_tracked_balance(asset_index, tracked_balance)?;
+
+    transfer_main_token(
+        &ctx.accounts.main_token_program.to_account_info(),
+        &ctx.accounts.donor_token.to_account_info(),
+        &ctx.accounts.main_token_mint.to_account_info(),
+        &ctx.accounts.pool_vault.to_account_info(),
+        &ctx.accounts.donor.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        decimals,
+        &[],
+    )?;
+
+    ctx.accounts.pool_vault.reload()?;
+    verify_vault_inflow(vault_balance, ctx.accounts.pool_vault.amount, received)?;
+
+    msg!("Donated: pool: {}, donor: {}, asset_index: {}, amount: {}, received: {}, tracked_balance: {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.donor.key(),
+         asset_index,
+         amount,
+         received,
+         tracked_balance);
+    emit!(DonationEvent {
+        pool: ctx.accounts.pool.key(),
+        donor: ctx.accounts.donor.key(),
+        asset_index,
+        amount,
+        received,
+    });
+
+    Ok(())
+}
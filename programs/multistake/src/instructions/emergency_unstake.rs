@@ -0,0 +1,332 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, BurnChecked, Mint, TokenAccount, TokenInterface};
+use crate::state::{Pool, StakeRecord, LP_MINT_DECIMALS, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::transfer::transfer_main_token;
+use super::rate_weight::apply_rate_weights;
+
+/// 创建保险基金 vault
+#[derive(Accounts)]
+pub struct InitInsuranceVault<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - 保险基金 vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 主币 Mint - 必须是 pool 已注册的主币
+    #[account(
+        constraint = pool.load()?.find_asset_index(&asset_mint.key()).is_some()
+            @ ErrorCode::InvalidTokenMint
+    )]
+    pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 保险基金 vault - 每种主币一个
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"insurance_vault", pool.key().as_ref(), asset_mint.key().as_ref()],
+        bump,
+        token::mint = asset_mint,
+        token::authority = pool_authority,
+        token::token_program = token_program,
+    )]
+    pub insurance_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 创建保险基金 vault
+pub fn init_insurance_vault(ctx: Context<InitInsuranceVault>) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    msg!("Insurance vault created: pool: {}, mint: {}, vault: {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.asset_mint.key(),
+         ctx.accounts.insurance_vault.key());
+    Ok(())
+}
+
+/// 设置紧急赎回配置
+#[derive(Accounts)]
+pub struct SetEmergencyConfig<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置紧急赎回配置
+/// exit_restricted: 是否处于退出受限状态
+/// penalty_bps: 紧急赎回罚金比例（bps）
+pub fn set_emergency_config(
+    ctx: Context<SetEmergencyConfig>,
+    exit_restricted: bool,
+    penalty_bps: u16,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后必须通过 queue_action 执行
+    pool.verify_direct_admin_action()?;
+
+    pool.set_emergency_config(exit_restricted, penalty_bps)?;
+
+    msg!("Emergency config updated: pool: {}, exit_restricted: {}, penalty_bps: {}",
+         ctx.accounts.pool.key(),
+         exit_restricted,
+         penalty_bps);
+
+    Ok(())
+}
+
+/// 紧急赎回：退出受限期间绕过限制，罚金转入保险基金
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct EmergencyUnstake<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// item 对应的主币 Vault
+    #[account(
+        mut,
+        address = Pool::load_versioned(&pool)?.get_item_vault(item_index as usize)? @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 主币 Mint - Token 或 Token-2022
+    #[account(address = pool_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub main_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 保险基金 vault - 与 pool_vault 使用相同主币
+    #[account(
+        mut,
+        seeds = [b"insurance_vault", pool.key().as_ref(), pool_vault.mint.as_ref()],
+        bump,
+    )]
+    pub insurance_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// LP mint - 对应的质押类型（Token 或 Token-2022）
+    #[account(
+        mut,
+        address = Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)? @ ErrorCode::InvalidTokenMint,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 用户的 LP 凭证账户（销毁来源）
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 用户的主币账户（赎回目标）
+    #[account(
+        mut,
+        token::mint = main_token_mint,
+        token::authority = user,
+    )]
+    pub user_main_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 用户签名，质押记录不存在时支付租金
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// 用户的质押记录 - 不存在时创建（如通过转账获得的 LP），结算积分后扣减 staked_amount
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeRecord::space(),
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            lp_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// LP mint 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// 紧急赎回
+/// item_index: 质押类型索引
+/// lp_amount: 要销毁的 LP 凭证数量
+///
+/// 逻辑：
+/// 1. 只有退出受限时可用
+/// 2. 按普通赎回公式计算 redeem_amount，扣除 emergency_penalty_bps 的罚金
+/// 3. 罚金转入保险基金，剩余转给用户（不再收取普通手续费）
+///
+/// remaining_accounts: 同一主币下每个配置了兑换率来源的 item 一组 [item_rate_source, source]，
+/// 之后为 TransferHook 额外账户
+pub fn emergency_unstake<'info>(
+    ctx: Context<'_, '_, 'info, 'info, EmergencyUnstake<'info>>,
+    item_index: u16,
+    lp_amount: u64,
+) -> Result<()> {
+    require!(lp_amount > 0, ErrorCode::InvalidTokenCount);
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    pool.verify_unstake_allowed()?;
+    require!(pool.is_exit_restricted(), ErrorCode::ExitNotRestricted);
+
+    // 结算奖励排放（LP 数量变化前），再按衰减计划更新 weight
+    let now = Clock::get()?.unix_timestamp;
+    pool.accrue_emission(now)?;
+    pool.apply_weight_schedules(item_index as usize, now)?;
+    // 按兑换率来源刷新 weight，之后的 remaining_accounts 为 TransferHook 额外账户
+    let hook_accounts = apply_rate_weights(
+        pool,
+        &ctx.accounts.pool.key(),
+        item_index as usize,
+        ctx.remaining_accounts,
+        &Clock::get()?,
+    )?;
+
+    // LP mint 地址已由账户约束验证
+    let item = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    let asset_index = item.get_main_asset_index();
+    let pool_vault_balance = ctx.accounts.pool_vault.amount;
+    let effective_balance = pool.effective_vault_balance(asset_index, pool_vault_balance)?;
+    let redeem_amount = pool.calculate_redeem_amount(
+        lp_amount,
+        item_index as usize,
+        pool_vault_balance,
+    )?;
+    // vault 中待领取的冷却赎回不可用于紧急赎回
+    require!(
+        pool_vault_balance.saturating_sub(pool.get_pending_unstake(asset_index)?) >= redeem_amount,
+        ErrorCode::InsufficientLiquidity
+    );
+
+    let (penalty_amount, amount_after_penalty) = pool.calculate_emergency_penalty(redeem_amount)?;
+    let decimals = pool.verify_asset_decimals(asset_index, ctx.accounts.main_token_mint.decimals)?;
+
+    // 1. 销毁用户的 LP 凭证
+    token_interface::burn_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            BurnChecked {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.user_lp_token.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        lp_amount,
+        LP_MINT_DECIMALS,
+    )?;
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    // 2. 罚金转入保险基金
+    if penalty_amount > 0 {
+        transfer_main_token(
+            &ctx.accounts.main_token_program.to_account_info(),
+            &ctx.accounts.pool_vault.to_account_info(),
+            &ctx.accounts.main_token_mint.to_account_info(),
+            &ctx.accounts.insurance_vault.to_account_info(),
+            &ctx.accounts.pool_authority,
+            hook_accounts,
+            penalty_amount,
+            decimals,
+            signer,
+        )?;
+    }
+
+    // 3. 剩余主币转给用户
+    transfer_main_token(
+        &ctx.accounts.main_token_program.to_account_info(),
+        &ctx.accounts.pool_vault.to_account_info(),
+        &ctx.accounts.main_token_mint.to_account_info(),
+        &ctx.accounts.user_main_token.to_account_info(),
+        &ctx.accounts.pool_authority,
+        hook_accounts,
+        amount_after_penalty,
+        decimals,
+        signer,
+    )?;
+
+    // 4. 更新 item 的 mint_amount 和质押记录
+    pool.sub_item_mint_amount(item_index as usize, lp_amount)?;
+    let item_mut = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+
+    let record = &mut ctx.accounts.stake_record;
+    if record.is_uninitialized() {
+        record.initialize(&pool_key, &ctx.accounts.user.key(), item_mut.mint_pubkey(), ctx.bumps.stake_record, Clock::get()?.slot);
+    }
+    record.accrue_points(item_mut.get_weight(), Clock::get()?.slot)?;
+    record.settle_rewards(item_mut.get_reward_per_share())?;
+    record.sub_staked_amount(lp_amount);
+
+    // 5. 更新 vault 记账余额（罚金转出 vault，一并扣除）
+    let tracked_balance = effective_balance
+        .checked_sub(redeem_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.set_tracked_balance(asset_index, tracked_balance)?;
+    pool.verify_min_vault_balance(asset_index)?;
+
+    msg!("Emergency unstaked: user: {}, item_index: {}, lp_burned: {}, redeem_amount: {}, penalty: {}, amount_after_penalty: {}",
+         ctx.accounts.user.key(),
+         item_index,
+         lp_amount,
+         redeem_amount,
+         penalty_amount,
+         amount_after_penalty);
+
+    Ok(())
+}
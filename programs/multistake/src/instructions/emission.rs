@@ -0,0 +1,481 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, MintToChecked, TokenInterface};
+use crate::state::delegation::{DELEGATE_CLAIM, DELEGATION_SEED};
+use crate::state::{Delegation, Pool, StakeRecord, LP_MINT_DECIMALS, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::events::RewardsCompoundedEvent;
+use crate::transfer::transfer_main_token;
+use super::delegation::verify_owner_or_delegate;
+use super::harvest::account_compound;
+use super::rate_weight::apply_rate_weights;
+
+/// 启用奖励排放，创建排放 vault
+#[derive(Accounts)]
+pub struct InitRewardEmission<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - 排放 vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 奖励币 Mint - 可以是任意 SPL Token
+    pub reward_mint: Box<Account<'info, Mint>>,
+
+    /// 排放 vault
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"emission_vault", pool.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = pool_authority
+    )]
+    pub emission_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 启用奖励排放
+/// emission_rate: 每秒排放的奖励数量（最小单位）
+pub fn init_reward_emission(ctx: Context<InitRewardEmission>, emission_rate: u64) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    require!(!pool.has_emission(), ErrorCode::InvalidEmissionConfig);
+
+    pool.emission_reward_mint = ctx.accounts.reward_mint.key();
+    pool.emission_reward_vault = ctx.accounts.emission_vault.key();
+    pool.emission_rate = emission_rate;
+    pool.emission_last_update_ts = Clock::get()?.unix_timestamp;
+
+    msg!("Reward emission enabled: pool: {}, reward_mint: {}, emission_vault: {}, emission_rate: {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.reward_mint.key(),
+         ctx.accounts.emission_vault.key(),
+         emission_rate);
+
+    Ok(())
+}
+
+/// 修改奖励排放速率
+#[derive(Accounts)]
+pub struct SetEmissionRate<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 修改奖励排放速率，之前的排放按旧速率结算
+/// emission_rate: 每秒排放的奖励数量，0 表示暂停排放
+pub fn set_emission_rate(ctx: Context<SetEmissionRate>, emission_rate: u64) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    require!(pool.has_emission(), ErrorCode::InvalidEmissionConfig);
+
+    pool.accrue_emission(Clock::get()?.unix_timestamp)?;
+    let old_rate = pool.emission_rate;
+    pool.emission_rate = emission_rate;
+
+    msg!("Emission rate updated: pool: {}, old: {}, new: {}",
+         ctx.accounts.pool.key(),
+         old_rate,
+         emission_rate);
+
+    Ok(())
+}
+
+/// 向排放 vault 注资
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 排放 vault
+    #[account(
+        mut,
+        address = pool.load()?.emission_reward_vault @ ErrorCode::InvalidEmissionConfig,
+    )]
+    pub emission_vault: Box<Account<'info, TokenAccount>>,
+
+    /// 注资来源账户
+    #[account(mut)]
+    pub funder_token: Box<Account<'info, TokenAccount>>,
+
+    /// 注资方 - 任何人都可以注资
+    pub funder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// 向排放 vault 注资
+/// amount: 注资数量
+pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidTokenCount);
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.funder_token.to_account_info(),
+                to: ctx.accounts.emission_vault.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    msg!("Rewards funded: pool: {}, funder: {}, amount: {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.funder.key(),
+         amount);
+
+    Ok(())
+}
+
+/// 领取排放奖励
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct ClaimRewards<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 排放 vault
+    #[account(
+        mut,
+        address = pool.load()?.emission_reward_vault @ ErrorCode::InvalidEmissionConfig,
+    )]
+    pub emission_vault: Box<Account<'info, TokenAccount>>,
+
+    /// 用户的质押记录 - 排放奖励按记录的 LP 数量结算
+    #[account(
+        mut,
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)?.as_ref(),
+        ],
+        bump = stake_record.bump,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// 用户的奖励币账户
+    #[account(
+        mut,
+        token::mint = emission_vault.mint,
+    )]
+    pub user_reward_token: Box<Account<'info, TokenAccount>>,
+
+    /// 用户 - 本人签名，或由 delegate 持 delegation 代为领取
+    /// CHECK: 在指令中验证签名或 delegation
+    pub user: UncheckedAccount<'info>,
+
+    /// 代理签名（可选）- 用户未签名时必须提供
+    pub delegate: Option<Signer<'info>>,
+
+    /// 用户的代理授权（可选）- 用户未签名时必须提供，且需拥有 claim 权限
+    #[account(
+        seeds = [DELEGATION_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump = delegation.bump,
+    )]
+    pub delegation: Option<Box<Account<'info, Delegation>>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// 领取排放奖励
+/// item_index: 质押类型索引
+///
+/// 逻辑：
+/// 1. 结算 pool 排放和质押记录的应得奖励
+/// 2. 按排放 vault 余额发放，余额不足时未发放部分保留在 rewards_owed 中
+pub fn claim_rewards(ctx: Context<ClaimRewards>, item_index: u16) -> Result<()> {
+    // 用户本人签名，或代理持 claim 权限；代理领取时奖励只能转入用户自己的账户
+    let delegated = verify_owner_or_delegate(
+        &ctx.accounts.user,
+        ctx.accounts.delegate.as_ref(),
+        ctx.accounts.delegation.as_deref(),
+        DELEGATE_CLAIM,
+    )?;
+    if delegated {
+        require!(
+            ctx.accounts.user_reward_token.owner == ctx.accounts.user.key(),
+            ErrorCode::DelegateNotAuthorized
+        );
+    }
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    pool.accrue_emission(Clock::get()?.unix_timestamp)?;
+
+    let reward_per_share = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?
+        .get_reward_per_share();
+
+    let record = &mut ctx.accounts.stake_record;
+    record.settle_rewards(reward_per_share)?;
+
+    let reward = record.rewards_owed.min(ctx.accounts.emission_vault.amount);
+    require!(reward > 0, ErrorCode::NothingToHarvest);
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.emission_vault.to_account_info(),
+                to: ctx.accounts.user_reward_token.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer,
+        ),
+        reward,
+    )?;
+
+    record.rewards_owed -= reward;
+
+    msg!("Rewards claimed: user: {}, item_index: {}, reward: {}, owed: {}",
+         ctx.accounts.user.key(),
+         item_index,
+         reward,
+         record.rewards_owed);
+
+    Ok(())
+}
+
+/// 将排放奖励复投为 LP（任何人都可以调用）
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct CompoundRewards<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 排放 vault - 奖励币必须与 item 的主币一致
+    #[account(
+        mut,
+        address = pool.load()?.emission_reward_vault @ ErrorCode::InvalidEmissionConfig,
+    )]
+    pub emission_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 奖励币 Mint - 与排放 vault 一致
+    #[account(address = emission_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub reward_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 被复投用户的质押记录 - 必须开启自动复投
+    #[account(
+        mut,
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)?.as_ref(),
+        ],
+        bump = stake_record.bump,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// 被复投的用户（无需签名）
+    /// CHECK: 通过 stake_record 的 seeds 绑定
+    pub user: UncheckedAccount<'info>,
+
+    /// item 对应的主币 Vault - 接收复投的奖励
+    #[account(
+        mut,
+        address = Pool::load_versioned(&pool)?.get_item_vault(item_index as usize)? @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// LP mint - 对应的质押类型（Token 或 Token-2022）
+    #[account(
+        mut,
+        address = Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)? @ ErrorCode::InvalidTokenMint,
+        mint::authority = pool_authority,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 用户的 LP 凭证账户 - 接收新铸造的 LP
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_lp_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 调用方 - 任何人都可以调用
+    pub cranker: Signer<'info>,
+
+    /// 奖励币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
+    /// LP mint 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 将排放奖励复投为 LP
+/// item_index: 质押类型索引
+///
+/// 逻辑：
+/// 1. 结算 pool 排放、衰减计划和兑换率 weight，再结算质押记录的应得奖励，记录需开启自动复投（set_auto_compound）
+/// 2. 应得奖励（不超过排放 vault 余额）按 harvest_for 自动复投的规则记账：与 stake 相同的前置检查，
+///    按转入前的兑换率铸造 LP（不收取质押手续费），奖励计入 vault 记账余额
+/// 3. 奖励从排放 vault 转入 pool_vault，铸造 LP 给用户
+///
+/// remaining_accounts: 同一主币下每个配置了兑换率来源的 item 一组 [item_rate_source, source]，
+/// 之后为 TransferHook 额外账户
+pub fn compound_rewards<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CompoundRewards<'info>>,
+    item_index: u16,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 结算奖励排放（复投会改变 LP 数量），再按衰减计划更新 weight
+    let clock = Clock::get()?;
+    pool.accrue_emission(clock.unix_timestamp)?;
+    pool.apply_weight_schedules(item_index as usize, clock.unix_timestamp)?;
+    // 按兑换率来源刷新 weight，之后的 remaining_accounts 为 TransferHook 额外账户
+    let hook_accounts = apply_rate_weights(
+        pool,
+        &ctx.accounts.pool.key(),
+        item_index as usize,
+        ctx.remaining_accounts,
+        &clock,
+    )?;
+
+    let record = &mut ctx.accounts.stake_record;
+    require!(record.is_auto_compound(), ErrorCode::AutoCompoundDisabled);
+
+    let item = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    let weight = item.get_weight();
+    let reward_per_share = item.get_reward_per_share();
+
+    // 1. 结算积分和排放奖励（LP 数量变化前）
+    record.accrue_points(weight, clock.slot)?;
+    record.settle_rewards(reward_per_share)?;
+
+    let reward = record.rewards_owed.min(ctx.accounts.emission_vault.amount);
+    require!(reward > 0, ErrorCode::NothingToHarvest);
+
+    // 2. 先记账，再执行 CPI
+    let pool_key = ctx.accounts.pool.key();
+    let lp_amount = account_compound(
+        pool,
+        &pool_key,
+        item_index,
+        record,
+        &ctx.accounts.reward_mint,
+        &ctx.accounts.pool_vault,
+        reward,
+    )?;
+    record.rewards_owed -= reward;
+
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    // 3. 奖励转入 pool_vault 并铸造 LP
+    transfer_main_token(
+        &ctx.accounts.main_token_program.to_account_info(),
+        &ctx.accounts.emission_vault.to_account_info(),
+        &ctx.accounts.reward_mint.to_account_info(),
+        &ctx.accounts.pool_vault.to_account_info(),
+        &ctx.accounts.pool_authority,
+        hook_accounts,
+        reward,
+        ctx.accounts.reward_mint.decimals,
+        signer,
+    )?;
+    token_interface::mint_to_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintToChecked {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                to: ctx.accounts.user_lp_token.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer,
+        ),
+        lp_amount,
+        LP_MINT_DECIMALS,
+    )?;
+
+    msg!("Rewards compounded: user: {}, item_index: {}, cranker: {}, reward: {}, lp_minted: {}, owed: {}",
+         record.owner,
+         item_index,
+         ctx.accounts.cranker.key(),
+         reward,
+         lp_amount,
+         record.rewards_owed);
+    emit!(RewardsCompoundedEvent {
+        pool: pool_key,
+        user: record.owner,
+        item_index,
+        reward,
+        lp_minted: lp_amount,
+    });
+
+    Ok(())
+}
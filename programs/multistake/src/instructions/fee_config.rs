@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::state::{FeeConfig, Pool, MAX_FEE_UPDATE_DELAY_SECONDS};
+
+/// 修改 Pool 费率
+#[derive(Accounts)]
+pub struct SetFee<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 修改 Pool 费率
+/// fee_config: 新的分路径费率，各路径不超过 MAX_FEE_BPS（10%）
+///
+/// 未上调任何路径费率或未设置时间锁时立即生效；上调费率时记录为待生效费率，
+/// fee_update_delay_seconds 之后通过 apply_fee 生效。新的调用会覆盖待生效费率
+pub fn set_fee(ctx: Context<SetFee>, fee_config: FeeConfig) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后必须通过 queue_action 执行
+    pool.verify_direct_admin_action()?;
+
+    fee_config.validate()?;
+
+    if pool.fee_update_delay_seconds > 0 && pool.is_fee_increase(&fee_config) {
+        let eta = Clock::get()?.unix_timestamp
+            .checked_add(pool.fee_update_delay_seconds)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool.queue_fee(fee_config, eta);
+
+        msg!("Fee update queued: pool: {}, fee_config: {:?}, eta: {}",
+             ctx.accounts.pool.key(),
+             fee_config,
+             eta);
+    } else {
+        pool.set_fee(fee_config);
+        pool.clear_pending_fee();
+
+        msg!("Fee updated: pool: {}, fee_config: {:?}",
+             ctx.accounts.pool.key(),
+             fee_config);
+    }
+
+    Ok(())
+}
+
+/// 应用到期的待生效费率（任何人可调用）
+#[derive(Accounts)]
+pub struct ApplyFee<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+}
+
+/// 应用到期的待生效费率
+pub fn apply_fee(ctx: Context<ApplyFee>) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    pool.apply_pending_fee(Clock::get()?.unix_timestamp)?;
+
+    msg!("Fee updated: pool: {}, fee_config: {:?}",
+         ctx.accounts.pool.key(),
+         pool.get_fee_config());
+
+    Ok(())
+}
+
+/// 迁移旧 pool 的手续费配置（任何人可调用）
+#[derive(Accounts)]
+pub struct MigrateFeeConfig<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+}
+
+/// 将旧版本的单一费率（fee_numerator / fee_denominator）写入为 FeeConfig
+/// 旧费率按 bps 向下取整后作用于所有路径，stake 手续费仍全部归协议。
+/// 未迁移的 pool 在计算手续费时已按相同规则换算，迁移只是持久化结果
+pub fn migrate_fee_config(ctx: Context<MigrateFeeConfig>) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    require!(pool.migrate_legacy_fee(), ErrorCode::InvalidFeeConfig);
+
+    msg!("Fee config migrated: pool: {}, fee_config: {:?}",
+         ctx.accounts.pool.key(),
+         pool.get_fee_config());
+
+    Ok(())
+}
+
+/// 设置费率上调的时间锁
+/// delay_seconds: 上调费率后至少等待的秒数，只能增大，不超过 MAX_FEE_UPDATE_DELAY_SECONDS
+pub fn set_fee_update_delay(ctx: Context<SetFee>, delay_seconds: i64) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    require!(
+        delay_seconds >= pool.fee_update_delay_seconds
+            && delay_seconds <= MAX_FEE_UPDATE_DELAY_SECONDS,
+        ErrorCode::InvalidFeeConfig
+    );
+
+    let old_delay = pool.fee_update_delay_seconds;
+    pool.fee_update_delay_seconds = delay_seconds;
+
+    msg!("Fee update delay updated: pool: {}, old: {}, new: {}",
+         ctx.accounts.pool.key(),
+         old_delay,
+         delay_seconds);
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TokenInterface};
+use crate::state::{Pool, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::transfer::transfer_main_token;
+
+/// 从手续费 vault 提取手续费
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - fee_vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 手续费 vault
+    #[account(
+        mut,
+        address = pool.load()?.fee_vault @ ErrorCode::InvalidFeeConfig,
+    )]
+    pub fee_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 主币 Mint - Token 或 Token-2022
+    #[account(address = fee_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub main_token_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 接收手续费的账户
+    #[account(mut)]
+    pub destination: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
+}
+
+/// 提取手续费
+/// amount: 提取数量，不超过 fee_vault 余额减去尚未领取的推荐返佣
+/// remaining_accounts: 主币 mint 启用 TransferHook 时，hook 需要的额外账户
+pub fn withdraw_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawFees<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidTokenCount);
+
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    require!(
+        amount <= pool.withdrawable_fees(ctx.accounts.fee_vault.amount),
+        ErrorCode::InsufficientLiquidity
+    );
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    transfer_main_token(
+        &ctx.accounts.main_token_program.to_account_info(),
+        &ctx.accounts.fee_vault.to_account_info(),
+        &ctx.accounts.main_token_mint.to_account_info(),
+        &ctx.accounts.destination.to_account_info(),
+        &ctx.accounts.pool_authority.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.main_token_mint.decimals,
+        signer,
+    )?;
+
+    msg!("Fees withdrawn: pool: {}, destination: {}, amount: {}, accumulated_fees: {}",
+         pool_key,
+         ctx.accounts.destination.key(),
+         amount,
+         pool.accumulated_fees);
+
+    Ok(())
+}
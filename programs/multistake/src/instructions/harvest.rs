@@ -0,0 +1,473 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{self, MintToChecked, TokenInterface};
+use crate::state::{Pool, PoolRefMut, RewardConfig, StakeRecord, LP_MINT_DECIMALS, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::events::RewardsCompoundedEvent;
+use crate::transfer::{received_amount, transfer_main_token};
+use super::rate_weight::apply_rate_weights;
+use super::stake::reconcile_vault_balance;
+
+/// 创建 Pool 的积分奖励配置及奖励 vault
+#[derive(Accounts)]
+pub struct InitRewardConfig<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - 奖励 vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 奖励主币 Mint - 与 pool 主币一致，便于复投
+    #[account(address = pool.load()?.pool_mint @ ErrorCode::InvalidTokenMint)]
+    pub reward_mint: Box<Account<'info, Mint>>,
+
+    /// 奖励配置 PDA
+    #[account(
+        init,
+        payer = payer,
+        space = RewardConfig::space(),
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    /// 奖励 vault
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"reward_vault", pool.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = pool_authority
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 创建积分奖励配置
+/// reward_rate: 每 REWARD_RATE_SCALE 积分兑换的主币数量（最小单位）
+/// keeper_fee_bps: keeper 代为收割时的抽成比例
+pub fn init_reward_config(
+    ctx: Context<InitRewardConfig>,
+    reward_rate: u64,
+    keeper_fee_bps: u16,
+) -> Result<()> {
+    RewardConfig::validate(keeper_fee_bps)?;
+
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let config = &mut ctx.accounts.reward_config;
+    config.pool = ctx.accounts.pool.key();
+    config.reward_vault = ctx.accounts.reward_vault.key();
+    config.reward_rate = reward_rate;
+    config.keeper_fee_bps = keeper_fee_bps;
+    config.bump = ctx.bumps.reward_config;
+
+    msg!("Reward config created: pool: {}, reward_vault: {}, reward_rate: {}, keeper_fee_bps: {}",
+         config.pool,
+         config.reward_vault,
+         reward_rate,
+         keeper_fee_bps);
+
+    Ok(())
+}
+
+/// 修改积分奖励配置
+#[derive(Accounts)]
+pub struct SetRewardConfig<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 奖励配置
+    #[account(
+        mut,
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump = reward_config.bump,
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 修改积分奖励配置
+pub fn set_reward_config(
+    ctx: Context<SetRewardConfig>,
+    reward_rate: u64,
+    keeper_fee_bps: u16,
+) -> Result<()> {
+    RewardConfig::validate(keeper_fee_bps)?;
+
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let config = &mut ctx.accounts.reward_config;
+    config.reward_rate = reward_rate;
+    config.keeper_fee_bps = keeper_fee_bps;
+
+    msg!("Reward config updated: pool: {}, reward_rate: {}, keeper_fee_bps: {}",
+         config.pool,
+         reward_rate,
+         keeper_fee_bps);
+
+    Ok(())
+}
+
+/// 设置质押记录的自动复投偏好
+#[derive(Accounts)]
+pub struct SetAutoCompound<'info> {
+    /// 质押记录
+    #[account(mut, has_one = owner)]
+    pub stake_record: Account<'info, StakeRecord>,
+
+    /// 记录所有者
+    pub owner: Signer<'info>,
+}
+
+/// 设置自动复投偏好
+/// enabled: true 时 keeper 收割的奖励复投为 LP，false 时直接转给用户
+pub fn set_auto_compound(ctx: Context<SetAutoCompound>, enabled: bool) -> Result<()> {
+    let record = &mut ctx.accounts.stake_record;
+    record.auto_compound = enabled as u8;
+
+    msg!("Auto compound updated: owner: {}, lp_mint: {}, enabled: {}",
+         record.owner,
+         record.lp_mint,
+         enabled);
+
+    Ok(())
+}
+
+/// keeper 代用户收割积分奖励
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct HarvestFor<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 奖励配置
+    #[account(
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump = reward_config.bump,
+        has_one = reward_vault,
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    /// 奖励 vault
+    #[account(mut)]
+    pub reward_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 奖励币 Mint - 与奖励 vault 一致
+    #[account(address = reward_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub reward_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 被收割用户的质押记录
+    #[account(
+        mut,
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)?.as_ref(),
+        ],
+        bump = stake_record.bump,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// 被收割的用户（无需签名）
+    /// CHECK: 通过 stake_record 的 seeds 绑定
+    pub user: UncheckedAccount<'info>,
+
+    /// item 对应的主币 Vault - 自动复投时接收奖励
+    #[account(
+        mut,
+        address = Pool::load_versioned(&pool)?.get_item_vault(item_index as usize)? @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// LP mint - 对应的质押类型（Token 或 Token-2022）
+    #[account(
+        mut,
+        address = Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)? @ ErrorCode::InvalidTokenMint,
+        mint::authority = pool_authority,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 用户的 LP 凭证账户 - 自动复投时接收新铸造的 LP
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_lp_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 用户的主币账户 - 未开启自动复投时接收奖励
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = user,
+    )]
+    pub user_main_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// keeper 的主币账户 - 接收抽成
+    #[account(
+        mut,
+        token::mint = reward_mint,
+    )]
+    pub keeper_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// keeper - 任何人都可以调用
+    pub keeper: Signer<'info>,
+
+    /// 奖励币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
+    /// LP mint 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// keeper 代用户收割积分奖励
+/// item_index: 质押类型索引
+///
+/// 逻辑：
+/// 1. 结算积分，未兑换积分按 reward_rate 折算为奖励（不超过奖励 vault 余额）
+/// 2. 按 keeper_fee_bps 抽成给 keeper
+/// 3. 用户开启自动复投时，剩余奖励转入 pool_vault 并按兑换率铸造 LP（不收取质押手续费）；
+///    否则直接转给用户
+///
+/// 自动复投与 stake 使用相同的前置检查（暂停状态、item 是否接受质押）
+/// remaining_accounts: 同一主币下每个配置了兑换率来源的 item 一组 [item_rate_source, source]，
+/// 之后为 TransferHook 额外账户
+pub fn harvest_for<'info>(
+    ctx: Context<'_, '_, 'info, 'info, HarvestFor<'info>>,
+    item_index: u16,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 结算奖励排放（自动复投会改变 LP 数量），再按衰减计划更新 weight
+    let clock = Clock::get()?;
+    pool.accrue_emission(clock.unix_timestamp)?;
+    pool.apply_weight_schedules(item_index as usize, clock.unix_timestamp)?;
+    // 按兑换率来源刷新 weight，之后的 remaining_accounts 为 TransferHook 额外账户
+    let hook_accounts = apply_rate_weights(
+        pool,
+        &ctx.accounts.pool.key(),
+        item_index as usize,
+        ctx.remaining_accounts,
+        &clock,
+    )?;
+
+    // LP mint 地址已由账户约束验证
+    let item = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    let weight = item.get_weight();
+    let reward_per_share = item.get_reward_per_share();
+
+    // 1. 结算积分并折算奖励
+    let config = &ctx.accounts.reward_config;
+    let record = &mut ctx.accounts.stake_record;
+    record.accrue_points(weight, clock.slot)?;
+    record.settle_rewards(reward_per_share)?;
+
+    let unclaimed_points = record.unclaimed_points();
+    let mut reward = config.points_to_reward(unclaimed_points)?;
+    let mut consumed_points = unclaimed_points;
+    let vault_balance = ctx.accounts.reward_vault.amount;
+    if reward > vault_balance {
+        reward = vault_balance;
+        consumed_points = config.reward_to_points(reward)?.min(unclaimed_points);
+    }
+    require!(reward > 0, ErrorCode::NothingToHarvest);
+
+    let (keeper_fee, user_reward) = config.split_keeper_fee(reward)?;
+    let reward_mint_info = ctx.accounts.reward_mint.to_account_info();
+    let decimals = ctx.accounts.reward_mint.decimals;
+
+    // 先完成全部记账，再执行外部 CPI
+    // 2. 自动复投时按 stake 的规则折算 LP 并更新记账
+    let compounded = record.is_auto_compound();
+    let lp_amount = if compounded {
+        account_compound(
+            pool,
+            &ctx.accounts.pool.key(),
+            item_index,
+            record,
+            &ctx.accounts.reward_mint,
+            &ctx.accounts.pool_vault,
+            user_reward,
+        )?
+    } else {
+        0
+    };
+
+    record.claimed_points = record.claimed_points
+        .checked_add(consumed_points)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    // 3. keeper 抽成
+    if keeper_fee > 0 {
+        transfer_main_token(
+            &ctx.accounts.main_token_program.to_account_info(),
+            &ctx.accounts.reward_vault.to_account_info(),
+            &reward_mint_info,
+            &ctx.accounts.keeper_token.to_account_info(),
+            &ctx.accounts.pool_authority,
+            hook_accounts,
+            keeper_fee,
+            decimals,
+            signer,
+        )?;
+    }
+
+    // 4. 复投或直接发放
+    if compounded {
+        transfer_main_token(
+            &ctx.accounts.main_token_program.to_account_info(),
+            &ctx.accounts.reward_vault.to_account_info(),
+            &reward_mint_info,
+            &ctx.accounts.pool_vault.to_account_info(),
+            &ctx.accounts.pool_authority,
+            hook_accounts,
+            user_reward,
+            decimals,
+            signer,
+        )?;
+        token_interface::mint_to_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintToChecked {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.user_lp_token.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer,
+            ),
+            lp_amount,
+            LP_MINT_DECIMALS,
+        )?;
+    } else {
+        transfer_main_token(
+            &ctx.accounts.main_token_program.to_account_info(),
+            &ctx.accounts.reward_vault.to_account_info(),
+            &reward_mint_info,
+            &ctx.accounts.user_main_token.to_account_info(),
+            &ctx.accounts.pool_authority,
+            hook_accounts,
+            user_reward,
+            decimals,
+            signer,
+        )?;
+    }
+
+    msg!("Harvested: user: {}, item_index: {}, keeper: {}, points: {}, reward: {}, keeper_fee: {}, compounded: {}",
+         ctx.accounts.user.key(),
+         item_index,
+         ctx.accounts.keeper.key(),
+         consumed_points,
+         reward,
+         keeper_fee,
+         compounded);
+    if compounded {
+        emit!(RewardsCompoundedEvent {
+            pool: pool_key,
+            user: ctx.accounts.user.key(),
+            item_index,
+            reward: user_reward,
+            lp_minted: lp_amount,
+        });
+    }
+
+    Ok(())
+}
+
+/// 自动复投的记账：按 stake 的规则把转入 pool_vault 的奖励折算为 LP（不收取质押手续费）
+/// harvest_for 和 compound_rewards 共用，调用方在记账后执行奖励转账和 LP 铸造 CPI
+///
+/// 与 stake 使用相同的前置检查（暂停状态、item 是否接受质押、主币精度），
+/// 主币启用 TransferFee 扩展时按 vault 实际到账数量折算 LP，记账前先与 vault 实际余额对账
+/// 返回铸造的 LP 数量
+pub(crate) fn account_compound(
+    pool: &mut PoolRefMut<'_>,
+    pool_key: &Pubkey,
+    item_index: u16,
+    record: &mut StakeRecord,
+    reward_mint: &InterfaceAccount<token_interface::Mint>,
+    pool_vault: &InterfaceAccount<token_interface::TokenAccount>,
+    amount: u64,
+) -> Result<u64> {
+    pool.verify_stake_allowed()?;
+    let item = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    item.verify_active()?;
+    let asset_index = item.get_main_asset_index();
+    require!(pool_vault.mint == reward_mint.key(), ErrorCode::InvalidTokenMint);
+    pool.verify_asset_decimals(asset_index, reward_mint.decimals)?;
+
+    let pool_vault_balance = pool_vault.amount;
+    let vault_received = received_amount(&reward_mint.to_account_info(), amount)?;
+    let lp_amount = pool.calculate_stake_lp_amount(
+        vault_received,
+        item_index as usize,
+        pool_vault_balance,
+    )?;
+    require!(lp_amount > 0, ErrorCode::InsufficientTokenAmount);
+
+    reconcile_vault_balance(pool, pool_key, asset_index, pool_vault_balance)?;
+    let effective_balance = pool.effective_vault_balance(asset_index, pool_vault_balance)?;
+
+    pool.add_item_mint_amount(item_index as usize, lp_amount)?;
+    record.add_staked_amount(lp_amount)?;
+
+    // 复投转入的奖励计入 vault 记账余额
+    let tracked_balance = effective_balance
+        .checked_add(vault_received)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.set_tracked_balance(asset_index, tracked_balance)?;
+    Ok(lp_amount)
+}
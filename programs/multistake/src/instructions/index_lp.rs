@@ -0,0 +1,373 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, BurnChecked, Mint, MintToChecked, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::state::{Pool, LP_MINT_DECIMALS, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+
+/// Index LP 的精度，与 item LP 保持一致
+pub const INDEX_LP_DECIMALS: u8 = 9;
+
+/// 创建 Index LP mint
+/// Index LP 代表按比例锁定的一篮子 item LP，权限归属于 pool authority
+#[derive(Accounts)]
+pub struct CreateIndexMint<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - Index LP mint 的 authority
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// Index LP mint - 每个 pool 唯一
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"index_mint", pool.key().as_ref()],
+        bump,
+        mint::decimals = INDEX_LP_DECIMALS,
+        mint::authority = pool_authority,
+        mint::token_program = token_program,
+    )]
+    pub index_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Index LP mint 所属的 token 程序，须与 item LP mint 一致
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 创建 Index LP mint
+/// 不受时间锁限制：mint authority 固定为 pool authority，wrap / unwrap 的比例由 pool 状态决定
+pub fn create_index_mint(ctx: Context<CreateIndexMint>) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    msg!("Index LP mint created: pool: {}, index_mint: {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.index_mint.key());
+    Ok(())
+}
+
+/// 为某个 item 创建 Index vault（存放被锁定的 item LP）
+#[derive(Accounts)]
+pub struct InitIndexVault<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - Index vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// item 的 LP mint - 必须是 pool 中已注册的 item
+    #[account(
+        constraint = Pool::load_versioned(&pool)?.find_token_index(&lp_mint.key()).is_some()
+            @ ErrorCode::InvalidTokenMint
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Index vault
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"index_vault", pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = pool_authority,
+        token::token_program = token_program,
+    )]
+    pub index_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 任何人都可以支付创建 vault 的费用
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// LP mint 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 为某个 item 创建 Index vault
+pub fn init_index_vault(ctx: Context<InitIndexVault>) -> Result<()> {
+    msg!("Index vault created: pool: {}, lp_mint: {}, index_vault: {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.lp_mint.key(),
+         ctx.accounts.index_vault.key());
+    Ok(())
+}
+
+/// 包装 / 解包 Index LP 共用的账户
+#[derive(Accounts)]
+pub struct WrapIndex<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// Index LP mint
+    #[account(
+        mut,
+        seeds = [b"index_mint", pool.key().as_ref()],
+        bump,
+        mint::token_program = token_program,
+    )]
+    pub index_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 用户的 Index LP 账户
+    #[account(
+        mut,
+        token::mint = index_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_index_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 用户签名
+    pub user: Signer<'info>,
+
+    /// Index LP 和 item LP 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 包装：锁定一篮子 item LP，铸造 Index LP
+/// index_amount: 要铸造的 Index LP 数量
+/// remaining_accounts: 按 item 顺序排列的 [lp_mint, user_lp_token, index_vault] 账户组（后两个可写）
+///
+/// 篮子比例：
+/// - Index LP 供应为 0 时，按 weight 比例：lp_i = ceil(index_amount × weight_i / Σweight)
+/// - 否则按当前 vault 持仓比例：lp_i = ceil(index_amount × vault_i / supply)
+///   保证新加入者不会稀释已有持有人
+pub fn wrap_index<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WrapIndex<'info>>,
+    index_amount: u64,
+) -> Result<()> {
+    require!(index_amount > 0, ErrorCode::InvalidTokenCount);
+
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    let token_count = pool.get_token_count();
+    require!(
+        ctx.remaining_accounts.len() == token_count * 3,
+        ErrorCode::InvalidTokenCount
+    );
+
+    let supply = ctx.accounts.index_mint.supply;
+    let total_weight = pool.calculate_total_weight()?;
+    let pool_authority_key = ctx.accounts.pool_authority.key();
+    let token_program_key = ctx.accounts.token_program.key();
+
+    // 1. 逐个 item 转入 LP
+    for index in 0..token_count {
+        let item = pool.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        let lp_mint_info = &ctx.remaining_accounts[index * 3];
+        let user_lp_info = &ctx.remaining_accounts[index * 3 + 1];
+        let vault_info = &ctx.remaining_accounts[index * 3 + 2];
+        require!(lp_mint_info.key() == *item.mint_pubkey(), ErrorCode::InvalidLpMint);
+        let vault = load_index_vault(vault_info, item.mint_pubkey(), &pool_authority_key, &token_program_key)?;
+
+        let amount = if supply == 0 {
+            mul_div_ceil(index_amount, item.get_weight() as u128, total_weight)?
+        } else {
+            mul_div_ceil(index_amount, vault.amount as u128, supply as u128)?
+        };
+        if amount == 0 {
+            continue;
+        }
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: user_lp_info.clone(),
+                    mint: lp_mint_info.clone(),
+                    to: vault_info.clone(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+            LP_MINT_DECIMALS,
+        )?;
+    }
+
+    // 2. 铸造 Index LP
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    token_interface::mint_to_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintToChecked {
+                mint: ctx.accounts.index_mint.to_account_info(),
+                to: ctx.accounts.user_index_token.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer,
+        ),
+        index_amount,
+        INDEX_LP_DECIMALS,
+    )?;
+
+    msg!("Index wrapped: user: {}, index_amount: {}, items: {}",
+         ctx.accounts.user.key(),
+         index_amount,
+         token_count);
+
+    Ok(())
+}
+
+/// 解包：销毁 Index LP，按持仓比例取回每个 item 的 LP
+/// index_amount: 要销毁的 Index LP 数量
+/// remaining_accounts: 按 item 顺序排列的 [lp_mint, user_lp_token, index_vault] 账户组（后两个可写）
+///
+/// 取回数量：lp_i = floor(vault_i × index_amount / supply)
+pub fn unwrap_index<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WrapIndex<'info>>,
+    index_amount: u64,
+) -> Result<()> {
+    require!(index_amount > 0, ErrorCode::InvalidTokenCount);
+
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    let token_count = pool.get_token_count();
+    require!(
+        ctx.remaining_accounts.len() == token_count * 3,
+        ErrorCode::InvalidTokenCount
+    );
+
+    let supply = ctx.accounts.index_mint.supply;
+    require!(supply >= index_amount, ErrorCode::InsufficientTokenAmount);
+    let pool_authority_key = ctx.accounts.pool_authority.key();
+    let token_program_key = ctx.accounts.token_program.key();
+
+    // 1. 销毁用户的 Index LP
+    token_interface::burn_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            BurnChecked {
+                mint: ctx.accounts.index_mint.to_account_info(),
+                from: ctx.accounts.user_index_token.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        index_amount,
+        INDEX_LP_DECIMALS,
+    )?;
+
+    // 2. 逐个 item 按比例转出 LP
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    for index in 0..token_count {
+        let item = pool.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        let lp_mint_info = &ctx.remaining_accounts[index * 3];
+        let user_lp_info = &ctx.remaining_accounts[index * 3 + 1];
+        let vault_info = &ctx.remaining_accounts[index * 3 + 2];
+        require!(lp_mint_info.key() == *item.mint_pubkey(), ErrorCode::InvalidLpMint);
+        let vault = load_index_vault(vault_info, item.mint_pubkey(), &pool_authority_key, &token_program_key)?;
+
+        let amount = (vault.amount as u128)
+            .checked_mul(index_amount as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(supply as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let amount = u64::try_from(amount).map_err(|_| ErrorCode::MathOverflow)?;
+        if amount == 0 {
+            continue;
+        }
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: vault_info.clone(),
+                    mint: lp_mint_info.clone(),
+                    to: user_lp_info.clone(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+            LP_MINT_DECIMALS,
+        )?;
+    }
+
+    msg!("Index unwrapped: user: {}, index_amount: {}, items: {}",
+         ctx.accounts.user.key(),
+         index_amount,
+         token_count);
+
+    Ok(())
+}
+
+/// 校验 Index vault：由 LP mint 所属的 token 程序持有，mint 必须是 item 的 LP mint，owner 必须是 pool authority
+fn load_index_vault(
+    info: &AccountInfo,
+    lp_mint: &Pubkey,
+    pool_authority: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<TokenAccount> {
+    require!(info.owner == token_program, ErrorCode::InvalidPoolVault);
+    let vault = TokenAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require!(vault.mint == *lp_mint, ErrorCode::InvalidLpMint);
+    require!(vault.owner == *pool_authority, ErrorCode::InvalidPoolVault);
+    Ok(vault)
+}
+
+/// 计算 ceil(amount × numerator / denominator)
+fn mul_div_ceil(amount: u64, numerator: u128, denominator: u128) -> Result<u64> {
+    require!(denominator > 0, ErrorCode::MathOverflow);
+    let product = (amount as u128)
+        .checked_mul(numerator)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let result = product
+        .checked_add(denominator - 1)
+        .ok_or(ErrorCode::MathOverflow)?
+        / denominator;
+    u64::try_from(result).map_err(|_| ErrorCode::MathOverflow.into())
+}
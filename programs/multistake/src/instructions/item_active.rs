@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+use crate::error::ErrorCode;
+
+/// 启用 / 停用质押类型
+#[derive(Accounts)]
+pub struct SetItemActive<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 启用 / 停用质押类型
+/// item_index: 质押类型索引
+/// active: false 时停用 - stake 和 convert_lp 转入被拒绝，unstake 不受影响，
+/// 用于无法销毁全部 LP 时的软下线
+pub fn set_item_active(ctx: Context<SetItemActive>, item_index: u16, active: bool) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let item = pool.get_token_mut(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    item.set_active(active);
+
+    msg!("Item active updated: pool: {}, item_index: {}, lp_mint: {}, active: {}",
+         ctx.accounts.pool.key(),
+         item_index,
+         item.mint_pubkey(),
+         active);
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+use crate::error::ErrorCode;
+
+/// 设置质押类型的显示名称和符号
+#[derive(Accounts)]
+pub struct SetItemMetadata<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置质押类型的显示名称和符号，UI 无需链下注册表即可标注质押类型
+/// item_index: 质押类型索引
+/// name: 名称，最多 16 字节（UTF-8）
+/// symbol: 符号，最多 8 字节（UTF-8）
+pub fn set_item_metadata(
+    ctx: Context<SetItemMetadata>,
+    item_index: u16,
+    name: String,
+    symbol: String,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let item = pool.get_token_mut(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    item.set_metadata(&name, &symbol)?;
+
+    msg!("Item metadata updated: pool: {}, item_index: {}, lp_mint: {}, name: {}, symbol: {}",
+         ctx.accounts.pool.key(),
+         item_index,
+         item.mint_pubkey(),
+         name,
+         symbol);
+
+    Ok(())
+}
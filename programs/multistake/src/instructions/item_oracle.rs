@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::{ItemOracle, Pool};
+use crate::state::oracle::{DEPEG_ACTION_PENALIZE, BPS_DENOMINATOR};
+use crate::error::ErrorCode;
+
+/// 配置质押类型的 oracle
+#[derive(Accounts)]
+pub struct SetItemOracle<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 质押类型的 LP mint
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 质押类型的 oracle 配置 PDA
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ItemOracle::space(),
+        seeds = [b"item_oracle", pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump
+    )]
+    pub item_oracle: Account<'info, ItemOracle>,
+
+    /// Pyth PriceUpdateV2 账户
+    /// CHECK: 在 OraclePrice::load_pyth 中校验 owner 和数据布局
+    pub price_feed: UncheckedAccount<'info>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 配置质押类型的 oracle
+/// max_staleness_seconds: 价格最大允许延迟（秒）
+/// max_confidence_bps: 置信区间占价格的最大比例（bps）
+/// depeg_threshold_bps: 脱锚阈值（bps），0 表示不检查
+/// depeg_action: 0 = 拒绝赎回，1 = 按 oracle 价格折价赎回
+pub fn set_item_oracle(
+    ctx: Context<SetItemOracle>,
+    max_staleness_seconds: u64,
+    max_confidence_bps: u16,
+    depeg_threshold_bps: u16,
+    depeg_action: u8,
+) -> Result<()> {
+    require!(
+        max_confidence_bps as u64 <= BPS_DENOMINATOR
+            && depeg_threshold_bps as u64 <= BPS_DENOMINATOR,
+        ErrorCode::InvalidOracleConfig
+    );
+    require!(depeg_action <= DEPEG_ACTION_PENALIZE, ErrorCode::InvalidOracleConfig);
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后不能直接修改 oracle 配置
+    pool.verify_direct_admin_action()?;
+
+    let lp_mint_key = ctx.accounts.lp_mint.key();
+    let token_index = pool.find_token_index(&lp_mint_key)
+        .ok_or(ErrorCode::InvalidTokenMint)?;
+
+    let oracle = &mut ctx.accounts.item_oracle;
+    oracle.pool = ctx.accounts.pool.key();
+    oracle.lp_mint = lp_mint_key;
+    oracle.price_feed = ctx.accounts.price_feed.key();
+    oracle.max_staleness_seconds = max_staleness_seconds;
+    oracle.max_confidence_bps = max_confidence_bps;
+    oracle.depeg_threshold_bps = depeg_threshold_bps;
+    oracle.depeg_action = depeg_action;
+    oracle.bump = ctx.bumps.item_oracle;
+
+    // 配置时读取一次价格，确保 price_feed 可用
+    oracle.read_scaled_price(&ctx.accounts.price_feed, Clock::get()?.unix_timestamp)?;
+
+    let item = pool.get_token_mut(token_index)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    // weight 已由兑换率来源决定的 item 不能再配置 oracle
+    require!(!item.has_rate_weight(), ErrorCode::InvalidOracleConfig);
+    item.oracle_enabled = 1;
+
+    msg!("Item oracle set: lp_mint: {}, price_feed: {}, staleness: {}, confidence_bps: {}, depeg_bps: {}, action: {}",
+         lp_mint_key,
+         oracle.price_feed,
+         max_staleness_seconds,
+         max_confidence_bps,
+         depeg_threshold_bps,
+         depeg_action);
+
+    Ok(())
+}
+
+/// 移除质押类型的 oracle 配置
+#[derive(Accounts)]
+pub struct RemoveItemOracle<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 质押类型的 oracle 配置 PDA - 关闭后租金退还给 admin
+    #[account(
+        mut,
+        close = admin,
+        has_one = pool,
+        seeds = [b"item_oracle", pool.key().as_ref(), item_oracle.lp_mint.as_ref()],
+        bump = item_oracle.bump
+    )]
+    pub item_oracle: Account<'info, ItemOracle>,
+
+    /// Pool 管理员 - 必须签名
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+/// 移除质押类型的 oracle 配置
+pub fn remove_item_oracle(ctx: Context<RemoveItemOracle>) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后不能直接修改 oracle 配置
+    pool.verify_direct_admin_action()?;
+
+    let lp_mint_key = ctx.accounts.item_oracle.lp_mint;
+    if let Some(token_index) = pool.find_token_index(&lp_mint_key) {
+        let item = pool.get_token_mut(token_index)
+            .ok_or(ErrorCode::InvalidTokenIndex)?;
+        item.oracle_enabled = 0;
+    }
+
+    msg!("Item oracle removed: lp_mint: {}", lp_mint_key);
+    Ok(())
+}
@@ -0,0 +1,640 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, MintToChecked, TokenInterface};
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+use crate::state::{ItemOracle, LockedPosition, Pool, StakeRecord, LP_MINT_DECIMALS, POOL_AUTHORITY_SEED};
+use crate::state::locked_position::lock_boost_bps;
+use crate::error::ErrorCode;
+use super::rate_weight::apply_rate_weights;
+#[cfg(feature = "invariant-checks")]
+use crate::state::invariant::check_pool_invariants;
+use crate::events::{LockedStakeEvent, LockedUnstakeEvent};
+use crate::transfer::{received_amount, transfer_main_token, verify_vault_inflow, verify_vault_outflow};
+use super::stake::reconcile_vault_balance;
+
+/// 锁仓质押主币
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct StakeLocked<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - LP mint 的 authority（fee_in_lp 时铸造 treasury LP）
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// item 对应的主币 Vault（单资产模式下即 pool_vault）
+    #[account(
+        mut,
+        address = Pool::load_versioned(&pool)?.get_item_vault(item_index as usize)? @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 主币 Mint - Token 或 Token-2022
+    #[account(address = pool_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub main_token_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// LP mint - 对应的质押类型，锁仓本身不铸造 LP，仅在 fee_in_lp 时铸造给 treasury
+    #[account(
+        mut,
+        address = Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)? @ ErrorCode::InvalidTokenMint,
+        mint::authority = pool_authority,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 用户的主币账户（质押来源）
+    #[account(
+        mut,
+        token::mint = main_token_mint,
+        token::authority = user,
+    )]
+    pub user_main_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 用户签名，创建锁仓头寸和质押记录时支付租金
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// 锁仓头寸 - 不存在时创建，已存在时追加
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = LockedPosition::space(),
+        seeds = [
+            b"locked_position",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            &item_index.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub locked_position: Box<Account<'info, LockedPosition>>,
+
+    /// 用户的质押记录 - 按含加成的有效数量累积积分和排放奖励
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeRecord::space(),
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            lp_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// treasury LP 账户（可选）- pool 启用 fee_in_lp 时必须传入，接收手续费对应的 LP
+    #[account(
+        mut,
+        seeds = [b"treasury_lp", pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury_lp_token: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 手续费 vault - pool 已创建 fee_vault 且手续费不以 LP 形式收取时必须传入
+    #[account(
+        mut,
+        address = pool.load()?.fee_vault @ ErrorCode::InvalidFeeConfig,
+    )]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// Instructions sysvar - pool 启用调用方白名单时用于识别 CPI 调用方
+    /// CHECK: 地址约束为 instructions sysvar
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    /// LP 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 锁仓质押主币
+/// item_index: 质押类型索引
+/// stake_amount: 质押的主币数量
+/// lock_seconds: 锁定时长，按 LOCK_TIERS 取满足的最高档位加成
+/// whitelist_proof: 许可池中 user 的白名单 merkle 证明，非许可池传空
+/// remaining_accounts:
+/// - 前 2 × N 个：同一主币下每个配置了兑换率来源的 item 一组 [item_rate_source, source]，N 为这类 item 的数量
+/// - 之后：主币 mint 启用 TransferHook 时，hook 需要的额外账户
+///
+/// 逻辑：
+/// 1. 与 stake 相同收取手续费并按兑换率折算 LP 数量，但不铸造 LP 代币，记入 LockedPosition
+/// 2. 按档位计算加成数量，lp_amount + boost_amount 一起计入 item 的 mint_amount，
+///    锁仓头寸在积分、排放奖励和赎回中按加成后的有效数量参与分配
+/// 3. 质押记录按有效数量增加 staked_amount
+/// 4. 解锁时间取已有头寸和本次锁定期中较晚者
+pub fn stake_locked<'info>(
+    ctx: Context<'_, '_, 'info, 'info, StakeLocked<'info>>,
+    item_index: u16,
+    stake_amount: u64,
+    lock_seconds: i64,
+    whitelist_proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    require!(stake_amount > 0, ErrorCode::InvalidTokenCount);
+    let boost_bps = lock_boost_bps(lock_seconds).ok_or(ErrorCode::InvalidLockDuration)?;
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 暂停状态检查
+    pool.verify_stake_allowed()?;
+    pool.verify_min_stake_amount(stake_amount)?;
+
+    // 验证调用方策略
+    pool.caller_allowlist.verify_caller(
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+    )?;
+
+    // 许可池白名单检查
+    pool.verify_whitelisted(&ctx.accounts.user.key(), &whitelist_proof)?;
+
+    // 结算奖励排放（LP 数量变化前），再按衰减计划更新 weight
+    let clock = Clock::get()?;
+    pool.accrue_emission(clock.unix_timestamp)?;
+    pool.apply_weight_schedules(item_index as usize, clock.unix_timestamp)?;
+    // 按兑换率来源刷新 weight，之后的 remaining_accounts 为 TransferHook 额外账户
+    let hook_accounts = apply_rate_weights(
+        pool,
+        &ctx.accounts.pool.key(),
+        item_index as usize,
+        ctx.remaining_accounts,
+        &clock,
+    )?;
+
+    let item = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    // 停用的 item 不接受新的质押
+    item.verify_active()?;
+    let asset_index = item.get_main_asset_index();
+    // 主币精度必须与创建时记录的一致
+    let decimals = pool.verify_asset_decimals(asset_index, ctx.accounts.main_token_mint.decimals)?;
+    let pool_vault_balance = ctx.accounts.pool_vault.amount;
+    let effective_balance = pool.effective_vault_balance(asset_index, pool_vault_balance)?;
+
+    // 计算手续费，协议部分转入 fee_vault 或以 LP 形式铸造给 treasury
+    let (fee_amount, amount_after_fee) = pool.calculate_stake_fee(stake_amount)?;
+    let (protocol_fee, _) = pool.calculate_protocol_fee(fee_amount)?;
+    let vault_fee = if pool.routes_fee_to_vault(asset_index) { protocol_fee } else { 0 };
+    let vault_amount = stake_amount
+        .checked_sub(vault_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // 主币启用 TransferFee 扩展时 vault 实际到账少于转出数量，差额从 LP 中扣除
+    let main_token_mint_info = ctx.accounts.main_token_mint.to_account_info();
+    let vault_received = received_amount(&main_token_mint_info, vault_amount)?;
+    let transfer_fee = vault_amount
+        .checked_sub(vault_received)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let amount_after_fee = amount_after_fee
+        .checked_sub(transfer_fee)
+        .ok_or(ErrorCode::InsufficientTokenAmount)?;
+    require!(amount_after_fee > 0, ErrorCode::InsufficientTokenAmount);
+
+    // 按存入前的兑换率折算 LP
+    let lp_amount = pool.calculate_stake_lp_amount(amount_after_fee, item_index as usize, pool_vault_balance)?;
+    require!(lp_amount > 0, ErrorCode::InsufficientTokenAmount);
+    let fee_lp_amount = if pool.is_fee_in_lp() && protocol_fee > 0 {
+        require!(ctx.accounts.treasury_lp_token.is_some(), ErrorCode::InvalidFeeConfig);
+        pool.calculate_stake_lp_amount(protocol_fee, item_index as usize, pool_vault_balance)?
+    } else {
+        0
+    };
+
+    // 记账前先与 vault 实际余额对账，记录外部捐赠或短缺
+    let pool_key = ctx.accounts.pool.key();
+    reconcile_vault_balance(pool, &pool_key, asset_index, pool_vault_balance)?;
+
+    // 先完成全部记账，再执行外部 CPI
+    // 1. 记入锁仓头寸，计算加成
+    let unlock_timestamp = clock.unix_timestamp
+        .checked_add(lock_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let position = &mut ctx.accounts.locked_position;
+    if position.is_uninitialized() {
+        position.pool = pool_key;
+        position.owner = ctx.accounts.user.key();
+        position.item_index = item_index;
+        position.bump = ctx.bumps.locked_position;
+    }
+    let boost_amount = position.add_lock(lp_amount, boost_bps, unlock_timestamp)?;
+    let effective_amount = lp_amount
+        .checked_add(boost_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // 2. 更新 item 的 mint_amount（含加成和 treasury LP）
+    pool.add_item_mint_amount(
+        item_index as usize,
+        effective_amount
+            .checked_add(fee_lp_amount)
+            .ok_or(ErrorCode::MathOverflow)?,
+    )?;
+    let item_mut = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+
+    // 3. 结算积分并按有效数量更新质押记录（首次时初始化）
+    let record = &mut ctx.accounts.stake_record;
+    if record.is_uninitialized() {
+        record.initialize(&pool_key, &ctx.accounts.user.key(), item_mut.mint_pubkey(), ctx.bumps.stake_record, clock.slot);
+    }
+    record.accrue_points(item_mut.get_weight(), clock.slot)?;
+    record.settle_rewards(item_mut.get_reward_per_share())?;
+    record.add_staked_amount(effective_amount)?;
+    record.stake_timestamp = clock.unix_timestamp;
+
+    // 4. 记录手续费，更新 vault 记账余额
+    if vault_fee > 0 {
+        require!(ctx.accounts.fee_vault.is_some(), ErrorCode::InvalidFeeConfig);
+        pool.add_accumulated_fees(received_amount(&main_token_mint_info, vault_fee)?)?;
+    }
+    let tracked_balance = effective_balance
+        .checked_add(vault_received)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.set_tracked_balance(asset_index, tracked_balance)?;
+    pool.verify_min_vault_balance(asset_index)?;
+
+    // 5. 用户转移主币到 pool_vault，协议手续费转入 fee_vault
+    transfer_main_token(
+        &ctx.accounts.main_token_program.to_account_info(),
+        &ctx.accounts.user_main_token.to_account_info(),
+        &ctx.accounts.main_token_mint.to_account_info(),
+        &ctx.accounts.pool_vault.to_account_info(),
+        &ctx.accounts.user.to_account_info(),
+        hook_accounts,
+        vault_amount,
+        decimals,
+        &[],
+    )?;
+
+    if vault_fee > 0 {
+        let fee_vault = ctx.accounts.fee_vault.as_ref()
+            .ok_or(ErrorCode::InvalidFeeConfig)?;
+        transfer_main_token(
+            &ctx.accounts.main_token_program.to_account_info(),
+            &ctx.accounts.user_main_token.to_account_info(),
+            &ctx.accounts.main_token_mint.to_account_info(),
+            &fee_vault.to_account_info(),
+            &ctx.accounts.user.to_account_info(),
+            hook_accounts,
+            vault_fee,
+            decimals,
+            &[],
+        )?;
+    }
+
+    // 6. 手续费以 LP 形式收取：铸造给 treasury
+    if fee_lp_amount > 0 {
+        let treasury_lp_token = ctx.accounts.treasury_lp_token.as_ref()
+            .ok_or(ErrorCode::InvalidFeeConfig)?;
+        let bump = ctx.bumps.pool_authority;
+        let authority_seed = pool.get_authority_seed();
+        let seeds = &[
+            POOL_AUTHORITY_SEED,
+            pool_key.as_ref(),
+            authority_seed,
+            &[bump],
+        ];
+        token_interface::mint_to_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintToChecked {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: treasury_lp_token.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            fee_lp_amount,
+            LP_MINT_DECIMALS,
+        )?;
+    }
+
+    // 7. 重新读取 vault，实际到账必须与记账一致
+    ctx.accounts.pool_vault.reload()?;
+    verify_vault_inflow(pool_vault_balance, ctx.accounts.pool_vault.amount, vault_received)?;
+
+    #[cfg(feature = "invariant-checks")]
+    {
+        check_pool_invariants(pool, asset_index, Some(ctx.accounts.pool_vault.amount))?;
+    }
+
+    let position = &ctx.accounts.locked_position;
+    msg!("Staked locked: user: {}, item_index: {}, amount: {}, fee: {}, lp_amount: {}, boost_amount: {}, unlock_timestamp: {}",
+         ctx.accounts.user.key(),
+         item_index,
+         stake_amount,
+         fee_amount,
+         lp_amount,
+         boost_amount,
+         position.unlock_timestamp);
+    emit!(LockedStakeEvent {
+        pool: pool_key,
+        user: ctx.accounts.user.key(),
+        item_index,
+        amount: stake_amount,
+        fee: fee_amount,
+        lp_amount,
+        boost_amount,
+        unlock_timestamp: position.unlock_timestamp,
+    });
+
+    Ok(())
+}
+
+/// 赎回到期的锁仓头寸
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct UnstakeLocked<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// item 对应的主币 Vault（单资产模式下即 pool_vault）
+    #[account(
+        mut,
+        address = Pool::load_versioned(&pool)?.get_item_vault(item_index as usize)? @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 主币 Mint - Token 或 Token-2022
+    #[account(address = pool_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub main_token_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 用户的主币账户（赎回目标）
+    #[account(
+        mut,
+        token::mint = main_token_mint,
+        token::authority = user,
+    )]
+    pub user_main_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 头寸所有者，头寸关闭后租金退还
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// 锁仓头寸 - 赎回后关闭
+    #[account(
+        mut,
+        close = user,
+        seeds = [
+            b"locked_position",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            &item_index.to_le_bytes(),
+        ],
+        bump = locked_position.bump,
+    )]
+    pub locked_position: Box<Account<'info, LockedPosition>>,
+
+    /// 用户的质押记录 - 结算积分并扣除有效数量
+    #[account(
+        mut,
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)?.as_ref(),
+        ],
+        bump = stake_record.bump,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// 质押类型的 oracle 配置 - item 配置了 oracle 时必须提供
+    #[account(has_one = pool @ ErrorCode::InvalidOracleAccount)]
+    pub item_oracle: Option<Box<Account<'info, ItemOracle>>>,
+
+    /// oracle 价格账户 - item 配置了 oracle 时必须提供
+    /// CHECK: 与 item_oracle.price_feed 比对，并在读取时校验 owner
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// 手续费 vault - pool 已创建 fee_vault 且手续费不以 LP 形式收取时必须传入
+    #[account(
+        mut,
+        address = pool.load()?.fee_vault @ ErrorCode::InvalidFeeConfig,
+    )]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
+}
+
+/// 赎回到期的锁仓头寸
+/// item_index: 质押类型索引
+/// min_redeem_amount: 滑点保护 - 扣除手续费后实际到账的主币数量低于该值时回滚
+/// remaining_accounts:
+/// - 前 2 × N 个：同一主币下每个配置了兑换率来源的 item 一组 [item_rate_source, source]，N 为这类 item 的数量
+/// - 之后：主币 mint 启用 TransferHook 时，hook 需要的额外账户
+///
+/// 逻辑：
+/// 1. 验证锁定期已结束
+/// 2. 按 lp_amount + boost_amount 计算赎回金额，按 unstake_fee_bps 扣除手续费
+/// 3. 从 item 的 mint_amount 和质押记录中扣除有效数量，关闭锁仓头寸
+pub fn unstake_locked<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UnstakeLocked<'info>>,
+    item_index: u16,
+    min_redeem_amount: u64,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 暂停状态检查
+    pool.verify_unstake_allowed()?;
+
+    let clock = Clock::get()?;
+    let position = &ctx.accounts.locked_position;
+    position.verify_unlocked(clock.unix_timestamp)?;
+    let lp_amount = position.lp_amount;
+    let boost_amount = position.boost_amount;
+    let effective_amount = position.effective_amount()?;
+
+    // 结算奖励排放（LP 数量变化前），再按衰减计划更新 weight
+    pool.accrue_emission(clock.unix_timestamp)?;
+    pool.apply_weight_schedules(item_index as usize, clock.unix_timestamp)?;
+    // 按兑换率来源刷新 weight，之后的 remaining_accounts 为 TransferHook 额外账户
+    let hook_accounts = apply_rate_weights(
+        pool,
+        &ctx.accounts.pool.key(),
+        item_index as usize,
+        ctx.remaining_accounts,
+        &clock,
+    )?;
+
+    let item = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    let asset_index = item.get_main_asset_index();
+    // 主币精度必须与创建时记录的一致
+    let decimals = pool.verify_asset_decimals(asset_index, ctx.accounts.main_token_mint.decimals)?;
+    let pool_vault_balance = ctx.accounts.pool_vault.amount;
+    let effective_balance = pool.effective_vault_balance(asset_index, pool_vault_balance)?;
+
+    // 按含加成的有效数量计算赎回金额
+    let redeem_amount = pool.calculate_redeem_amount(
+        effective_amount,
+        item_index as usize,
+        pool_vault_balance,
+    )?;
+
+    // 脱锚保护：oracle 价格偏离 weight 隐含价格超过阈值时拒绝或折价赎回
+    let redeem_amount = if item.has_oracle() {
+        let oracle = ctx.accounts.item_oracle.as_ref()
+            .ok_or(ErrorCode::InvalidOracleAccount)?;
+        require!(
+            oracle.lp_mint == *item.mint_pubkey(),
+            ErrorCode::InvalidOracleAccount
+        );
+        let price_feed = ctx.accounts.price_feed.as_ref()
+            .ok_or(ErrorCode::InvalidOracleAccount)?;
+        oracle.adjust_redeem_amount(
+            price_feed,
+            clock.unix_timestamp,
+            item.get_weight(),
+            redeem_amount,
+        )?
+    } else {
+        redeem_amount
+    };
+    require!(redeem_amount > 0, ErrorCode::DustAmount);
+
+    // vault 中待领取的冷却赎回不可用于即时赎回
+    require!(
+        pool_vault_balance.saturating_sub(pool.get_pending_unstake(asset_index)?) >= redeem_amount,
+        ErrorCode::InsufficientLiquidity
+    );
+
+    // 赎回限速：占用当前窗口额度
+    pool.consume_redemption_capacity(redeem_amount, false, clock.slot)?;
+
+    let (fee_amount, amount_after_fee) = pool.calculate_unstake_fee(redeem_amount)?;
+    let (protocol_fee, _) = pool.calculate_protocol_fee(fee_amount)?;
+    let vault_fee = if pool.routes_fee_to_vault(asset_index) { protocol_fee } else { 0 };
+    require!(
+        received_amount(&ctx.accounts.main_token_mint.to_account_info(), amount_after_fee)?
+            >= min_redeem_amount,
+        ErrorCode::SlippageExceeded
+    );
+
+    // 记账前先与 vault 实际余额对账，记录外部捐赠或短缺
+    let pool_key = ctx.accounts.pool.key();
+    reconcile_vault_balance(pool, &pool_key, asset_index, pool_vault_balance)?;
+
+    // 先完成全部记账，再执行外部 CPI
+    // 1. 扣除 item 的 mint_amount
+    pool.sub_item_mint_amount(item_index as usize, effective_amount)?;
+    let item_mut = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+
+    // 2. 结算积分并更新质押记录
+    let record = &mut ctx.accounts.stake_record;
+    record.accrue_points(item_mut.get_weight(), clock.slot)?;
+    record.settle_rewards(item_mut.get_reward_per_share())?;
+    record.sub_staked_amount(effective_amount);
+
+    // 3. 记录手续费，更新 vault 记账余额
+    if vault_fee > 0 {
+        require!(ctx.accounts.fee_vault.is_some(), ErrorCode::InvalidFeeConfig);
+        pool.add_accumulated_fees(
+            received_amount(&ctx.accounts.main_token_mint.to_account_info(), vault_fee)?,
+        )?;
+    }
+    let tracked_balance = effective_balance
+        .checked_sub(amount_after_fee)
+        .and_then(|balance| balance.checked_sub(vault_fee))
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.set_tracked_balance(asset_index, tracked_balance)?;
+    pool.verify_min_vault_balance(asset_index)?;
+
+    // 4. 从 pool_vault 转移主币给用户，协议手续费转入 fee_vault
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    transfer_main_token(
+        &ctx.accounts.main_token_program.to_account_info(),
+        &ctx.accounts.pool_vault.to_account_info(),
+        &ctx.accounts.main_token_mint.to_account_info(),
+        &ctx.accounts.user_main_token.to_account_info(),
+        &ctx.accounts.pool_authority.to_account_info(),
+        hook_accounts,
+        amount_after_fee,
+        decimals,
+        signer,
+    )?;
+
+    if vault_fee > 0 {
+        let fee_vault = ctx.accounts.fee_vault.as_ref()
+            .ok_or(ErrorCode::InvalidFeeConfig)?;
+        transfer_main_token(
+            &ctx.accounts.main_token_program.to_account_info(),
+            &ctx.accounts.pool_vault.to_account_info(),
+            &ctx.accounts.main_token_mint.to_account_info(),
+            &fee_vault.to_account_info(),
+            &ctx.accounts.pool_authority.to_account_info(),
+            hook_accounts,
+            vault_fee,
+            decimals,
+            signer,
+        )?;
+    }
+
+    // 5. 重新读取 vault，实际转出必须与记账一致
+    ctx.accounts.pool_vault.reload()?;
+    verify_vault_outflow(
+        pool_vault_balance,
+        ctx.accounts.pool_vault.amount,
+        amount_after_fee.checked_add(vault_fee).ok_or(ErrorCode::MathOverflow)?,
+    )?;
+
+    #[cfg(feature = "invariant-checks")]
+    {
+        check_pool_invariants(pool, asset_index, Some(ctx.accounts.pool_vault.amount))?;
+    }
+
+    msg!("Unstaked locked: user: {}, item_index: {}, lp_amount: {}, boost_amount: {}, redeem_amount: {}, fee: {}, amount_after_fee: {}",
+         ctx.accounts.user.key(),
+         item_index,
+         lp_amount,
+         boost_amount,
+         redeem_amount,
+         fee_amount,
+         amount_after_fee);
+    emit!(LockedUnstakeEvent {
+        pool: pool_key,
+        user: ctx.accounts.user.key(),
+        item_index,
+        lp_amount,
+        boost_amount,
+        redeem_amount,
+        fee: fee_amount,
+        amount_after_fee,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,297 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token_interface::Mint;
+use crate::state::{Pool, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+
+/// Metaplex Token Metadata 程序 ID
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Token Metadata 的 UpdateMetadataAccountV2 指令编号
+const UPDATE_METADATA_ACCOUNT_V2: u8 = 15;
+
+/// Token Metadata 的 CreateMetadataAccountV3 指令编号
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+
+/// LP 元数据的可修改字段
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LpMetadataData {
+    /// 名称
+    pub name: String,
+    /// 符号
+    pub symbol: String,
+    /// 元数据 URI
+    pub uri: String,
+}
+
+/// Metaplex DataV2 的 Borsh 布局
+/// LP 不需要 creators / collection / uses，固定为 None（None 的编码与原类型无关）
+#[derive(AnchorSerialize)]
+struct MetadataDataV2 {
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Option<()>,
+    collection: Option<()>,
+    uses: Option<()>,
+}
+
+/// UpdateMetadataAccountV2 参数的 Borsh 布局
+#[derive(AnchorSerialize)]
+struct UpdateMetadataAccountArgsV2 {
+    data: Option<MetadataDataV2>,
+    update_authority: Option<Pubkey>,
+    primary_sale_happened: Option<bool>,
+    is_mutable: Option<bool>,
+}
+
+/// CreateMetadataAccountV3 参数的 Borsh 布局
+#[derive(AnchorSerialize)]
+struct CreateMetadataAccountArgsV3 {
+    data: MetadataDataV2,
+    is_mutable: bool,
+    collection_details: Option<()>,
+}
+
+impl From<&LpMetadataData> for MetadataDataV2 {
+    fn from(data: &LpMetadataData) -> Self {
+        MetadataDataV2 {
+            name: data.name.clone(),
+            symbol: data.symbol.clone(),
+            uri: data.uri.clone(),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        }
+    }
+}
+
+/// 为 LP mint 创建 Metaplex 元数据
+#[derive(Accounts)]
+pub struct CreateLpMetadata<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - LP mint 的 mint authority，同时作为元数据的 update authority
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// LP mint - 必须是 pool 中已注册的 item
+    #[account(
+        constraint = Pool::load_versioned(&pool)?.find_token_index(&lp_mint.key()).is_some()
+            @ ErrorCode::InvalidTokenMint
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// LP mint 的元数据账户（未创建）
+    /// CHECK: PDA ["metadata", token_metadata_program, lp_mint]，由 token metadata 程序创建
+    #[account(
+        mut,
+        seeds = [b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), lp_mint.key().as_ref()],
+        bump,
+        seeds::program = TOKEN_METADATA_PROGRAM_ID,
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    /// 支付元数据账户的租金
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: 地址约束为 Metaplex Token Metadata 程序
+    #[account(address = TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 为 LP mint 创建元数据，钱包可据此显示 LP 的名称 / 符号 / 图标
+/// data: 名称 / 符号 / URI
+///
+/// 元数据的 update authority 为 pool authority PDA，之后通过 update_lp_metadata 修改
+pub fn create_lp_metadata(ctx: Context<CreateLpMetadata>, data: LpMetadataData) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let args = CreateMetadataAccountArgsV3 {
+        data: MetadataDataV2::from(&data),
+        is_mutable: true,
+        collection_details: None,
+    };
+    let mut instruction_data = vec![CREATE_METADATA_ACCOUNT_V3];
+    args.serialize(&mut instruction_data)?;
+
+    let instruction = Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(ctx.accounts.metadata.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.lp_mint.key(), false),
+            // mint authority
+            AccountMeta::new_readonly(ctx.accounts.pool_authority.key(), true),
+            AccountMeta::new(ctx.accounts.payer.key(), true),
+            // update authority
+            AccountMeta::new_readonly(ctx.accounts.pool_authority.key(), true),
+            AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
+        ],
+        data: instruction_data,
+    };
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    invoke_signed(
+        &instruction,
+        &[
+            ctx.accounts.metadata.to_account_info(),
+            ctx.accounts.lp_mint.to_account_info(),
+            ctx.accounts.pool_authority.to_account_info(),
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.token_metadata_program.to_account_info(),
+        ],
+        signer,
+    )?;
+
+    msg!("LP metadata created: pool: {}, lp_mint: {}, name: {}, symbol: {}, uri: {}",
+         pool_key,
+         ctx.accounts.lp_mint.key(),
+         data.name,
+         data.symbol,
+         data.uri);
+
+    Ok(())
+}
+
+/// 更新 LP mint 的 Metaplex 元数据
+#[derive(Accounts)]
+pub struct UpdateLpMetadata<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - 元数据的 update authority
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// LP mint - 必须是 pool 中已注册的 item
+    #[account(
+        constraint = Pool::load_versioned(&pool)?.find_token_index(&lp_mint.key()).is_some()
+            @ ErrorCode::InvalidTokenMint
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// LP mint 的元数据账户
+    /// CHECK: PDA ["metadata", token_metadata_program, lp_mint]，由 token metadata 程序校验内容
+    #[account(
+        mut,
+        seeds = [b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), lp_mint.key().as_ref()],
+        bump,
+        seeds::program = TOKEN_METADATA_PROGRAM_ID,
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    /// CHECK: 地址约束为 Metaplex Token Metadata 程序
+    #[account(address = TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+}
+
+/// 更新 LP mint 的元数据或转移其 update authority
+/// data: 新的名称 / 符号 / URI，None 表示不修改
+/// new_update_authority: 新的 update authority，None 表示保持 pool authority
+///
+/// 元数据的 update authority 为 pool authority PDA，由管理员通过本指令代签；
+/// 转移 update authority 后本指令不再可用于该 LP
+pub fn update_lp_metadata(
+    ctx: Context<UpdateLpMetadata>,
+    data: Option<LpMetadataData>,
+    new_update_authority: Option<Pubkey>,
+) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    require!(
+        data.is_some() || new_update_authority.is_some(),
+        ErrorCode::InvalidTokenCount
+    );
+
+    let args = UpdateMetadataAccountArgsV2 {
+        data: data.as_ref().map(MetadataDataV2::from),
+        update_authority: new_update_authority,
+        primary_sale_happened: None,
+        is_mutable: None,
+    };
+    let mut instruction_data = vec![UPDATE_METADATA_ACCOUNT_V2];
+    args.serialize(&mut instruction_data)?;
+
+    let instruction = Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(ctx.accounts.metadata.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.pool_authority.key(), true),
+        ],
+        data: instruction_data,
+    };
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    invoke_signed(
+        &instruction,
+        &[
+            ctx.accounts.metadata.to_account_info(),
+            ctx.accounts.pool_authority.to_account_info(),
+            ctx.accounts.token_metadata_program.to_account_info(),
+        ],
+        signer,
+    )?;
+
+    msg!("LP metadata updated: pool: {}, lp_mint: {}, data_updated: {}, new_update_authority: {:?}",
+         pool_key,
+         ctx.accounts.lp_mint.key(),
+         data.is_some(),
+         new_update_authority);
+
+    Ok(())
+}
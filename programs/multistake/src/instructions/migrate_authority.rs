@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, spl_token_2022::instruction::AuthorityType, SetAuthority, TokenAccount, TokenInterface,
+};
+use crate::state::{authority_seed_suffix, Pool, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+
+/// 将 pool authority 迁移到下一个 seed 版本
+#[derive(Accounts)]
+pub struct MigratePoolAuthority<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 当前版本的 pool authority PDA
+    /// CHECK: PDA derived from pool key and current seed version
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 下一个版本的 pool authority PDA
+    /// CHECK: PDA derived from pool key and next seed version
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            authority_seed_suffix(
+                pool.load()?.get_authority_seed_version()
+                    .checked_add(1)
+                    .ok_or(ErrorCode::MathOverflow)?
+            ),
+        ],
+        bump
+    )]
+    pub new_pool_authority: AccountInfo<'info>,
+
+    /// Pool 的主币 Vault - owner 将转移给新的 authority
+    #[account(
+        mut,
+        address = pool.load()?.pool_vault @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    /// LP mint 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
+}
+
+/// 将 pool authority 迁移到下一个 seed 版本
+/// remaining_accounts（均可写）:
+/// - 所有 item 的 LP mint，顺序与 pool.tokens 一致
+/// - 之后是所有额外主币的 vault，顺序与 pool.extra_assets 一致（须属于 token_program 或 main_token_program）
+/// - 之后可追加共同管理员签名者
+///
+/// 逻辑：
+/// 1. 将 pool_vault 及额外主币 vault 的 owner 转移给新 authority
+/// 2. 将每个 LP mint 的 mint authority 转移给新 authority
+/// 3. authority_seed_version 递增
+///
+/// 不受时间锁限制：新 authority 是由程序推导的 PDA，管理员无法借此转移资产
+pub fn migrate_pool_authority<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigratePoolAuthority<'info>>,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    // 必须一次性迁移所有 LP mint 和 vault，避免 authority 版本分裂
+    let token_count = pool.get_token_count();
+    let extra_asset_count = pool.get_main_asset_count() - 1;
+    require!(
+        ctx.remaining_accounts.len() >= token_count + extra_asset_count,
+        ErrorCode::InvalidTokenCount
+    );
+    let (lp_mints, rest) = ctx.remaining_accounts.split_at(token_count);
+    let extra_vaults = &rest[..extra_asset_count];
+    for (index, account) in lp_mints.iter().enumerate() {
+        let item = pool.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        require!(
+            account.key() == *item.mint_pubkey(),
+            ErrorCode::InvalidLpMint
+        );
+    }
+    for (index, account) in extra_vaults.iter().enumerate() {
+        require!(
+            account.key() == pool.get_asset_vault(index as u8 + 1)?,
+            ErrorCode::InvalidPoolVault
+        );
+    }
+    require!(
+        ctx.accounts.pool_vault.to_account_info().owner == ctx.accounts.main_token_program.key,
+        ErrorCode::InvalidPoolVault
+    );
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let old_version = pool.get_authority_seed_version();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed_suffix(old_version),
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+    let new_authority = ctx.accounts.new_pool_authority.key();
+
+    // 1. 转移 pool_vault 及额外主币 vault 的 owner
+    token_interface::set_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.main_token_program.to_account_info(),
+            SetAuthority {
+                current_authority: ctx.accounts.pool_authority.to_account_info(),
+                account_or_mint: ctx.accounts.pool_vault.to_account_info(),
+            },
+            signer,
+        ),
+        AuthorityType::AccountOwner,
+        Some(new_authority),
+    )?;
+
+    // 额外主币可能与主币分属不同的 token 程序，按 vault 的 owner 选择
+    let token_programs = [
+        ctx.accounts.main_token_program.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    ];
+    for account in extra_vaults.iter() {
+        let vault_program = token_programs.iter()
+            .find(|program| program.key == account.owner)
+            .ok_or(ErrorCode::InvalidPoolVault)?;
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                vault_program.clone(),
+                SetAuthority {
+                    current_authority: ctx.accounts.pool_authority.to_account_info(),
+                    account_or_mint: account.clone(),
+                },
+                signer,
+            ),
+            AuthorityType::AccountOwner,
+            Some(new_authority),
+        )?;
+    }
+
+    // 2. 转移每个 LP mint 的 mint authority
+    for account in lp_mints.iter() {
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: ctx.accounts.pool_authority.to_account_info(),
+                    account_or_mint: account.clone(),
+                },
+                signer,
+            ),
+            AuthorityType::MintTokens,
+            Some(new_authority),
+        )?;
+    }
+
+    // 3. 递增 seed 版本
+    pool.authority_seed_version = old_version
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    msg!("Pool authority migrated: pool: {}, seed_version: {} -> {}, new_authority: {}, lp_mints: {}",
+         pool_key,
+         old_version,
+         pool.authority_seed_version,
+         new_authority,
+         token_count);
+
+    Ok(())
+}
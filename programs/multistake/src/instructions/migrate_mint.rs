@@ -0,0 +1,258 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::asset::PRIMARY_ASSET_INDEX;
+use crate::state::invariant::check_pool_invariants;
+use crate::state::mint_migration::MINT_MIGRATION_DELAY_SECONDS;
+use crate::state::{MintMigration, Pool, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::transfer::transfer_main_token;
+
+/// 提议主币迁移（开始时间锁）
+#[derive(Accounts)]
+pub struct ProposeMainMintMigration<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - 新 vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 待执行的主币迁移 - 重复提议会覆盖并重置时间锁
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = MintMigration::space(),
+        seeds = [b"mint_migration", pool.key().as_ref()],
+        bump,
+    )]
+    pub mint_migration: Account<'info, MintMigration>,
+
+    /// 新的主币 Mint
+    pub new_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 新的主币 Vault - owner 必须是 pool authority
+    #[account(
+        token::mint = new_mint,
+        token::authority = pool_authority,
+    )]
+    pub new_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool 管理员 - 必须签名，并支付租金
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 提议主币迁移
+/// ratio_numerator / ratio_denominator: 1 个旧主币最小单位兑换的新主币最小单位数量（已包含精度差异）
+/// 记录生效时间 now + MINT_MIGRATION_DELAY_SECONDS
+pub fn propose_main_mint_migration(
+    ctx: Context<ProposeMainMintMigration>,
+    ratio_numerator: u64,
+    ratio_denominator: u64,
+) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let new_mint = ctx.accounts.new_mint.key();
+    require!(
+        new_mint != pool.pool_mint && ratio_numerator > 0 && ratio_denominator > 0,
+        ErrorCode::InvalidMintMigration
+    );
+
+    let eta = Clock::get()?
+        .unix_timestamp
+        .checked_add(MINT_MIGRATION_DELAY_SECONDS)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let migration = &mut ctx.accounts.mint_migration;
+    migration.pool = ctx.accounts.pool.key();
+    migration.new_mint = new_mint;
+    migration.new_vault = ctx.accounts.new_vault.key();
+    migration.ratio_numerator = ratio_numerator;
+    migration.ratio_denominator = ratio_denominator;
+    migration.eta = eta;
+    migration.bump = ctx.bumps.mint_migration;
+
+    msg!("Main mint migration proposed: pool: {}, new_mint: {}, new_vault: {}, ratio: {}/{}, eta: {}",
+         migration.pool,
+         new_mint,
+         migration.new_vault,
+         ratio_numerator,
+         ratio_denominator,
+         eta);
+
+    Ok(())
+}
+
+/// 执行主币迁移
+#[derive(Accounts)]
+pub struct MigrateMainMint<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - 新旧 vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 待执行的主币迁移 - 执行后关闭，租金退还给 admin
+    #[account(
+        mut,
+        has_one = pool,
+        close = admin,
+        seeds = [b"mint_migration", pool.key().as_ref()],
+        bump = mint_migration.bump,
+    )]
+    pub mint_migration: Account<'info, MintMigration>,
+
+    /// 旧的主币 Mint
+    #[account(address = pool.load()?.pool_mint @ ErrorCode::InvalidTokenMint)]
+    pub old_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 旧的主币 Vault - 余额全部转给 old_vault_destination
+    #[account(
+        mut,
+        address = pool.load()?.pool_vault @ ErrorCode::InvalidPoolVault,
+    )]
+    pub old_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 接收旧主币的账户（管理员用于在外部迁移程序中兑换）
+    #[account(
+        mut,
+        token::mint = old_mint,
+        token::token_program = old_token_program,
+    )]
+    pub old_vault_destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 新的主币 Mint
+    #[account(address = mint_migration.new_mint @ ErrorCode::InvalidTokenMint)]
+    pub new_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 新的主币 Vault - 必须已按兑换比例注资
+    #[account(
+        address = mint_migration.new_vault @ ErrorCode::InvalidPoolVault,
+        token::mint = new_mint,
+        token::authority = pool_authority,
+    )]
+    pub new_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 手续费 vault - pool 已创建 fee_vault 时必须传入，且余额必须已全部提取
+    #[account(address = pool.load()?.fee_vault @ ErrorCode::InvalidFeeConfig)]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Pool 管理员 - 必须签名
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// 旧主币所属的 token 程序（Token 或 Token-2022）
+    pub old_token_program: Interface<'info, TokenInterface>,
+}
+
+/// 执行主币迁移
+/// remaining_accounts: 共同管理员签名者；旧主币 mint 启用 TransferHook 时追加 hook 需要的额外账户
+///
+/// 逻辑：
+/// 1. 验证时间锁已到期，且没有以旧主币计价的未结清负债（待领取赎回、推荐返佣、fee_vault 余额、委托质押）
+/// 2. 迁移前按旧 vault 余额检查 pool 不变量，新 vault 余额不得低于旧 vault 余额 × 兑换比例（向上取整）
+/// 3. 记账余额按兑换比例折算，pool_mint / pool_vault / 主币精度切换为新主币，fee_vault 停用（手续费留在 vault 中）
+/// 4. 迁移后按新 vault 余额再次检查 pool 不变量，旧 vault 的余额转给 old_vault_destination
+///
+/// 只迁移主币（资产索引 0），额外主币不受影响；PoolRegistry 仍按旧 mint 登记。
+/// min_stake_amount、min_vault_balance 等以主币数量计的配置不会自动折算，需由管理员按新主币重新设置
+pub fn migrate_main_mint<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigrateMainMint<'info>>,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let migration = &ctx.accounts.mint_migration;
+    migration.verify_ready(Clock::get()?.unix_timestamp)?;
+
+    // 1. 以旧主币计价的负债必须已结清
+    require!(
+        pool.get_pending_unstake(PRIMARY_ASSET_INDEX)? == 0
+            && pool.referral_fees_owed == 0
+            && pool.strategy_delegated_amount == 0,
+        ErrorCode::InvalidMintMigration
+    );
+    if pool.fee_vault != Pubkey::default() {
+        let fee_vault = ctx.accounts.fee_vault.as_ref()
+            .ok_or(ErrorCode::InvalidFeeConfig)?;
+        require!(fee_vault.amount == 0, ErrorCode::InvalidMintMigration);
+    }
+
+    // 2. 迁移前检查不变量，新 vault 必须已按兑换比例注资
+    let old_balance = ctx.accounts.old_vault.amount;
+    check_pool_invariants(pool, PRIMARY_ASSET_INDEX, Some(old_balance))?;
+    let required_balance = migration.required_funding(old_balance)?;
+    let new_balance = ctx.accounts.new_vault.amount;
+    require!(new_balance >= required_balance, ErrorCode::MintMigrationUnderfunded);
+
+    // 3. 先完成记账，再转出旧 vault
+    let old_tracked = pool.get_tracked_balance(PRIMARY_ASSET_INDEX)?;
+    let new_tracked = migration.convert_floor(old_tracked)?;
+    pool.set_tracked_balance(PRIMARY_ASSET_INDEX, new_tracked)?;
+    pool.set_pool_mint(&migration.new_mint);
+    pool.set_pool_vault(&migration.new_vault);
+    pool.main_token_decimals = ctx.accounts.new_mint.decimals;
+    pool.fee_vault = Pubkey::default();
+
+    // 4. 迁移后按新 vault 检查不变量
+    check_pool_invariants(pool, PRIMARY_ASSET_INDEX, Some(new_balance))?;
+
+    if old_balance > 0 {
+        let pool_key = ctx.accounts.pool.key();
+        let bump = ctx.bumps.pool_authority;
+        let authority_seed = pool.get_authority_seed();
+        let seeds = &[
+            POOL_AUTHORITY_SEED,
+            pool_key.as_ref(),
+            authority_seed,
+            &[bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        transfer_main_token(
+            &ctx.accounts.old_token_program.to_account_info(),
+            &ctx.accounts.old_vault.to_account_info(),
+            &ctx.accounts.old_mint.to_account_info(),
+            &ctx.accounts.old_vault_destination.to_account_info(),
+            &ctx.accounts.pool_authority,
+            ctx.remaining_accounts,
+            old_balance,
+            ctx.accounts.old_mint.decimals,
+            signer,
+        )?;
+    }
+
+    msg!("Main mint migrated: pool: {}, old_mint: {}, new_mint: {}, old_balance: {}, new_balance: {}, tracked: {} -> {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.old_mint.key(),
+         migration.new_mint,
+         old_balance,
+         new_balance,
+         old_tracked,
+         new_tracked);
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::state::{Pool, UNVERSIONED_POOL_VERSION};
+use crate::error::ErrorCode;
+
+/// 迁移 Pool 账户布局版本
+#[derive(Accounts)]
+pub struct MigratePoolV2<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    /// Pool 的主币 Vault - 用于初始化 vault 记账余额
+    pub pool_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// 主币 Mint - 用于记录主币精度
+    pub main_token_mint: InterfaceAccount<'info, Mint>,
+}
+
+/// 把最初部署的 pool（v1 布局，无版本字段）迁移到当前布局（v2）
+///
+/// 逻辑：
+/// 1. 账户版本号须为 UNVERSIONED_POOL_VERSION，且大小可容纳已有的 item（不足时先通过 resize_pool 扩容）
+/// 2. 按 Pool::migrate_from_v1 重排头部和 item 数组，item 容量按账户大小计算，写入 POOL_VERSION
+/// 3. 按主币 mint 和 vault 记录精度和记账余额
+///
+/// 以后布局再变更时，POOL_VERSION 递增并新增 migrate_pool_vN：
+/// 只接受版本号为 N - 1 的账户，完成数据搬移后写入 N
+pub fn migrate_pool_v2(ctx: Context<MigratePoolV2>) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+
+    let migrated_items = {
+        let mut data = pool_info.try_borrow_mut_data()?;
+
+        // 未迁移的账户不能 load()，直接读取 admin 字段验证权限
+        let admin = Pool::read_admin(&data)?;
+        require!(
+            ctx.accounts.admin.key() == admin,
+            ErrorCode::InvalidAdmin
+        );
+        require!(
+            Pool::read_version(&data) == UNVERSIONED_POOL_VERSION,
+            ErrorCode::WrongAccountVersion
+        );
+
+        Pool::migrate_from_v1(&mut data)?
+    };
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    require!(
+        ctx.accounts.pool_vault.key() == pool.pool_vault,
+        ErrorCode::InvalidPoolVault
+    );
+    require!(
+        ctx.accounts.main_token_mint.key() == pool.pool_mint,
+        ErrorCode::InvalidTokenMint
+    );
+    pool.main_token_decimals = ctx.accounts.main_token_mint.decimals;
+    pool.tracked_vault_balance = ctx.accounts.pool_vault.amount;
+
+    msg!("Pool migrated: pool: {}, version: {}, items: {}, capacity: {}, decimals: {}, tracked_balance: {}",
+         ctx.accounts.pool.key(),
+         pool.version,
+         migrated_items,
+         pool.capacity,
+         pool.main_token_decimals,
+         pool.tracked_vault_balance);
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::{MintIndex, Pool};
+use crate::error::ErrorCode;
+
+/// 为已存在的 item 补建 mint → index 映射
+#[derive(Accounts)]
+pub struct InitMintIndex<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// item 的 LP mint
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    /// mint → index 映射 PDA
+    #[account(
+        init,
+        payer = payer,
+        space = MintIndex::space(),
+        seeds = [b"mint_index", pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump
+    )]
+    pub mint_index: Account<'info, MintIndex>,
+
+    /// 任何人都可以支付创建费用
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 为已存在的 item 补建 mint → index 映射
+/// 用于映射引入之前添加的 item，只需扫描一次
+pub fn init_mint_index(ctx: Context<InitMintIndex>) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    let lp_mint_key = ctx.accounts.lp_mint.key();
+    let index = pool.find_token_index(&lp_mint_key)
+        .ok_or(ErrorCode::InvalidTokenMint)?;
+
+    let mint_index = &mut ctx.accounts.mint_index;
+    mint_index.pool = ctx.accounts.pool.key();
+    mint_index.lp_mint = lp_mint_key;
+    mint_index.item_index = index as u16;
+    mint_index.bump = ctx.bumps.mint_index;
+
+    msg!("Mint index created: pool: {}, lp_mint: {}, item_index: {}",
+         mint_index.pool,
+         lp_mint_key,
+         index);
+
+    Ok(())
+}
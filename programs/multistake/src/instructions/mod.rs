@@ -4,10 +4,118 @@ pub mod remove_token;
 pub mod modify_weight;
 pub mod stake;
 pub mod unstake;
+pub mod migrate_authority;
+pub mod resize_pool;
+pub mod rotate_vault;
+pub mod index_lp;
+pub mod caller_policy;
+pub mod weight_cooldown;
+pub mod points;
+pub mod multi_asset;
+pub mod item_oracle;
+pub mod emergency_unstake;
+pub mod treasury_lp;
+pub mod preview;
+pub mod referral;
+pub mod harvest;
+pub mod rate_history;
+pub mod weight_oracle;
+pub mod admin_queue;
+pub mod mint_index;
+pub mod convert;
+pub mod rate_guard;
+pub mod position;
+pub mod checkpoint;
+pub mod lp_metadata;
+pub mod stake_strategy;
+pub mod redemption_limit;
+pub mod admin_transfer;
+pub mod pause;
+pub mod unbonding;
+pub mod emission;
+pub mod fee_vault;
+pub mod fee_config;
+pub mod oracle_weights;
+pub mod close_pool;
+pub mod item_active;
+pub mod native_sol;
+pub mod whitelist;
+pub mod total_weighted;
+pub mod pool_registry;
+pub mod donate;
+pub mod weight_history;
+pub mod locked_stake;
+pub mod delegation;
+pub mod rescue;
+pub mod migrate_mint;
+pub mod snapshot;
+pub mod program_config;
+pub mod rescale_weights;
+pub mod pool_stats;
+pub mod weight_schedule;
+pub mod rate_weight;
+pub mod item_metadata;
+pub mod migrate_pool;
+#[cfg(feature = "localnet-bootstrap")]
+pub mod bootstrap;
 
 pub use create_pool::*;
 pub use add_token::*;
 pub use remove_token::*;
 pub use modify_weight::*;
 pub use stake::*;
-pub use unstake::*;
\ No newline at end of file
+pub use unstake::*;
+pub use migrate_authority::*;
+pub use resize_pool::*;
+pub use rotate_vault::*;
+pub use index_lp::*;
+pub use caller_policy::*;
+pub use weight_cooldown::*;
+pub use points::*;
+pub use multi_asset::*;
+pub use item_oracle::*;
+pub use emergency_unstake::*;
+pub use treasury_lp::*;
+pub use preview::*;
+pub use referral::*;
+pub use harvest::*;
+pub use rate_history::*;
+pub use weight_oracle::*;
+pub use admin_queue::*;
+pub use mint_index::*;
+pub use convert::*;
+pub use rate_guard::*;
+pub use position::*;
+pub use checkpoint::*;
+pub use lp_metadata::*;
+pub use stake_strategy::*;
+pub use redemption_limit::*;
+pub use admin_transfer::*;
+pub use pause::*;
+pub use unbonding::*;
+pub use emission::*;
+pub use fee_vault::*;
+pub use fee_config::*;
+pub use oracle_weights::*;
+pub use close_pool::*;
+pub use item_active::*;
+pub use native_sol::*;
+pub use whitelist::*;
+pub use total_weighted::*;
+pub use pool_registry::*;
+pub use donate::*;
+pub use weight_history::*;
+pub use locked_stake::*;
+pub use delegation::*;
+pub use rescue::*;
+pub use migrate_mint::*;
+pub use snapshot::*;
+pub use program_config::*;
+pub use rescale_weights::*;
+pub use pool_stats::*;
+pub use weight_schedule::*;
+pub use rate_weight::*;
+pub use item_metadata::*;
+pub use migrate_pool::*;
+#[cfg(feature = "localnet-bootstrap")]
+pub use bootstrap::*;
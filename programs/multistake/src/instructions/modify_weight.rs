@@ -1,6 +1,11 @@
 use anchor_lang::prelude::*;
-use crate::state::Pool;
+use crate::state::{Pool, WeightChange, WeightHistory, FORCE_WEIGHT_DELAY_SECONDS};
 use crate::error::ErrorCode;
+#[cfg(feature = "invariant-checks")]
+use crate::state::invariant::check_pool_invariants;
+use crate::math;
+use crate::events::WeightModifiedEvent;
+use super::weight_history::record_weight_change;
 
 /// 修改 token 的 weight
 #[derive(Accounts)]
@@ -8,37 +13,259 @@ pub struct ModifyTokenWeight<'info> {
     #[account(mut)]
     pub pool: AccountLoader<'info, Pool>,
 
-    /// Pool 管理员 - 必须签名所有操作
-    /// CHECK: 验证是否为 pool 的管理员
+    /// Pool 管理员 - 必须签名所有操作（modify_token_weight 也可由 weight 操作员签名）
+    /// CHECK: 验证是否为 pool 的管理员或 weight 操作员
     pub admin: Signer<'info>,
+
+    /// weight 修改历史（可选）- pool 启用 weight 历史后修改 weight 时必须传入
+    #[account(
+        mut,
+        seeds = [b"weight_history", pool.key().as_ref()],
+        bump = weight_history.bump,
+    )]
+    pub weight_history: Option<Box<Account<'info, WeightHistory>>>,
 }
 
 /// 修改 token 的 weight
 /// new_weight: 新的权重值
-/// remaining_accounts: token的mint账户列表
+/// remaining_accounts: token的mint账户列表（与 new_weights 一一对应），之后可追加共同管理员签名者
+/// 签名者为 weight 操作员时无需共同管理员签名，冷却期、变化幅度和时间锁限制同样适用
 /// 注意：修改 weight 会影响池的恒定乘积和，需要谨慎操作
 pub fn modify_token_weight(
     ctx: Context<ModifyTokenWeight>,
     new_weights: Vec<u64>,
 ) -> Result<()> {
-    require!(new_weights.len() == ctx.remaining_accounts.len(), ErrorCode::InvalidTokenCount);
-    
-    let pool = &mut ctx.accounts.pool.load_mut()?;
-    // 验证管理员权限
-    pool.verify_admin(&ctx.accounts.admin.key())?;
+    require!(new_weights.len() <= ctx.remaining_accounts.len(), ErrorCode::InvalidTokenCount);
     
-    for (index, account) in  ctx.remaining_accounts.iter().enumerate() {
+    let pool_key = ctx.accounts.pool.key();
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    // 验证管理员或 weight 操作员权限
+    pool.verify_weight_authority(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后必须通过 queue_action 执行
+    pool.verify_direct_admin_action()?;
+
+    // 结算奖励排放（weight 变化前）
+    pool.accrue_emission(Clock::get()?.unix_timestamp)?;
+
+    let current_slot = Clock::get()?.slot;
+    let cooldown_slots = pool.get_weight_update_cooldown_slots();
+    let max_change_bps = pool.get_max_weight_change_bps();
+
+    for (index, account) in ctx.remaining_accounts[..new_weights.len()].iter().enumerate() {
         let mint_key = account.key();
         let token_index = pool.find_token_index(&mint_key)
             .ok_or(ErrorCode::InvalidTokenMint)?;
-        let token = pool.get_token_mut(token_index)
+        let token = pool.get_token(token_index)
             .ok_or(ErrorCode::InvalidTokenIndex)?;
+        // 同一 item 的 weight 修改必须间隔 cooldown_slots
+        token.verify_weight_cooldown(current_slot, cooldown_slots)?;
+        // 单次变化幅度不超过 max_weight_change_bps
+        token.verify_weight_change(new_weights[index], max_change_bps)?;
         let old_weight = token.get_weight();
-        token.set_weight(new_weights[index]);
+        pool.update_item_weight(token_index, new_weights[index], current_slot)?;
+        record_weight_change(pool, ctx.accounts.weight_history.as_mut(), WeightChange {
+            unix_timestamp: Clock::get()?.unix_timestamp,
+            item_index: token_index as u16,
+            old_weight,
+            new_weight: new_weights[index],
+            authority: ctx.accounts.admin.key(),
+        })?;
         msg!("Token weight modified: mint: {}, old_weight: {}, new_weight: {}", 
              mint_key, old_weight, new_weights[index]);
+        emit!(WeightModifiedEvent {
+            pool: pool_key,
+            item_index: token_index as u16,
+            lp_mint: mint_key,
+            old_weight,
+            new_weight: new_weights[index],
+            slot: current_slot,
+        });
+    }
+
+    // 未传入 vault 账户，按记账余额校验各主币
+    #[cfg(feature = "invariant-checks")]
+    for asset_index in 0..pool.get_main_asset_count() {
+        check_pool_invariants(pool, asset_index as u8, None)?;
     }
 
     Ok(())
 }
 
+/// 紧凑编码批量修改 weight
+/// start_index: 第一个被修改的 item 索引
+/// packed_deltas: 从 start_index 开始连续 item 的 weight 增量（zigzag LEB128 编码，见 math::decode_weight_deltas）
+/// 增量为 0 的 item 保持不变，也不受冷却期限制
+/// 相比 Vec<u64> + remaining_accounts，数百个 item 的调整可以放进一笔交易
+pub fn modify_weights_packed(
+    ctx: Context<ModifyTokenWeight>,
+    start_index: u16,
+    packed_deltas: Vec<u8>,
+) -> Result<()> {
+    let deltas = math::decode_weight_deltas(&packed_deltas)
+        .ok_or(ErrorCode::InvalidPackedWeights)?;
+
+    let pool_key = ctx.accounts.pool.key();
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后必须通过 queue_action 执行
+    pool.verify_direct_admin_action()?;
+
+    let start = start_index as usize;
+    require!(
+        start + deltas.len() <= pool.get_token_count(),
+        ErrorCode::InvalidTokenCount
+    );
+
+    // 结算奖励排放（weight 变化前）
+    pool.accrue_emission(Clock::get()?.unix_timestamp)?;
+
+    let current_slot = Clock::get()?.slot;
+    let cooldown_slots = pool.get_weight_update_cooldown_slots();
+    let max_change_bps = pool.get_max_weight_change_bps();
+    let mut updated = 0usize;
+
+    for (offset, delta) in deltas.iter().enumerate() {
+        if *delta == 0 {
+            continue;
+        }
+        let token = pool.get_token(start + offset)
+            .ok_or(ErrorCode::InvalidTokenIndex)?;
+        // 同一 item 的 weight 修改必须间隔 cooldown_slots
+        token.verify_weight_cooldown(current_slot, cooldown_slots)?;
+        let old_weight = token.get_weight();
+        let new_weight = math::apply_weight_delta(old_weight, *delta)
+            .ok_or(ErrorCode::MathOverflow)?;
+        // 单次变化幅度不超过 max_weight_change_bps
+        token.verify_weight_change(new_weight, max_change_bps)?;
+        let lp_mint = *token.mint_pubkey();
+        pool.update_item_weight(start + offset, new_weight, current_slot)?;
+        record_weight_change(pool, ctx.accounts.weight_history.as_mut(), WeightChange {
+            unix_timestamp: Clock::get()?.unix_timestamp,
+            item_index: (start + offset) as u16,
+            old_weight,
+            new_weight,
+            authority: ctx.accounts.admin.key(),
+        })?;
+        emit!(WeightModifiedEvent {
+            pool: pool_key,
+            item_index: (start + offset) as u16,
+            lp_mint,
+            old_weight,
+            new_weight,
+            slot: current_slot,
+        });
+        updated += 1;
+    }
+
+    msg!("Token weights modified (packed): start_index: {}, items: {}, updated: {}",
+         start_index, deltas.len(), updated);
+
+    Ok(())
+}
+
+
+/// 设置单次修改 weight 的最大变化幅度
+/// max_change_bps: 最大变化幅度（bps），必须大于 0；已设置时只能收紧
+/// 超过上限的调整需通过 propose_force_weight / force_modify_weight 走时间锁
+pub fn set_max_weight_change(ctx: Context<ModifyTokenWeight>, max_change_bps: u64) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let old_max = pool.get_max_weight_change_bps();
+    require!(
+        max_change_bps > 0 && (old_max == 0 || max_change_bps <= old_max),
+        ErrorCode::InvalidWeightChangeLimit
+    );
+    pool.max_weight_change_bps = max_change_bps;
+
+    msg!("Max weight change updated: pool: {}, old: {}, new: {}",
+         ctx.accounts.pool.key(), old_max, max_change_bps);
+
+    Ok(())
+}
+
+/// 提议强制修改 weight（不受 max_weight_change_bps 限制）
+/// lp_mint: 质押类型的 LP mint
+/// new_weight: 新的权重值
+/// 记录生效时间 now + FORCE_WEIGHT_DELAY_SECONDS，重复提议会覆盖之前的提议并重置时间锁
+pub fn propose_force_weight(
+    ctx: Context<ModifyTokenWeight>,
+    lp_mint: Pubkey,
+    new_weight: u64,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    require!(pool.find_token_index(&lp_mint).is_some(), ErrorCode::InvalidTokenMint);
+
+    let eta = Clock::get()?.unix_timestamp
+        .checked_add(FORCE_WEIGHT_DELAY_SECONDS)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.pending_force_weight_mint = lp_mint;
+    pool.pending_force_weight = new_weight;
+    pool.pending_force_weight_eta = eta;
+
+    msg!("Force weight proposed: lp_mint: {}, new_weight: {}, eta: {}",
+         lp_mint, new_weight, eta);
+
+    Ok(())
+}
+
+/// 执行到期的强制 weight 修改
+/// 仍遵守 weight 修改冷却期
+pub fn force_modify_weight(ctx: Context<ModifyTokenWeight>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    // 验证时间锁
+    require!(pool.pending_force_weight_eta != 0, ErrorCode::NoPendingAction);
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp >= pool.pending_force_weight_eta,
+        ErrorCode::TimelockNotElapsed
+    );
+
+    let lp_mint = pool.pending_force_weight_mint;
+    let new_weight = pool.pending_force_weight;
+    let token_index = pool.find_token_index(&lp_mint)
+        .ok_or(ErrorCode::InvalidTokenMint)?;
+
+    // 结算奖励排放（weight 变化前）
+    pool.accrue_emission(clock.unix_timestamp)?;
+
+    let cooldown_slots = pool.get_weight_update_cooldown_slots();
+    let token = pool.get_token(token_index)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    token.verify_weight_cooldown(clock.slot, cooldown_slots)?;
+    let old_weight = token.get_weight();
+    pool.update_item_weight(token_index, new_weight, clock.slot)?;
+    record_weight_change(pool, ctx.accounts.weight_history.as_mut(), WeightChange {
+        unix_timestamp: clock.unix_timestamp,
+        item_index: token_index as u16,
+        old_weight,
+        new_weight,
+        authority: ctx.accounts.admin.key(),
+    })?;
+
+    pool.pending_force_weight_mint = Pubkey::default();
+    pool.pending_force_weight = 0;
+    pool.pending_force_weight_eta = 0;
+
+    msg!("Token weight force modified: mint: {}, old_weight: {}, new_weight: {}",
+         lp_mint, old_weight, new_weight);
+    emit!(WeightModifiedEvent {
+        pool: pool_key,
+        item_index: token_index as u16,
+        lp_mint,
+        old_weight,
+        new_weight,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
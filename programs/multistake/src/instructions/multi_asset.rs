@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Pool, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+
+/// 注册额外主币（多资产模式）
+#[derive(Accounts)]
+pub struct AddMainAsset<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - 新 vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 新主币的 Mint - Token 或 Token-2022
+    #[account(mint::token_program = token_program)]
+    pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 新主币的 Vault
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pool_vault", pool.key().as_ref(), asset_mint.key().as_ref()],
+        bump,
+        token::mint = asset_mint,
+        token::authority = pool_authority,
+        token::token_program = token_program,
+    )]
+    pub asset_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// 新主币所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 注册额外主币，创建对应的 vault PDA
+pub fn add_main_asset(ctx: Context<AddMainAsset>) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let asset_mint = ctx.accounts.asset_mint.key();
+    let asset_vault = ctx.accounts.asset_vault.key();
+    let asset_index = pool.add_main_asset(&asset_mint, &asset_vault, ctx.accounts.asset_mint.decimals)?;
+
+    msg!("Main asset added: pool: {}, asset_index: {}, mint: {}, vault: {}",
+         ctx.accounts.pool.key(),
+         asset_index,
+         asset_mint,
+         asset_vault);
+
+    Ok(())
+}
+
+/// 设置质押类型对应的主币
+#[derive(Accounts)]
+pub struct SetItemMainAsset<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置质押类型对应的主币
+/// item_index: 质押类型索引
+/// asset_index: 主币资产索引（0 = pool_mint）
+/// 注意：只能在该类型没有流通 LP 时修改，否则已有 LP 的赎回资产会被改变
+pub fn set_item_main_asset(
+    ctx: Context<SetItemMainAsset>,
+    item_index: u16,
+    asset_index: u8,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    // 验证资产索引有效
+    pool.get_asset_vault(asset_index)?;
+
+    let item = pool.get_token_mut(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    require!(item.get_mint_amount() == 0, ErrorCode::ItemHasOutstandingLp);
+
+    let old_asset_index = item.get_main_asset_index();
+    item.main_asset_index = asset_index;
+
+    msg!("Item main asset updated: item_index: {}, asset_index: {} -> {}",
+         item_index,
+         old_asset_index,
+         asset_index);
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer as SystemTransfer};
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_interface::{self, CloseAccount, SyncNative};
+use crate::error::ErrorCode;
+use super::stake::*;
+use super::unstake::*;
+
+/// 使用原生 SOL 质押（主币为 wSOL 的 pool）
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct StakeSol<'info> {
+    /// 与 stake 相同的账户，user_main_token 为用户的 wSOL 账户
+    pub stake: Stake<'info>,
+}
+
+/// 使用原生 SOL 质押
+/// item_index: 质押类型索引
+/// lamports: 质押的 SOL 数量
+/// tag: 可选的 32 字节标签，只写入日志不存储
+/// referrer: 可选的推荐人，同 stake
+/// whitelist_proof: 许可池的白名单 merkle 证明，同 stake
+///
+/// 逻辑：
+/// 1. 将 lamports 从用户转入其 wSOL 账户并 sync_native
+/// 2. 按 stake 流程质押
+/// 3. 用户 wSOL 账户余额为 0 时关闭，租金退还给用户
+pub fn stake_sol<'info>(
+    ctx: Context<'_, '_, 'info, 'info, StakeSol<'info>>,
+    item_index: u16,
+    lamports: u64,
+    tag: Option<[u8; 32]>,
+    referrer: Option<Pubkey>,
+    whitelist_proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let accounts = &mut ctx.accounts.stake;
+    require!(
+        accounts.main_token_mint.key() == native_mint::ID,
+        ErrorCode::InvalidTokenMint
+    );
+
+    // 1. 包装 SOL
+    system_program::transfer(
+        CpiContext::new(
+            accounts.system_program.to_account_info(),
+            SystemTransfer {
+                from: accounts.user.to_account_info(),
+                to: accounts.user_main_token.to_account_info(),
+            },
+        ),
+        lamports,
+    )?;
+    token_interface::sync_native(CpiContext::new(
+        accounts.main_token_program.to_account_info(),
+        SyncNative {
+            account: accounts.user_main_token.to_account_info(),
+        },
+    ))?;
+
+    // 2. 质押
+    stake(
+        Context::new(ctx.program_id, accounts, ctx.remaining_accounts, ctx.bumps.stake),
+        item_index,
+        lamports,
+        tag,
+        referrer,
+        whitelist_proof,
+    )?;
+
+    // 3. 关闭已用完的 wSOL 账户
+    let accounts = &mut ctx.accounts.stake;
+    accounts.user_main_token.reload()?;
+    if accounts.user_main_token.amount == 0 {
+        token_interface::close_account(CpiContext::new(
+            accounts.main_token_program.to_account_info(),
+            CloseAccount {
+                account: accounts.user_main_token.to_account_info(),
+                destination: accounts.user.to_account_info(),
+                authority: accounts.user.to_account_info(),
+            },
+        ))?;
+    }
+
+    msg!("Staked SOL: user: {}, item_index: {}, lamports: {}",
+         accounts.user.key(),
+         item_index,
+         lamports);
+
+    Ok(())
+}
+
+/// 赎回为原生 SOL（主币为 wSOL 的 pool）
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct UnstakeSol<'info> {
+    /// 与 unstake 相同的账户，user_main_token 为用户的 wSOL 账户
+    pub unstake: Unstake<'info>,
+}
+
+/// 销毁 LP 凭证，赎回为原生 SOL
+/// 参数与 unstake 相同
+///
+/// 逻辑：
+/// 1. 按 unstake 流程赎回 wSOL 到用户的 wSOL 账户
+/// 2. 关闭用户的 wSOL 账户，全部 lamports（含账户中原有的 wSOL）退还给用户
+pub fn unstake_sol<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UnstakeSol<'info>>,
+    item_index: u16,
+    lp_amount: u64,
+    min_redeem_amount: u64,
+    tag: Option<[u8; 32]>,
+) -> Result<()> {
+    let accounts = &mut ctx.accounts.unstake;
+    require!(
+        accounts.main_token_mint.key() == native_mint::ID,
+        ErrorCode::InvalidTokenMint
+    );
+
+    // 1. 赎回
+    unstake(
+        Context::new(ctx.program_id, accounts, ctx.remaining_accounts, ctx.bumps.unstake),
+        item_index,
+        lp_amount,
+        min_redeem_amount,
+        tag,
+    )?;
+
+    // 2. 解包 SOL
+    let accounts = &mut ctx.accounts.unstake;
+    token_interface::close_account(CpiContext::new(
+        accounts.main_token_program.to_account_info(),
+        CloseAccount {
+            account: accounts.user_main_token.to_account_info(),
+            destination: accounts.user.to_account_info(),
+            authority: accounts.user.to_account_info(),
+        },
+    ))?;
+
+    msg!("Unstaked SOL: user: {}, item_index: {}, lp_burned: {}",
+         accounts.user.key(),
+         item_index,
+         lp_amount);
+
+    Ok(())
+}
@@ -0,0 +1,275 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TokenInterface};
+use crate::state::{ItemOracle, Pool, PoolRefMut, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::events::WeightModifiedEvent;
+use crate::transfer::transfer_main_token;
+
+/// 开启或关闭 oracle weight 更新
+#[derive(Accounts)]
+pub struct SetOracleWeights<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 开启或关闭 oracle weight 更新
+/// enabled: 开启后任何人都可以按 ItemOracle 价格刷新已配置 oracle 的 item 的 weight
+pub fn set_oracle_weights(ctx: Context<SetOracleWeights>, enabled: bool) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    pool.oracle_weights_enabled = enabled as u8;
+
+    msg!("Oracle weights updated: pool: {}, enabled: {}",
+         ctx.accounts.pool.key(),
+         enabled);
+
+    Ok(())
+}
+
+/// 按 oracle 价格刷新 weight（任何人可调用）
+#[derive(Accounts)]
+pub struct UpdateWeightsFromOracle<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+}
+
+/// 按 oracle 价格刷新 weight
+/// remaining_accounts: 每两个一组 [item_oracle, price_feed]，item 由 item_oracle.lp_mint 确定
+///
+/// weight = price × 10^expo × WEIGHT_PRICE_SCALE，价格需通过 ItemOracle 配置的
+/// 延迟与置信区间校验；仍遵守 weight 修改冷却期
+pub fn update_weights_from_oracle<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateWeightsFromOracle<'info>>,
+) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    let clock = Clock::get()?;
+
+    let updated = apply_oracle_weights(pool, &pool_key, ctx.remaining_accounts, &clock)?;
+
+    msg!("Oracle weights refreshed: pool: {}, items: {}",
+         pool_key,
+         updated);
+
+    Ok(())
+}
+
+/// 按 [item_oracle, price_feed] 账户对刷新 weight，返回更新的 item 数量
+pub(crate) fn apply_oracle_weights<'info>(
+    pool: &mut PoolRefMut<'_>,
+    pool_key: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+    clock: &Clock,
+) -> Result<usize> {
+    require!(pool.is_oracle_weights_enabled(), ErrorCode::OracleWeightsDisabled);
+    require!(
+        !accounts.is_empty() && accounts.len() % 2 == 0,
+        ErrorCode::InvalidOracleAccount
+    );
+
+    // 结算奖励排放（weight 变化前）
+    pool.accrue_emission(clock.unix_timestamp)?;
+
+    let cooldown_slots = pool.get_weight_update_cooldown_slots();
+    let max_change_bps = pool.get_max_weight_change_bps();
+    for pair in accounts.chunks(2) {
+        let item_oracle = Account::<ItemOracle>::try_from(&pair[0])?;
+        require!(item_oracle.pool == *pool_key, ErrorCode::InvalidOracleAccount);
+        let new_weight = item_oracle.read_weight(&pair[1], clock.unix_timestamp)?;
+
+        let index = pool.find_token_index(&item_oracle.lp_mint)
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        let token = pool.get_token(index)
+            .ok_or(ErrorCode::InvalidTokenIndex)?;
+        token.verify_weight_cooldown(clock.slot, cooldown_slots)?;
+        token.verify_weight_change(new_weight, max_change_bps)?;
+        let old_weight = token.get_weight();
+        pool.update_item_weight(index, new_weight, clock.slot)?;
+        emit!(WeightModifiedEvent {
+            pool: *pool_key,
+            item_index: index as u16,
+            lp_mint: item_oracle.lp_mint,
+            old_weight,
+            new_weight,
+            slot: clock.slot,
+        });
+    }
+
+    pool.last_weight_update_ts = clock.unix_timestamp;
+
+    Ok(accounts.len() / 2)
+}
+
+/// 设置 crank_weights 的调用间隔和奖励
+#[derive(Accounts)]
+pub struct SetCrankConfig<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置 crank_weights 的调用间隔和奖励
+/// interval_seconds: 两次 crank 之间的最小间隔（秒），设置奖励时必须大于 0
+/// reward: 每次 crank 从 fee_vault 支付给调用者的主币数量，0 表示不支付
+pub fn set_crank_config(
+    ctx: Context<SetCrankConfig>,
+    interval_seconds: i64,
+    reward: u64,
+) -> Result<()> {
+    require!(
+        interval_seconds >= 0 && (reward == 0 || interval_seconds > 0),
+        ErrorCode::InvalidCrankConfig
+    );
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    require!(
+        reward == 0 || pool.fee_vault != Pubkey::default(),
+        ErrorCode::InvalidCrankConfig
+    );
+
+    pool.crank_interval_seconds = interval_seconds;
+    pool.crank_reward = reward;
+
+    msg!("Crank config updated: pool: {}, interval_seconds: {}, reward: {}",
+         ctx.accounts.pool.key(),
+         interval_seconds,
+         reward);
+
+    Ok(())
+}
+
+/// 定时刷新所有 oracle weight（任何人可调用）
+#[derive(Accounts)]
+pub struct CrankWeights<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - fee_vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 手续费 vault - 设置了 crank 奖励时必须传入
+    #[account(
+        mut,
+        address = pool.load()?.fee_vault @ ErrorCode::InvalidFeeConfig,
+    )]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 主币 Mint - 设置了 crank 奖励时必须传入
+    pub main_token_mint: Option<Box<InterfaceAccount<'info, token_interface::Mint>>>,
+
+    /// 调用者的主币账户 - 接收 crank 奖励
+    #[account(mut)]
+    pub cranker_token: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 调用者
+    pub cranker: Signer<'info>,
+
+    /// 主币所属的 token 程序（Token 或 Token-2022）- 设置了 crank 奖励时必须传入
+    pub main_token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+/// 定时刷新所有 oracle weight
+/// remaining_accounts:
+/// - 前 2 × N 个：每个配置了 oracle 的 item 一组 [item_oracle, price_feed]，N 为这类 item 的数量
+/// - 之后：主币 mint 启用 TransferHook 时，hook 需要的额外账户
+///
+/// 距离上次刷新至少 crank_interval_seconds，必须刷新全部配置了 oracle 的 item；
+/// 成功后从 fee_vault 向调用者支付 crank_reward（不足时支付全部余额）
+pub fn crank_weights<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CrankWeights<'info>>,
+) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    let clock = Clock::get()?;
+
+    pool.verify_crank_interval(clock.unix_timestamp)?;
+
+    let oracle_count = pool.oracle_item_count();
+    require!(
+        ctx.remaining_accounts.len() >= oracle_count * 2,
+        ErrorCode::InvalidOracleAccount
+    );
+    let (oracle_accounts, hook_accounts) = ctx.remaining_accounts.split_at(oracle_count * 2);
+    apply_oracle_weights(pool, &pool_key, oracle_accounts, &clock)?;
+
+    // 每个配置了 oracle 的 item 都必须在本次刷新
+    require!(
+        pool.tokens[..pool.get_token_count()]
+            .iter()
+            .all(|item| item.oracle_enabled == 0 || item.get_last_weight_update_slot() == clock.slot),
+        ErrorCode::InvalidOracleAccount
+    );
+
+    // 支付 crank 奖励
+    let reward = if pool.crank_reward > 0 {
+        let fee_vault = ctx.accounts.fee_vault.as_ref()
+            .ok_or(ErrorCode::InvalidFeeConfig)?;
+        let main_token_mint = ctx.accounts.main_token_mint.as_ref()
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        let cranker_token = ctx.accounts.cranker_token.as_ref()
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        let main_token_program = ctx.accounts.main_token_program.as_ref()
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        require!(
+            main_token_mint.key() == fee_vault.mint && cranker_token.mint == fee_vault.mint,
+            ErrorCode::InvalidTokenMint
+        );
+
+        let reward = pool.crank_reward.min(fee_vault.amount);
+        if reward > 0 {
+            let bump = ctx.bumps.pool_authority;
+            let authority_seed = pool.get_authority_seed();
+            let seeds = &[
+                POOL_AUTHORITY_SEED,
+                pool_key.as_ref(),
+                authority_seed,
+                &[bump],
+            ];
+            let signer = &[&seeds[..]];
+
+            transfer_main_token(
+                &main_token_program.to_account_info(),
+                &fee_vault.to_account_info(),
+                &main_token_mint.to_account_info(),
+                &cranker_token.to_account_info(),
+                &ctx.accounts.pool_authority.to_account_info(),
+                hook_accounts,
+                reward,
+                main_token_mint.decimals,
+                signer,
+            )?;
+        }
+        reward
+    } else {
+        0
+    };
+
+    msg!("Weights cranked: pool: {}, cranker: {}, items: {}, reward: {}",
+         pool_key,
+         ctx.accounts.cranker.key(),
+         oracle_count,
+         reward);
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+
+/// 设置 Pool 暂停状态
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置 Pool 暂停状态（紧急刹车）
+/// paused: 是否暂停 - 暂停后拒绝 stake 和 convert_lp
+/// withdraw_only: 暂停时是否仍允许 unstake / emergency_unstake（只出不进模式）
+/// 启用时间锁后，paused = false 必须通过 AdminAction::SetPause 入队执行
+pub fn set_pause(ctx: Context<SetPause>, paused: bool, withdraw_only: bool) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 暂停可以立即生效；启用时间锁后，解除暂停必须通过 queue_action 执行
+    if !paused {
+        pool.verify_direct_admin_action()?;
+    }
+
+    pool.set_paused(paused, withdraw_only);
+
+    msg!("Pool pause updated: pool: {}, paused: {}, withdraw_only: {}",
+         ctx.accounts.pool.key(),
+         paused,
+         withdraw_only);
+
+    Ok(())
+}
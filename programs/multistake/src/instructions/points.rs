@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use crate::state::{Pool, StakeRecord};
+use crate::error::ErrorCode;
+
+/// 为用户创建某个质押类型的质押记录（用于积分累积）
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct OpenStakeRecord<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 质押记录 PDA
+    #[account(
+        init,
+        payer = payer,
+        space = StakeRecord::space(),
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)?.as_ref(),
+        ],
+        bump
+    )]
+    pub stake_record: Account<'info, StakeRecord>,
+
+    /// 记录所有者
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 创建质押记录
+/// 创建之后的 stake/unstake 传入该记录即可开始累积积分
+pub fn open_stake_record(ctx: Context<OpenStakeRecord>, item_index: u16) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    let lp_mint = pool.get_item_mint(item_index as usize)?;
+
+    let record = &mut ctx.accounts.stake_record;
+    record.initialize(
+        &ctx.accounts.pool.key(),
+        &ctx.accounts.owner.key(),
+        &lp_mint,
+        ctx.bumps.stake_record,
+        Clock::get()?.slot,
+    );
+
+    msg!("Stake record opened: pool: {}, owner: {}, item_index: {}, lp_mint: {}",
+         record.pool,
+         record.owner,
+         item_index,
+         lp_mint);
+
+    Ok(())
+}
+
+/// 查询积分（只读）
+#[derive(Accounts)]
+pub struct GetPoints<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 质押记录
+    #[account(has_one = pool)]
+    pub stake_record: Account<'info, StakeRecord>,
+}
+
+/// 查询截至当前 slot 的总积分
+/// 不修改状态，结果由 Anchor 写入 return data（u128 小端字节）
+pub fn get_points(ctx: Context<GetPoints>) -> Result<u128> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    let record = &ctx.accounts.stake_record;
+    let item = pool.get_token_by_mint(&record.lp_mint)
+        .ok_or(ErrorCode::InvalidTokenMint)?;
+
+    let points = record.total_points(item.get_weight(), Clock::get()?.slot)?;
+
+    msg!("Points: owner: {}, lp_mint: {}, points: {}",
+         record.owner,
+         record.lp_mint,
+         points);
+
+    Ok(points)
+}
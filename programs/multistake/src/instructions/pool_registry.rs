@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::state::{Pool, PoolRegistry};
+
+/// 将已有 pool 登记到主币注册表
+#[derive(Accounts)]
+pub struct RegisterPool<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 主币的 pool 注册表 - 不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PoolRegistry::space(),
+        seeds = [b"pool_registry", pool.load()?.pool_mint.as_ref()],
+        bump,
+    )]
+    pub pool_registry: Box<Account<'info, PoolRegistry>>,
+
+    /// 支付注册表租金
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 将已有 pool 登记到主币注册表（任何人可调用）
+/// 用于注册表上线前创建的 pool，新建的 pool 由 create_pool 自动登记
+pub fn register_pool(ctx: Context<RegisterPool>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let main_token_mint = Pool::load_versioned(&ctx.accounts.pool)?.pool_mint;
+
+    let registry = &mut ctx.accounts.pool_registry;
+    if registry.is_uninitialized() {
+        registry.main_token_mint = main_token_mint;
+        registry.bump = ctx.bumps.pool_registry;
+    }
+    registry.register(&pool_key)?;
+
+    msg!("Pool registered: pool: {}, main_token_mint: {}, pool_count: {}",
+         pool_key,
+         main_token_mint,
+         registry.pools.len());
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::{Pool, PoolStats};
+use crate::error::ErrorCode;
+
+/// 创建 pool 的统计账户
+#[derive(Accounts)]
+pub struct InitPoolStats<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 统计 PDA - 按当前资产和质押类型数量分配空间
+    #[account(
+        init,
+        payer = payer,
+        space = PoolStats::space_for(&*pool.load()?)?,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump,
+    )]
+    pub pool_stats: Box<Account<'info, PoolStats>>,
+
+    /// 任何人都可以支付创建费用
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 创建 pool 的统计账户（任何人都可以调用），创建后通过 refresh_stats 写入数据
+pub fn init_pool_stats(ctx: Context<InitPoolStats>) -> Result<()> {
+    let stats = &mut ctx.accounts.pool_stats;
+    stats.pool = ctx.accounts.pool.key();
+    stats.bump = ctx.bumps.pool_stats;
+
+    msg!("Pool stats created: pool: {}", stats.pool);
+
+    Ok(())
+}
+
+/// 刷新 pool 的派生统计数据
+#[derive(Accounts)]
+pub struct RefreshStats<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 主资产的主币 Vault
+    #[account(
+        address = pool.load()?.pool_vault @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 统计 PDA - 质押类型数量变化后按需扩容
+    #[account(
+        mut,
+        has_one = pool,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump = pool_stats.bump,
+        realloc = PoolStats::space_for(&*pool.load()?)?,
+        realloc::payer = payer,
+        realloc::zero = false,
+    )]
+    pub pool_stats: Box<Account<'info, PoolStats>>,
+
+    /// 任何人都可以调用，扩容时支付新增的租金
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 刷新 pool 的派生统计数据（任何人都可以调用）
+/// remaining_accounts: 额外主币资产的 vault，按资产索引排列
+/// 记录各主币的有效 vault 余额和总加权质押量，以及每个 item 的 weight、LP 发行量和赎回汇率
+pub fn refresh_stats<'info>(ctx: Context<'_, '_, 'info, 'info, RefreshStats<'info>>) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    let extra_asset_count = pool.get_main_asset_count() - 1;
+    require!(
+        ctx.remaining_accounts.len() == extra_asset_count,
+        ErrorCode::InvalidAssetIndex
+    );
+
+    let mut vault_balances = Vec::with_capacity(pool.get_main_asset_count());
+    vault_balances.push(ctx.accounts.pool_vault.amount);
+    for (offset, info) in ctx.remaining_accounts.iter().enumerate() {
+        require!(
+            info.key() == pool.get_asset_vault(offset as u8 + 1)?,
+            ErrorCode::InvalidPoolVault
+        );
+        let vault = InterfaceAccount::<TokenAccount>::try_from(info)?;
+        vault_balances.push(vault.amount);
+    }
+
+    let clock = Clock::get()?;
+    let stats = &mut ctx.accounts.pool_stats;
+    stats.slot = clock.slot;
+    stats.unix_timestamp = clock.unix_timestamp;
+    stats.refresh(&pool, &vault_balances)?;
+
+    msg!("Pool stats refreshed: pool: {}, slot: {}, asset_count: {}, token_count: {}",
+         stats.pool,
+         stats.slot,
+         stats.assets.len(),
+         stats.items.len());
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use crate::state::{Pool, StakeRecord};
+use crate::error::ErrorCode;
+
+/// 设置质押记录是否允许转让
+#[derive(Accounts)]
+pub struct SetPositionTransferable<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置质押记录是否允许转让
+pub fn set_position_transferable(
+    ctx: Context<SetPositionTransferable>,
+    enabled: bool,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    pool.position_transferable = enabled as u8;
+
+    msg!("Position transferable updated: pool: {}, enabled: {}",
+         ctx.accounts.pool.key(),
+         enabled);
+
+    Ok(())
+}
+
+/// 转让质押记录
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct TransferPosition<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 转出方的质押记录 - 转让后关闭，租金退还给原所有者
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)?.as_ref(),
+        ],
+        bump = stake_record.bump,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// 接收方的质押记录 - 不存在时创建，已存在时合并
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = StakeRecord::space(),
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            new_owner.key().as_ref(),
+            Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)?.as_ref(),
+        ],
+        bump
+    )]
+    pub new_stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// 当前所有者 - 必须签名，支付接收方记录的租金
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// 新所有者（无需签名），例如用户的另一个钱包或托管方
+    /// CHECK: 只作为 PDA seed 和记录字段
+    pub new_owner: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 转让质押记录
+/// item_index: 质押类型索引
+///
+/// 逻辑：
+/// 1. pool 必须开启 position_transferable
+/// 2. 双方记录都结算积分到当前 slot
+/// 3. 转出方的 LP 数量和积分合并到接收方记录，转出方记录关闭
+///
+/// 注意：只转移积分记录，LP 凭证本身需要另行转账
+pub fn transfer_position(ctx: Context<TransferPosition>, item_index: u16) -> Result<()> {
+    let new_owner = ctx.accounts.new_owner.key();
+    require!(
+        new_owner != ctx.accounts.owner.key(),
+        ErrorCode::InvalidNewOwner
+    );
+
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    require!(pool.is_position_transferable(), ErrorCode::PositionNotTransferable);
+
+    let item = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    let weight = item.get_weight();
+    // 双方结算到同一个 reward_per_share 即可正确合并，无需先结算 pool 的排放
+    let reward_per_share = item.get_reward_per_share();
+    let current_slot = Clock::get()?.slot;
+
+    let record = &mut ctx.accounts.stake_record;
+    record.accrue_points(weight, current_slot)?;
+    record.settle_rewards(reward_per_share)?;
+
+    let new_record = &mut ctx.accounts.new_stake_record;
+    if new_record.is_uninitialized() {
+        // 新建的记录
+        new_record.initialize(
+            &ctx.accounts.pool.key(),
+            &new_owner,
+            item.mint_pubkey(),
+            ctx.bumps.new_stake_record,
+            current_slot,
+        );
+    }
+    new_record.accrue_points(weight, current_slot)?;
+    new_record.settle_rewards(reward_per_share)?;
+    new_record.absorb(record)?;
+
+    msg!("Position transferred: pool: {}, item_index: {}, from: {}, to: {}, staked_amount: {}, points: {}",
+         new_record.pool,
+         item_index,
+         record.owner,
+         new_owner,
+         record.staked_amount,
+         record.points);
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::{ItemOracle, Pool};
+use crate::error::ErrorCode;
+
+/// stake 预览结果
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct StakePreview {
+    /// 手续费
+    pub fee_amount: u64,
+    /// 用户将获得的 LP 数量
+    pub lp_amount: u64,
+    /// 以 LP 形式铸造给 treasury 的手续费（未启用 fee_in_lp 时为 0）
+    pub fee_lp_amount: u64,
+}
+
+/// unstake 预览结果
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct UnstakePreview {
+    /// 按 weight（及脱锚调整）计算的赎回金额
+    pub redeem_amount: u64,
+    /// 手续费
+    pub fee_amount: u64,
+    /// 用户实际收到的主币数量
+    pub amount_after_fee: u64,
+}
+
+/// 预览 stake（只读）
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct PreviewStake<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// item 对应的主币 Vault - 用于按兑换率折算 LP
+    #[account(
+        address = Pool::load_versioned(&pool)?.get_item_vault(item_index as usize)? @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// 预览 stake
+/// 计算结果写入 return data（Borsh 编码的 StakePreview），随后以 PreviewOnly 中止，
+/// 保证同一交易中之前的指令也不会生效，适合通过 simulateTransaction 获取批量流程的精确结果
+pub fn preview_stake(
+    ctx: Context<PreviewStake>,
+    item_index: u16,
+    stake_amount: u64,
+) -> Result<()> {
+    let preview = compute_stake_preview(&ctx, item_index, stake_amount)?;
+    set_return_data(&preview.try_to_vec()?);
+    err!(ErrorCode::PreviewOnly)
+}
+
+/// 报价 stake
+/// 与 preview_stake 计算相同，结果写入 return data（Borsh 编码的 StakePreview）后正常返回，
+/// 客户端可直接 simulate 单条指令读取链上精确结果，无需在本地复刻手续费和兑换率计算
+pub fn quote_stake(
+    ctx: Context<PreviewStake>,
+    item_index: u16,
+    amount: u64,
+) -> Result<()> {
+    let preview = compute_stake_preview(&ctx, item_index, amount)?;
+    set_return_data(&preview.try_to_vec()?);
+    Ok(())
+}
+
+fn compute_stake_preview(
+    ctx: &Context<PreviewStake>,
+    item_index: u16,
+    stake_amount: u64,
+) -> Result<StakePreview> {
+    require!(stake_amount > 0, ErrorCode::InvalidTokenCount);
+
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    require!(
+        (item_index as usize) < pool.get_token_count(),
+        ErrorCode::InvalidTokenIndex
+    );
+    pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?
+        .verify_active()?;
+
+    let (fee_amount, amount_after_fee) = pool.calculate_stake_fee(stake_amount)?;
+    let pool_vault_balance = ctx.accounts.pool_vault.amount;
+    let lp_amount =
+        pool.calculate_stake_lp_amount(amount_after_fee, item_index as usize, pool_vault_balance)?;
+    let fee_lp_amount = if pool.is_fee_in_lp() {
+        let (protocol_fee, _) = pool.calculate_protocol_fee(fee_amount)?;
+        pool.calculate_stake_lp_amount(protocol_fee, item_index as usize, pool_vault_balance)?
+    } else {
+        0
+    };
+
+    let preview = StakePreview {
+        fee_amount,
+        lp_amount,
+        fee_lp_amount,
+    };
+
+    msg!("Stake preview: item_index: {}, amount: {}, fee: {}, lp_amount: {}, fee_lp_amount: {}",
+         item_index,
+         stake_amount,
+         fee_amount,
+         lp_amount,
+         fee_lp_amount);
+
+    Ok(preview)
+}
+
+/// 预览 unstake（只读）
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct PreviewUnstake<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// item 对应的主币 Vault
+    #[account(
+        address = Pool::load_versioned(&pool)?.get_item_vault(item_index as usize)? @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 质押类型的 oracle 配置 - item 配置了 oracle 时必须提供
+    #[account(has_one = pool @ ErrorCode::InvalidOracleAccount)]
+    pub item_oracle: Option<Box<Account<'info, ItemOracle>>>,
+
+    /// oracle 价格账户 - item 配置了 oracle 时必须提供
+    /// CHECK: 与 item_oracle.price_feed 比对，并在读取时校验 owner
+    pub price_feed: Option<UncheckedAccount<'info>>,
+}
+
+/// 预览 unstake
+/// 计算结果写入 return data（Borsh 编码的 UnstakePreview），随后以 PreviewOnly 中止
+pub fn preview_unstake(
+    ctx: Context<PreviewUnstake>,
+    item_index: u16,
+    lp_amount: u64,
+) -> Result<()> {
+    let preview = compute_unstake_preview(&ctx, item_index, lp_amount)?;
+    set_return_data(&preview.try_to_vec()?);
+    err!(ErrorCode::PreviewOnly)
+}
+
+/// 报价 unstake
+/// 与 preview_unstake 计算相同，结果写入 return data（Borsh 编码的 UnstakePreview）后正常返回
+pub fn quote_unstake(
+    ctx: Context<PreviewUnstake>,
+    item_index: u16,
+    lp_amount: u64,
+) -> Result<()> {
+    let preview = compute_unstake_preview(&ctx, item_index, lp_amount)?;
+    set_return_data(&preview.try_to_vec()?);
+    Ok(())
+}
+
+fn compute_unstake_preview(
+    ctx: &Context<PreviewUnstake>,
+    item_index: u16,
+    lp_amount: u64,
+) -> Result<UnstakePreview> {
+    require!(lp_amount > 0, ErrorCode::InvalidTokenCount);
+
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    let item = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+
+    let pool_vault_balance = ctx.accounts.pool_vault.amount;
+    let redeem_amount = pool.calculate_redeem_amount(
+        lp_amount,
+        item_index as usize,
+        pool_vault_balance,
+    )?;
+
+    let redeem_amount = if item.has_oracle() {
+        let oracle = ctx.accounts.item_oracle.as_ref()
+            .ok_or(ErrorCode::InvalidOracleAccount)?;
+        require!(
+            oracle.lp_mint == *item.mint_pubkey(),
+            ErrorCode::InvalidOracleAccount
+        );
+        let price_feed = ctx.accounts.price_feed.as_ref()
+            .ok_or(ErrorCode::InvalidOracleAccount)?;
+        oracle.adjust_redeem_amount(
+            price_feed,
+            Clock::get()?.unix_timestamp,
+            item.get_weight(),
+            redeem_amount,
+        )?
+    } else {
+        redeem_amount
+    };
+
+    require!(
+        pool_vault_balance >= redeem_amount,
+        ErrorCode::InsufficientLiquidity
+    );
+
+    let (fee_amount, amount_after_fee) = pool.calculate_unstake_fee(redeem_amount)?;
+
+    let preview = UnstakePreview {
+        redeem_amount,
+        fee_amount,
+        amount_after_fee,
+    };
+
+    msg!("Unstake preview: item_index: {}, lp_amount: {}, redeem_amount: {}, fee: {}, amount_after_fee: {}",
+         item_index,
+         lp_amount,
+         redeem_amount,
+         fee_amount,
+         amount_after_fee);
+
+    Ok(preview)
+}
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::error::ErrorCode;
+use crate::program::Multistake;
+use crate::state::program_config::{ProgramConfig, PROGRAM_CONFIG_SEED};
+
+/// 创建全局程序配置
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = ProgramConfig::space(),
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump,
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    /// 本程序
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::InvalidConfigAuthority)]
+    pub program: Program<'info, Multistake>,
+
+    /// 本程序的 ProgramData - 用于确认 authority 为升级权限
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::InvalidConfigAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// 程序升级权限 - 必须签名，成为配置管理员并支付租金
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 创建全局程序配置（仅程序升级权限可调用，只能创建一次）
+/// protocol_treasury: 协议金库，接收分成的主币账户须归其所有
+/// protocol_fee_share_bps: 协议分成比例，占各 pool 转入 fee_vault 的手续费
+pub fn initialize_config(
+    ctx: Context<InitializeConfig>,
+    protocol_treasury: Pubkey,
+    protocol_fee_share_bps: u16,
+) -> Result<()> {
+    let config = &mut ctx.accounts.program_config;
+    config.authority = ctx.accounts.authority.key();
+    config.bump = ctx.bumps.program_config;
+    config.set(protocol_treasury, protocol_fee_share_bps)?;
+
+    msg!("Program config initialized: authority: {}, protocol_treasury: {}, protocol_fee_share_bps: {}",
+         config.authority,
+         protocol_treasury,
+         protocol_fee_share_bps);
+
+    Ok(())
+}
+
+/// 更新全局程序配置
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump = program_config.bump,
+        has_one = authority @ ErrorCode::InvalidConfigAuthority,
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    /// 配置管理员 - 必须签名
+    pub authority: Signer<'info>,
+}
+
+/// 更新协议金库、分成比例和配置管理员
+/// new_authority: 传入时移交配置管理员
+pub fn update_config(
+    ctx: Context<UpdateConfig>,
+    protocol_treasury: Pubkey,
+    protocol_fee_share_bps: u16,
+    new_authority: Option<Pubkey>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.program_config;
+    config.set(protocol_treasury, protocol_fee_share_bps)?;
+    if let Some(new_authority) = new_authority {
+        require!(new_authority != Pubkey::default(), ErrorCode::InvalidConfigAuthority);
+        config.authority = new_authority;
+    }
+
+    msg!("Program config updated: authority: {}, protocol_treasury: {}, protocol_fee_share_bps: {}",
+         config.authority,
+         protocol_treasury,
+         protocol_fee_share_bps);
+
+    Ok(())
+}
+
+/// 按全局配置拆分转入 fee_vault 的手续费
+/// 返回: (转入协议金库的部分, 仍转入 fee_vault 的部分)
+/// 配置尚未创建或分成比例为 0 时全部转入 fee_vault；有分成时必须传入协议金库的主币账户
+pub(crate) fn split_protocol_fee(
+    program_config: &AccountInfo,
+    protocol_treasury_token: Option<&InterfaceAccount<TokenAccount>>,
+    mint: &Pubkey,
+    fee_amount: u64,
+) -> Result<(u64, u64)> {
+    let config = match ProgramConfig::load_optional(program_config)? {
+        Some(config) if fee_amount > 0 => config,
+        _ => return Ok((0, fee_amount)),
+    };
+    let (protocol_share, pool_share) = config.split_fee(fee_amount)?;
+    if protocol_share > 0 {
+        let treasury_token = protocol_treasury_token.ok_or(ErrorCode::InvalidProtocolTreasury)?;
+        require!(
+            treasury_token.owner == config.protocol_treasury && treasury_token.mint == *mint,
+            ErrorCode::InvalidProtocolTreasury
+        );
+    }
+    Ok((protocol_share, pool_share))
+}
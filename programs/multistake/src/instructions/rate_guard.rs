@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+use crate::state::oracle::BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+
+/// 设置兑换率操纵保护参数
+#[derive(Accounts)]
+pub struct SetRateGuard<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置兑换率操纵保护参数
+/// min_vault_balance: 存在未赎回 LP 时 vault 记账余额的最小值，0 表示不限制
+/// max_donation_bps: 单笔交易中直接捐赠可推高兑换率的上限（bps），0 表示不限制
+///
+/// 记账余额只随 stake/unstake 变化，直接转入 vault 的捐赠按 max_donation_bps 逐笔计入兑换率，
+/// 攻击者无法在一笔交易内通过捐赠大幅抬高赎回价格
+pub fn set_rate_guard(
+    ctx: Context<SetRateGuard>,
+    min_vault_balance: u64,
+    max_donation_bps: u16,
+) -> Result<()> {
+    require!(
+        (max_donation_bps as u64) <= BPS_DENOMINATOR,
+        ErrorCode::InvalidRateGuardConfig
+    );
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    pool.min_vault_balance = min_vault_balance;
+    pool.max_donation_bps = max_donation_bps;
+
+    msg!("Rate guard updated: pool: {}, min_vault_balance: {}, max_donation_bps: {}",
+         ctx.accounts.pool.key(),
+         min_vault_balance,
+         max_donation_bps);
+
+    Ok(())
+}
+
+/// 设置最小质押数量
+#[derive(Accounts)]
+pub struct SetMinStakeAmount<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置最小质押数量
+/// min_stake_amount: 单笔 stake 的最小主币数量，0 表示不限制
+pub fn set_min_stake_amount(ctx: Context<SetMinStakeAmount>, min_stake_amount: u64) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    pool.min_stake_amount = min_stake_amount;
+
+    msg!("Min stake amount updated: pool: {}, min_stake_amount: {}",
+         ctx.accounts.pool.key(),
+         min_stake_amount);
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::state::{ItemRateHistory, Pool};
+use crate::state::rate_history::{RateSample, RATE_SAMPLE_LP_AMOUNT};
+use crate::error::ErrorCode;
+
+/// 为某个 item 创建汇率历史账户
+#[derive(Accounts)]
+pub struct InitRateHistory<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// item 的 LP mint - 必须是 pool 中已注册的 item
+    #[account(
+        constraint = Pool::load_versioned(&pool)?.find_token_index(&lp_mint.key()).is_some()
+            @ ErrorCode::InvalidTokenMint
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 汇率历史 PDA
+    #[account(
+        init,
+        payer = payer,
+        space = ItemRateHistory::space(),
+        seeds = [b"rate_history", pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump
+    )]
+    pub rate_history: Box<Account<'info, ItemRateHistory>>,
+
+    /// 任何人都可以支付创建费用
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 为某个 item 创建汇率历史账户
+pub fn init_rate_history(ctx: Context<InitRateHistory>) -> Result<()> {
+    let history = &mut ctx.accounts.rate_history;
+    history.pool = ctx.accounts.pool.key();
+    history.lp_mint = ctx.accounts.lp_mint.key();
+    history.head = 0;
+    history.count = 0;
+    history.bump = ctx.bumps.rate_history;
+    history.samples = Default::default();
+
+    msg!("Rate history created: pool: {}, lp_mint: {}",
+         history.pool,
+         history.lp_mint);
+
+    Ok(())
+}
+
+/// 记录 item 的汇率样本（任何人都可以调用）
+#[derive(Accounts)]
+#[instruction(item_index: u16)]
+pub struct RecordRateSample<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// item 对应的主币 Vault
+    #[account(
+        address = Pool::load_versioned(&pool)?.get_item_vault(item_index as usize)? @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 汇率历史
+    #[account(
+        mut,
+        has_one = pool,
+        seeds = [b"rate_history", pool.key().as_ref(), rate_history.lp_mint.as_ref()],
+        bump = rate_history.bump,
+    )]
+    pub rate_history: Box<Account<'info, ItemRateHistory>>,
+}
+
+/// 记录 item 的汇率样本
+/// 汇率 = RATE_SAMPLE_LP_AMOUNT 个 LP 按当前 weight 和 vault 余额可赎回的主币数量
+/// 每个 epoch 只能记录一次，两次采样之比即为该区间的实际收益
+pub fn record_rate_sample(ctx: Context<RecordRateSample>, item_index: u16) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    let item = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    require!(
+        ctx.accounts.rate_history.lp_mint == *item.mint_pubkey(),
+        ErrorCode::InvalidTokenMint
+    );
+
+    let rate = pool.calculate_redeem_amount(
+        RATE_SAMPLE_LP_AMOUNT,
+        item_index as usize,
+        ctx.accounts.pool_vault.amount,
+    )?;
+
+    let clock = Clock::get()?;
+    let sample = RateSample {
+        epoch: clock.epoch,
+        unix_timestamp: clock.unix_timestamp,
+        rate,
+    };
+    ctx.accounts.rate_history.push(sample)?;
+
+    msg!("Rate sampled: pool: {}, item_index: {}, epoch: {}, rate: {}",
+         ctx.accounts.pool.key(),
+         item_index,
+         clock.epoch,
+         rate);
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::state::{ItemRateSource, Pool, PoolRefMut};
+use crate::state::oracle::BPS_DENOMINATOR;
+use crate::state::rate_source::{ITEM_RATE_SOURCE_SEED, RATE_SOURCE_STAKE_POOL};
+use crate::error::ErrorCode;
+
+/// 配置质押类型的兑换率来源
+#[derive(Accounts)]
+pub struct SetItemRateSource<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 质押类型的 LP mint
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    /// 质押类型的兑换率来源 PDA
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ItemRateSource::space(),
+        seeds = [ITEM_RATE_SOURCE_SEED, pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump
+    )]
+    pub item_rate_source: Account<'info, ItemRateSource>,
+
+    /// 兑换率来源账户（Pyth PriceUpdateV2 或 SPL stake pool 状态账户）
+    /// CHECK: 在 ItemRateSource::read_weight 中按来源类型校验 owner 和数据布局
+    pub source: UncheckedAccount<'info>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 配置质押类型的兑换率来源（LST 类质押类型，weight 跟随底层兑换率）
+/// kind: 0 = Pyth feed，1 = SPL stake pool 状态账户
+/// max_staleness: 最大允许延迟，Pyth 为秒，stake pool 为 epoch 数
+/// max_confidence_bps: 置信区间占价格的最大比例（bps），仅 Pyth 使用
+///
+/// 配置时立即按当前兑换率设置 weight 并取消衰减计划；之后 stake / unstake 等指令必须在
+/// remaining_accounts 中传入同一主币下所有此类 item 的 [item_rate_source, source]，执行时刷新 weight
+/// 不能与 oracle weight（ItemOracle）同时配置
+pub fn set_item_rate_source(
+    ctx: Context<SetItemRateSource>,
+    kind: u8,
+    max_staleness: u64,
+    max_confidence_bps: u16,
+) -> Result<()> {
+    require!(
+        kind <= RATE_SOURCE_STAKE_POOL && max_confidence_bps as u64 <= BPS_DENOMINATOR,
+        ErrorCode::InvalidOracleConfig
+    );
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后必须通过 queue_action 执行
+    pool.verify_direct_admin_action()?;
+
+    let lp_mint_key = ctx.accounts.lp_mint.key();
+    let token_index = pool.find_token_index(&lp_mint_key)
+        .ok_or(ErrorCode::InvalidTokenMint)?;
+    let item = pool.get_token(token_index)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    require!(!item.has_oracle(), ErrorCode::InvalidOracleConfig);
+
+    let rate_source = &mut ctx.accounts.item_rate_source;
+    rate_source.pool = ctx.accounts.pool.key();
+    rate_source.lp_mint = lp_mint_key;
+    rate_source.source = ctx.accounts.source.key();
+    rate_source.kind = kind;
+    rate_source.max_staleness = max_staleness;
+    rate_source.max_confidence_bps = max_confidence_bps;
+    rate_source.bump = ctx.bumps.item_rate_source;
+
+    // 配置时读取一次兑换率，确保 source 可用
+    let clock = Clock::get()?;
+    let weight = rate_source.read_weight(&ctx.accounts.source, &clock)?;
+
+    // 结算奖励排放（weight 变化前）
+    pool.accrue_emission(clock.unix_timestamp)?;
+    pool.update_item_weight(token_index, weight, clock.slot)?;
+    pool.tokens[token_index].rate_weight_enabled = 1;
+
+    msg!("Item rate source set: lp_mint: {}, source: {}, kind: {}, staleness: {}, confidence_bps: {}, weight: {}",
+         lp_mint_key,
+         rate_source.source,
+         kind,
+         max_staleness,
+         max_confidence_bps,
+         weight);
+
+    Ok(())
+}
+
+/// 移除质押类型的兑换率来源
+#[derive(Accounts)]
+pub struct RemoveItemRateSource<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 质押类型的兑换率来源 PDA - 关闭后租金退还给 admin
+    #[account(
+        mut,
+        close = admin,
+        has_one = pool,
+        seeds = [ITEM_RATE_SOURCE_SEED, pool.key().as_ref(), item_rate_source.lp_mint.as_ref()],
+        bump = item_rate_source.bump
+    )]
+    pub item_rate_source: Account<'info, ItemRateSource>,
+
+    /// Pool 管理员 - 必须签名
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+/// 移除质押类型的兑换率来源，weight 保持最后一次读取的值
+pub fn remove_item_rate_source(ctx: Context<RemoveItemRateSource>) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let lp_mint_key = ctx.accounts.item_rate_source.lp_mint;
+    if let Some(token_index) = pool.find_token_index(&lp_mint_key) {
+        let item = pool.get_token_mut(token_index)
+            .ok_or(ErrorCode::InvalidTokenIndex)?;
+        item.rate_weight_enabled = 0;
+    }
+
+    msg!("Item rate source removed: lp_mint: {}", lp_mint_key);
+    Ok(())
+}
+
+/// 按兑换率来源刷新 item_index 所属主币资产下所有此类 item 的 weight（赎回率取决于同一主币下全部 item 的 weight）
+/// accounts: remaining_accounts，前 2 × N 个为每个此类 item 一组 [item_rate_source, source]，
+/// N 为该主币下配置了兑换率来源的 item 数量
+/// 返回其后的账户（transfer hook 需要的额外账户）
+/// 调用前须先结算奖励排放
+pub(crate) fn apply_rate_weights<'a, 'info>(
+    pool: &mut PoolRefMut<'_>,
+    pool_key: &Pubkey,
+    item_index: usize,
+    accounts: &'a [AccountInfo<'info>],
+    clock: &Clock,
+) -> Result<&'a [AccountInfo<'info>]> {
+    let asset_index = pool.get_token(item_index)
+        .ok_or(ErrorCode::InvalidTokenIndex)?
+        .get_main_asset_index();
+    let count = pool.rate_weight_item_count(asset_index);
+    require!(accounts.len() >= count * 2, ErrorCode::InvalidRateSource);
+    let (rate_accounts, hook_accounts) = accounts.split_at(count * 2);
+
+    let mut refreshed = Vec::with_capacity(count);
+    for pair in rate_accounts.chunks(2) {
+        let rate_source = ItemRateSource::load(&pair[0])?;
+        require!(rate_source.pool == *pool_key, ErrorCode::InvalidRateSource);
+        let index = pool.find_token_index(&rate_source.lp_mint)
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        let item = pool.get_token(index)
+            .ok_or(ErrorCode::InvalidTokenIndex)?;
+        require!(
+            item.has_rate_weight() && item.get_main_asset_index() == asset_index,
+            ErrorCode::InvalidRateSource
+        );
+        let weight = rate_source.read_weight(&pair[1], clock)?;
+        pool.sync_item_weight(index, weight)?;
+        refreshed.push(index);
+    }
+
+    // 每个此类 item 都必须在本次刷新（不可重复传入同一 item）
+    refreshed.sort_unstable();
+    refreshed.dedup();
+    require!(refreshed.len() == count, ErrorCode::InvalidRateSource);
+
+    Ok(hook_accounts)
+}
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use crate::state::{Pool, PriorityRedeemer};
+use crate::error::ErrorCode;
+
+/// 设置赎回限速
+#[derive(Accounts)]
+pub struct SetRedemptionLimit<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置赎回限速
+/// window_slots: 窗口长度（slot），0 表示关闭限速
+/// window_cap: 每个窗口内 unstake 可赎回的主币总量上限
+/// priority_reserve: 上限中为优先赎回地址预留的额度（不超过 window_cap）
+pub fn set_redemption_limit(
+    ctx: Context<SetRedemptionLimit>,
+    window_slots: u64,
+    window_cap: u64,
+    priority_reserve: u64,
+) -> Result<()> {
+    require!(priority_reserve <= window_cap, ErrorCode::InvalidRedemptionLimit);
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    pool.redemption_window_slots = window_slots;
+    pool.redemption_window_cap = window_cap;
+    pool.priority_reserve = priority_reserve;
+    // 配置变更后从下一笔赎回开始新窗口
+    pool.redemption_window_start = 0;
+    pool.redemption_window_used = 0;
+
+    msg!("Redemption limit updated: pool: {}, window_slots: {}, window_cap: {}, priority_reserve: {}",
+         ctx.accounts.pool.key(),
+         window_slots,
+         window_cap,
+         priority_reserve);
+
+    Ok(())
+}
+
+/// 登记优先赎回地址
+#[derive(Accounts)]
+#[instruction(redeemer: Pubkey)]
+pub struct AddPriorityRedeemer<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 优先赎回地址登记 PDA
+    #[account(
+        init,
+        payer = admin,
+        space = PriorityRedeemer::space(),
+        seeds = [b"priority_redeemer", pool.key().as_ref(), redeemer.as_ref()],
+        bump
+    )]
+    pub priority_redeemer: Account<'info, PriorityRedeemer>,
+
+    /// Pool 管理员 - 必须签名
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 登记优先赎回地址
+/// redeemer: 可使用预留优先额度的地址（unstake 的 user）
+pub fn add_priority_redeemer(ctx: Context<AddPriorityRedeemer>, redeemer: Pubkey) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let entry = &mut ctx.accounts.priority_redeemer;
+    entry.pool = ctx.accounts.pool.key();
+    entry.redeemer = redeemer;
+    entry.bump = ctx.bumps.priority_redeemer;
+
+    msg!("Priority redeemer added: pool: {}, redeemer: {}",
+         entry.pool,
+         redeemer);
+
+    Ok(())
+}
+
+/// 移除优先赎回地址
+#[derive(Accounts)]
+pub struct RemovePriorityRedeemer<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 优先赎回地址登记 PDA - 关闭后租金退还给管理员
+    #[account(
+        mut,
+        close = admin,
+        has_one = pool,
+        seeds = [
+            b"priority_redeemer",
+            pool.key().as_ref(),
+            priority_redeemer.redeemer.as_ref(),
+        ],
+        bump = priority_redeemer.bump,
+    )]
+    pub priority_redeemer: Account<'info, PriorityRedeemer>,
+
+    /// Pool 管理员 - 必须签名
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+/// 移除优先赎回地址
+pub fn remove_priority_redeemer(ctx: Context<RemovePriorityRedeemer>) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    msg!("Priority redeemer removed: pool: {}, redeemer: {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.priority_redeemer.redeemer);
+
+    Ok(())
+}
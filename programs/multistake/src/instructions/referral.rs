@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use anchor_spl::token_interface::{self, TokenInterface};
+use crate::state::{Pool, ReferralAccount, ReferralCode, POOL_AUTHORITY_SEED};
+use crate::state::oracle::BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+use crate::transfer::transfer_main_token;
+
+/// 注册推荐码
+#[derive(Accounts)]
+#[instruction(code: Vec<u8>)]
+pub struct RegisterReferralCode<'info> {
+    /// 推荐码 PDA - 推荐码全局唯一，先到先得
+    #[account(
+        init,
+        payer = referrer,
+        space = ReferralCode::space(),
+        seeds = [b"referral", code.as_slice()],
+        bump
+    )]
+    pub referral: Account<'info, ReferralCode>,
+
+    /// 推荐返佣的收款 token 账户
+    pub fee_account: Box<Account<'info, TokenAccount>>,
+
+    /// 推荐人 - 支付租金
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 注册推荐码
+/// code: 推荐码（1~16 字节，ASCII 字母、数字、'-'、'_'）
+/// stake 时传入该推荐码对应的 PDA 即可给推荐人返佣
+pub fn register_referral_code(ctx: Context<RegisterReferralCode>, code: Vec<u8>) -> Result<()> {
+    ReferralCode::validate_code(&code)?;
+
+    let referral = &mut ctx.accounts.referral;
+    referral.referrer = ctx.accounts.referrer.key();
+    referral.fee_account = ctx.accounts.fee_account.key();
+    referral.code = code;
+    referral.bump = ctx.bumps.referral;
+
+    msg!("Referral code registered: code: {}, referrer: {}, fee_account: {}",
+         String::from_utf8_lossy(&referral.code),
+         referral.referrer,
+         referral.fee_account);
+
+    Ok(())
+}
+
+/// 设置推荐返佣比例
+#[derive(Accounts)]
+pub struct SetReferralShare<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置推荐返佣比例
+/// share_bps: stake 手续费中分给推荐人的比例（bps）
+pub fn set_referral_share(ctx: Context<SetReferralShare>, share_bps: u16) -> Result<()> {
+    require!(share_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidFeeConfig);
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    pool.referral_share_bps = share_bps;
+
+    msg!("Referral share updated: pool: {}, share_bps: {}",
+         ctx.accounts.pool.key(),
+         share_bps);
+
+    Ok(())
+}
+
+/// 领取推荐返佣
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - fee_vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 手续费 vault - 推荐返佣暂存于此
+    #[account(
+        mut,
+        address = pool.load()?.fee_vault @ ErrorCode::InvalidFeeConfig,
+    )]
+    pub fee_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 主币 Mint - Token 或 Token-2022
+    #[account(address = fee_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub main_token_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 推荐人的返佣账户
+    #[account(
+        mut,
+        seeds = [b"referral_account", pool.key().as_ref(), referrer.key().as_ref()],
+        bump = referral_account.bump,
+        has_one = pool @ ErrorCode::InvalidReferralAccount,
+        has_one = referrer @ ErrorCode::InvalidReferralAccount,
+    )]
+    pub referral_account: Account<'info, ReferralAccount>,
+
+    /// 推荐人 - 必须签名
+    pub referrer: Signer<'info>,
+
+    /// 接收返佣的账户
+    #[account(mut)]
+    pub destination: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
+}
+
+/// 领取推荐返佣
+/// 将 ReferralAccount 中全部未领取的返佣从 fee_vault 转给推荐人
+/// remaining_accounts: 主币 mint 启用 TransferHook 时，hook 需要的额外账户
+pub fn claim_referral_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimReferralFees<'info>>,
+) -> Result<()> {
+    let amount = ctx.accounts.referral_account.unclaimed_fees;
+    require!(amount > 0, ErrorCode::NoReferralFees);
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    pool.sub_referral_fees_owed(amount)?;
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    transfer_main_token(
+        &ctx.accounts.main_token_program.to_account_info(),
+        &ctx.accounts.fee_vault.to_account_info(),
+        &ctx.accounts.main_token_mint.to_account_info(),
+        &ctx.accounts.destination.to_account_info(),
+        &ctx.accounts.pool_authority.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.main_token_mint.decimals,
+        signer,
+    )?;
+
+    ctx.accounts.referral_account.unclaimed_fees = 0;
+
+    msg!("Referral fees claimed: pool: {}, referrer: {}, destination: {}, amount: {}",
+         pool_key,
+         ctx.accounts.referrer.key(),
+         ctx.accounts.destination.key(),
+         amount);
+
+    Ok(())
+}
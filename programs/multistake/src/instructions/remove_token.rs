@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
-use crate::state::Pool;
+use anchor_spl::token_interface::{self, spl_token_2022::instruction::AuthorityType, Mint, SetAuthority, TokenInterface};
+use crate::state::{MintIndex, Pool, POOL_AUTHORITY_SEED};
 use crate::error::ErrorCode;
+use crate::events::TokenRemovedEvent;
 
 /// 从 pool 中移除质押类型
 #[derive(Accounts)]
@@ -9,28 +10,65 @@ pub struct RemoveTokenFromPool<'info> {
     #[account(mut)]
     pub pool: AccountLoader<'info, Pool>,
 
-    /// 要移除的 LP mint 账户
-    pub lp_mint: Account<'info, Mint>,
+    /// Pool authority PDA - LP mint 的 mint authority，移除时放弃该权限
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 要移除的 LP mint 账户 - mint authority 会被设置为 None
+    #[account(mut, mint::token_program = token_program)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    /// 被移除 item 的 mint → index 映射（可选）- 传入时 O(1) 定位并关闭，租金退还给 admin
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"mint_index", pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump = mint_index.bump,
+    )]
+    pub mint_index: Option<Account<'info, MintIndex>>,
+
+    /// 被移动到空位的最后一个 item 的映射 - 移除的不是最后一个 item 且其存在映射时需要传入
+    #[account(
+        mut,
+        has_one = pool,
+        seeds = [b"mint_index", pool.key().as_ref(), moved_mint_index.lp_mint.as_ref()],
+        bump = moved_mint_index.bump,
+    )]
+    pub moved_mint_index: Option<Account<'info, MintIndex>>,
 
     /// Pool 管理员 - 必须签名
+    #[account(mut)]
     pub admin: Signer<'info>,
+
+    /// LP mint 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 /// 从 pool 中移除质押类型
 /// 注意：移除前需要确保该类型的 LP 已全部销毁（mint_amount = 0）
+/// 移除后 LP mint 的 mint authority 被设置为 None，该 mint 不能再被铸造或重新使用
 pub fn remove_token_from_pool(ctx: Context<RemoveTokenFromPool>) -> Result<()> {
-    let pool = &mut ctx.accounts.pool.load_mut()?;
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
 
     // 验证管理员权限
-    pool.verify_admin(&ctx.accounts.admin.key())?;
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后必须通过 queue_action 执行
+    pool.verify_direct_admin_action()?;
 
     let lp_mint_key = ctx.accounts.lp_mint.key();
-    let token_index = pool.find_token_index(&lp_mint_key)
-        .ok_or(ErrorCode::InvalidTokenMint)?;
-
-    // 检查是否是最后一个 token
-    let token_count = pool.get_token_count();
-    require!(token_count > 0, ErrorCode::InvalidTokenCount);
+    let token_index = match ctx.accounts.mint_index.as_ref() {
+        Some(mint_index) => mint_index.resolve(pool)?,
+        None => pool.find_token_index(&lp_mint_key)
+            .ok_or(ErrorCode::InvalidTokenMint)?,
+    };
 
     // 验证 LP mint 地址是否匹配
     let token_item = pool.get_token(token_index).ok_or(ErrorCode::InvalidTokenIndex)?;
@@ -39,30 +77,65 @@ pub fn remove_token_from_pool(ctx: Context<RemoveTokenFromPool>) -> Result<()> {
         ErrorCode::InvalidTokenMint
     );
 
-    // 检查该类型的 LP 是否已全部销毁
-    require!(
-        token_item.get_mint_amount() == 0,
-        ErrorCode::InsufficientTokenAmount
-    );
+    let moved_from = pool.remove_token(token_index)?;
 
-    // 如果是最后一个 token，直接减少计数
-    if token_index == token_count - 1 {
-        pool.token_count -= 1;
-    } else {
-        // 如果不是最后一个，将最后一个 token 移动到当前位置
-        let last_index = token_count - 1;
-
-        // 先获取最后一个 token 的数据（通过索引访问，避免借用冲突）
-        let last_token_data = pool.tokens[last_index];
-
-        // 复制最后一个 token 到当前位置
-        pool.tokens[token_index] = last_token_data;
-
-        // 减少计数
-        pool.token_count -= 1;
+    // 更新被移动 item 的映射
+    if let (Some(_), Some(moved_mint_index)) = (moved_from, ctx.accounts.moved_mint_index.as_mut()) {
+        require!(
+            moved_mint_index.lp_mint == pool.tokens[token_index].mint_account,
+            ErrorCode::InvalidTokenMint
+        );
+        moved_mint_index.item_index = token_index as u16;
     }
 
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    revoke_lp_mint_authority(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.lp_mint,
+        &ctx.accounts.pool_authority,
+        &[&seeds[..]],
+    )?;
+
     msg!("Staking type removed from pool: lp_mint: {}", lp_mint_key);
+    emit!(TokenRemovedEvent {
+        pool: ctx.accounts.pool.key(),
+        item_index: token_index as u16,
+        lp_mint: lp_mint_key,
+        moved_from: moved_from.map(|index| index as u16),
+    });
     Ok(())
 }
 
+
+/// 放弃 LP mint 的 mint authority（设置为 None）
+/// mint authority 已不是 pool_authority（例如已被放弃）时跳过
+pub(crate) fn revoke_lp_mint_authority<'info>(
+    token_program: &AccountInfo<'info>,
+    lp_mint: &InterfaceAccount<'info, Mint>,
+    pool_authority: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    if lp_mint.mint_authority != Some(pool_authority.key()).into() {
+        return Ok(());
+    }
+    token_interface::set_authority(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            SetAuthority {
+                current_authority: pool_authority.clone(),
+                account_or_mint: lp_mint.to_account_info(),
+            },
+            signer,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+#[cfg(feature = "invariant-checks")]
+use crate::state::invariant::check_pool_invariants;
+
+/// 等比缩放所有 item 的 weight
+#[derive(Accounts)]
+pub struct RescaleWeights<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 将所有 item 的 weight 乘以 numerator / denominator
+/// 用于把 weight 超出 [MIN_ITEM_WEIGHT, MAX_ITEM_WEIGHT] 的旧 pool 迁移到 WEIGHT_SCALE 基准：
+/// 每个 weight 必须被整除，缩放后 item 之间的比例不变，兑换率和排放分配不受影响，
+/// 因此不受冷却期和单次变化幅度的限制；积分按新的 weight 累积
+/// remaining_accounts: 共同管理员签名者
+pub fn rescale_weights(
+    ctx: Context<RescaleWeights>,
+    numerator: u64,
+    denominator: u64,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后必须通过 queue_action 执行
+    pool.verify_direct_admin_action()?;
+
+    // 结算奖励排放（weight 变化前）
+    pool.accrue_emission(Clock::get()?.unix_timestamp)?;
+    pool.rescale_weights(numerator, denominator)?;
+
+    // 未传入 vault 账户，按记账余额校验各主币
+    #[cfg(feature = "invariant-checks")]
+    for asset_index in 0..pool.get_main_asset_count() {
+        check_pool_invariants(pool, asset_index as u8, None)?;
+    }
+
+    msg!("Weights rescaled: pool: {}, numerator: {}, denominator: {}, token_count: {}",
+         ctx.accounts.pool.key(),
+         numerator,
+         denominator,
+         pool.get_token_count());
+
+    Ok(())
+}
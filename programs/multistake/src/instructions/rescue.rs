@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Pool, PoolItem, PoolView, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::transfer::transfer_main_token;
+use std::ops::Deref;
+
+/// 提取误转入 pool authority 账户的其他代币
+#[derive(Accounts)]
+pub struct RescueTokens<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - 来源账户的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 来源账户 - owner 必须是 pool authority
+    #[account(
+        mut,
+        token::authority = pool_authority,
+        token::token_program = token_program,
+    )]
+    pub source: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 被提取的 mint
+    #[account(address = source.mint @ ErrorCode::InvalidTokenMint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 目标账户
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 积分奖励 vault 地址 - 已创建时其 mint 不能被提取
+    /// CHECK: 地址由 seeds 约束，只读取 token 账户的 mint 字段
+    #[account(seeds = [b"reward_vault", pool.key().as_ref()], bump)]
+    pub reward_vault: UncheckedAccount<'info>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    /// 被提取 mint 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 提取误转入 pool authority 账户的其他代币
+/// amount: 提取数量
+/// remaining_accounts: 共同管理员签名者；mint 启用 TransferHook 时追加 hook 需要的额外账户
+///
+/// 主币、LP、排放奖励币和积分奖励币不能提取；启用时间锁后必须通过 queue_action（RescueTokens）执行
+pub fn rescue_tokens<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RescueTokens<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后必须通过 queue_action 执行
+    pool.verify_direct_admin_action()?;
+
+    let pool_key = ctx.accounts.pool.key();
+    rescue_token_account(
+        &pool,
+        &pool_key,
+        &ctx.accounts.pool_authority,
+        ctx.bumps.pool_authority,
+        &ctx.accounts.source,
+        &ctx.accounts.mint,
+        &ctx.accounts.destination,
+        &ctx.accounts.reward_vault,
+        &ctx.accounts.token_program.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+    )
+}
+
+/// 校验并从 pool authority 的账户转出被误转入的代币（rescue_tokens 和时间锁 RescueTokens 共用）
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn rescue_token_account<'info>(
+    pool: &PoolView<impl Deref<Target = Pool>, impl Deref<Target = [PoolItem]>>,
+    pool_key: &Pubkey,
+    pool_authority: &AccountInfo<'info>,
+    authority_bump: u8,
+    source: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    reward_vault: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    additional_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidTokenCount);
+    require!(source.owner == pool_authority.key(), ErrorCode::InvalidRescueSource);
+    require!(
+        source.mint == mint.key() && destination.mint == mint.key(),
+        ErrorCode::InvalidTokenMint
+    );
+
+    // 主币、LP、排放奖励币由 pool 记录
+    pool.verify_rescuable_mint(&mint.key())?;
+    // 积分奖励 vault 已创建时，token 账户数据的前 32 字节为其 mint
+    if !reward_vault.data_is_empty() {
+        let data = reward_vault.try_borrow_data()?;
+        let reward_mint = data.get(..32)
+            .map(Pubkey::try_from)
+            .ok_or(ErrorCode::InvalidTokenMint)?
+            .map_err(|_| ErrorCode::InvalidTokenMint)?;
+        require!(reward_mint != mint.key(), ErrorCode::MintNotRescuable);
+    }
+
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[authority_bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    transfer_main_token(
+        token_program,
+        &source.to_account_info(),
+        &mint.to_account_info(),
+        &destination.to_account_info(),
+        pool_authority,
+        additional_accounts,
+        amount,
+        mint.decimals,
+        signer,
+    )?;
+
+    msg!("Tokens rescued: pool: {}, mint: {}, source: {}, destination: {}, amount: {}",
+         pool_key,
+         mint.key(),
+         source.key(),
+         destination.key(),
+         amount);
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use anchor_lang::system_program::{self, Transfer};
+use crate::state::{Pool, MAX_TOKENS};
+use crate::error::ErrorCode;
+
+/// 扩容 Pool 账户
+/// 程序升级新增字段或提高 item 容量后，已有 pool 通过该指令扩展账户空间
+#[derive(Accounts)]
+pub struct ResizePool<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    /// 补足新增空间所需的租金
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 扩容 Pool 账户
+/// new_size: 新的账户大小（字节，包含 discriminator）
+///
+/// 逻辑：
+/// 1. 校验 new_size 不小于当前大小，且单次增长不超过 10KB
+/// 2. payer 补足租金差额
+/// 3. realloc 账户，新增部分清零
+///
+/// 旧 pool 与目标大小的差距可能超过 10KB，需多次调用逐步扩容；
+/// v1 pool 的 item 超过原地迁移可容纳的数量（168 个）时，须先扩容到 Pool::space_for(token_count) 再迁移
+pub fn resize_pool(ctx: Context<ResizePool>, new_size: u64) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+
+    // 升级前的旧账户可能小于当前 Pool 布局，不能 load()，直接读取 admin 字段验证权限
+    let admin = Pool::read_admin(&pool_info.try_borrow_data()?)?;
+    require!(
+        ctx.accounts.admin.key() == admin,
+        ErrorCode::InvalidAdmin
+    );
+
+    let current_size = pool_info.data_len();
+    let new_size = usize::try_from(new_size).map_err(|_| ErrorCode::InvalidAccountSize)?;
+
+    // 只允许扩容
+    require!(
+        new_size >= current_size,
+        ErrorCode::InvalidAccountSize
+    );
+    require!(
+        new_size - current_size <= MAX_PERMITTED_DATA_INCREASE,
+        ErrorCode::InvalidAccountSize
+    );
+
+    // payer 补足租金差额
+    let lamports_delta = fund_rent(&ctx.accounts.payer, &pool_info, &ctx.accounts.system_program, new_size)?;
+
+    pool_info.resize(new_size)?;
+
+    msg!("Pool resized: pool: {}, old_size: {}, new_size: {}, rent_paid: {}",
+         ctx.accounts.pool.key(),
+         current_size,
+         new_size,
+         lamports_delta);
+
+    Ok(())
+}
+
+/// 增加 Pool 的 item 容量
+#[derive(Accounts)]
+pub struct GrowPool<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    /// 补足新增 item 空间所需的租金
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 增加 Pool 的 item 容量
+/// additional: 新增的 item 槽位数量
+///
+/// 逻辑：
+/// 1. 验证管理员权限，新容量不超过 MAX_TOKENS
+/// 2. payer 补足租金差额，realloc 账户到 Pool::space_for(新容量)，单次增长不超过 10KB（约 75 个 item）
+/// 3. 写入新的 capacity
+///
+/// pool 可以从 Small 档位创建，随质押类型增加分批扩容
+pub fn grow_pool(ctx: Context<GrowPool>, additional: u16) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+
+    let old_capacity = {
+        let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+        // 验证管理员权限
+        pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+        pool.get_capacity()
+    };
+
+    require!(additional > 0, ErrorCode::InvalidAccountSize);
+    let new_capacity = old_capacity + additional as usize;
+    require!(new_capacity <= MAX_TOKENS, ErrorCode::MaxTokensReached);
+
+    // migrate_pool_v2 或 resize_pool 可能留下多余空间，只在不足时 realloc
+    let current_size = pool_info.data_len();
+    let new_size = Pool::space_for(new_capacity).max(current_size);
+    require!(
+        new_size - current_size <= MAX_PERMITTED_DATA_INCREASE,
+        ErrorCode::InvalidAccountSize
+    );
+
+    let lamports_delta = fund_rent(&ctx.accounts.payer, &pool_info, &ctx.accounts.system_program, new_size)?;
+    // 新增空间在入口处已清零，新槽位即为空 item
+    pool_info.resize(new_size)?;
+
+    let mut pool = Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    pool.capacity = new_capacity as u16;
+
+    msg!("Pool grown: pool: {}, old_capacity: {}, new_capacity: {}, size: {}, rent_paid: {}",
+         ctx.accounts.pool.key(),
+         old_capacity,
+         new_capacity,
+         new_size,
+         lamports_delta);
+
+    Ok(())
+}
+
+/// payer 补足 pool 账户扩容到 new_size 所需的租金差额，返回实际转入的 lamports
+fn fund_rent<'info>(
+    payer: &Signer<'info>,
+    pool_info: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    new_size: usize,
+) -> Result<u64> {
+    let required_lamports = Rent::get()?.minimum_balance(new_size);
+    let lamports_delta = required_lamports.saturating_sub(pool_info.lamports());
+    if lamports_delta > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: pool_info.clone(),
+                },
+            ),
+            lamports_delta,
+        )?;
+    }
+    Ok(lamports_delta)
+}
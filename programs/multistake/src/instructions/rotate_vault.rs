@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+use crate::state::{Pool, VAULT_ROTATION_DELAY_SECONDS, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::transfer::transfer_main_token;
+
+/// 提议轮换 pool vault（开始时间锁）
+#[derive(Accounts)]
+pub struct ProposeVaultRotation<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 提议轮换 pool vault
+/// 记录生效时间 now + VAULT_ROTATION_DELAY_SECONDS，重复提议会重置时间锁
+pub fn propose_vault_rotation(ctx: Context<ProposeVaultRotation>) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    pool.vault_rotation_eta = now
+        .checked_add(VAULT_ROTATION_DELAY_SECONDS)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    msg!("Vault rotation proposed: pool: {}, eta: {}",
+         ctx.accounts.pool.key(),
+         pool.vault_rotation_eta);
+
+    Ok(())
+}
+
+/// 执行 pool vault 轮换
+#[derive(Accounts)]
+pub struct RotatePoolVault<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - 新旧 vault 的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 主币 Mint - Token 或 Token-2022
+    #[account(
+        address = pool.load()?.pool_mint @ ErrorCode::InvalidTokenMint,
+        mint::token_program = token_program,
+    )]
+    pub main_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// 当前的 pool vault - 余额全部转出后关闭
+    #[account(
+        mut,
+        address = pool.load()?.pool_vault @ ErrorCode::InvalidPoolVault,
+    )]
+    pub old_pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 新的 pool vault - 使用下一代 vault_generation 作为 seed
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            b"pool_vault",
+            pool.key().as_ref(),
+            &[pool.load()?.get_vault_generation()
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?],
+        ],
+        bump,
+        token::mint = main_token_mint,
+        token::authority = pool_authority,
+        token::token_program = token_program,
+    )]
+    pub new_pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    /// 支付新 vault 租金，并接收旧 vault 关闭后的租金
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 执行 pool vault 轮换
+///
+/// 逻辑：
+/// 1. 验证时间锁已到期
+/// 2. 通过 pool authority 将旧 vault 的全部余额转入新 vault
+/// 3. 关闭旧 vault，租金退还给 payer
+/// 4. 更新 pool.pool_vault 和 vault_generation
+///
+/// remaining_accounts: 共同管理员签名者；主币启用 TransferHook 时追加 hook 需要的额外账户
+pub fn rotate_pool_vault<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RotatePoolVault<'info>>,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    // 验证时间锁
+    require!(pool.vault_rotation_eta != 0, ErrorCode::NoPendingAction);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= pool.vault_rotation_eta, ErrorCode::TimelockNotElapsed);
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    // 1. 转移旧 vault 的全部余额
+    let balance = ctx.accounts.old_pool_vault.amount;
+    if balance > 0 {
+        transfer_main_token(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.old_pool_vault.to_account_info(),
+            &ctx.accounts.main_token_mint.to_account_info(),
+            &ctx.accounts.new_pool_vault.to_account_info(),
+            &ctx.accounts.pool_authority,
+            ctx.remaining_accounts,
+            balance,
+            ctx.accounts.main_token_mint.decimals,
+            signer,
+        )?;
+    }
+
+    // 2. 关闭旧 vault
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.old_pool_vault.to_account_info(),
+            destination: ctx.accounts.payer.to_account_info(),
+            authority: ctx.accounts.pool_authority.to_account_info(),
+        },
+        signer,
+    ))?;
+
+    // 3. 更新 pool 记录
+    let new_vault_key = ctx.accounts.new_pool_vault.key();
+    pool.set_pool_vault(&new_vault_key);
+    pool.vault_generation = pool.vault_generation
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.vault_rotation_eta = 0;
+
+    msg!("Pool vault rotated: pool: {}, old_vault: {}, new_vault: {}, generation: {}, balance: {}",
+         pool_key,
+         ctx.accounts.old_pool_vault.key(),
+         new_vault_key,
+         pool.vault_generation,
+         balance);
+
+    Ok(())
+}
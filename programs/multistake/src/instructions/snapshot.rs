@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::state::{Pool, PoolSnapshot};
+use crate::error::ErrorCode;
+
+/// 生成当前 epoch 的 LP 发行量和权重快照
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct Snapshot<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 快照 PDA - 每个 epoch 创建一次，账户大小按当前质押类型数量计算
+    #[account(
+        init,
+        payer = payer,
+        space = PoolSnapshot::space_for(&*pool.load()?)?,
+        seeds = [
+            b"snapshot",
+            pool.key().as_ref(),
+            &epoch.to_le_bytes(),
+        ],
+        bump,
+        constraint = epoch == Clock::get()?.epoch @ ErrorCode::InvalidSnapshotEpoch,
+    )]
+    pub snapshot: Box<Account<'info, PoolSnapshot>>,
+
+    /// 任何人都可以调用并支付创建费用
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 生成当前 epoch 的快照（任何人都可以调用）
+/// epoch: 当前 epoch，作为快照 PDA 的 seed，与链上时钟不一致时拒绝
+/// 记录每个 item 的 LP mint、mint_amount 和 weight，供外部治理程序在固定时间点计算加权投票权重
+pub fn snapshot(ctx: Context<Snapshot>, epoch: u64) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+    let snapshot = &mut ctx.accounts.snapshot;
+    snapshot.pool = ctx.accounts.pool.key();
+    snapshot.epoch = epoch;
+    snapshot.slot = Clock::get()?.slot;
+    snapshot.bump = ctx.bumps.snapshot;
+    snapshot.capture(&pool)?;
+
+    msg!("Snapshot: pool: {}, epoch: {}, slot: {}, token_count: {}",
+         snapshot.pool,
+         snapshot.epoch,
+         snapshot.slot,
+         snapshot.items.len());
+
+    Ok(())
+}
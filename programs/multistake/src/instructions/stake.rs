@@ -1,11 +1,22 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo, Transfer};
-use crate::state::Pool;
+use anchor_spl::token_interface::{self, MintToChecked, TokenInterface};
+use anchor_lang::solana_program::program::set_return_data;
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+use crate::state::delegation::{DELEGATE_STAKE, DELEGATION_SEED};
+use crate::state::{Delegation, Pool, ReferralAccount, ReferralCode, StakeRecord, LP_MINT_DECIMALS, POOL_AUTHORITY_SEED};
 use crate::error::ErrorCode;
+use super::rate_weight::apply_rate_weights;
+#[cfg(feature = "invariant-checks")]
+use crate::state::invariant::check_pool_invariants;
+use crate::events::{StakeEvent, VaultReconciledEvent};
+use crate::transfer::{received_amount, transfer_main_token, verify_vault_inflow};
+use crate::state::program_config::PROGRAM_CONFIG_SEED;
+use super::delegation::verify_owner_or_delegate;
+use super::program_config::split_protocol_fee;
 
 /// 质押主币，铸造 LP 凭证
 #[derive(Accounts)]
-#[instruction(item_index: u16)]
+#[instruction(item_index: u16, stake_amount: u64, tag: Option<[u8; 32]>, referrer: Option<Pubkey>)]
 pub struct Stake<'info> {
     #[account(mut)]
     pub pool: AccountLoader<'info, Pool>,
@@ -13,54 +24,212 @@ pub struct Stake<'info> {
     /// Pool authority PDA - LP mint 的 authority
     /// CHECK: PDA derived from pool key
     #[account(
-        seeds = [b"anyswap_authority", pool.key().as_ref()],
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
         bump
     )]
     pub pool_authority: AccountInfo<'info>,
 
-    /// Pool 的主币 Vault
+    /// item 对应的主币 Vault（单资产模式下即 pool_vault）
     #[account(
         mut,
-        seeds = [b"pool_vault", pool.key().as_ref()],
-        bump,
+        address = Pool::load_versioned(&pool)?.get_item_vault(item_index as usize)? @ ErrorCode::InvalidPoolVault,
     )]
-    pub pool_vault: Box<Account<'info, TokenAccount>>,
+    pub pool_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-    /// LP mint - 对应的质押类型
-    /// 通过 pool.get_token() 验证地址是否匹配
-    #[account(mut)]
-    pub lp_mint: Box<Account<'info, Mint>>,
+    /// 主币 Mint - Token 或 Token-2022
+    #[account(address = pool_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub main_token_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// LP mint - 对应的质押类型（Token 或 Token-2022）
+    /// 地址必须与 item 登记的 mint 一致，且由 pool authority 铸造
+    #[account(
+        mut,
+        address = Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)? @ ErrorCode::InvalidTokenMint,
+        mint::authority = pool_authority,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     /// 用户的主币账户（质押来源）
-    #[account(mut)]
-    pub user_main_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = main_token_mint,
+        token::authority = user,
+    )]
+    pub user_main_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     /// 用户的 LP 凭证账户（铸造目标）
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_lp_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 用户 - 本人签名，或由 delegate 持 delegation 代为操作
+    /// 本人签名时首次 stake 创建质押记录和推荐人返佣账户的租金由用户支付，代理操作时这些账户需已存在
+    /// CHECK: 在指令中验证签名或 delegation
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+
+    /// 代理签名（可选）- 用户未签名时必须提供
+    pub delegate: Option<Signer<'info>>,
+
+    /// 用户的代理授权（可选）- 用户未签名时必须提供，且需拥有 stake 权限
+    #[account(
+        seeds = [DELEGATION_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump = delegation.bump,
+    )]
+    pub delegation: Option<Box<Account<'info, Delegation>>>,
+
+    /// 用户的质押记录 - 结算积分和排放奖励并累加铸造的 LP，不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeRecord::space(),
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            lp_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// treasury LP 账户（可选）- pool 启用 fee_in_lp 时必须传入，接收手续费对应的 LP
+    #[account(
+        mut,
+        seeds = [b"treasury_lp", pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury_lp_token: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 推荐码（可选）- 客户端按 ["referral", code] 推导
+    #[account(
+        seeds = [b"referral", referral.code.as_slice()],
+        bump = referral.bump,
+    )]
+    pub referral: Option<Box<Account<'info, ReferralCode>>>,
+
+    /// 推荐人收款账户 - 传入 referral 时必须提供，且与推荐码登记的账户一致
+    #[account(mut)]
+    pub referrer_fee_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 推荐人返佣账户 - 传入 referrer 参数时必须提供，不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = ReferralAccount::space(),
+        seeds = [
+            b"referral_account",
+            pool.key().as_ref(),
+            referrer.unwrap_or_default().as_ref(),
+        ],
+        bump,
+    )]
+    pub referral_account: Option<Box<Account<'info, ReferralAccount>>>,
+
+    /// 手续费 vault - pool 已创建 fee_vault 且手续费不以 LP 形式收取时必须传入
+    #[account(
+        mut,
+        address = pool.load()?.fee_vault @ ErrorCode::InvalidFeeConfig,
+    )]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 全局程序配置 - 尚未创建时不抽取协议分成
+    /// CHECK: 地址由 seeds 约束，由 ProgramConfig::load_optional 校验 owner 后反序列化
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+
+    /// 协议金库的主币账户 - 全局配置设置了协议分成且有手续费转入 fee_vault 时必须传入
     #[account(mut)]
-    pub user_lp_token: Box<Account<'info, TokenAccount>>,
+    pub protocol_treasury_token: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
 
-    /// 用户签名
-    pub user: Signer<'info>,
+    /// Instructions sysvar - pool 启用调用方白名单时用于识别 CPI 调用方
+    /// CHECK: 地址约束为 instructions sysvar
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
 
-    pub token_program: Program<'info, Token>,
+    /// LP 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
 }
 
 /// 质押主币，铸造 LP 凭证
 /// item_index: 质押类型索引
 /// stake_amount: 质押的主币数量
+/// tag: 可选的 32 字节标签，只写入日志不存储，便于托管方关联内部订单号
+/// referrer: 可选的推荐人，返佣转入 fee_vault 并记入其 ReferralAccount，不能与推荐码同时使用
+/// whitelist_proof: 许可池中 user 的白名单 merkle 证明，非许可池传空
+/// remaining_accounts:
+/// - 前 2 × N 个：同一主币下每个配置了兑换率来源的 item 一组 [item_rate_source, source]，N 为这类 item 的数量
+/// - 之后：主币 mint 启用 TransferHook 时，hook 需要的额外账户
+///
+/// 用户未签名时由 delegate 按 delegation 的 stake 权限代为操作，主币由 delegation PDA 从用户账户转出，LP 仍铸造给用户
 ///
 /// 逻辑：
-/// 1. 用户转移主币到 pool_vault
-/// 2. 铸造等量的 LP 凭证给用户（1:1）
-/// 3. 更新 item 的 mint_amount
-pub fn stake(
-    ctx: Context<Stake>,
+/// 1. 用户转移主币到 pool_vault，按 stake_fee_bps 收取手续费，其中 protocol_fee_bps 部分转入 fee_vault
+///    （pool 已创建 fee_vault 时），其余留在 vault 中归 LP 持有人；全局配置设置了协议分成时，
+///    转入 fee_vault 的部分再按 protocol_fee_share_bps 转给协议金库
+/// 2. 扣除手续费后的主币按兑换率折算为 LP 铸造给用户（主币的 TransferFee 扩展手续费也从中扣除）
+///    lp = amount × total_weighted / (vault_balance × weight)，该主币下尚无 LP 时 1:1
+/// 3. 启用 fee_in_lp 时，协议手续费部分按兑换率铸造为 LP 存入 treasury LP 账户（协议持有，自动复利）
+/// 4. 更新 item 的 mint_amount
+pub fn stake<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Stake<'info>>,
     item_index: u16,
     stake_amount: u64,
+    tag: Option<[u8; 32]>,
+    referrer: Option<Pubkey>,
+    whitelist_proof: Vec<[u8; 32]>,
 ) -> Result<()> {
     require!(stake_amount > 0, ErrorCode::InvalidTokenCount);
 
-    let pool = &mut ctx.accounts.pool.load_mut()?;
+    // 用户本人签名，或代理持 stake 权限
+    let delegated = verify_owner_or_delegate(
+        &ctx.accounts.user,
+        ctx.accounts.delegate.as_ref(),
+        ctx.accounts.delegation.as_deref(),
+        DELEGATE_STAKE,
+    )?;
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 暂停状态检查
+    pool.verify_stake_allowed()?;
+    pool.verify_min_stake_amount(stake_amount)?;
+
+    // 验证调用方策略
+    pool.caller_allowlist.verify_caller(
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+    )?;
+
+    // 许可池白名单检查
+    pool.verify_whitelisted(&ctx.accounts.user.key(), &whitelist_proof)?;
+
+    // 结算奖励排放（LP 数量变化前），再按衰减计划更新 weight
+    let now = Clock::get()?.unix_timestamp;
+    pool.accrue_emission(now)?;
+    pool.apply_weight_schedules(item_index as usize, now)?;
+    // 按兑换率来源刷新 weight，之后的 remaining_accounts 为 TransferHook 额外账户
+    let hook_accounts = apply_rate_weights(
+        pool,
+        &ctx.accounts.pool.key(),
+        item_index as usize,
+        ctx.remaining_accounts,
+        &Clock::get()?,
+    )?;
 
     // 验证 item_index 有效
     require!(
@@ -68,65 +237,337 @@ pub fn stake(
         ErrorCode::InvalidTokenIndex
     );
 
-    // 验证 LP mint 地址匹配
+    // LP mint 地址已由账户约束验证
     let item = pool.get_token(item_index as usize)
         .ok_or(ErrorCode::InvalidTokenIndex)?;
-    require!(
-        ctx.accounts.lp_mint.key() == *item.mint_pubkey(),
-        ErrorCode::InvalidTokenMint
-    );
-
-    // 计算手续费
-    let (fee_amount, amount_after_fee) = pool.calculate_fee(stake_amount)?;
+    // 停用的 item 不接受新的质押
+    item.verify_active()?;
+    let asset_index = item.get_main_asset_index();
+    // 主币精度必须与创建时记录的一致
+    let decimals = pool.verify_asset_decimals(asset_index, ctx.accounts.main_token_mint.decimals)?;
+    let effective_balance =
+        pool.effective_vault_balance(asset_index, ctx.accounts.pool_vault.amount)?;
 
-    // 1. 用户转移全额主币到 pool_vault
-    token::transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user_main_token.to_account_info(),
-                to: ctx.accounts.pool_vault.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            },
-        ),
-        stake_amount,
+    // 计算手续费，传入推荐码或推荐人时按 referral_share_bps 分出推荐返佣
+    let (fee_amount, amount_after_fee) = pool.calculate_stake_fee(stake_amount)?;
+    let (referral_amount, fee_amount) = match (ctx.accounts.referral.as_ref(), referrer) {
+        (Some(_), Some(_)) => return err!(ErrorCode::InvalidReferralAccount),
+        (Some(referral), None) => {
+            let fee_account = ctx.accounts.referrer_fee_account.as_ref()
+                .ok_or(ErrorCode::InvalidReferralAccount)?;
+            require!(
+                fee_account.key() == referral.fee_account
+                    && fee_account.mint == ctx.accounts.pool_vault.mint,
+                ErrorCode::InvalidReferralAccount
+            );
+            pool.calculate_referral_share(fee_amount)?
+        }
+        (None, Some(referrer)) => {
+            require!(
+                referrer != ctx.accounts.user.key() && ctx.accounts.referral_account.is_some(),
+                ErrorCode::InvalidReferralAccount
+            );
+            // 返佣暂存在 fee_vault 中，等待推荐人领取
+            require!(pool.has_fee_vault(asset_index), ErrorCode::InvalidFeeConfig);
+            pool.calculate_referral_share(fee_amount)?
+        }
+        (None, None) => (0, fee_amount),
+    };
+    // 推荐返佣之后的剩余手续费按 protocol_fee_bps 拆分，LP 部分留在 vault 中计入兑换率
+    let (protocol_fee, _) = pool.calculate_protocol_fee(fee_amount)?;
+    // 转入 fee_vault 的协议手续费
+    let vault_fee = if pool.routes_fee_to_vault(asset_index) { protocol_fee } else { 0 };
+    // 按全局配置从中抽取协议金库的分成
+    let (treasury_fee, vault_fee) = split_protocol_fee(
+        &ctx.accounts.program_config,
+        ctx.accounts.protocol_treasury_token.as_deref(),
+        &ctx.accounts.pool_vault.mint,
+        vault_fee,
     )?;
+    let vault_amount = stake_amount
+        .checked_sub(referral_amount)
+        .and_then(|amount| amount.checked_sub(vault_fee))
+        .and_then(|amount| amount.checked_sub(treasury_fee))
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // 主币启用 TransferFee 扩展时 vault 实际到账少于转出数量，差额从用户的 LP 中扣除
+    let main_token_mint_info = ctx.accounts.main_token_mint.to_account_info();
+    let vault_received = received_amount(&main_token_mint_info, vault_amount)?;
+    let transfer_fee = vault_amount
+        .checked_sub(vault_received)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let amount_after_fee = amount_after_fee
+        .checked_sub(transfer_fee)
+        .ok_or(ErrorCode::InsufficientTokenAmount)?;
+    require!(amount_after_fee > 0, ErrorCode::InsufficientTokenAmount);
+
+    // 按存入前的兑换率折算 LP；手续费以 LP 形式收取时同样按兑换率折算
+    let pool_vault_balance = ctx.accounts.pool_vault.amount;
+    let lp_amount = pool.calculate_stake_lp_amount(amount_after_fee, item_index as usize, pool_vault_balance)?;
+    require!(lp_amount > 0, ErrorCode::InsufficientTokenAmount);
+    let fee_lp_amount = if pool.is_fee_in_lp() && protocol_fee > 0 {
+        pool.calculate_stake_lp_amount(protocol_fee, item_index as usize, pool_vault_balance)?
+    } else {
+        0
+    };
 
-    // 2. 铸造扣除手续费后的 LP 凭证给用户
+    // 记账前先与 vault 实际余额对账，记录外部捐赠或短缺
     let pool_key = ctx.accounts.pool.key();
+    reconcile_vault_balance(pool, &pool_key, asset_index, pool_vault_balance)?;
+
+    // 先完成全部记账，再执行外部 CPI：Token-2022 transfer hook 等回调观察到的 pool 状态已是最终状态
+    let fee_lp_minted = fee_lp_amount;
+    if fee_lp_minted > 0 {
+        require!(ctx.accounts.treasury_lp_token.is_some(), ErrorCode::InvalidFeeConfig);
+    }
+
+    // 1. 更新 item 的 mint_amount（记录实际铸造的全部 LP）
+    pool.add_item_mint_amount(
+        item_index as usize,
+        lp_amount
+            .checked_add(fee_lp_minted)
+            .ok_or(ErrorCode::MathOverflow)?,
+    )?;
+    let item_mut = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+
+    // 2. 结算积分并更新质押记录（首次 stake 时初始化）
+    let clock = Clock::get()?;
+    let record = &mut ctx.accounts.stake_record;
+    if record.is_uninitialized() {
+        record.initialize(
+            &pool_key,
+            &ctx.accounts.user.key(),
+            item_mut.mint_pubkey(),
+            ctx.bumps.stake_record,
+            clock.slot,
+        );
+    }
+    record.accrue_points(item_mut.get_weight(), clock.slot)?;
+    record.settle_rewards(item_mut.get_reward_per_share())?;
+    record.add_staked_amount(lp_amount)?;
+    record.stake_timestamp = clock.unix_timestamp;
+
+    // 3. 记录手续费和推荐返佣
+    if vault_fee > 0 {
+        require!(ctx.accounts.fee_vault.is_some(), ErrorCode::InvalidFeeConfig);
+        pool.add_accumulated_fees(received_amount(&main_token_mint_info, vault_fee)?)?;
+    }
+    if let (Some(referrer), true) = (referrer, referral_amount > 0) {
+        require!(ctx.accounts.fee_vault.is_some(), ErrorCode::InvalidFeeConfig);
+        let received = received_amount(&main_token_mint_info, referral_amount)?;
+        let referral_account = ctx.accounts.referral_account.as_mut()
+            .ok_or(ErrorCode::InvalidReferralAccount)?;
+        if referral_account.is_uninitialized() {
+            referral_account.pool = pool_key;
+            referral_account.referrer = referrer;
+            referral_account.bump = ctx.bumps.referral_account.unwrap_or_default();
+        }
+        referral_account.accrue(received)?;
+        pool.add_referral_fees_owed(received)?;
+    }
+
+    // 4. 更新 vault 记账余额，仍有 LP 时不得低于最小值
+    let tracked_balance = effective_balance
+        .checked_add(vault_received)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.set_tracked_balance(asset_index, tracked_balance)?;
+    pool.verify_min_vault_balance(asset_index)?;
+
+    // 5. 用户转移主币到 pool_vault（推荐返佣部分直接转给推荐人，手续费转入 fee_vault）
+    // 代理操作时由 delegation PDA 作为 approve 的代理签名转出
+    let user_key = ctx.accounts.user.key();
+    let delegation_bump = [ctx.accounts.delegation.as_ref().map_or(0, |delegation| delegation.bump)];
+    let delegation_seeds: &[&[u8]] = &[DELEGATION_SEED, pool_key.as_ref(), user_key.as_ref(), &delegation_bump];
+    let (transfer_authority, transfer_signer): (AccountInfo<'info>, &[&[&[u8]]]) = match ctx.accounts.delegation.as_ref() {
+        Some(delegation) if delegated => (delegation.to_account_info(), &[delegation_seeds]),
+        _ => (ctx.accounts.user.to_account_info(), &[]),
+    };
+    transfer_main_token(
+        &ctx.accounts.main_token_program.to_account_info(),
+        &ctx.accounts.user_main_token.to_account_info(),
+        &ctx.accounts.main_token_mint.to_account_info(),
+        &ctx.accounts.pool_vault.to_account_info(),
+        &transfer_authority,
+        hook_accounts,
+        vault_amount,
+        decimals,
+        transfer_signer,
+    )?;
+
+    if vault_fee > 0 {
+        let fee_vault = ctx.accounts.fee_vault.as_ref()
+            .ok_or(ErrorCode::InvalidFeeConfig)?;
+        transfer_main_token(
+            &ctx.accounts.main_token_program.to_account_info(),
+            &ctx.accounts.user_main_token.to_account_info(),
+            &ctx.accounts.main_token_mint.to_account_info(),
+            &fee_vault.to_account_info(),
+            &transfer_authority,
+            hook_accounts,
+            vault_fee,
+            decimals,
+            transfer_signer,
+        )?;
+    }
+
+    if treasury_fee > 0 {
+        let protocol_treasury_token = ctx.accounts.protocol_treasury_token.as_ref()
+            .ok_or(ErrorCode::InvalidProtocolTreasury)?;
+        transfer_main_token(
+            &ctx.accounts.main_token_program.to_account_info(),
+            &ctx.accounts.user_main_token.to_account_info(),
+            &ctx.accounts.main_token_mint.to_account_info(),
+            &protocol_treasury_token.to_account_info(),
+            &transfer_authority,
+            hook_accounts,
+            treasury_fee,
+            decimals,
+            transfer_signer,
+        )?;
+    }
+
+    if referral_amount > 0 {
+        // 传入推荐人时返佣转入 fee_vault（已记入 ReferralAccount），否则直接转给推荐码的收款账户
+        let referral_destination = match referrer {
+            Some(_) => ctx.accounts.fee_vault.as_ref()
+                .ok_or(ErrorCode::InvalidFeeConfig)?
+                .to_account_info(),
+            None => ctx.accounts.referrer_fee_account.as_ref()
+                .ok_or(ErrorCode::InvalidReferralAccount)?
+                .to_account_info(),
+        };
+        transfer_main_token(
+            &ctx.accounts.main_token_program.to_account_info(),
+            &ctx.accounts.user_main_token.to_account_info(),
+            &ctx.accounts.main_token_mint.to_account_info(),
+            &referral_destination,
+            &transfer_authority,
+            hook_accounts,
+            referral_amount,
+            decimals,
+            transfer_signer,
+        )?;
+    }
+
+    // 6. 铸造扣除手续费后的 LP 凭证给用户
     let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
     let seeds = &[
-        b"anyswap_authority",
+        POOL_AUTHORITY_SEED,
         pool_key.as_ref(),
+        authority_seed,
         &[bump],
     ];
     let signer = &[&seeds[..]];
 
-    token::mint_to(
+    token_interface::mint_to_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            MintTo {
+            MintToChecked {
                 mint: ctx.accounts.lp_mint.to_account_info(),
                 to: ctx.accounts.user_lp_token.to_account_info(),
                 authority: ctx.accounts.pool_authority.to_account_info(),
             },
             signer,
         ),
-        amount_after_fee,
+        lp_amount,
+        LP_MINT_DECIMALS,
     )?;
 
-    // 3. 更新 item 的 mint_amount（只记录扣除手续费后的数量）
-    let item_mut = pool.get_token_mut(item_index as usize)
-        .ok_or(ErrorCode::InvalidTokenIndex)?;
-    item_mut.add_mint_amount(amount_after_fee)?;
+    // 7. 手续费以 LP 形式收取：铸造给 treasury，vault 仍然足额支撑所有 LP
+    if fee_lp_minted > 0 {
+        let treasury_lp_token = ctx.accounts.treasury_lp_token.as_ref()
+            .ok_or(ErrorCode::InvalidFeeConfig)?;
+        token_interface::mint_to_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintToChecked {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: treasury_lp_token.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer,
+            ),
+            fee_lp_minted,
+            LP_MINT_DECIMALS,
+        )?;
+    }
+
+    // 8. 重新读取 vault，实际到账必须与记账一致
+    ctx.accounts.pool_vault.reload()?;
+    verify_vault_inflow(pool_vault_balance, ctx.accounts.pool_vault.amount, vault_received)?;
+
+    #[cfg(feature = "invariant-checks")]
+    {
+        check_pool_invariants(pool, asset_index, Some(ctx.accounts.pool_vault.amount))?;
+    }
 
-    msg!("Staked: user: {}, item_index: {}, amount: {}, fee: {}, lp_minted: {}",
+    msg!("Staked: user: {}, item_index: {}, amount: {}, fee: {}, referral: {}, lp_minted: {}, fee_lp_minted: {}",
          ctx.accounts.user.key(),
          item_index,
          stake_amount,
          fee_amount,
-         amount_after_fee);
+         referral_amount,
+         lp_amount,
+         fee_lp_minted);
+    if let Some(referrer) = referrer {
+        msg!("Stake referrer: {}", referrer);
+    }
+    if treasury_fee > 0 {
+        msg!("Stake protocol fee share: {}", treasury_fee);
+    }
+    if let Some(tag) = tag.as_ref() {
+        msg!("Stake tag: {}", format_tag(tag));
+    }
+    emit!(StakeEvent {
+        pool: pool_key,
+        user: ctx.accounts.user.key(),
+        item_index,
+        amount: stake_amount,
+        fee: fee_amount,
+        referral_amount,
+        referrer,
+        lp_minted: lp_amount,
+        fee_lp_minted,
+        tag,
+    });
+
+    // 铸造给用户的 LP 数量写入 return data（Borsh 编码的 u64），供 CPI 调用方读取
+    set_return_data(&lp_amount.try_to_vec()?);
+
+    Ok(())
+}
 
+/// vault 实际余额与记账余额不一致时发出 VaultReconciledEvent
+/// 捐赠部分按 max_donation_bps 逐步计入兑换率，短缺由全体 LP 按比例承担，此处只记录不拒绝
+pub(crate) fn reconcile_vault_balance(
+    pool: &Pool,
+    pool_key: &Pubkey,
+    asset_index: u8,
+    vault_balance: u64,
+) -> Result<()> {
+    let discrepancy = pool.vault_balance_discrepancy(asset_index, vault_balance)?;
+    if discrepancy != 0 {
+        let tracked_balance = pool.get_tracked_balance(asset_index)?;
+        let actual_balance = pool.asset_total_balance(asset_index, vault_balance)?;
+        msg!("Vault reconciled: pool: {}, asset_index: {}, tracked: {}, actual: {}",
+             pool_key,
+             asset_index,
+             tracked_balance,
+             actual_balance);
+        emit!(VaultReconciledEvent {
+            pool: *pool_key,
+            asset_index,
+            tracked_balance,
+            actual_balance,
+        });
+    }
     Ok(())
 }
 
+/// 将 32 字节标签格式化为十六进制字符串
+pub(crate) fn format_tag(tag: &[u8; 32]) -> String {
+    tag.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
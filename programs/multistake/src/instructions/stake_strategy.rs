@@ -0,0 +1,522 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::sysvar::stake_history;
+use anchor_lang::system_program::{self, CreateAccount, Transfer as SystemTransfer};
+use anchor_spl::token::{self, spl_token::native_mint, CloseAccount, Mint, SyncNative, Token, TokenAccount, Transfer};
+use solana_stake_interface::instruction as stake_instruction;
+use solana_stake_interface::state::{Authorized, Lockup};
+use crate::state::{Pool, StakeStrategy, POOL_AUTHORITY_SEED};
+use crate::state::stake_strategy::STAKE_ACCOUNT_SPACE;
+use crate::state::asset::PRIMARY_ASSET_INDEX;
+use crate::state::oracle::BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+
+/// 配置原生质押策略
+#[derive(Accounts)]
+pub struct ConfigureStakeStrategy<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 质押策略 PDA - 首次配置时创建
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = StakeStrategy::space(),
+        seeds = [b"stake_strategy", pool.key().as_ref()],
+        bump
+    )]
+    pub stake_strategy: Box<Account<'info, StakeStrategy>>,
+
+    /// Pool 管理员 - 必须签名
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 配置原生质押策略（仅限主币为 WSOL 的 pool）
+/// vote_account: 委托的验证者投票账户，存在委托时不能更换
+/// max_delegation_bps: 最多可委托的比例（bps），0 表示停止新的委托
+pub fn configure_stake_strategy(
+    ctx: Context<ConfigureStakeStrategy>,
+    vote_account: Pubkey,
+    max_delegation_bps: u16,
+) -> Result<()> {
+    require!(
+        (max_delegation_bps as u64) <= BPS_DENOMINATOR,
+        ErrorCode::InvalidStakeStrategyConfig
+    );
+
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    require!(
+        *pool.get_pool_mint() == native_mint::ID,
+        ErrorCode::InvalidTokenMint
+    );
+
+    let strategy = &mut ctx.accounts.stake_strategy;
+    if strategy.pool == Pubkey::default() {
+        strategy.pool = ctx.accounts.pool.key();
+        strategy.bump = ctx.bumps.stake_strategy;
+    } else if strategy.vote_account != vote_account {
+        require!(
+            pool.strategy_delegated_amount == 0,
+            ErrorCode::StrategyStakeActive
+        );
+    }
+    strategy.vote_account = vote_account;
+    strategy.max_delegation_bps = max_delegation_bps;
+
+    msg!("Stake strategy configured: pool: {}, vote_account: {}, max_delegation_bps: {}",
+         strategy.pool,
+         vote_account,
+         max_delegation_bps);
+
+    Ok(())
+}
+
+/// 将 vault 中的部分 SOL 委托给验证者
+#[derive(Accounts)]
+pub struct DelegateVaultStake<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// 质押策略
+    #[account(
+        has_one = pool,
+        has_one = vote_account,
+        seeds = [b"stake_strategy", pool.key().as_ref()],
+        bump = stake_strategy.bump,
+    )]
+    pub stake_strategy: Box<Account<'info, StakeStrategy>>,
+
+    /// Pool authority PDA - vault owner，同时作为质押账户的 staker / withdrawer
+    /// 中转 SOL 时临时持有 lamports
+    /// CHECK: PDA derived from pool key
+    #[account(
+        mut,
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// Pool 的主币 Vault（WSOL）
+    #[account(
+        mut,
+        address = pool.load()?.pool_vault @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<Account<'info, TokenAccount>>,
+
+    /// 中转用的 WSOL 账户 - 本指令内创建并关闭
+    #[account(
+        init,
+        payer = admin,
+        token::mint = native_mint,
+        token::authority = pool_authority,
+        seeds = [b"strategy_wsol", pool.key().as_ref()],
+        bump
+    )]
+    pub strategy_wsol: Box<Account<'info, TokenAccount>>,
+
+    /// WSOL mint
+    #[account(address = native_mint::ID @ ErrorCode::InvalidTokenMint)]
+    pub native_mint: Box<Account<'info, Mint>>,
+
+    /// 策略质押账户 PDA - 本指令内创建，必须尚不存在
+    /// CHECK: PDA derived from pool key，由 stake 程序初始化
+    #[account(
+        mut,
+        seeds = [b"strategy_stake", pool.key().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// 验证者投票账户
+    /// CHECK: 地址由 stake_strategy.vote_account 约束，由 stake 程序校验
+    pub vote_account: UncheckedAccount<'info>,
+
+    /// Pool 管理员 - 必须签名，支付质押账户租金
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: 地址约束为 stake history sysvar
+    #[account(address = stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK: 地址约束为 stake config（stake 程序为兼容性仍要求传入）
+    #[account(address = stake_config_id())]
+    pub stake_config: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: 地址约束为 stake 程序
+    #[account(address = solana_stake_interface::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+/// 将 vault 中的部分 SOL 委托给验证者
+/// amount: 委托的 SOL 数量（lamports）
+///
+/// 逻辑：
+/// 1. 创建策略质押账户（管理员支付租金）
+/// 2. vault 的 WSOL 转入中转账户并关闭，SOL 经 pool authority 转入质押账户
+/// 3. 初始化质押账户并委托给验证者
+/// 4. 记录 strategy_delegated_amount，委托部分继续计入 vault 资产，兑换率不变
+pub fn delegate_vault_stake(ctx: Context<DelegateVaultStake>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidTokenCount);
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后不能再把 vault 资金委托出去
+    pool.verify_direct_admin_action()?;
+
+    // 单个质押账户：上一次委托完全提取后才能再次委托
+    require!(
+        pool.strategy_delegated_amount == 0 && ctx.accounts.stake_account.data_is_empty(),
+        ErrorCode::StrategyStakeActive
+    );
+    let total_balance = pool.asset_total_balance(PRIMARY_ASSET_INDEX, ctx.accounts.pool_vault.amount)?;
+    ctx.accounts.stake_strategy.verify_delegation_limit(amount, total_balance)?;
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+    let stake_bump = ctx.bumps.stake_account;
+    let stake_seeds = &[b"strategy_stake", pool_key.as_ref(), &[stake_bump]];
+
+    // 1. 创建质押账户
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.admin.to_account_info(),
+                to: ctx.accounts.stake_account.to_account_info(),
+            },
+            &[&stake_seeds[..]],
+        ),
+        ctx.accounts.rent.minimum_balance(STAKE_ACCOUNT_SPACE),
+        STAKE_ACCOUNT_SPACE as u64,
+        &solana_stake_interface::program::ID,
+    )?;
+
+    // 2. WSOL 解包：vault -> 中转账户 -> 关闭到 pool authority -> 质押账户
+    let wsol_rent = ctx.accounts.strategy_wsol.to_account_info().lamports();
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.strategy_wsol.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.strategy_wsol.to_account_info(),
+            destination: ctx.accounts.pool_authority.to_account_info(),
+            authority: ctx.accounts.pool_authority.to_account_info(),
+        },
+        signer,
+    ))?;
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            SystemTransfer {
+                from: ctx.accounts.pool_authority.to_account_info(),
+                to: ctx.accounts.stake_account.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+    // 中转账户的租金退还给管理员
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            SystemTransfer {
+                from: ctx.accounts.pool_authority.to_account_info(),
+                to: ctx.accounts.admin.to_account_info(),
+            },
+            signer,
+        ),
+        wsol_rent,
+    )?;
+
+    // 3. 初始化并委托
+    let authority_key = ctx.accounts.pool_authority.key();
+    invoke(
+        &stake_instruction::initialize(
+            &ctx.accounts.stake_account.key(),
+            &Authorized { staker: authority_key, withdrawer: authority_key },
+            &Lockup::default(),
+        ),
+        &[
+            ctx.accounts.stake_account.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+        ],
+    )?;
+    invoke_signed(
+        &stake_instruction::delegate_stake(
+            &ctx.accounts.stake_account.key(),
+            &authority_key,
+            &ctx.accounts.vote_account.key(),
+        ),
+        &[
+            ctx.accounts.stake_account.to_account_info(),
+            ctx.accounts.vote_account.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            ctx.accounts.stake_history.to_account_info(),
+            ctx.accounts.stake_config.to_account_info(),
+            ctx.accounts.pool_authority.to_account_info(),
+        ],
+        signer,
+    )?;
+
+    // 4. 记录委托数量（vault 余额减少的部分由 strategy_delegated_amount 补足）
+    pool.strategy_delegated_amount = amount;
+
+    msg!("Vault stake delegated: pool: {}, vote_account: {}, stake_account: {}, amount: {}",
+         pool_key,
+         ctx.accounts.vote_account.key(),
+         ctx.accounts.stake_account.key(),
+         amount);
+
+    Ok(())
+}
+
+/// 取消策略质押账户的委托
+#[derive(Accounts)]
+pub struct DeactivateVaultStake<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - 质押账户的 staker
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 策略质押账户 PDA
+    /// CHECK: PDA derived from pool key，由 stake 程序校验
+    #[account(
+        mut,
+        seeds = [b"strategy_stake", pool.key().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    /// CHECK: 地址约束为 stake 程序
+    #[account(address = solana_stake_interface::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+/// 取消策略质押账户的委托，冷却期（通常一个 epoch）结束后可提取
+/// 与 withdraw_vault_stake 一样不受时间锁限制
+pub fn deactivate_vault_stake(ctx: Context<DeactivateVaultStake>) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    invoke_signed(
+        &stake_instruction::deactivate_stake(
+            &ctx.accounts.stake_account.key(),
+            &ctx.accounts.pool_authority.key(),
+        ),
+        &[
+            ctx.accounts.stake_account.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            ctx.accounts.pool_authority.to_account_info(),
+        ],
+        signer,
+    )?;
+
+    msg!("Vault stake deactivated: pool: {}, stake_account: {}",
+         pool_key,
+         ctx.accounts.stake_account.key());
+
+    Ok(())
+}
+
+/// 提取策略质押账户的全部 SOL 回 vault
+#[derive(Accounts)]
+pub struct WithdrawVaultStake<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - 质押账户的 withdrawer
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 策略质押账户 PDA - 提取后关闭
+    /// CHECK: PDA derived from pool key，由 stake 程序校验
+    #[account(
+        mut,
+        seeds = [b"strategy_stake", pool.key().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// Pool 的主币 Vault（WSOL）- 接收本金和质押收益
+    #[account(
+        mut,
+        address = pool.load()?.pool_vault @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pool 管理员 - 必须签名，取回质押账户租金
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: 地址约束为 stake history sysvar
+    #[account(address = stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: 地址约束为 stake 程序
+    #[account(address = solana_stake_interface::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+/// 提取策略质押账户的全部 SOL 回 vault（需先 deactivate 并等待冷却）
+///
+/// 逻辑：
+/// 1. 扣除租金后的全部 lamports（本金 + 收益）提取到 vault，sync_native 更新 WSOL 余额
+/// 2. 租金提取给管理员，质押账户随之关闭
+/// 3. strategy_delegated_amount 清零，收益作为 vault 增量按 max_donation_bps 逐步计入兑换率
+///
+/// 不受时间锁限制：资金只能回到 vault，启用时间锁前委托的资金仍可随时收回
+pub fn withdraw_vault_stake(ctx: Context<WithdrawVaultStake>) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let stake_lamports = ctx.accounts.stake_account.lamports();
+    let rent_reserve = ctx.accounts.rent.minimum_balance(STAKE_ACCOUNT_SPACE);
+    let recovered = stake_lamports.saturating_sub(rent_reserve);
+    let authority_key = ctx.accounts.pool_authority.key();
+
+    // 1. 本金 + 收益提取到 vault，租金提取给管理员（质押账户随之关闭）
+    for (to, lamports) in [
+        (ctx.accounts.pool_vault.to_account_info(), recovered),
+        (ctx.accounts.admin.to_account_info(), stake_lamports - recovered),
+    ] {
+        if lamports == 0 {
+            continue;
+        }
+        invoke_signed(
+            &stake_instruction::withdraw(
+                &ctx.accounts.stake_account.key(),
+                &authority_key,
+                to.key,
+                lamports,
+                None,
+            ),
+            &[
+                ctx.accounts.stake_account.to_account_info(),
+                to.clone(),
+                ctx.accounts.clock.to_account_info(),
+                ctx.accounts.stake_history.to_account_info(),
+                ctx.accounts.pool_authority.to_account_info(),
+            ],
+            signer,
+        )?;
+    }
+
+    // 2. 更新 vault 的 WSOL 余额
+    token::sync_native(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        SyncNative {
+            account: ctx.accounts.pool_vault.to_account_info(),
+        },
+    ))?;
+
+    // 3. 清零委托记录
+    let delegated = pool.strategy_delegated_amount;
+    pool.strategy_delegated_amount = 0;
+
+    msg!("Vault stake withdrawn: pool: {}, stake_account: {}, delegated: {}, recovered: {}",
+         pool_key,
+         ctx.accounts.stake_account.key(),
+         delegated,
+         recovered);
+
+    Ok(())
+}
+
+/// stake config 账户地址（已废弃，但 delegate_stake 仍要求传入）
+#[allow(deprecated)]
+fn stake_config_id() -> Pubkey {
+    solana_stake_interface::config::ID
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+
+/// 重新计算总加权质押量缓存（任何人可调用）
+#[derive(Accounts)]
+pub struct SyncTotalWeighted<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+}
+
+/// 遍历全部 items 重新计算各主币资产的总加权质押量，覆盖 pool.asset_total_weighted
+/// 缓存由 stake / unstake / weight 修改增量维护，该指令只用于调试和修复：
+/// 升级前创建的 pool 需调用一次完成初始化，结果与 items 完全由链上数据决定，因此无需管理员权限
+pub fn sync_total_weighted(ctx: Context<SyncTotalWeighted>) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    let changed = pool.sync_asset_total_weighted()?;
+
+    msg!("Total weighted synced: pool: {}, changed: {}, total_weighted: {}",
+         ctx.accounts.pool.key(),
+         changed,
+         pool.get_asset_total_weighted(0)?);
+
+    Ok(())
+}
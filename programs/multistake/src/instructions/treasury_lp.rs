@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Pool, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+
+/// 为某个 item 创建 treasury LP 账户（存放以 LP 形式收取的手续费）
+#[derive(Accounts)]
+pub struct InitTreasuryLp<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA - treasury LP 账户的 owner
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// item 的 LP mint - 必须是 pool 中已注册的 item
+    #[account(
+        constraint = Pool::load_versioned(&pool)?.find_token_index(&lp_mint.key()).is_some()
+            @ ErrorCode::InvalidTokenMint
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// treasury LP 账户 - 每个 item 一个
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"treasury_lp", pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = pool_authority,
+        token::token_program = token_program,
+    )]
+    pub treasury_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 任何人都可以支付创建账户的费用
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// LP mint 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// 为某个 item 创建 treasury LP 账户
+pub fn init_treasury_lp(ctx: Context<InitTreasuryLp>) -> Result<()> {
+    msg!("Treasury LP created: pool: {}, lp_mint: {}, treasury_lp_token: {}",
+         ctx.accounts.pool.key(),
+         ctx.accounts.lp_mint.key(),
+         ctx.accounts.treasury_lp_token.key());
+    Ok(())
+}
+
+/// 设置手续费收取方式
+#[derive(Accounts)]
+pub struct SetFeeInLp<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置手续费收取方式
+/// enabled: true 时 stake 手续费铸造为协议持有的 LP，false 时手续费留在 vault 中
+pub fn set_fee_in_lp(ctx: Context<SetFeeInLp>, enabled: bool) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    pool.fee_in_lp = enabled as u8;
+
+    msg!("Fee in LP updated: pool: {}, enabled: {}",
+         ctx.accounts.pool.key(),
+         enabled);
+
+    Ok(())
+}
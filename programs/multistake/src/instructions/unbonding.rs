@@ -0,0 +1,388 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, BurnChecked, TokenInterface};
+use crate::state::{ItemOracle, Pool, StakeRecord, UnstakeTicket, LP_MINT_DECIMALS, POOL_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use super::rate_weight::apply_rate_weights;
+use crate::events::UnstakeEvent;
+use crate::transfer::transfer_main_token;
+
+/// 设置赎回冷却期
+#[derive(Accounts)]
+pub struct SetUnstakeCooldown<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置赎回冷却期
+/// cooldown_seconds: request_unstake 到 claim_unstake 的最短间隔（秒），0 表示关闭冷却期、恢复即时 unstake
+/// 修改只影响之后创建的凭证
+pub fn set_unstake_cooldown(ctx: Context<SetUnstakeCooldown>, cooldown_seconds: i64) -> Result<()> {
+    require!(cooldown_seconds >= 0, ErrorCode::InvalidCooldown);
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let old_cooldown = pool.cooldown_seconds;
+    pool.cooldown_seconds = cooldown_seconds;
+
+    msg!("Unstake cooldown updated: pool: {}, old: {}, new: {}",
+         ctx.accounts.pool.key(),
+         old_cooldown,
+         cooldown_seconds);
+
+    Ok(())
+}
+
+/// 发起冷却赎回：销毁 LP，主币锁定在凭证中
+#[derive(Accounts)]
+#[instruction(item_index: u16, lp_amount: u64, min_redeem_amount: u64, ticket_id: u64)]
+pub struct RequestUnstake<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// item 对应的主币 Vault - 只读取余额，主币在 claim_unstake 时转出
+    #[account(
+        address = Pool::load_versioned(&pool)?.get_item_vault(item_index as usize)? @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// LP mint - 对应的质押类型（Token 或 Token-2022）
+    #[account(
+        mut,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 用户的 LP 凭证账户（销毁来源）
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_lp_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 赎回凭证 PDA
+    #[account(
+        init,
+        payer = user,
+        space = UnstakeTicket::space(),
+        seeds = [
+            b"unstake_ticket",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            &ticket_id.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub unstake_ticket: Box<Account<'info, UnstakeTicket>>,
+
+    /// 用户签名，支付凭证租金
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// 用户的质押记录 - 不存在时创建（如通过转账获得的 LP），结算积分后扣减 staked_amount
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeRecord::space(),
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            lp_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// 质押类型的 oracle 配置 - item 配置了 oracle 时必须提供
+    #[account(
+        seeds = [b"item_oracle", pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump = item_oracle.bump,
+    )]
+    pub item_oracle: Option<Box<Account<'info, ItemOracle>>>,
+
+    /// oracle 价格账户 - item 配置了 oracle 时必须提供
+    /// CHECK: 与 item_oracle.price_feed 比对，并在读取时校验 owner
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// LP mint 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// 发起冷却赎回
+/// item_index: 质押类型索引
+/// lp_amount: 要销毁的 LP 凭证数量
+/// min_redeem_amount: 滑点保护 - 扣除手续费后的主币数量低于该值时回滚
+/// ticket_id: 凭证编号，同一用户不可重复使用未领取的编号
+///
+/// 逻辑：
+/// 1. 按 unstake 相同公式计算赎回数量并扣除手续费，兑换率在此时锁定
+/// 2. 销毁 LP，主币留在 vault 中并计入 pending_unstake，不再参与兑换率
+/// 3. 冷却期结束后通过 claim_unstake 领取
+///
+/// remaining_accounts: 同一主币下每个配置了兑换率来源的 item 一组 [item_rate_source, source]
+pub fn request_unstake(
+    ctx: Context<RequestUnstake>,
+    item_index: u16,
+    lp_amount: u64,
+    min_redeem_amount: u64,
+    ticket_id: u64,
+) -> Result<()> {
+    require!(lp_amount > 0, ErrorCode::InvalidTokenCount);
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 暂停状态检查
+    pool.verify_unstake_allowed()?;
+
+    // 结算奖励排放（LP 数量变化前），再按衰减计划更新 weight
+    let now = Clock::get()?.unix_timestamp;
+    pool.accrue_emission(now)?;
+    pool.apply_weight_schedules(item_index as usize, now)?;
+    // 按兑换率来源刷新 weight
+    apply_rate_weights(
+        pool,
+        &ctx.accounts.pool.key(),
+        item_index as usize,
+        ctx.remaining_accounts,
+        &Clock::get()?,
+    )?;
+
+    // 验证 LP mint 地址匹配
+    let item = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+    require!(
+        ctx.accounts.lp_mint.key() == *item.mint_pubkey(),
+        ErrorCode::InvalidTokenMint
+    );
+
+    // 计算能赎回的主币数量（基于 weight）
+    let asset_index = item.get_main_asset_index();
+    let pool_vault_balance = ctx.accounts.pool_vault.amount;
+    let effective_balance = pool.effective_vault_balance(asset_index, pool_vault_balance)?;
+    let redeem_amount = pool.calculate_redeem_amount(
+        lp_amount,
+        item_index as usize,
+        pool_vault_balance,
+    )?;
+
+    // 脱锚保护：oracle 价格偏离 weight 隐含价格超过阈值时拒绝或折价赎回
+    let clock = Clock::get()?;
+    let redeem_amount = if item.has_oracle() {
+        let oracle = ctx.accounts.item_oracle.as_ref()
+            .ok_or(ErrorCode::InvalidOracleAccount)?;
+        let price_feed = ctx.accounts.price_feed.as_ref()
+            .ok_or(ErrorCode::InvalidOracleAccount)?;
+        oracle.adjust_redeem_amount(
+            price_feed,
+            clock.unix_timestamp,
+            item.get_weight(),
+            redeem_amount,
+        )?
+    } else {
+        redeem_amount
+    };
+    // LP 过少时赎回金额向下取整为 0，拒绝以免白白销毁 LP
+    require!(redeem_amount > 0, ErrorCode::DustAmount);
+
+    // 赎回限速：发起时占用当前窗口额度
+    pool.consume_redemption_capacity(redeem_amount, false, clock.slot)?;
+
+    let (fee_amount, amount_after_fee) = pool.calculate_unstake_fee(redeem_amount)?;
+    require!(
+        amount_after_fee >= min_redeem_amount,
+        ErrorCode::SlippageExceeded
+    );
+
+    // 1. 销毁用户的 LP 凭证
+    token_interface::burn_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            BurnChecked {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.user_lp_token.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        lp_amount,
+        LP_MINT_DECIMALS,
+    )?;
+
+    // 2. 更新 item 的 mint_amount
+    pool.sub_item_mint_amount(item_index as usize, lp_amount)?;
+    let item_mut = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+
+    // 3. 结算积分并更新质押记录
+    let pool_key = ctx.accounts.pool.key();
+    let record = &mut ctx.accounts.stake_record;
+    if record.is_uninitialized() {
+        record.initialize(&pool_key, &ctx.accounts.user.key(), item_mut.mint_pubkey(), ctx.bumps.stake_record, clock.slot);
+    }
+    record.accrue_points(item_mut.get_weight(), clock.slot)?;
+    record.settle_rewards(item_mut.get_reward_per_share())?;
+    record.sub_staked_amount(lp_amount);
+
+    // 4. 锁定待领取的主币：计入 pending_unstake，记账余额与 unstake 一致扣减
+    let pending = pool.get_pending_unstake(asset_index)?
+        .checked_add(amount_after_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.set_pending_unstake(asset_index, pending)?;
+    let tracked_balance = effective_balance
+        .checked_sub(amount_after_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.set_tracked_balance(asset_index, tracked_balance)?;
+    pool.verify_min_vault_balance(asset_index)?;
+
+    // 5. 写入凭证
+    let unlock_timestamp = clock.unix_timestamp
+        .checked_add(pool.cooldown_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let ticket = &mut ctx.accounts.unstake_ticket;
+    ticket.pool = ctx.accounts.pool.key();
+    ticket.owner = ctx.accounts.user.key();
+    ticket.ticket_id = ticket_id;
+    ticket.asset_index = asset_index;
+    ticket.amount = amount_after_fee;
+    ticket.unlock_timestamp = unlock_timestamp;
+    ticket.bump = ctx.bumps.unstake_ticket;
+
+    msg!("Unstake requested: user: {}, item_index: {}, ticket_id: {}, lp_burned: {}, redeem_amount: {}, fee: {}, amount: {}, unlock_timestamp: {}",
+         ctx.accounts.user.key(),
+         item_index,
+         ticket_id,
+         lp_amount,
+         redeem_amount,
+         fee_amount,
+         amount_after_fee,
+         unlock_timestamp);
+    emit!(UnstakeEvent {
+        pool: ctx.accounts.pool.key(),
+        user: ctx.accounts.user.key(),
+        item_index,
+        lp_burned: lp_amount,
+        redeem_amount,
+        fee: fee_amount,
+        // 冷却赎回不转出主币，手续费全部留在 vault 中
+        protocol_fee: 0,
+        amount_after_fee,
+        tag: None,
+    });
+
+    Ok(())
+}
+
+/// 领取冷却赎回的主币
+#[derive(Accounts)]
+pub struct ClaimUnstake<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool authority PDA
+    /// CHECK: PDA derived from pool key
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// 赎回凭证 - 领取后关闭，租金退还给用户
+    #[account(
+        mut,
+        close = user,
+        seeds = [
+            b"unstake_ticket",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            &unstake_ticket.ticket_id.to_le_bytes(),
+        ],
+        bump = unstake_ticket.bump,
+    )]
+    pub unstake_ticket: Box<Account<'info, UnstakeTicket>>,
+
+    /// 凭证对应的主币 Vault
+    #[account(
+        mut,
+        address = pool.load()?.get_asset_vault(unstake_ticket.asset_index)? @ ErrorCode::InvalidPoolVault,
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 主币 Mint - Token 或 Token-2022
+    #[account(address = pool_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub main_token_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// 用户的主币账户（领取目标）
+    #[account(mut)]
+    pub user_main_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 凭证所有者签名
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
+}
+
+/// 领取冷却赎回的主币
+/// remaining_accounts: 主币 mint 启用 TransferHook 时，hook 需要的额外账户
+pub fn claim_unstake<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimUnstake<'info>>,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 暂停状态检查
+    pool.verify_unstake_allowed()?;
+
+    let ticket = &ctx.accounts.unstake_ticket;
+    ticket.verify_unlocked(Clock::get()?.unix_timestamp)?;
+
+    // 1. 释放 pending_unstake（vault 余额与 pending 同步减少，兑换率不变）
+    let pending = pool.get_pending_unstake(ticket.asset_index)?
+        .checked_sub(ticket.amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.set_pending_unstake(ticket.asset_index, pending)?;
+
+    // 2. 从 vault 转移主币给用户
+    let pool_key = ctx.accounts.pool.key();
+    let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
+    let seeds = &[
+        POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        authority_seed,
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    transfer_main_token(
+        &ctx.accounts.main_token_program.to_account_info(),
+        &ctx.accounts.pool_vault.to_account_info(),
+        &ctx.accounts.main_token_mint.to_account_info(),
+        &ctx.accounts.user_main_token.to_account_info(),
+        &ctx.accounts.pool_authority.to_account_info(),
+        ctx.remaining_accounts,
+        ticket.amount,
+        ctx.accounts.main_token_mint.decimals,
+        signer,
+    )?;
+
+    msg!("Unstake claimed: user: {}, ticket_id: {}, amount: {}",
+         ctx.accounts.user.key(),
+         ticket.ticket_id,
+         ticket.amount);
+
+    Ok(())
+}
@@ -1,7 +1,19 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
-use crate::state::Pool;
+use anchor_spl::token_interface::{self, BurnChecked, TokenInterface};
+use anchor_lang::solana_program::program::set_return_data;
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+use crate::state::delegation::{DELEGATE_UNSTAKE, DELEGATION_SEED};
+use crate::state::{Delegation, ItemOracle, Pool, PriorityRedeemer, StakeRecord, LP_MINT_DECIMALS, POOL_AUTHORITY_SEED};
 use crate::error::ErrorCode;
+use super::rate_weight::apply_rate_weights;
+#[cfg(feature = "invariant-checks")]
+use crate::state::invariant::check_pool_invariants;
+use crate::events::UnstakeEvent;
+use crate::transfer::{received_amount, transfer_main_token, verify_vault_outflow};
+use crate::state::program_config::PROGRAM_CONFIG_SEED;
+use super::delegation::verify_owner_or_delegate;
+use super::program_config::split_protocol_fee;
+use super::stake::{format_tag, reconcile_vault_balance};
 
 /// 销毁 LP 凭证，赎回主币
 #[derive(Accounts)]
@@ -13,55 +25,192 @@ pub struct Unstake<'info> {
     /// Pool authority PDA
     /// CHECK: PDA derived from pool key
     #[account(
-        seeds = [b"anyswap_authority", pool.key().as_ref()],
+        seeds = [
+            POOL_AUTHORITY_SEED,
+            pool.key().as_ref(),
+            pool.load()?.get_authority_seed(),
+        ],
         bump
     )]
     pub pool_authority: AccountInfo<'info>,
 
-    /// Pool 的主币 Vault
+    /// item 对应的主币 Vault（单资产模式下即 pool_vault）
     #[account(
         mut,
-        seeds = [b"pool_vault", pool.key().as_ref()],
-        bump,
+        address = Pool::load_versioned(&pool)?.get_item_vault(item_index as usize)? @ ErrorCode::InvalidPoolVault,
     )]
-    pub pool_vault: Box<Account<'info, TokenAccount>>,
+    pub pool_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-    /// LP mint - 对应的质押类型
-    /// 通过 pool.get_token() 验证地址是否匹配
-    #[account(mut)]
-    pub lp_mint: Box<Account<'info, Mint>>,
+    /// 主币 Mint - Token 或 Token-2022
+    #[account(address = pool_vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub main_token_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// LP mint - 对应的质押类型（Token 或 Token-2022）
+    /// 地址必须与 item 登记的 mint 一致，且由 pool authority 铸造
+    #[account(
+        mut,
+        address = Pool::load_versioned(&pool)?.get_item_mint(item_index as usize)? @ ErrorCode::InvalidTokenMint,
+        mint::authority = pool_authority,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     /// 用户的 LP 凭证账户（销毁来源）
-    #[account(mut)]
-    pub user_lp_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_lp_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     /// 用户的主币账户（赎回目标）
+    #[account(
+        mut,
+        token::mint = main_token_mint,
+        token::authority = user,
+    )]
+    pub user_main_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// 用户 - 本人签名，或由 delegate 持 delegation 代为操作
+    /// 本人签名时质押记录不存在则由用户支付租金创建，代理操作时质押记录需已存在
+    /// CHECK: 在指令中验证签名或 delegation
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+
+    /// 代理签名（可选）- 用户未签名时必须提供
+    pub delegate: Option<Signer<'info>>,
+
+    /// 用户的代理授权（可选）- 用户未签名时必须提供，且需拥有 unstake 权限
+    #[account(
+        seeds = [DELEGATION_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump = delegation.bump,
+    )]
+    pub delegation: Option<Box<Account<'info, Delegation>>>,
+
+    /// 用户的质押记录 - 不存在时创建（如通过转账获得的 LP），结算积分后扣减 staked_amount
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeRecord::space(),
+        seeds = [
+            b"stake_record",
+            pool.key().as_ref(),
+            user.key().as_ref(),
+            lp_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    /// 质押类型的 oracle 配置 - item 配置了 oracle 时必须提供
+    #[account(
+        seeds = [b"item_oracle", pool.key().as_ref(), lp_mint.key().as_ref()],
+        bump = item_oracle.bump,
+    )]
+    pub item_oracle: Option<Box<Account<'info, ItemOracle>>>,
+
+    /// oracle 价格账户 - item 配置了 oracle 时必须提供
+    /// CHECK: 与 item_oracle.price_feed 比对，并在读取时校验 owner
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// 优先赎回地址登记（可选）- 赎回限速生效时可使用预留的优先额度
+    #[account(
+        seeds = [b"priority_redeemer", pool.key().as_ref(), user.key().as_ref()],
+        bump = priority_redeemer.bump,
+    )]
+    pub priority_redeemer: Option<Box<Account<'info, PriorityRedeemer>>>,
+
+    /// 手续费 vault - pool 已创建 fee_vault 且手续费不以 LP 形式收取时必须传入
+    #[account(
+        mut,
+        address = pool.load()?.fee_vault @ ErrorCode::InvalidFeeConfig,
+    )]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// 全局程序配置 - 尚未创建时不抽取协议分成
+    /// CHECK: 地址由 seeds 约束，由 ProgramConfig::load_optional 校验 owner 后反序列化
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+
+    /// 协议金库的主币账户 - 全局配置设置了协议分成且有手续费转入 fee_vault 时必须传入
     #[account(mut)]
-    pub user_main_token: Box<Account<'info, TokenAccount>>,
+    pub protocol_treasury_token: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// Instructions sysvar - pool 启用调用方白名单时用于识别 CPI 调用方
+    /// CHECK: 地址约束为 instructions sysvar
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    /// LP 所属的 token 程序（Token 或 Token-2022）
+    pub token_program: Interface<'info, TokenInterface>,
 
-    /// 用户签名
-    pub user: Signer<'info>,
+    /// 主币所属的 token 程序（Token 或 Token-2022）
+    pub main_token_program: Interface<'info, TokenInterface>,
 
-    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 /// 销毁 LP 凭证，赎回主币
 /// item_index: 质押类型索引
 /// lp_amount: 要销毁的 LP 凭证数量
+/// min_redeem_amount: 滑点保护 - 扣除手续费后实际到账的主币数量低于该值时回滚
+/// tag: 可选的 32 字节标签，只写入日志不存储
+/// remaining_accounts:
+/// - 前 2 × N 个：同一主币下每个配置了兑换率来源的 item 一组 [item_rate_source, source]，N 为这类 item 的数量
+/// - 之后：主币 mint 启用 TransferHook 时，hook 需要的额外账户
+///
+/// 用户未签名时由 delegate 按 delegation 的 unstake 权限代为操作，LP 由 delegation PDA 销毁，赎回的主币仍转给用户
 ///
 /// 逻辑：
 /// 1. 销毁用户的 LP 凭证
-/// 2. 根据 weight 计算能赎回的主币数量
-/// 3. 从 pool_vault 转移主币给用户
+/// 2. 根据 weight 计算能赎回的主币数量，按 unstake_fee_bps 扣除手续费
+/// 3. 从 pool_vault 转移主币给用户，手续费中 protocol_fee_bps 部分转入 fee_vault（pool 已创建 fee_vault 时），
+///    其余留在 vault 中归剩余 LP 持有人；全局配置设置了协议分成时，转入 fee_vault 的部分再按
+///    protocol_fee_share_bps 转给协议金库
 /// 4. 更新 item 的 mint_amount
-pub fn unstake(
-    ctx: Context<Unstake>,
+pub fn unstake<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>,
     item_index: u16,
     lp_amount: u64,
+    min_redeem_amount: u64,
+    tag: Option<[u8; 32]>,
 ) -> Result<()> {
     require!(lp_amount > 0, ErrorCode::InvalidTokenCount);
 
-    let pool = &mut ctx.accounts.pool.load_mut()?;
+    // 用户本人签名，或代理持 unstake 权限
+    let delegated = verify_owner_or_delegate(
+        &ctx.accounts.user,
+        ctx.accounts.delegate.as_ref(),
+        ctx.accounts.delegation.as_deref(),
+        DELEGATE_UNSTAKE,
+    )?;
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 暂停状态检查
+    pool.verify_unstake_allowed()?;
+    // 启用冷却期时只能通过 request_unstake 赎回
+    require!(!pool.has_unstake_cooldown(), ErrorCode::UnstakeCooldownRequired);
+
+    // 验证调用方策略
+    pool.caller_allowlist.verify_caller(
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+    )?;
+
+    // 结算奖励排放（LP 数量变化前），再按衰减计划更新 weight
+    let now = Clock::get()?.unix_timestamp;
+    pool.accrue_emission(now)?;
+    pool.apply_weight_schedules(item_index as usize, now)?;
+    // 按兑换率来源刷新 weight，之后的 remaining_accounts 为 TransferHook 额外账户
+    let hook_accounts = apply_rate_weights(
+        pool,
+        &ctx.accounts.pool.key(),
+        item_index as usize,
+        ctx.remaining_accounts,
+        &Clock::get()?,
+    )?;
 
     // 验证 item_index 有效
     require!(
@@ -69,78 +218,229 @@ pub fn unstake(
         ErrorCode::InvalidTokenIndex
     );
 
-    // 验证 LP mint 地址匹配
+    // LP mint 地址已由账户约束验证
     let item = pool.get_token(item_index as usize)
         .ok_or(ErrorCode::InvalidTokenIndex)?;
-    require!(
-        ctx.accounts.lp_mint.key() == *item.mint_pubkey(),
-        ErrorCode::InvalidTokenMint
-    );
 
     // 计算能赎回的主币数量（基于 weight）
+    let asset_index = item.get_main_asset_index();
+    // 主币精度必须与创建时记录的一致
+    let decimals = pool.verify_asset_decimals(asset_index, ctx.accounts.main_token_mint.decimals)?;
     let pool_vault_balance = ctx.accounts.pool_vault.amount;
+    let effective_balance = pool.effective_vault_balance(asset_index, pool_vault_balance)?;
     let redeem_amount = pool.calculate_redeem_amount(
         lp_amount,
         item_index as usize,
         pool_vault_balance,
     )?;
 
+    // 脱锚保护：oracle 价格偏离 weight 隐含价格超过阈值时拒绝或折价赎回
+    let redeem_amount = if item.has_oracle() {
+        let oracle = ctx.accounts.item_oracle.as_ref()
+            .ok_or(ErrorCode::InvalidOracleAccount)?;
+        let price_feed = ctx.accounts.price_feed.as_ref()
+            .ok_or(ErrorCode::InvalidOracleAccount)?;
+        oracle.adjust_redeem_amount(
+            price_feed,
+            Clock::get()?.unix_timestamp,
+            item.get_weight(),
+            redeem_amount,
+        )?
+    } else {
+        redeem_amount
+    };
+    // LP 过少时赎回金额向下取整为 0，拒绝以免白白销毁 LP
+    require!(redeem_amount > 0, ErrorCode::DustAmount);
+
+    // vault 中待领取的冷却赎回不可用于即时赎回
     require!(
-        pool_vault_balance >= redeem_amount,
+        pool_vault_balance.saturating_sub(pool.get_pending_unstake(asset_index)?) >= redeem_amount,
         ErrorCode::InsufficientLiquidity
     );
 
+    // 赎回限速：占用当前窗口额度，优先赎回地址可使用预留额度
+    pool.consume_redemption_capacity(
+        redeem_amount,
+        ctx.accounts.priority_redeemer.is_some(),
+        Clock::get()?.slot,
+    )?;
+
     // 对 redeem_amount 计算手续费
-    let (fee_amount, amount_after_fee) = pool.calculate_fee(redeem_amount)?;
+    let (fee_amount, amount_after_fee) = pool.calculate_unstake_fee(redeem_amount)?;
+    let (protocol_fee, _) = pool.calculate_protocol_fee(fee_amount)?;
+    // 转入 fee_vault 的协议手续费
+    let vault_fee = if pool.routes_fee_to_vault(asset_index) { protocol_fee } else { 0 };
+    // 按全局配置从中抽取协议金库的分成
+    let (treasury_fee, vault_fee) = split_protocol_fee(
+        &ctx.accounts.program_config,
+        ctx.accounts.protocol_treasury_token.as_deref(),
+        &ctx.accounts.pool_vault.mint,
+        vault_fee,
+    )?;
+    // 主币启用 TransferFee 扩展时按用户实际到账数量检查滑点
+    require!(
+        received_amount(&ctx.accounts.main_token_mint.to_account_info(), amount_after_fee)?
+            >= min_redeem_amount,
+        ErrorCode::SlippageExceeded
+    );
+
+    // 记账前先与 vault 实际余额对账，记录外部捐赠或短缺
+    let pool_key = ctx.accounts.pool.key();
+    reconcile_vault_balance(pool, &pool_key, asset_index, pool_vault_balance)?;
+
+    // 先完成全部记账，再执行外部 CPI：Token-2022 transfer hook 等回调观察到的 pool 状态已是最终状态
+    // 1. 更新 item 的 mint_amount
+    pool.sub_item_mint_amount(item_index as usize, lp_amount)?;
+    let item_mut = pool.get_token(item_index as usize)
+        .ok_or(ErrorCode::InvalidTokenIndex)?;
+
+    // 2. 结算积分并更新质押记录
+    let record = &mut ctx.accounts.stake_record;
+    if record.is_uninitialized() {
+        record.initialize(&pool_key, &ctx.accounts.user.key(), item_mut.mint_pubkey(), ctx.bumps.stake_record, Clock::get()?.slot);
+    }
+    record.accrue_points(item_mut.get_weight(), Clock::get()?.slot)?;
+    record.settle_rewards(item_mut.get_reward_per_share())?;
+    record.sub_staked_amount(lp_amount);
 
-    // 1. 销毁用户的 LP 凭证
-    token::burn(
-        CpiContext::new(
+    // 3. 记录转入 fee_vault 的协议手续费
+    if vault_fee > 0 {
+        require!(ctx.accounts.fee_vault.is_some(), ErrorCode::InvalidFeeConfig);
+        pool.add_accumulated_fees(
+            received_amount(&ctx.accounts.main_token_mint.to_account_info(), vault_fee)?,
+        )?;
+    }
+
+    // 4. 更新 vault 记账余额（未转入 fee_vault 的手续费留在 vault 中），仍有 LP 时不得低于最小值
+    let tracked_balance = effective_balance
+        .checked_sub(amount_after_fee)
+        .and_then(|balance| balance.checked_sub(vault_fee))
+        .and_then(|balance| balance.checked_sub(treasury_fee))
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.set_tracked_balance(asset_index, tracked_balance)?;
+    pool.verify_min_vault_balance(asset_index)?;
+
+    // 5. 销毁用户的 LP 凭证，代理操作时由 delegation PDA 作为 approve 的代理签名销毁
+    let user_key = ctx.accounts.user.key();
+    let delegation_bump = [ctx.accounts.delegation.as_ref().map_or(0, |delegation| delegation.bump)];
+    let delegation_seeds: &[&[u8]] = &[DELEGATION_SEED, pool_key.as_ref(), user_key.as_ref(), &delegation_bump];
+    let (burn_authority, burn_signer): (AccountInfo<'info>, &[&[&[u8]]]) = match ctx.accounts.delegation.as_ref() {
+        Some(delegation) if delegated => (delegation.to_account_info(), &[delegation_seeds]),
+        _ => (ctx.accounts.user.to_account_info(), &[]),
+    };
+    token_interface::burn_checked(
+        CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Burn {
+            BurnChecked {
                 mint: ctx.accounts.lp_mint.to_account_info(),
                 from: ctx.accounts.user_lp_token.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
+                authority: burn_authority,
             },
+            burn_signer,
         ),
         lp_amount,
+        LP_MINT_DECIMALS,
     )?;
 
-    // 2. 从 pool_vault 转移主币给用户
-    let pool_key = ctx.accounts.pool.key();
+    // 6. 从 pool_vault 转移主币给用户
     let bump = ctx.bumps.pool_authority;
+    let authority_seed = pool.get_authority_seed();
     let seeds = &[
-        b"anyswap_authority",
+        POOL_AUTHORITY_SEED,
         pool_key.as_ref(),
+        authority_seed,
         &[bump],
     ];
     let signer = &[&seeds[..]];
 
-    token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.pool_vault.to_account_info(),
-                to: ctx.accounts.user_main_token.to_account_info(),
-                authority: ctx.accounts.pool_authority.to_account_info(),
-            },
-            signer,
-        ),
+    transfer_main_token(
+        &ctx.accounts.main_token_program.to_account_info(),
+        &ctx.accounts.pool_vault.to_account_info(),
+        &ctx.accounts.main_token_mint.to_account_info(),
+        &ctx.accounts.user_main_token.to_account_info(),
+        &ctx.accounts.pool_authority.to_account_info(),
+        hook_accounts,
         amount_after_fee,
+        decimals,
+        signer,
     )?;
 
-    // 3. 更新 item 的 mint_amount
-    let item_mut = pool.get_token_mut(item_index as usize)
-        .ok_or(ErrorCode::InvalidTokenIndex)?;
-    item_mut.sub_mint_amount(lp_amount)?;
+    if vault_fee > 0 {
+        let fee_vault = ctx.accounts.fee_vault.as_ref()
+            .ok_or(ErrorCode::InvalidFeeConfig)?;
+        transfer_main_token(
+            &ctx.accounts.main_token_program.to_account_info(),
+            &ctx.accounts.pool_vault.to_account_info(),
+            &ctx.accounts.main_token_mint.to_account_info(),
+            &fee_vault.to_account_info(),
+            &ctx.accounts.pool_authority.to_account_info(),
+            hook_accounts,
+            vault_fee,
+            decimals,
+            signer,
+        )?;
+    }
+
+    if treasury_fee > 0 {
+        let protocol_treasury_token = ctx.accounts.protocol_treasury_token.as_ref()
+            .ok_or(ErrorCode::InvalidProtocolTreasury)?;
+        transfer_main_token(
+            &ctx.accounts.main_token_program.to_account_info(),
+            &ctx.accounts.pool_vault.to_account_info(),
+            &ctx.accounts.main_token_mint.to_account_info(),
+            &protocol_treasury_token.to_account_info(),
+            &ctx.accounts.pool_authority.to_account_info(),
+            hook_accounts,
+            treasury_fee,
+            decimals,
+            signer,
+        )?;
+    }
 
-    msg!("Unstaked: user: {}, item_index: {}, lp_burned: {}, redeem_amount: {}, fee: {}, amount_after_fee: {}",
+    // 7. 重新读取 vault，实际转出必须与记账一致
+    ctx.accounts.pool_vault.reload()?;
+    verify_vault_outflow(
+        pool_vault_balance,
+        ctx.accounts.pool_vault.amount,
+        amount_after_fee
+            .checked_add(vault_fee)
+            .and_then(|amount| amount.checked_add(treasury_fee))
+            .ok_or(ErrorCode::MathOverflow)?,
+    )?;
+
+    #[cfg(feature = "invariant-checks")]
+    {
+        check_pool_invariants(pool, asset_index, Some(ctx.accounts.pool_vault.amount))?;
+    }
+
+    msg!("Unstaked: user: {}, item_index: {}, lp_burned: {}, redeem_amount: {}, fee: {}, protocol_fee: {}, amount_after_fee: {}",
          ctx.accounts.user.key(),
          item_index,
          lp_amount,
          redeem_amount,
          fee_amount,
+         vault_fee,
          amount_after_fee);
+    if treasury_fee > 0 {
+        msg!("Unstake protocol fee share: {}", treasury_fee);
+    }
+    if let Some(tag) = tag.as_ref() {
+        msg!("Unstake tag: {}", format_tag(tag));
+    }
+    emit!(UnstakeEvent {
+        pool: pool_key,
+        user: ctx.accounts.user.key(),
+        item_index,
+        lp_burned: lp_amount,
+        redeem_amount,
+        fee: fee_amount,
+        protocol_fee: vault_fee,
+        amount_after_fee,
+        tag,
+    });
+
+    // 转给用户的主币数量（扣除手续费后）写入 return data（Borsh 编码的 u64），供 CPI 调用方读取
+    set_return_data(&amount_after_fee.try_to_vec()?);
 
     Ok(())
 }
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+
+/// 设置 weight 修改冷却期
+#[derive(Accounts)]
+pub struct SetWeightCooldown<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置 weight 修改冷却期
+/// cooldown_slots: 同一 item 两次修改 weight 之间的最小 slot 间隔，0 表示不限制
+pub fn set_weight_cooldown(ctx: Context<SetWeightCooldown>, cooldown_slots: u64) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let old_cooldown = pool.get_weight_update_cooldown_slots();
+    pool.weight_update_cooldown_slots = cooldown_slots;
+
+    msg!("Weight cooldown updated: pool: {}, old: {}, new: {}",
+         ctx.accounts.pool.key(),
+         old_cooldown,
+         cooldown_slots);
+
+    Ok(())
+}
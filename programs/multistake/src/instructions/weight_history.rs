@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::state::{Pool, WeightChange, WeightHistory};
+use crate::error::ErrorCode;
+
+/// 创建 pool 的 weight 修改历史
+#[derive(Accounts)]
+pub struct InitWeightHistory<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// weight 历史 PDA
+    #[account(
+        init,
+        payer = payer,
+        space = WeightHistory::space(),
+        seeds = [b"weight_history", pool.key().as_ref()],
+        bump
+    )]
+    pub weight_history: Box<Account<'info, WeightHistory>>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 创建 pool 的 weight 修改历史
+/// 创建后 modify_token_weight、modify_weights_packed、force_modify_weight 以及执行 SetWeights 时必须传入该账户
+pub fn init_weight_history(ctx: Context<InitWeightHistory>) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    pool.weight_history_enabled = 1;
+
+    let history = &mut ctx.accounts.weight_history;
+    history.pool = ctx.accounts.pool.key();
+    history.head = 0;
+    history.count = 0;
+    history.bump = ctx.bumps.weight_history;
+    history.records = Default::default();
+
+    msg!("Weight history created: pool: {}", history.pool);
+
+    Ok(())
+}
+
+/// 写入一条 weight 修改记录
+/// pool 启用 weight 历史时必须传入 history
+pub(crate) fn record_weight_change(
+    pool: &Pool,
+    history: Option<&mut Box<Account<WeightHistory>>>,
+    record: WeightChange,
+) -> Result<()> {
+    match history {
+        Some(history) => {
+            history.push(record);
+            Ok(())
+        }
+        None => {
+            require!(!pool.is_weight_history_enabled(), ErrorCode::WeightHistoryRequired);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+use crate::state::{Pool, WeightOracle};
+use crate::error::ErrorCode;
+use crate::events::WeightModifiedEvent;
+
+/// 设置链下 weight oracle 的签名公钥
+#[derive(Accounts)]
+pub struct SetWeightOracle<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// weight oracle 配置 PDA
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = WeightOracle::space(),
+        seeds = [b"weight_oracle", pool.key().as_ref()],
+        bump
+    )]
+    pub weight_oracle: Account<'info, WeightOracle>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 设置链下 weight oracle 的签名公钥
+/// signer: oracle 的 ed25519 公钥，传 Pubkey::default() 表示停用
+/// 更换签名者不会重置 nonce
+pub fn set_weight_oracle(ctx: Context<SetWeightOracle>, signer: Pubkey) -> Result<()> {
+    let pool = Pool::load_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+
+    let oracle = &mut ctx.accounts.weight_oracle;
+    oracle.pool = ctx.accounts.pool.key();
+    oracle.signer = signer;
+    oracle.bump = ctx.bumps.weight_oracle;
+
+    msg!("Weight oracle updated: pool: {}, signer: {}",
+         oracle.pool,
+         signer);
+
+    Ok(())
+}
+
+/// 提交 oracle 签名的 weight
+#[derive(Accounts)]
+pub struct SubmitOracleWeights<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// weight oracle 配置
+    #[account(
+        mut,
+        seeds = [b"weight_oracle", pool.key().as_ref()],
+        bump = weight_oracle.bump,
+    )]
+    pub weight_oracle: Account<'info, WeightOracle>,
+
+    /// Instructions sysvar - 用于读取前一条 ed25519 验签指令
+    /// CHECK: 地址约束为 instructions sysvar
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+/// 提交 oracle 签名的 weight
+/// weights: 所有 item 的新 weight，顺序与 pool.tokens 一致
+/// nonce: 必须大于上一次提交的 nonce
+/// expiry: 签名过期时间（unix 秒）
+///
+/// 交易中前一条指令必须是 ed25519 验签指令，验证 oracle 对
+/// pool ‖ nonce ‖ expiry ‖ weights 的签名；提交者无需任何权限
+pub fn submit_oracle_weights(
+    ctx: Context<SubmitOracleWeights>,
+    weights: Vec<u64>,
+    nonce: u64,
+    expiry: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp <= expiry, ErrorCode::InvalidOracleSignature);
+
+    let oracle = &mut ctx.accounts.weight_oracle;
+    require!(
+        oracle.signer != Pubkey::default(),
+        ErrorCode::InvalidOracleSignature
+    );
+
+    let pool_key = ctx.accounts.pool.key();
+    let message = WeightOracle::build_message(&pool_key, nonce, expiry, &weights);
+    oracle.verify_signature(&ctx.accounts.instructions_sysvar, &message)?;
+    oracle.consume_nonce(nonce)?;
+
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+    require!(
+        weights.len() == pool.get_token_count(),
+        ErrorCode::InvalidTokenCount
+    );
+
+    // 结算奖励排放（weight 变化前）
+    pool.accrue_emission(clock.unix_timestamp)?;
+
+    let cooldown_slots = pool.get_weight_update_cooldown_slots();
+    let max_change_bps = pool.get_max_weight_change_bps();
+    for (index, weight) in weights.iter().enumerate() {
+        let token = pool.get_token(index)
+            .ok_or(ErrorCode::InvalidTokenIndex)?;
+        // 与 admin 修改相同，遵守 weight 修改冷却期和变化幅度上限
+        token.verify_weight_cooldown(clock.slot, cooldown_slots)?;
+        token.verify_weight_change(*weight, max_change_bps)?;
+        let old_weight = token.get_weight();
+        let lp_mint = *token.mint_pubkey();
+        pool.update_item_weight(index, *weight, clock.slot)?;
+        emit!(WeightModifiedEvent {
+            pool: pool_key,
+            item_index: index as u16,
+            lp_mint,
+            old_weight,
+            new_weight: *weight,
+            slot: clock.slot,
+        });
+    }
+
+    msg!("Oracle weights submitted: pool: {}, nonce: {}, items: {}",
+         pool_key,
+         nonce,
+         weights.len());
+
+    Ok(())
+}
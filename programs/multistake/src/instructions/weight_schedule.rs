@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+
+/// 设置质押类型的 weight 衰减计划
+#[derive(Accounts)]
+pub struct SetWeightSchedule<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置质押类型的 weight 衰减计划（如逐步下线某种资产）
+/// item_index: 质押类型索引
+/// target: 计划结束时的 weight，须在 [MIN_ITEM_WEIGHT, MAX_ITEM_WEIGHT] 范围内
+/// start_ts / end_ts: 起止时间（unix 秒），start_ts 不早于当前时间；end_ts 为 0 时取消计划
+/// 计划期间 weight 从当前值线性变化到 target，由涉及同一主币的 stake / unstake 等指令惰性更新，无需管理员持续修改
+/// 之后手动修改该 item 的 weight 会取消计划
+pub fn set_weight_schedule(
+    ctx: Context<SetWeightSchedule>,
+    item_index: u16,
+    target: u64,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后必须通过 queue_action 执行
+    pool.verify_direct_admin_action()?;
+
+    // 结算奖励排放并应用该 item 已有的计划，新计划从当前有效 weight 开始
+    let now = Clock::get()?.unix_timestamp;
+    pool.accrue_emission(now)?;
+    pool.apply_weight_schedules(item_index as usize, now)?;
+
+    pool.set_weight_schedule(item_index as usize, target, start_ts, end_ts, now)?;
+    let item = &pool.tokens[item_index as usize];
+
+    msg!("Weight schedule updated: pool: {}, item_index: {}, lp_mint: {}, start_weight: {}, target: {}, start_ts: {}, end_ts: {}",
+         ctx.accounts.pool.key(),
+         item_index,
+         item.mint_pubkey(),
+         item.weight_decay.start_weight,
+         target,
+         start_ts,
+         end_ts);
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+use super::stake::format_tag;
+
+/// 设置质押白名单
+#[derive(Accounts)]
+pub struct SetWhitelist<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Pool 管理员 - 必须签名
+    pub admin: Signer<'info>,
+}
+
+/// 设置质押白名单（许可池）
+/// whitelist_root: 白名单 merkle 根，叶子为 sha256(user)，每层按字节序排序后拼接哈希
+/// permissioned: 是否启用白名单 - 启用后 stake 必须提供 user 的 merkle 证明
+/// 轮换白名单时传入新的根即可，已持有的 LP 不受影响
+pub fn set_whitelist(
+    ctx: Context<SetWhitelist>,
+    whitelist_root: [u8; 32],
+    permissioned: bool,
+) -> Result<()> {
+    let pool = &mut Pool::load_mut_versioned(&ctx.accounts.pool)?;
+
+    // 验证管理员权限
+    pool.verify_admins(&ctx.accounts.admin.key(), ctx.remaining_accounts)?;
+    // 启用时间锁后必须通过 queue_action 执行
+    pool.verify_direct_admin_action()?;
+
+    pool.set_whitelist(whitelist_root, permissioned)?;
+
+    msg!("Whitelist updated: pool: {}, permissioned: {}, root: {}",
+         ctx.accounts.pool.key(),
+         permissioned,
+         format_tag(&whitelist_root));
+
+    Ok(())
+}
@@ -3,21 +3,37 @@ use anchor_lang::prelude::*;
 pub mod instructions;
 pub mod state;
 pub mod error;
+pub mod events;
+pub mod math;
+pub mod transfer;
+#[cfg(feature = "offchain")]
+pub mod offchain;
+#[cfg(feature = "cpi")]
+pub mod cpi_builders;
 
 use instructions::*;
+use state::{AdminAction, FeeConfig, PoolSizeTier};
 declare_id!("2mgSDKAjDo8fQN6oms6YzczHhyeYEJunTzxjQgegYADf");
 
 #[program]
 pub mod multistake {
     use super::*;
 
-    /// 创建 Pool（PDA）
+    /// 分配 Pool PDA 的账户空间（每次最多 10KB，create_pool 之前重复调用直到达到完整大小）
+    pub fn allocate_pool(
+        ctx: Context<AllocatePool>,
+        size_tier: PoolSizeTier,
+    ) -> Result<()> {
+        instructions::allocate_pool(ctx, size_tier)
+    }
+
+    /// 创建 Pool（PDA），size_tier 决定 item 容量和租金
     pub fn create_pool(
         ctx: Context<CreatePool>,
-        fee_numerator: u64,
-        fee_denominator: u64,
+        fee_config: FeeConfig,
+        size_tier: PoolSizeTier,
     ) -> Result<()> {
-        instructions::create_pool(ctx, fee_numerator, fee_denominator)
+        instructions::create_pool(ctx, fee_config, size_tier)
     }
 
     /// 添加质押类型到 Pool
@@ -44,21 +60,901 @@ pub mod multistake {
         instructions::modify_token_weight(ctx, new_weights)
     }
 
+    /// 紧凑编码批量修改 weight（zigzag LEB128 增量）
+    pub fn modify_weights_packed(
+        ctx: Context<ModifyTokenWeight>,
+        start_index: u16,
+        packed_deltas: Vec<u8>,
+    ) -> Result<()> {
+        instructions::modify_weights_packed(ctx, start_index, packed_deltas)
+    }
+
+    /// 设置单次修改 weight 的最大变化幅度（bps），只能收紧
+    pub fn set_max_weight_change(ctx: Context<ModifyTokenWeight>, max_change_bps: u64) -> Result<()> {
+        instructions::set_max_weight_change(ctx, max_change_bps)
+    }
+
+    /// 提议强制修改 weight（不受变化幅度限制，需等待时间锁）
+    pub fn propose_force_weight(
+        ctx: Context<ModifyTokenWeight>,
+        lp_mint: Pubkey,
+        new_weight: u64,
+    ) -> Result<()> {
+        instructions::propose_force_weight(ctx, lp_mint, new_weight)
+    }
+
+    /// 执行到期的强制 weight 修改
+    pub fn force_modify_weight(ctx: Context<ModifyTokenWeight>) -> Result<()> {
+        instructions::force_modify_weight(ctx)
+    }
+
     /// 质押主币，铸造 LP 凭证
-    pub fn stake(
-        ctx: Context<Stake>,
+    pub fn stake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Stake<'info>>,
         item_index: u16,
         stake_amount: u64,
+        tag: Option<[u8; 32]>,
+        referrer: Option<Pubkey>,
+        whitelist_proof: Vec<[u8; 32]>,
     ) -> Result<()> {
-        instructions::stake(ctx, item_index, stake_amount)
+        instructions::stake(ctx, item_index, stake_amount, tag, referrer, whitelist_proof)
     }
 
     /// 销毁 LP 凭证，赎回主币
-    pub fn unstake(
-        ctx: Context<Unstake>,
+    pub fn unstake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>,
         item_index: u16,
         lp_amount: u64,
+        min_redeem_amount: u64,
+        tag: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::unstake(ctx, item_index, lp_amount, min_redeem_amount, tag)
+    }
+
+    /// 将 pool authority 迁移到下一个 seed 版本
+    pub fn migrate_pool_authority<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigratePoolAuthority<'info>>,
+    ) -> Result<()> {
+        instructions::migrate_pool_authority(ctx)
+    }
+
+    /// 扩容 Pool 账户（程序升级后使用）
+    pub fn resize_pool(
+        ctx: Context<ResizePool>,
+        new_size: u64,
+    ) -> Result<()> {
+        instructions::resize_pool(ctx, new_size)
+    }
+
+    /// 增加 Pool 的 item 容量（单次最多约 75 个）
+    pub fn grow_pool(
+        ctx: Context<GrowPool>,
+        additional: u16,
+    ) -> Result<()> {
+        instructions::grow_pool(ctx, additional)
+    }
+
+    /// 提议轮换 pool vault（时间锁到期后才能执行）
+    pub fn propose_vault_rotation(
+        ctx: Context<ProposeVaultRotation>,
+    ) -> Result<()> {
+        instructions::propose_vault_rotation(ctx)
+    }
+
+    /// 执行 pool vault 轮换：创建新 vault 并转移全部余额
+    pub fn rotate_pool_vault<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RotatePoolVault<'info>>,
+    ) -> Result<()> {
+        instructions::rotate_pool_vault(ctx)
+    }
+
+    /// 创建 Index LP mint（代表一篮子 item LP）
+    pub fn create_index_mint(
+        ctx: Context<CreateIndexMint>,
+    ) -> Result<()> {
+        instructions::create_index_mint(ctx)
+    }
+
+    /// 为 item 创建存放锁定 LP 的 Index vault
+    pub fn init_index_vault(
+        ctx: Context<InitIndexVault>,
+    ) -> Result<()> {
+        instructions::init_index_vault(ctx)
+    }
+
+    /// 锁定一篮子 item LP，铸造 Index LP
+    pub fn wrap_index<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WrapIndex<'info>>,
+        index_amount: u64,
+    ) -> Result<()> {
+        instructions::wrap_index(ctx, index_amount)
+    }
+
+    /// 销毁 Index LP，按比例取回 item LP
+    pub fn unwrap_index<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WrapIndex<'info>>,
+        index_amount: u64,
+    ) -> Result<()> {
+        instructions::unwrap_index(ctx, index_amount)
+    }
+
+    /// 设置调用方策略（顶层调用限制 / CPI 调用方白名单）
+    pub fn set_caller_policy(
+        ctx: Context<SetCallerPolicy>,
+        mode: u8,
+        programs: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_caller_policy(ctx, mode, programs)
+    }
+
+    /// 设置同一 item 两次修改 weight 之间的最小 slot 间隔
+    pub fn set_weight_cooldown(
+        ctx: Context<SetWeightCooldown>,
+        cooldown_slots: u64,
+    ) -> Result<()> {
+        instructions::set_weight_cooldown(ctx, cooldown_slots)
+    }
+
+    /// 创建质押记录（用于积分累积）
+    pub fn open_stake_record(
+        ctx: Context<OpenStakeRecord>,
+        item_index: u16,
+    ) -> Result<()> {
+        instructions::open_stake_record(ctx, item_index)
+    }
+
+    /// 查询质押记录截至当前 slot 的积分
+    pub fn get_points(
+        ctx: Context<GetPoints>,
+    ) -> Result<u128> {
+        instructions::get_points(ctx)
+    }
+
+    /// 注册额外主币（多资产模式）
+    pub fn add_main_asset(
+        ctx: Context<AddMainAsset>,
+    ) -> Result<()> {
+        instructions::add_main_asset(ctx)
+    }
+
+    /// 设置质押类型对应的主币
+    pub fn set_item_main_asset(
+        ctx: Context<SetItemMainAsset>,
+        item_index: u16,
+        asset_index: u8,
+    ) -> Result<()> {
+        instructions::set_item_main_asset(ctx, item_index, asset_index)
+    }
+
+    /// 配置质押类型的 oracle（脱锚保护）
+    pub fn set_item_oracle(
+        ctx: Context<SetItemOracle>,
+        max_staleness_seconds: u64,
+        max_confidence_bps: u16,
+        depeg_threshold_bps: u16,
+        depeg_action: u8,
+    ) -> Result<()> {
+        instructions::set_item_oracle(
+            ctx,
+            max_staleness_seconds,
+            max_confidence_bps,
+            depeg_threshold_bps,
+            depeg_action,
+        )
+    }
+
+    /// 移除质押类型的 oracle 配置
+    pub fn remove_item_oracle(
+        ctx: Context<RemoveItemOracle>,
+    ) -> Result<()> {
+        instructions::remove_item_oracle(ctx)
+    }
+
+    /// 创建保险基金 vault
+    pub fn init_insurance_vault(
+        ctx: Context<InitInsuranceVault>,
+    ) -> Result<()> {
+        instructions::init_insurance_vault(ctx)
+    }
+
+    /// 设置紧急赎回配置（退出受限状态、罚金比例）
+    pub fn set_emergency_config(
+        ctx: Context<SetEmergencyConfig>,
+        exit_restricted: bool,
+        penalty_bps: u16,
+    ) -> Result<()> {
+        instructions::set_emergency_config(ctx, exit_restricted, penalty_bps)
+    }
+
+    /// 紧急赎回：退出受限期间绕过限制，罚金转入保险基金
+    pub fn emergency_unstake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EmergencyUnstake<'info>>,
+        item_index: u16,
+        lp_amount: u64,
+    ) -> Result<()> {
+        instructions::emergency_unstake(ctx, item_index, lp_amount)
+    }
+
+    /// 为某个 item 创建 treasury LP 账户
+    pub fn init_treasury_lp(
+        ctx: Context<InitTreasuryLp>,
+    ) -> Result<()> {
+        instructions::init_treasury_lp(ctx)
+    }
+
+    /// 设置 stake 手续费是否以 LP 形式收取
+    pub fn set_fee_in_lp(
+        ctx: Context<SetFeeInLp>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::set_fee_in_lp(ctx, enabled)
+    }
+
+    /// 预览 stake：结果写入 return data 后以 PreviewOnly 中止
+    pub fn preview_stake(
+        ctx: Context<PreviewStake>,
+        item_index: u16,
+        stake_amount: u64,
+    ) -> Result<()> {
+        instructions::preview_stake(ctx, item_index, stake_amount)
+    }
+
+    /// 预览 unstake：结果写入 return data 后以 PreviewOnly 中止
+    pub fn preview_unstake(
+        ctx: Context<PreviewUnstake>,
+        item_index: u16,
+        lp_amount: u64,
+    ) -> Result<()> {
+        instructions::preview_unstake(ctx, item_index, lp_amount)
+    }
+
+    /// 报价 stake：结果写入 return data（只读，正常返回）
+    pub fn quote_stake(
+        ctx: Context<PreviewStake>,
+        item_index: u16,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::quote_stake(ctx, item_index, amount)
+    }
+
+    /// 报价 unstake：结果写入 return data（只读，正常返回）
+    pub fn quote_unstake(
+        ctx: Context<PreviewUnstake>,
+        item_index: u16,
+        lp_amount: u64,
+    ) -> Result<()> {
+        instructions::quote_unstake(ctx, item_index, lp_amount)
+    }
+
+    /// 注册推荐码（推荐码 → 推荐人收款账户）
+    pub fn register_referral_code(
+        ctx: Context<RegisterReferralCode>,
+        code: Vec<u8>,
+    ) -> Result<()> {
+        instructions::register_referral_code(ctx, code)
+    }
+
+    /// 设置 stake 手续费的推荐返佣比例
+    pub fn set_referral_share(
+        ctx: Context<SetReferralShare>,
+        share_bps: u16,
+    ) -> Result<()> {
+        instructions::set_referral_share(ctx, share_bps)
+    }
+
+    /// 领取 stake 时通过 referrer 参数累积的推荐返佣
+    pub fn claim_referral_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimReferralFees<'info>>,
+    ) -> Result<()> {
+        instructions::claim_referral_fees(ctx)
+    }
+
+    /// 创建积分奖励配置及奖励 vault
+    pub fn init_reward_config(
+        ctx: Context<InitRewardConfig>,
+        reward_rate: u64,
+        keeper_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::init_reward_config(ctx, reward_rate, keeper_fee_bps)
+    }
+
+    /// 修改积分奖励配置
+    pub fn set_reward_config(
+        ctx: Context<SetRewardConfig>,
+        reward_rate: u64,
+        keeper_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::set_reward_config(ctx, reward_rate, keeper_fee_bps)
+    }
+
+    /// 设置质押记录的自动复投偏好
+    pub fn set_auto_compound(
+        ctx: Context<SetAutoCompound>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::set_auto_compound(ctx, enabled)
+    }
+
+    /// keeper 代用户收割积分奖励（可按用户偏好自动复投）
+    pub fn harvest_for<'info>(
+        ctx: Context<'_, '_, 'info, 'info, HarvestFor<'info>>,
+        item_index: u16,
+    ) -> Result<()> {
+        instructions::harvest_for(ctx, item_index)
+    }
+
+    /// 为某个 item 创建汇率历史账户
+    pub fn init_rate_history(
+        ctx: Context<InitRateHistory>,
+    ) -> Result<()> {
+        instructions::init_rate_history(ctx)
+    }
+
+    /// 记录 item 的汇率样本（每个 epoch 一次）
+    pub fn record_rate_sample(
+        ctx: Context<RecordRateSample>,
+        item_index: u16,
+    ) -> Result<()> {
+        instructions::record_rate_sample(ctx, item_index)
+    }
+
+    /// 设置链下 weight oracle 的签名公钥
+    pub fn set_weight_oracle(
+        ctx: Context<SetWeightOracle>,
+        signer: Pubkey,
+    ) -> Result<()> {
+        instructions::set_weight_oracle(ctx, signer)
+    }
+
+    /// 提交 oracle 签名（ed25519）的 weight
+    pub fn submit_oracle_weights(
+        ctx: Context<SubmitOracleWeights>,
+        weights: Vec<u64>,
+        nonce: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        instructions::submit_oracle_weights(ctx, weights, nonce, expiry)
+    }
+
+    /// 配置管理操作时间锁（guardian、延迟）
+    pub fn configure_timelock(
+        ctx: Context<ConfigureTimelock>,
+        guardian: Pubkey,
+        delay_slots: u64,
+    ) -> Result<()> {
+        instructions::configure_timelock(ctx, guardian, delay_slots)
+    }
+
+    /// 将管理操作加入时间锁队列
+    pub fn queue_action(
+        ctx: Context<QueueAction>,
+        action: AdminAction,
+    ) -> Result<()> {
+        instructions::queue_action(ctx, action)
+    }
+
+    /// 执行到期的管理操作
+    pub fn execute_action<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteAction<'info>>,
+    ) -> Result<()> {
+        instructions::execute_action(ctx)
+    }
+
+    /// 取消待执行的管理操作（guardian 或 admin）
+    pub fn cancel_action(
+        ctx: Context<CancelAction>,
+    ) -> Result<()> {
+        instructions::cancel_action(ctx)
+    }
+
+    /// 为已存在的 item 补建 mint → index 映射
+    pub fn init_mint_index(
+        ctx: Context<InitMintIndex>,
+    ) -> Result<()> {
+        instructions::init_mint_index(ctx)
+    }
+
+    /// 将一个质押类型的 LP 转换为另一个质押类型的 LP（不经过主币 vault）
+    pub fn convert_lp(
+        ctx: Context<ConvertLp>,
+        from_index: u16,
+        to_index: u16,
+        lp_amount: u64,
+    ) -> Result<()> {
+        instructions::convert_lp(ctx, from_index, to_index, lp_amount)
+    }
+
+    /// 将头寸从一个质押类型迁移到另一个质押类型（只收取一次转换手续费，带滑点保护）
+    pub fn restake(
+        ctx: Context<ConvertLp>,
+        from_index: u16,
+        to_index: u16,
+        lp_amount: u64,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        instructions::restake(ctx, from_index, to_index, lp_amount, min_lp_out)
+    }
+
+    /// 设置兑换率操纵保护参数（最小 vault 余额、捐赠限速）
+    pub fn set_rate_guard(
+        ctx: Context<SetRateGuard>,
+        min_vault_balance: u64,
+        max_donation_bps: u16,
+    ) -> Result<()> {
+        instructions::set_rate_guard(ctx, min_vault_balance, max_donation_bps)
+    }
+
+    /// 设置单笔 stake 的最小主币数量
+    pub fn set_min_stake_amount(
+        ctx: Context<SetMinStakeAmount>,
+        min_stake_amount: u64,
+    ) -> Result<()> {
+        instructions::set_min_stake_amount(ctx, min_stake_amount)
+    }
+
+    /// 设置质押记录是否允许转让
+    pub fn set_position_transferable(
+        ctx: Context<SetPositionTransferable>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::set_position_transferable(ctx, enabled)
+    }
+
+    /// 将质押记录转让给新所有者
+    pub fn transfer_position(ctx: Context<TransferPosition>, item_index: u16) -> Result<()> {
+        instructions::transfer_position(ctx, item_index)
+    }
+
+    /// 生成 pool 记账状态检查点（任何人都可以调用）
+    pub fn checkpoint(ctx: Context<Checkpoint>) -> Result<()> {
+        instructions::checkpoint(ctx)
+    }
+
+    /// 为 LP mint 创建 Metaplex 元数据
+    pub fn create_lp_metadata(ctx: Context<CreateLpMetadata>, data: LpMetadataData) -> Result<()> {
+        instructions::create_lp_metadata(ctx, data)
+    }
+
+    /// 更新 LP 元数据或转移其 update authority
+    pub fn update_lp_metadata(
+        ctx: Context<UpdateLpMetadata>,
+        data: Option<LpMetadataData>,
+        new_update_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_lp_metadata(ctx, data, new_update_authority)
+    }
+
+    /// 配置 WSOL 池的原生质押策略
+    pub fn configure_stake_strategy(
+        ctx: Context<ConfigureStakeStrategy>,
+        vote_account: Pubkey,
+        max_delegation_bps: u16,
+    ) -> Result<()> {
+        instructions::configure_stake_strategy(ctx, vote_account, max_delegation_bps)
+    }
+
+    /// 将 vault 中的部分 SOL 委托给验证者
+    pub fn delegate_vault_stake(ctx: Context<DelegateVaultStake>, amount: u64) -> Result<()> {
+        instructions::delegate_vault_stake(ctx, amount)
+    }
+
+    /// 取消策略质押账户的委托
+    pub fn deactivate_vault_stake(ctx: Context<DeactivateVaultStake>) -> Result<()> {
+        instructions::deactivate_vault_stake(ctx)
+    }
+
+    /// 提取策略质押账户的全部 SOL 回 vault
+    pub fn withdraw_vault_stake(ctx: Context<WithdrawVaultStake>) -> Result<()> {
+        instructions::withdraw_vault_stake(ctx)
+    }
+
+    /// 设置赎回限速（窗口长度、窗口上限、优先通道预留额度）
+    pub fn set_redemption_limit(
+        ctx: Context<SetRedemptionLimit>,
+        window_slots: u64,
+        window_cap: u64,
+        priority_reserve: u64,
+    ) -> Result<()> {
+        instructions::set_redemption_limit(ctx, window_slots, window_cap, priority_reserve)
+    }
+
+    /// 登记优先赎回地址
+    pub fn add_priority_redeemer(ctx: Context<AddPriorityRedeemer>, redeemer: Pubkey) -> Result<()> {
+        instructions::add_priority_redeemer(ctx, redeemer)
+    }
+
+    /// 移除优先赎回地址
+    pub fn remove_priority_redeemer(ctx: Context<RemovePriorityRedeemer>) -> Result<()> {
+        instructions::remove_priority_redeemer(ctx)
+    }
+
+    /// 提议新的 Pool 管理员（两步交接第一步）
+    pub fn propose_admin(
+        ctx: Context<ProposeAdmin>,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_admin(ctx, new_admin)
+    }
+
+    /// 新管理员签名接受交接（两步交接第二步）
+    pub fn accept_admin(
+        ctx: Context<AcceptAdmin>,
+    ) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    /// 设置 Pool 暂停状态
+    /// withdraw_only 为 true 时暂停期间仍允许赎回
+    pub fn set_pause(
+        ctx: Context<SetPause>,
+        paused: bool,
+        withdraw_only: bool,
+    ) -> Result<()> {
+        instructions::set_pause(ctx, paused, withdraw_only)
+    }
+
+    /// 设置赎回冷却期（秒），0 表示允许即时 unstake
+    pub fn set_unstake_cooldown(
+        ctx: Context<SetUnstakeCooldown>,
+        cooldown_seconds: i64,
+    ) -> Result<()> {
+        instructions::set_unstake_cooldown(ctx, cooldown_seconds)
+    }
+
+    /// 发起冷却赎回：销毁 LP，创建赎回凭证
+    pub fn request_unstake(
+        ctx: Context<RequestUnstake>,
+        item_index: u16,
+        lp_amount: u64,
+        min_redeem_amount: u64,
+        ticket_id: u64,
+    ) -> Result<()> {
+        instructions::request_unstake(ctx, item_index, lp_amount, min_redeem_amount, ticket_id)
+    }
+
+    /// 冷却期结束后领取赎回的主币
+    pub fn claim_unstake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimUnstake<'info>>,
+    ) -> Result<()> {
+        instructions::claim_unstake(ctx)
+    }
+
+    /// 启用奖励排放，创建排放 vault
+    pub fn init_reward_emission(
+        ctx: Context<InitRewardEmission>,
+        emission_rate: u64,
+    ) -> Result<()> {
+        instructions::init_reward_emission(ctx, emission_rate)
+    }
+
+    /// 修改奖励排放速率
+    pub fn set_emission_rate(
+        ctx: Context<SetEmissionRate>,
+        emission_rate: u64,
+    ) -> Result<()> {
+        instructions::set_emission_rate(ctx, emission_rate)
+    }
+
+    /// 向排放 vault 注资（任何人可调用）
+    pub fn fund_rewards(
+        ctx: Context<FundRewards>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::fund_rewards(ctx, amount)
+    }
+
+    /// 领取质押记录的排放奖励
+    pub fn claim_rewards(
+        ctx: Context<ClaimRewards>,
+        item_index: u16,
+    ) -> Result<()> {
+        instructions::claim_rewards(ctx, item_index)
+    }
+
+    /// 将开启自动复投的质押记录的排放奖励复投为 LP（任何人都可以调用）
+    pub fn compound_rewards<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CompoundRewards<'info>>,
+        item_index: u16,
+    ) -> Result<()> {
+        instructions::compound_rewards(ctx, item_index)
+    }
+
+    /// 设置质押代理，代理可按权限位代为 stake / unstake / claim_rewards
+    pub fn set_delegate(
+        ctx: Context<SetDelegate>,
+        delegate: Pubkey,
+        permissions: u8,
+    ) -> Result<()> {
+        instructions::set_delegate(ctx, delegate, permissions)
+    }
+
+    /// 撤销质押代理
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        instructions::revoke_delegate(ctx)
+    }
+
+    /// 管理员从手续费 vault 提取手续费
+    pub fn withdraw_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawFees<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_fees(ctx, amount)
+    }
+
+    /// 修改费率（上调时受时间锁约束）
+    pub fn set_fee(ctx: Context<SetFee>, fee_config: FeeConfig) -> Result<()> {
+        instructions::set_fee(ctx, fee_config)
+    }
+
+    /// 应用到期的待生效费率
+    pub fn apply_fee(ctx: Context<ApplyFee>) -> Result<()> {
+        instructions::apply_fee(ctx)
+    }
+
+    /// 将旧 pool 的单一费率迁移为分路径的 FeeConfig（任何人可调用）
+    pub fn migrate_fee_config(ctx: Context<MigrateFeeConfig>) -> Result<()> {
+        instructions::migrate_fee_config(ctx)
+    }
+
+    /// 设置费率上调的时间锁
+    pub fn set_fee_update_delay(ctx: Context<SetFee>, delay_seconds: i64) -> Result<()> {
+        instructions::set_fee_update_delay(ctx, delay_seconds)
+    }
+
+    /// 开启或关闭 oracle weight 更新
+    pub fn set_oracle_weights(ctx: Context<SetOracleWeights>, enabled: bool) -> Result<()> {
+        instructions::set_oracle_weights(ctx, enabled)
+    }
+
+    /// 按 Pyth 价格刷新已配置 oracle 的 item 的 weight（任何人可调用）
+    pub fn update_weights_from_oracle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateWeightsFromOracle<'info>>,
+    ) -> Result<()> {
+        instructions::update_weights_from_oracle(ctx)
+    }
+
+    /// 设置 crank_weights 的调用间隔和奖励
+    pub fn set_crank_config(
+        ctx: Context<SetCrankConfig>,
+        interval_seconds: i64,
+        reward: u64,
+    ) -> Result<()> {
+        instructions::set_crank_config(ctx, interval_seconds, reward)
+    }
+
+    /// 定时刷新所有 oracle weight，并向调用者支付奖励（任何人可调用）
+    pub fn crank_weights<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CrankWeights<'info>>,
+    ) -> Result<()> {
+        instructions::crank_weights(ctx)
+    }
+
+    /// 登记共同管理员
+    pub fn add_admin_signer(ctx: Context<ManageAdminSigners>, signer: Pubkey) -> Result<()> {
+        instructions::add_admin_signer(ctx, signer)
+    }
+
+    /// 移除共同管理员
+    pub fn remove_admin_signer(ctx: Context<ManageAdminSigners>, signer: Pubkey) -> Result<()> {
+        instructions::remove_admin_signer(ctx, signer)
+    }
+
+    /// 设置管理操作所需的最少管理员签名数（M-of-N）
+    pub fn set_admin_threshold(ctx: Context<ManageAdminSigners>, threshold: u8) -> Result<()> {
+        instructions::set_admin_threshold(ctx, threshold)
+    }
+
+    /// 设置 weight 操作员（只能修改 weight 的低权限角色）
+    pub fn set_weight_operator(ctx: Context<ManageAdminSigners>, operator: Pubkey) -> Result<()> {
+        instructions::set_weight_operator(ctx, operator)
+    }
+
+    /// 关闭 Pool，回收租金
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        instructions::close_pool(ctx)
+    }
+
+    /// 启用 / 停用质押类型（停用后只允许赎回）
+    pub fn set_item_active(ctx: Context<SetItemActive>, item_index: u16, active: bool) -> Result<()> {
+        instructions::set_item_active(ctx, item_index, active)
+    }
+
+    /// 使用原生 SOL 质押（自动包装为 wSOL）
+    pub fn stake_sol<'info>(
+        ctx: Context<'_, '_, 'info, 'info, StakeSol<'info>>,
+        item_index: u16,
+        lamports: u64,
+        tag: Option<[u8; 32]>,
+        referrer: Option<Pubkey>,
+        whitelist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::stake_sol(ctx, item_index, lamports, tag, referrer, whitelist_proof)
+    }
+
+    /// 销毁 LP 凭证，赎回为原生 SOL（自动解包 wSOL）
+    pub fn unstake_sol<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnstakeSol<'info>>,
+        item_index: u16,
+        lp_amount: u64,
+        min_redeem_amount: u64,
+        tag: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::unstake_sol(ctx, item_index, lp_amount, min_redeem_amount, tag)
+    }
+
+    /// 设置质押白名单 merkle 根及许可池开关
+    pub fn set_whitelist(
+        ctx: Context<SetWhitelist>,
+        whitelist_root: [u8; 32],
+        permissioned: bool,
+    ) -> Result<()> {
+        instructions::set_whitelist(ctx, whitelist_root, permissioned)
+    }
+
+    /// 按 items 重新计算总加权质押量缓存（调试 / 修复用）
+    pub fn sync_total_weighted(ctx: Context<SyncTotalWeighted>) -> Result<()> {
+        instructions::sync_total_weighted(ctx)
+    }
+
+    /// 将注册表上线前创建的 pool 登记到主币注册表
+    pub fn register_pool(ctx: Context<RegisterPool>) -> Result<()> {
+        instructions::register_pool(ctx)
+    }
+
+    /// 向主币 vault 捐赠主币（无需权限），立即按兑换率分配给现有 LP 持有人
+    pub fn donate_to_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DonateToPool<'info>>,
+        asset_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::donate_to_pool(ctx, asset_index, amount)
+    }
+
+    /// 创建 pool 的 weight 修改历史（管理员），之后管理员修改 weight 时自动记录
+    pub fn init_weight_history(
+        ctx: Context<InitWeightHistory>,
+    ) -> Result<()> {
+        instructions::init_weight_history(ctx)
+    }
+
+    /// 锁仓质押：不铸造 LP，按锁定时长档位加成后计入锁仓头寸，到期前不可赎回
+    pub fn stake_locked<'info>(
+        ctx: Context<'_, '_, 'info, 'info, StakeLocked<'info>>,
+        item_index: u16,
+        stake_amount: u64,
+        lock_seconds: i64,
+        whitelist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::stake_locked(ctx, item_index, stake_amount, lock_seconds, whitelist_proof)
+    }
+
+    /// 赎回到期的锁仓头寸（按含加成的有效数量），赎回后关闭头寸
+    pub fn unstake_locked<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnstakeLocked<'info>>,
+        item_index: u16,
+        min_redeem_amount: u64,
+    ) -> Result<()> {
+        instructions::unstake_locked(ctx, item_index, min_redeem_amount)
+    }
+
+    /// 提取误转入 pool authority 账户的其他代币（主币、LP 和奖励币除外）
+    pub fn rescue_tokens<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RescueTokens<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::rescue_tokens(ctx, amount)
+    }
+
+    /// 提议主币迁移到新 mint（时间锁到期后由 migrate_main_mint 执行）
+    pub fn propose_main_mint_migration(
+        ctx: Context<ProposeMainMintMigration>,
+        ratio_numerator: u64,
+        ratio_denominator: u64,
+    ) -> Result<()> {
+        instructions::propose_main_mint_migration(ctx, ratio_numerator, ratio_denominator)
+    }
+
+    /// 执行主币迁移，切换 pool_mint / pool_vault
+    pub fn migrate_main_mint<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateMainMint<'info>>,
+    ) -> Result<()> {
+        instructions::migrate_main_mint(ctx)
+    }
+
+    /// 生成当前 epoch 的 LP 发行量和权重快照（任何人都可以调用）
+    pub fn snapshot(ctx: Context<Snapshot>, epoch: u64) -> Result<()> {
+        instructions::snapshot(ctx, epoch)
+    }
+
+    /// 创建全局程序配置（仅程序升级权限）
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        protocol_treasury: Pubkey,
+        protocol_fee_share_bps: u16,
+    ) -> Result<()> {
+        instructions::initialize_config(ctx, protocol_treasury, protocol_fee_share_bps)
+    }
+
+    /// 更新全局程序配置：协议金库、协议分成比例，可选移交配置管理员
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        protocol_treasury: Pubkey,
+        protocol_fee_share_bps: u16,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_config(ctx, protocol_treasury, protocol_fee_share_bps, new_authority)
+    }
+
+    /// 等比缩放所有 item 的 weight，用于把旧 pool 迁移到 WEIGHT_SCALE 基准
+    pub fn rescale_weights(
+        ctx: Context<RescaleWeights>,
+        numerator: u64,
+        denominator: u64,
+    ) -> Result<()> {
+        instructions::rescale_weights(ctx, numerator, denominator)
+    }
+
+    /// 创建 pool 的统计账户（任何人可调用）
+    pub fn init_pool_stats(ctx: Context<InitPoolStats>) -> Result<()> {
+        instructions::init_pool_stats(ctx)
+    }
+
+    /// 刷新 pool 的派生统计数据（任何人可调用）
+    pub fn refresh_stats<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefreshStats<'info>>,
+    ) -> Result<()> {
+        instructions::refresh_stats(ctx)
+    }
+
+    /// 设置质押类型的 weight 衰减计划（end_ts 为 0 时取消）
+    pub fn set_weight_schedule(
+        ctx: Context<SetWeightSchedule>,
+        item_index: u16,
+        target: u64,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Result<()> {
+        instructions::set_weight_schedule(ctx, item_index, target, start_ts, end_ts)
+    }
+
+    /// 配置质押类型的兑换率来源（weight 在 stake / unstake 时从 Pyth feed 或 SPL stake pool 读取）
+    pub fn set_item_rate_source(
+        ctx: Context<SetItemRateSource>,
+        kind: u8,
+        max_staleness: u64,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        instructions::set_item_rate_source(ctx, kind, max_staleness, max_confidence_bps)
+    }
+
+    /// 移除质押类型的兑换率来源
+    pub fn remove_item_rate_source(ctx: Context<RemoveItemRateSource>) -> Result<()> {
+        instructions::remove_item_rate_source(ctx)
+    }
+
+    /// 设置质押类型的显示名称和符号
+    pub fn set_item_metadata(
+        ctx: Context<SetItemMetadata>,
+        item_index: u16,
+        name: String,
+        symbol: String,
+    ) -> Result<()> {
+        instructions::set_item_metadata(ctx, item_index, name, symbol)
+    }
+
+    /// 把最初部署的 pool（v1 布局）迁移到当前布局版本，须先通过 resize_pool 扩容
+    pub fn migrate_pool_v2(ctx: Context<MigratePoolV2>) -> Result<()> {
+        instructions::migrate_pool_v2(ctx)
+    }
+
+    /// 一次性创建测试 pool、若干 item 和已注资的用户账户（仅 localnet-bootstrap feature）
+    #[cfg(feature = "localnet-bootstrap")]
+    pub fn bootstrap_test_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BootstrapTestPool<'info>>,
+        fee_config: FeeConfig,
+        item_count: u8,
+        fund_amount: u64,
     ) -> Result<()> {
-        instructions::unstake(ctx, item_index, lp_amount)
+        instructions::bootstrap_test_pool(ctx, fee_config, item_count, fund_amount)
     }
 }
@@ -0,0 +1,587 @@
+//! 质押池核心计算（纯函数）
+//!
+//! 不依赖账户类型和 anchor 错误类型，溢出或非法输入返回 `None`。
+//! `Pool` 的同名方法委托到这里，链下模拟器 / 风控引擎可通过 `offchain` feature 直接复用。
+//! 取整方向见 `rounding`：手续费向上取整，付给用户的金额向下取整。
+
+pub mod rounding;
+#[cfg(test)]
+mod proptests;
+
+use primitive_types::U256;
+use rounding::{mul_div_ceil, mul_div_floor};
+
+/// 计算手续费（用户支付给 pool）
+/// 返回: (手续费金额, 扣除手续费后的金额)
+/// 公式: fee = amount × fee_numerator / fee_denominator（向上取整）
+pub fn calculate_fee(amount: u64, fee_numerator: u64, fee_denominator: u64) -> Option<(u64, u64)> {
+    let fee_amount = narrow_u64(mul_div_ceil(
+        U256::from(amount),
+        U256::from(fee_numerator),
+        U256::from(fee_denominator),
+    )?)?;
+    let amount_after_fee = amount.checked_sub(fee_amount)?;
+    Some((fee_amount, amount_after_fee))
+}
+
+/// 按比例拆分金额（从 pool 一侧付出的分成，如推荐返佣、协议分成、keeper 抽成）
+/// 返回: (分成金额, 剩余金额)
+/// 公式: share = amount × share_numerator / denominator（向下取整）
+pub fn calculate_share(amount: u64, share_numerator: u64, denominator: u64) -> Option<(u64, u64)> {
+    let share_amount = narrow_u64(mul_div_floor(
+        U256::from(amount),
+        U256::from(share_numerator),
+        U256::from(denominator),
+    )?)?;
+    let remaining = amount.checked_sub(share_amount)?;
+    Some((share_amount, remaining))
+}
+
+/// 按兑换率计算存入 amount 主币可获得的 LP 数量
+/// 公式: lp = amount × total_weighted / (vault_balance × weight)（向下取整）
+/// 与 calculate_redeem_amount 互逆：存入后立即赎回得到的主币不超过 amount，已有持有人不被稀释
+/// 引导：total_weighted == 0（尚无 LP）时 1:1 铸造；已有 LP 但 vault 为 0 时无法定价，返回 None
+pub fn calculate_stake_lp_amount(
+    amount: u64,
+    weight: u64,
+    total_weighted: U256,
+    vault_balance: u64,
+) -> Option<u64> {
+    if weight == 0 {
+        return None;
+    }
+    if total_weighted.is_zero() {
+        return Some(amount);
+    }
+    if vault_balance == 0 {
+        return None;
+    }
+    let denominator = U256::from(vault_balance).checked_mul(U256::from(weight))?;
+    narrow_u64(mul_div_floor(U256::from(amount), total_weighted, denominator)?)
+}
+
+/// 计算所有质押类型的总加权质押量
+/// items: 每个质押类型的 (weight, mint_amount)，切片可用 `items.iter().copied()` 传入
+/// 公式: sum(weight_i × mint_amount_i)
+/// 单项乘积可达 2^128，MAX_TOKENS 个 item 求和会超出 u128，因此中间值使用 U256
+pub fn calculate_total_weighted_mint_amount<I>(items: I) -> Option<U256>
+where
+    I: IntoIterator<Item = (u64, u64)>,
+{
+    items
+        .into_iter()
+        .try_fold(U256::zero(), |total, (weight, mint_amount)| {
+            let weighted = U256::from(weight).checked_mul(U256::from(mint_amount))?;
+            total.checked_add(weighted)
+        })
+}
+
+/// 计算赎回的主币数量
+/// 公式: pool_vault_balance × lp_amount × weight / total_weighted（向下取整）
+/// 分子最大约 2^192，使用 U256 计算，结果超出 u64 时返回 None
+pub fn calculate_redeem_amount(
+    lp_amount: u64,
+    weight: u64,
+    total_weighted: U256,
+    pool_vault_balance: u64,
+) -> Option<u64> {
+    if weight == 0 || total_weighted.is_zero() {
+        return None;
+    }
+    let weighted_amount = U256::from(lp_amount).checked_mul(U256::from(weight))?;
+    narrow_u64(mul_div_floor(U256::from(pool_vault_balance), weighted_amount, total_weighted)?)
+}
+
+/// 按各质押类型的 (weight, mint_amount) 计算赎回 item_index 的 lp_amount 可得的主币数量
+/// items 只包含共享同一主币 vault 的质押类型；与 Pool 使用缓存的总加权量不同，这里每次遍历求和
+/// 公式同 calculate_redeem_amount，item_index 越界时返回 None
+pub fn calculate_redeem_amount_for_items(
+    items: &[(u64, u64)],
+    item_index: usize,
+    lp_amount: u64,
+    pool_vault_balance: u64,
+) -> Option<u64> {
+    let (weight, _) = *items.get(item_index)?;
+    let total_weighted = calculate_total_weighted_mint_amount(items.iter().copied())?;
+    calculate_redeem_amount(lp_amount, weight, total_weighted, pool_vault_balance)
+}
+
+/// U256 收窄为 u64，超出范围时返回 None
+pub fn narrow_u64(value: U256) -> Option<u64> {
+    u64::try_from(value).ok()
+}
+
+/// 计算计入兑换率的 vault 余额（防止直接捐赠操纵兑换率）
+/// 实际余额低于记账余额（亏损）时立即按实际余额计算；
+/// 高于记账余额的部分视为直接捐赠，单笔交易最多计入 tracked × max_donation_bps / 10000
+/// tracked == 0（尚未记账的旧 pool）或 max_donation_bps == 0 时不限制
+pub fn calculate_effective_vault_balance(
+    actual_balance: u64,
+    tracked_balance: u64,
+    max_donation_bps: u16,
+) -> Option<u64> {
+    if tracked_balance == 0 || max_donation_bps == 0 || actual_balance <= tracked_balance {
+        return Some(actual_balance);
+    }
+    let max_increase = (tracked_balance as u128)
+        .checked_mul(max_donation_bps as u128)?
+        .checked_div(10_000)?;
+    let cap = (tracked_balance as u128).checked_add(max_increase)?;
+    Some(actual_balance.min(u64::try_from(cap).unwrap_or(u64::MAX)))
+}
+
+/// 解码紧凑编码的 weight 增量
+/// 每个增量为 zigzag 编码的 LEB128 变长整数（小变化只占 1~2 字节）
+/// 返回按顺序排列的有符号增量，编码非法或溢出时返回 None
+pub fn decode_weight_deltas(bytes: &[u8]) -> Option<Vec<i64>> {
+    let mut deltas = Vec::new();
+    let mut value: u64 = 0;
+    let mut shift: u32 = 0;
+    for &byte in bytes {
+        if shift >= 64 || (shift == 63 && byte & 0x7f > 1) {
+            return None;
+        }
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            deltas.push(((value >> 1) as i64) ^ -((value & 1) as i64));
+            value = 0;
+            shift = 0;
+        } else {
+            shift += 7;
+        }
+    }
+    // 最后一个变长整数未结束
+    if shift != 0 {
+        return None;
+    }
+    Some(deltas)
+}
+
+/// 编码 weight 增量（decode_weight_deltas 的逆运算），供链下构造交易使用
+pub fn encode_weight_deltas(deltas: &[i64]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for &delta in deltas {
+        let mut value = ((delta << 1) ^ (delta >> 63)) as u64;
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                break;
+            }
+            bytes.push(byte | 0x80);
+        }
+    }
+    bytes
+}
+
+/// 将增量应用到 weight，结果溢出或为负时返回 None
+pub fn apply_weight_delta(weight: u64, delta: i64) -> Option<u64> {
+    weight.checked_add_signed(delta)
+}
+
+/// 检查 weight 变化幅度是否在 max_change_bps 以内
+/// 公式: |new_weight - old_weight| × 10000 ≤ old_weight × max_change_bps
+/// max_change_bps 为 0 表示不限制；old_weight 为 0 时只允许保持为 0
+pub fn is_weight_change_within(old_weight: u64, new_weight: u64, max_change_bps: u64) -> bool {
+    if max_change_bps == 0 {
+        return true;
+    }
+    let change = old_weight.abs_diff(new_weight) as u128 * 10_000;
+    change <= old_weight as u128 * max_change_bps as u128
+}
+
+/// 按兑换率计算 weight：1 份凭证可兑换的底层资产数量 × scale
+/// 公式: underlying × scale / supply（向下取整），supply 为 0 或结果超出 u64 时返回 None
+/// 如 SPL stake pool 的 total_lamports / pool_token_supply
+pub fn calculate_rate_weight(underlying: u64, supply: u64, scale: u64) -> Option<u64> {
+    if supply == 0 {
+        return None;
+    }
+    u64::try_from(underlying as u128 * scale as u128 / supply as u128).ok()
+}
+
+/// 按线性衰减计划计算 now 时刻的有效 weight
+/// now ≤ start_ts 时为 start_weight，now ≥ end_ts 时为 target_weight，其间线性插值（向 start_weight 方向取整）
+/// 公式: start_weight - (start_weight - target_weight) × (now - start_ts) / (end_ts - start_ts)
+/// end_ts ≤ start_ts 时返回 None
+pub fn calculate_scheduled_weight(
+    start_weight: u64,
+    target_weight: u64,
+    start_ts: i64,
+    end_ts: i64,
+    now: i64,
+) -> Option<u64> {
+    if end_ts <= start_ts {
+        return None;
+    }
+    if now <= start_ts {
+        return Some(start_weight);
+    }
+    if now >= end_ts {
+        return Some(target_weight);
+    }
+    let elapsed = (now as i128 - start_ts as i128) as u128;
+    let duration = (end_ts as i128 - start_ts as i128) as u128;
+    let change = (start_weight.abs_diff(target_weight) as u128 * elapsed / duration) as u64;
+    if target_weight < start_weight {
+        start_weight.checked_sub(change)
+    } else {
+        start_weight.checked_add(change)
+    }
+}
+
+/// 奖励排放累加器精度：reward_per_share 表示每单位 LP 累积的奖励 × REWARD_PER_SHARE_SCALE
+pub const REWARD_PER_SHARE_SCALE: u128 = 1_000_000_000_000;
+
+/// 计算一段时间内某个质押类型每单位 LP 新增的奖励（放大 REWARD_PER_SHARE_SCALE 倍）
+/// 排放按 weight × mint_amount 在所有质押类型间分配，单位 LP 所得与 weight 成正比
+/// 公式: elapsed × emission_rate × weight × SCALE / total_weighted
+/// total_weighted 为 0 时返回 None
+pub fn calculate_reward_per_share_delta(
+    elapsed: u64,
+    emission_rate: u64,
+    weight: u64,
+    total_weighted: U256,
+) -> Option<u128> {
+    let delta = U256::from(elapsed)
+        .checked_mul(U256::from(emission_rate))?
+        .checked_mul(U256::from(weight))?
+        .checked_mul(U256::from(REWARD_PER_SHARE_SCALE))?
+        .checked_div(total_weighted)?;
+    u128::try_from(delta).ok()
+}
+
+/// 计算质押记录自上次结算以来的奖励
+/// 公式: staked_amount × (reward_per_share - reward_per_share_paid) / SCALE
+pub fn calculate_pending_reward(
+    staked_amount: u64,
+    reward_per_share: u128,
+    reward_per_share_paid: u128,
+) -> Option<u64> {
+    let delta = reward_per_share.checked_sub(reward_per_share_paid)?;
+    let reward = U256::from(staked_amount)
+        .checked_mul(U256::from(delta))?
+        / U256::from(REWARD_PER_SHARE_SCALE);
+    narrow_u64(reward)
+}
+
+/// 计算锁仓加成的额外计量数量（向下取整，加成从其他 LP 持有人处分配）
+/// 公式: amount × (boost_bps - 10000) / 10000，boost_bps 低于 10000 时返回 None
+pub fn calculate_boost_amount(amount: u64, boost_bps: u16) -> Option<u64> {
+    let bonus_bps = (boost_bps as u64).checked_sub(10_000)?;
+    narrow_u64(mul_div_floor(
+        U256::from(amount),
+        U256::from(bonus_bps),
+        U256::from(10_000u64),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_TOKENS: usize = crate::state::MAX_TOKENS;
+
+    #[test]
+    fn total_weighted_exceeds_u128_at_max_tokens() {
+        let items = vec![(u64::MAX, u64::MAX); MAX_TOKENS];
+        let total = calculate_total_weighted_mint_amount(items).unwrap();
+
+        let single = U256::from(u64::MAX) * U256::from(u64::MAX);
+        assert_eq!(total, single * U256::from(MAX_TOKENS));
+        assert!(total > U256::from(u128::MAX));
+    }
+
+    #[test]
+    fn redeem_at_max_tokens_scale() {
+        // 所有 item 相同，赎回一个 item 的全部 LP 应得到 vault 的 1/MAX_TOKENS
+        let items = vec![(u64::MAX, u64::MAX); MAX_TOKENS];
+        let total = calculate_total_weighted_mint_amount(items).unwrap();
+        let redeem = calculate_redeem_amount(u64::MAX, u64::MAX, total, u64::MAX).unwrap();
+        assert_eq!(redeem, u64::MAX / MAX_TOKENS as u64);
+    }
+
+    #[test]
+    fn redeem_numerator_beyond_u128() {
+        // vault × lp × weight ≈ 2^192，u128 中间值会溢出
+        let total = U256::from(u64::MAX) * U256::from(u64::MAX);
+        let redeem = calculate_redeem_amount(u64::MAX, u64::MAX, total, u64::MAX).unwrap();
+        assert_eq!(redeem, u64::MAX);
+    }
+
+    #[test]
+    fn redeem_rejects_result_beyond_u64() {
+        // total 小于单个 item 的加权量（非法输入），结果超出 u64 时返回 None
+        assert_eq!(calculate_redeem_amount(u64::MAX, u64::MAX, U256::one(), u64::MAX), None);
+        assert_eq!(narrow_u64(U256::from(u64::MAX) + U256::one()), None);
+        assert_eq!(narrow_u64(U256::from(u64::MAX)), Some(u64::MAX));
+    }
+
+    #[test]
+    fn redeem_across_u128_overflow_boundary() {
+        // vault × lp × weight 略低于 / 略高于 u128::MAX，单一持有人始终赎回整个 vault
+        let vault = u64::MAX;
+        let lp = 1u64 << 32;
+        for weight in [1u64 << 32, (1u64 << 32) + 1] {
+            let product = (vault as u128)
+                .checked_mul(lp as u128)
+                .and_then(|value| value.checked_mul(weight as u128));
+            assert_eq!(product.is_none(), weight > 1u64 << 32);
+
+            let total = U256::from(lp) * U256::from(weight);
+            assert_eq!(calculate_redeem_amount(lp, weight, total, vault), Some(vault));
+            assert_eq!(calculate_redeem_amount(lp, weight, total * 2, vault), Some(vault / 2));
+        }
+    }
+
+    #[test]
+    fn redeem_downcast_boundary() {
+        // 结果恰为 u64::MAX 时成功，u64::MAX + 1 时返回 None
+        assert_eq!(calculate_redeem_amount(1, 1, U256::one(), u64::MAX), Some(u64::MAX));
+        assert_eq!(calculate_redeem_amount(2, 1, U256::one(), 1u64 << 63), None);
+        assert_eq!(calculate_redeem_amount(2, 1, U256::from(2), u64::MAX), Some(u64::MAX));
+    }
+
+    #[test]
+    fn redeem_rejects_zero_weight_or_total() {
+        assert_eq!(calculate_redeem_amount(1, 0, U256::one(), 1), None);
+        assert_eq!(calculate_redeem_amount(1, 1, U256::zero(), 1), None);
+    }
+
+    #[test]
+    fn redeem_documented_example() {
+        // vault 10_000：item A weight 2x 持有 1_000 LP，item B weight 1x 持有 3_000 LP
+        // 总加权量 5_000x，1 LP(A) 价值 4，1 LP(B) 价值 2
+        let items = [(200_000_000u64, 1_000u64), (100_000_000, 3_000)];
+        assert_eq!(calculate_redeem_amount_for_items(&items, 0, 1, 10_000), Some(4));
+        assert_eq!(calculate_redeem_amount_for_items(&items, 1, 1, 10_000), Some(2));
+        assert_eq!(calculate_redeem_amount_for_items(&items, 0, 1_000, 10_000), Some(4_000));
+        assert_eq!(calculate_redeem_amount_for_items(&items, 1, 3_000, 10_000), Some(6_000));
+    }
+
+    #[test]
+    fn redeem_single_staker_takes_whole_vault() {
+        assert_eq!(calculate_redeem_amount_for_items(&[(100_000_000, 1_000)], 0, 1_000, 12_345), Some(12_345));
+        assert_eq!(calculate_redeem_amount_for_items(&[(100_000_000, 1_000)], 0, 500, 12_345), Some(6_172));
+        assert_eq!(
+            calculate_redeem_amount_for_items(&[(u64::MAX, u64::MAX)], 0, u64::MAX, u64::MAX),
+            Some(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn redeem_with_zero_weights() {
+        // weight 为 0 的 item 不参与分配，也无法从中赎回
+        let items = [(0u64, 1_000u64), (100_000_000, 1_000)];
+        assert_eq!(calculate_redeem_amount_for_items(&items, 1, 1_000, 5_000), Some(5_000));
+        assert_eq!(calculate_redeem_amount_for_items(&items, 0, 1_000, 5_000), None);
+        // 全部为 0 时无法定价
+        assert_eq!(calculate_redeem_amount_for_items(&[(0, 1_000)], 0, 1, 5_000), None);
+    }
+
+    #[test]
+    fn redeem_for_items_edge_cases() {
+        assert_eq!(calculate_redeem_amount_for_items(&[], 0, 1, 1), None);
+        assert_eq!(calculate_redeem_amount_for_items(&[(1, 1)], 1, 1, 1), None);
+        // 尚无 LP 时总加权量为 0，无法赎回
+        assert_eq!(calculate_redeem_amount_for_items(&[(1, 0)], 0, 1, 1), None);
+        assert_eq!(calculate_redeem_amount_for_items(&[(1, 1)], 0, 0, 1_000), Some(0));
+        assert_eq!(calculate_redeem_amount_for_items(&[(1, 1)], 0, 1, 0), Some(0));
+    }
+
+    #[test]
+    fn redeeming_everyone_never_exceeds_vault() {
+        // 向下取整：全体持有人赎回之和不超过 vault
+        let items = [(300_000_007u64, 333u64), (100_000_000, 777), (7, 1_000_003)];
+        let vault = 1_000_003u64;
+        let total: u64 = (0..items.len())
+            .map(|index| calculate_redeem_amount_for_items(&items, index, items[index].1, vault).unwrap())
+            .sum();
+        assert!(total <= vault);
+        assert!(vault - total < items.len() as u64);
+    }
+
+    #[test]
+    fn donation_is_rate_limited_per_transaction() {
+        // 记账 1_000，攻击者直接捐赠 1_000_000，限速 1%
+        let tracked = 1_000;
+        let actual = 1_001_000;
+        let effective = calculate_effective_vault_balance(actual, tracked, 100).unwrap();
+        assert_eq!(effective, 1_010);
+
+        // 下一笔交易以新的记账余额继续限速，捐赠只能逐步计入
+        let effective = calculate_effective_vault_balance(actual, effective, 100).unwrap();
+        assert_eq!(effective, 1_020);
+    }
+
+    #[test]
+    fn donation_then_redeem_cannot_drain_vault() {
+        // 攻击者持有 1 LP，其他人持有 999 LP，权重相同
+        let tracked = 1_000;
+        let actual = tracked + 1_000_000;
+        let effective = calculate_effective_vault_balance(actual, tracked, 100).unwrap();
+        let redeem = calculate_redeem_amount(1, 1, U256::from(1_000), effective).unwrap();
+        // 不限速时攻击者可以拿回 1001 枚；限速后只能拿到 1 枚
+        assert_eq!(calculate_redeem_amount(1, 1, U256::from(1_000), actual).unwrap(), 1_001);
+        assert_eq!(redeem, 1);
+    }
+
+    #[test]
+    fn losses_are_recognized_immediately() {
+        assert_eq!(calculate_effective_vault_balance(900, 1_000, 100), Some(900));
+    }
+
+    #[test]
+    fn unlimited_when_untracked_or_disabled() {
+        assert_eq!(calculate_effective_vault_balance(5_000, 0, 100), Some(5_000));
+        assert_eq!(calculate_effective_vault_balance(5_000, 1_000, 0), Some(5_000));
+    }
+
+    #[test]
+    fn cap_saturates_at_u64_max() {
+        assert_eq!(
+            calculate_effective_vault_balance(u64::MAX, u64::MAX - 1, 10_000),
+            Some(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn reward_emission_is_weighted_per_lp() {
+        // item A: weight 2x, 100 LP；item B: weight 1x, 200 LP - 加权量相同，各得一半排放
+        let total = calculate_total_weighted_mint_amount(
+            [(200_000_000u64, 100u64), (100_000_000u64, 200u64)],
+        ).unwrap();
+        let delta_a = calculate_reward_per_share_delta(10, 1_000, 200_000_000, total).unwrap();
+        let delta_b = calculate_reward_per_share_delta(10, 1_000, 100_000_000, total).unwrap();
+        assert_eq!(delta_a, 2 * delta_b);
+
+        let reward_a = calculate_pending_reward(100, delta_a, 0).unwrap();
+        let reward_b = calculate_pending_reward(200, delta_b, 0).unwrap();
+        assert_eq!(reward_a, 5_000);
+        assert_eq!(reward_b, 5_000);
+    }
+
+    #[test]
+    fn reward_emission_edge_cases() {
+        assert_eq!(calculate_reward_per_share_delta(1, 1, 1, U256::zero()), None);
+        assert_eq!(calculate_pending_reward(1, 0, 1), None);
+        assert_eq!(calculate_pending_reward(1_000, 5, 5), Some(0));
+        // 极端参数下不溢出中间值
+        let delta = calculate_reward_per_share_delta(u64::MAX, u64::MAX, u64::MAX, U256::from(u128::MAX));
+        assert!(delta.is_some());
+    }
+
+    #[test]
+    fn weight_change_guard() {
+        // 上限 10%：100 → 110 / 90 允许，111 / 89 拒绝
+        assert!(is_weight_change_within(100, 110, 1_000));
+        assert!(is_weight_change_within(100, 90, 1_000));
+        assert!(!is_weight_change_within(100, 111, 1_000));
+        assert!(!is_weight_change_within(100, 89, 1_000));
+        // 0 表示不限制
+        assert!(is_weight_change_within(1, u64::MAX, 0));
+        // 从 0 只能保持为 0
+        assert!(is_weight_change_within(0, 0, 1_000));
+        assert!(!is_weight_change_within(0, 1, 1_000));
+        // 极端值不溢出
+        assert!(is_weight_change_within(u64::MAX, u64::MAX / 2, 5_001));
+    }
+
+    #[test]
+    fn rate_weight_tracks_exchange_rate() {
+        // 1.05 SOL / jitoSOL → weight 1.05 × 10^8
+        assert_eq!(calculate_rate_weight(1_050_000, 1_000_000, 100_000_000), Some(105_000_000));
+        // 向下取整
+        assert_eq!(calculate_rate_weight(2, 3, 100), Some(66));
+        assert_eq!(calculate_rate_weight(1, 0, 100), None);
+        assert_eq!(calculate_rate_weight(u64::MAX, u64::MAX, 100_000_000), Some(100_000_000));
+        assert_eq!(calculate_rate_weight(u64::MAX, 1, 100_000_000), None);
+    }
+
+    #[test]
+    fn scheduled_weight_interpolates_linearly() {
+        // 1000 → 200，时间 [100, 200]
+        assert_eq!(calculate_scheduled_weight(1_000, 200, 100, 200, 50), Some(1_000));
+        assert_eq!(calculate_scheduled_weight(1_000, 200, 100, 200, 100), Some(1_000));
+        assert_eq!(calculate_scheduled_weight(1_000, 200, 100, 200, 125), Some(800));
+        assert_eq!(calculate_scheduled_weight(1_000, 200, 100, 200, 150), Some(600));
+        assert_eq!(calculate_scheduled_weight(1_000, 200, 100, 200, 200), Some(200));
+        assert_eq!(calculate_scheduled_weight(1_000, 200, 100, 200, i64::MAX), Some(200));
+        // 向上调整同样线性，取整偏向起始值
+        assert_eq!(calculate_scheduled_weight(100, 103, 0, 4, 1), Some(100));
+        assert_eq!(calculate_scheduled_weight(100, 103, 0, 4, 2), Some(101));
+        assert_eq!(calculate_scheduled_weight(103, 100, 0, 4, 1), Some(103));
+        // 无效区间
+        assert_eq!(calculate_scheduled_weight(1_000, 200, 100, 100, 100), None);
+        // 极端值不溢出
+        assert_eq!(
+            calculate_scheduled_weight(u64::MAX, 0, i64::MIN, i64::MAX, 0),
+            Some(u64::MAX / 2)
+        );
+    }
+
+    #[test]
+    fn stake_lp_bootstraps_one_to_one() {
+        assert_eq!(calculate_stake_lp_amount(1_000, 7, U256::zero(), 0), Some(1_000));
+        assert_eq!(calculate_stake_lp_amount(1_000, 7, U256::zero(), 500), Some(1_000));
+        assert_eq!(calculate_stake_lp_amount(1_000, 0, U256::zero(), 0), None);
+        // 已有 LP 但 vault 为 0 时无法定价
+        assert_eq!(calculate_stake_lp_amount(1_000, 7, U256::one(), 0), None);
+    }
+
+    #[test]
+    fn stake_lp_round_trips_through_redeem() {
+        // 两个 item：weight 2 持有 1_000 LP，weight 1 持有 3_000 LP，vault 因收益增长到 10_000
+        let mut items = [(2u64, 1_000u64), (1, 3_000)];
+        let vault = 10_000u64;
+        let total = calculate_total_weighted_mint_amount(items.iter().copied()).unwrap();
+
+        let amount = 2_500u64;
+        let lp = calculate_stake_lp_amount(amount, 2, total, vault).unwrap();
+        // 1 LP(weight 2) 价值 10_000 × 2 / 5_000 = 4，2_500 主币换 625 LP
+        assert_eq!(lp, 625);
+
+        items[0].1 += lp;
+        let total = calculate_total_weighted_mint_amount(items.iter().copied()).unwrap();
+        let vault = vault + amount;
+        assert_eq!(calculate_redeem_amount(lp, 2, total, vault), Some(amount));
+        // 原有持有人的价值不变
+        assert_eq!(calculate_redeem_amount(3_000, 1, total, vault), Some(6_000));
+    }
+
+    #[test]
+    fn fee_rounds_up_and_share_rounds_down() {
+        // 30 bps：1 主币的手续费不足 1，仍收取 1
+        assert_eq!(calculate_fee(1, 30, 10_000), Some((1, 0)));
+        assert_eq!(calculate_fee(10_001, 30, 10_000), Some((31, 9_970)));
+        assert_eq!(calculate_fee(10_000, 30, 10_000), Some((30, 9_970)));
+        assert_eq!(calculate_fee(u64::MAX, 10_000, 10_000), Some((u64::MAX, 0)));
+        assert_eq!(calculate_fee(1_000, 0, 10_000), Some((0, 1_000)));
+        assert_eq!(calculate_fee(1_000, 1, 0), None);
+        // 分成付出 pool，向下取整
+        assert_eq!(calculate_share(1, 30, 10_000), Some((0, 1)));
+        assert_eq!(calculate_share(10_001, 30, 10_000), Some((30, 9_971)));
+        assert_eq!(calculate_share(u64::MAX, 10_000, 10_000), Some((u64::MAX, 0)));
+        // 比例超过 100% 时无法拆分
+        assert_eq!(calculate_share(1_000, 20_000, 10_000), None);
+    }
+
+    #[test]
+    fn boost_amount_rounds_down() {
+        assert_eq!(calculate_boost_amount(1_000, 10_000), Some(0));
+        assert_eq!(calculate_boost_amount(1_000, 12_500), Some(250));
+        assert_eq!(calculate_boost_amount(3, 15_000), Some(1));
+        assert_eq!(calculate_boost_amount(u64::MAX, 20_000), Some(u64::MAX));
+        assert_eq!(calculate_boost_amount(1_000, 9_999), None);
+    }
+
+    #[test]
+    fn stake_lp_rounds_down_in_favor_of_pool() {
+        let total = U256::from(3u64);
+        let lp = calculate_stake_lp_amount(10, 1, total, 7).unwrap();
+        assert_eq!(lp, 4);
+        let redeemed = calculate_redeem_amount(lp, 1, total + U256::from(lp), 17).unwrap();
+        assert!(redeemed <= 10);
+    }
+}
@@ -0,0 +1,103 @@
+//! 质押计算的属性测试：随机 weight、数量和质押类型数量（最多 MAX_TOKENS 个）下验证记账不变量
+
+use super::*;
+use proptest::prelude::*;
+use crate::state::MAX_TOKENS;
+
+/// 单个质押类型的 (weight, mint_amount)
+/// 取值覆盖实际使用的量级（weight 以 1e8 为基准，主币 9 位精度），保证中间值不超出 u64 结果范围
+fn item() -> impl Strategy<Value = (u64, u64)> {
+    (1u64..=1_000_000_000_000, 0u64..=1_000_000_000_000_000)
+}
+
+fn items() -> impl Strategy<Value = Vec<(u64, u64)>> {
+    prop::collection::vec(item(), 1..=MAX_TOKENS)
+}
+
+proptest! {
+    #[test]
+    fn total_weighted_matches_naive_sum(items in items()) {
+        let total = calculate_total_weighted_mint_amount(items.iter().copied()).unwrap();
+        let naive = items.iter().fold(U256::zero(), |sum, &(weight, mint_amount)| {
+            sum + U256::from(weight) * U256::from(mint_amount)
+        });
+        prop_assert_eq!(total, naive);
+    }
+
+    #[test]
+    fn redeemable_never_exceeds_vault(items in items(), vault in 0u64..=u64::MAX / 2) {
+        let total = calculate_total_weighted_mint_amount(items.iter().copied()).unwrap();
+        prop_assume!(!total.is_zero());
+
+        let redeemable = items.iter().try_fold(0u128, |sum, &(weight, mint_amount)| {
+            calculate_redeem_amount(mint_amount, weight, total, vault).map(|r| sum + r as u128)
+        });
+        prop_assert!(redeemable.unwrap() <= vault as u128);
+    }
+
+    #[test]
+    fn stake_then_unstake_never_profits(
+        items in items(),
+        index in any::<prop::sample::Index>(),
+        vault in 1u64..=1_000_000_000_000_000_000,
+        amount in 1u64..=1_000_000_000_000_000,
+    ) {
+        let mut items = items;
+        let total = calculate_total_weighted_mint_amount(items.iter().copied()).unwrap();
+        prop_assume!(!total.is_zero());
+
+        let i = index.index(items.len());
+        let (weight, _) = items[i];
+        let lp = calculate_stake_lp_amount(amount, weight, total, vault);
+        prop_assume!(lp.is_some());
+        let lp = lp.unwrap();
+
+        // 其他持有人在 stake 前的可赎回金额
+        let before: Vec<u64> = items.iter()
+            .map(|&(w, m)| calculate_redeem_amount(m, w, total, vault).unwrap())
+            .collect();
+
+        items[i].1 += lp;
+        let total_after = calculate_total_weighted_mint_amount(items.iter().copied()).unwrap();
+        let vault_after = vault + amount;
+
+        // 存入后立即赎回不会多于存入的数量
+        let redeemed = calculate_redeem_amount(lp, weight, total_after, vault_after).unwrap();
+        prop_assert!(redeemed <= amount);
+
+        // 已有持有人不被稀释
+        for (j, &(w, m)) in items.iter().enumerate() {
+            let held = if j == i { m - lp } else { m };
+            let after = calculate_redeem_amount(held, w, total_after, vault_after).unwrap();
+            prop_assert!(after >= before[j]);
+        }
+    }
+
+    #[test]
+    fn first_stake_into_empty_vault_round_trips(weight in 1u64..=u64::MAX, amount in 1u64..=u64::MAX) {
+        let lp = calculate_stake_lp_amount(amount, weight, U256::zero(), 0).unwrap();
+        prop_assert_eq!(lp, amount);
+        let total = calculate_total_weighted_mint_amount([(weight, lp)]).unwrap();
+        prop_assert_eq!(calculate_redeem_amount(lp, weight, total, amount), Some(amount));
+    }
+
+    #[test]
+    fn fee_and_share_conserve_amount(amount in any::<u64>(), bps in 0u64..=10_000) {
+        let (fee, after_fee) = calculate_fee(amount, bps, 10_000).unwrap();
+        prop_assert_eq!(fee as u128 + after_fee as u128, amount as u128);
+        // 手续费不少于精确值
+        prop_assert!(fee as u128 * 10_000 >= amount as u128 * bps as u128);
+
+        let (share, rest) = calculate_share(amount, bps, 10_000).unwrap();
+        prop_assert_eq!(share as u128 + rest as u128, amount as u128);
+        // 分成不多于精确值
+        prop_assert!(share as u128 * 10_000 <= amount as u128 * bps as u128);
+    }
+
+    #[test]
+    fn effective_balance_is_bounded(actual in any::<u64>(), tracked in any::<u64>(), bps in any::<u16>()) {
+        let effective = calculate_effective_vault_balance(actual, tracked, bps).unwrap();
+        prop_assert!(effective <= actual);
+        prop_assert!(effective >= actual.min(tracked));
+    }
+}
@@ -0,0 +1,80 @@
+//! 显式取整方向的乘除运算
+//!
+//! 链上计算统一按有利于 pool 的方向取整：用户支付的手续费向上取整，
+//! 付给用户的金额（赎回、铸造 LP、返佣等）向下取整，避免反复小额操作从 pool 中套取舍入误差。
+
+use primitive_types::U256;
+
+/// 计算 a × b / denominator，向下取整
+/// denominator 为 0 或乘积溢出 U256 时返回 None
+pub fn mul_div_floor(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    a.checked_mul(b)?.checked_div(denominator)
+}
+
+/// 计算 a × b / denominator，向上取整
+/// denominator 为 0 或乘积溢出 U256 时返回 None
+pub fn mul_div_ceil(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    let product = a.checked_mul(b)?;
+    let quotient = product.checked_div(denominator)?;
+    if (quotient * denominator) == product {
+        Some(quotient)
+    } else {
+        quotient.checked_add(U256::one())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn floor(a: u64, b: u64, d: u64) -> Option<U256> {
+        mul_div_floor(U256::from(a), U256::from(b), U256::from(d))
+    }
+
+    fn ceil(a: u64, b: u64, d: u64) -> Option<U256> {
+        mul_div_ceil(U256::from(a), U256::from(b), U256::from(d))
+    }
+
+    #[test]
+    fn exact_division_rounds_the_same() {
+        assert_eq!(floor(6, 5, 3), Some(U256::from(10)));
+        assert_eq!(ceil(6, 5, 3), Some(U256::from(10)));
+        assert_eq!(floor(0, 5, 3), Some(U256::zero()));
+        assert_eq!(ceil(0, 5, 3), Some(U256::zero()));
+    }
+
+    #[test]
+    fn inexact_division_differs_by_one() {
+        assert_eq!(floor(7, 1, 3), Some(U256::from(2)));
+        assert_eq!(ceil(7, 1, 3), Some(U256::from(3)));
+        // 最小的非零结果：1 × 1 / 10000
+        assert_eq!(floor(1, 1, 10_000), Some(U256::zero()));
+        assert_eq!(ceil(1, 1, 10_000), Some(U256::one()));
+        // 余数为 denominator - 1
+        assert_eq!(floor(9_999, 1, 10_000), Some(U256::zero()));
+        assert_eq!(ceil(10_001, 1, 10_000), Some(U256::from(2)));
+    }
+
+    #[test]
+    fn zero_denominator_returns_none() {
+        assert_eq!(floor(1, 1, 0), None);
+        assert_eq!(ceil(1, 1, 0), None);
+    }
+
+    #[test]
+    fn u64_extremes_do_not_overflow() {
+        let max = u64::MAX;
+        assert_eq!(floor(max, max, max), Some(U256::from(max)));
+        assert_eq!(ceil(max, max, max), Some(U256::from(max)));
+        assert_eq!(floor(max, max - 1, max), Some(U256::from(max - 1)));
+        assert_eq!(ceil(max, 1, 2), Some(U256::from(max / 2 + 1)));
+    }
+
+    #[test]
+    fn u256_overflow_returns_none() {
+        assert_eq!(mul_div_floor(U256::MAX, U256::from(2), U256::one()), None);
+        assert_eq!(mul_div_ceil(U256::MAX, U256::from(2), U256::one()), None);
+        // 商恰为 U256::MAX 且无余数时不需要进位
+        assert_eq!(mul_div_ceil(U256::MAX, U256::one(), U256::one()), Some(U256::MAX));
+    }
+}
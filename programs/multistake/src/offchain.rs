@@ -0,0 +1,23 @@
+//! 链下复用的纯计算接口
+//!
+//! 启用 `offchain` feature 后导出，与链上指令使用完全相同的实现。
+
+pub use crate::math::{
+    apply_weight_delta,
+    calculate_effective_vault_balance,
+    calculate_fee,
+    calculate_share,
+    calculate_pending_reward,
+    calculate_redeem_amount,
+    calculate_redeem_amount_for_items,
+    calculate_reward_per_share_delta,
+    calculate_stake_lp_amount,
+    calculate_total_weighted_mint_amount,
+    decode_weight_deltas,
+    encode_weight_deltas,
+    is_weight_change_within,
+    narrow_u64,
+    REWARD_PER_SHARE_SCALE,
+};
+pub use crate::math::rounding::{mul_div_ceil, mul_div_floor};
+pub use primitive_types::U256;
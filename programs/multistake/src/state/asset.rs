@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+use std::mem::size_of;
+
+/// 除主币（pool_mint）外，pool 最多额外支持的主币数量
+pub const MAX_EXTRA_MAIN_ASSETS: usize = 7;
+
+/// 主币资产索引 0 固定指向 pool.pool_mint / pool.pool_vault
+pub const PRIMARY_ASSET_INDEX: u8 = 0;
+
+/// 多资产模式下的额外主币
+/// 每种主币拥有独立的 vault PDA: ["pool_vault", pool, mint]
+#[zero_copy]
+#[repr(C)]
+#[derive(Debug)]
+pub struct MainAsset {
+    /// 主币 Mint 地址
+    pub mint: Pubkey,
+    /// 主币 Vault 地址
+    pub vault: Pubkey,
+    /// 记账余额 - 通过 stake/unstake 进出 vault 的主币数量，用于限制直接捐赠对兑换率的影响
+    pub tracked_balance: u64,
+    /// 已通过 request_unstake 销毁 LP、尚未领取的主币数量，仍在 vault 中但不计入兑换率
+    pub pending_unstake: u64,
+    /// 主币 Mint 的精度 - 注册时记录
+    pub decimals: u8,
+    /// 填充字节（确保 8 字节对齐）
+    pub padding: [u8; 7],
+}
+
+const_assert_eq!(size_of::<MainAsset>(), 32 + 32 + 8 + 8 + 1 + 7);
+
+impl MainAsset {
+    /// 检查是否为空（未使用）
+    pub fn is_empty(&self) -> bool {
+        self.mint == Pubkey::default()
+    }
+}
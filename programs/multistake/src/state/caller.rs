@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use crate::error::ErrorCode;
+use static_assertions::const_assert_eq;
+use std::mem::size_of;
+
+/// 白名单中最多允许的调用方程序数量
+pub const MAX_ALLOWED_CALLERS: usize = 4;
+
+/// 调用方策略：不限制
+pub const CALLER_MODE_OPEN: u8 = 0;
+/// 调用方策略：只允许交易顶层直接调用（禁止 CPI）
+pub const CALLER_MODE_TOP_LEVEL_ONLY: u8 = 1;
+/// 调用方策略：只允许白名单程序通过 CPI 调用（禁止直接调用）
+pub const CALLER_MODE_ALLOWLIST: u8 = 2;
+
+/// 调用方程序白名单
+/// 用于限制 pool 只能通过指定的 vault / router 程序使用
+#[zero_copy]
+#[repr(C)]
+#[derive(Debug)]
+pub struct CallerAllowlist {
+    /// 允许通过 CPI 调用 stake/unstake 的程序 ID
+    pub programs: [Pubkey; MAX_ALLOWED_CALLERS],
+    /// 白名单中实际使用的数量
+    pub count: u8,
+    /// 调用方策略（CALLER_MODE_*）
+    pub mode: u8,
+    /// 填充字节（确保 8 字节对齐）
+    pub padding: [u8; 6],
+}
+
+const_assert_eq!(size_of::<CallerAllowlist>(), 32 * MAX_ALLOWED_CALLERS + 1 + 1 + 6);
+const_assert_eq!(size_of::<CallerAllowlist>() % 8, 0);
+
+impl CallerAllowlist {
+    /// 设置调用方策略和白名单
+    pub fn set(&mut self, mode: u8, programs: &[Pubkey]) -> Result<()> {
+        require!(mode <= CALLER_MODE_ALLOWLIST, ErrorCode::InvalidCallerPolicy);
+        require!(
+            programs.len() <= MAX_ALLOWED_CALLERS,
+            ErrorCode::InvalidCallerPolicy
+        );
+        require!(
+            mode != CALLER_MODE_ALLOWLIST || !programs.is_empty(),
+            ErrorCode::InvalidCallerPolicy
+        );
+
+        self.programs = [Pubkey::default(); MAX_ALLOWED_CALLERS];
+        self.programs[..programs.len()].copy_from_slice(programs);
+        self.count = programs.len() as u8;
+        self.mode = mode;
+        Ok(())
+    }
+
+    /// 检查程序是否在白名单中
+    pub fn contains(&self, program_id: &Pubkey) -> bool {
+        self.programs[..self.count as usize].contains(program_id)
+    }
+
+    /// 验证当前调用方是否符合策略
+    /// instructions_sysvar: CALLER_MODE_ALLOWLIST 模式下必须提供，用于识别调用方程序
+    pub fn verify_caller(&self, instructions_sysvar: Option<&AccountInfo>) -> Result<()> {
+        let is_top_level = get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT;
+        match self.mode {
+            CALLER_MODE_OPEN => Ok(()),
+            CALLER_MODE_TOP_LEVEL_ONLY => {
+                require!(is_top_level, ErrorCode::UnauthorizedCaller);
+                Ok(())
+            }
+            CALLER_MODE_ALLOWLIST => {
+                require!(!is_top_level, ErrorCode::UnauthorizedCaller);
+                let sysvar = instructions_sysvar.ok_or(ErrorCode::UnauthorizedCaller)?;
+                // 当前正在执行的顶层指令即为发起 CPI 的程序
+                let current_index = load_current_index_checked(sysvar)?;
+                let instruction = load_instruction_at_checked(current_index as usize, sysvar)?;
+                require!(
+                    self.contains(&instruction.program_id),
+                    ErrorCode::UnauthorizedCaller
+                );
+                Ok(())
+            }
+            _ => err!(ErrorCode::InvalidCallerPolicy),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+use crate::error::ErrorCode;
+use super::item::PoolItem;
+use super::pool::{Pool, PoolView};
+use std::ops::Deref;
+
+/// pool 记账状态的检查点
+/// PDA seeds: ["checkpoint", pool]
+/// 只保留最近一次检查点，历史检查点可从交易日志（msg!）或归档账户数据中获取
+#[account]
+#[derive(Debug)]
+pub struct PoolCheckpoint {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 检查点序号，每次 checkpoint 递增
+    pub sequence: u64,
+    /// 生成检查点的 slot
+    pub slot: u64,
+    /// 生成检查点时的质押类型数量
+    pub token_count: u16,
+    /// 记账状态摘要（sha256），计算方式见 compute_digest
+    pub digest: [u8; 32],
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl PoolCheckpoint {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        8 + // sequence
+        8 + // slot
+        2 + // token_count
+        32 + // digest
+        1 // bump
+    }
+
+    /// 计算 pool 记账状态摘要（sha256 哈希链，每步一次 syscall，避免在链上拼接大块数据）
+    /// 审计方可用归档的 pool 账户数据按相同顺序重算并比对：
+    /// h_0 = sha256(pool ‖ slot ‖ token_count ‖ 每个主币资产的记账余额)
+    /// h_i = sha256(h_{i-1} ‖ lp_mint_i ‖ mint_amount_i ‖ weight_i ‖ main_asset_index_i)
+    /// digest = h_{token_count}，整数均为小端字节
+    pub fn compute_digest(pool_key: &Pubkey, slot: u64, pool: &PoolView<impl Deref<Target = Pool>, impl Deref<Target = [PoolItem]>>) -> Result<[u8; 32]> {
+        let token_count = (pool.get_token_count() as u16).to_le_bytes();
+        let mut digest = hashv(&[pool_key.as_ref(), &slot.to_le_bytes(), &token_count]).to_bytes();
+
+        for asset_index in 0..pool.get_main_asset_count() {
+            let tracked_balance = pool.get_tracked_balance(asset_index as u8)?.to_le_bytes();
+            digest = hashv(&[&digest, &tracked_balance]).to_bytes();
+        }
+
+        for index in 0..pool.get_token_count() {
+            let item = pool.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+            digest = hashv(&[
+                &digest,
+                item.mint_pubkey().as_ref(),
+                &item.get_mint_amount().to_le_bytes(),
+                &item.get_weight().to_le_bytes(),
+                &[item.get_main_asset_index()],
+            ]).to_bytes();
+        }
+
+        Ok(digest)
+    }
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+/// Delegation PDA seed
+pub const DELEGATION_SEED: &[u8] = b"delegation";
+
+/// 允许代为 stake
+pub const DELEGATE_STAKE: u8 = 1 << 0;
+/// 允许代为 unstake
+pub const DELEGATE_UNSTAKE: u8 = 1 << 1;
+/// 允许代为领取排放奖励
+pub const DELEGATE_CLAIM: u8 = 1 << 2;
+/// 全部权限位
+pub const DELEGATE_ALL: u8 = DELEGATE_STAKE | DELEGATE_UNSTAKE | DELEGATE_CLAIM;
+
+/// 质押代理授权
+/// PDA seeds: ["delegation", pool, owner]
+/// 托管方或机器人无需持有 owner 私钥即可按权限位代为 stake / unstake / claim_rewards；
+/// 涉及 owner token 账户的转出和销毁由本 PDA 签名，owner 需先对主币账户和 LP 账户 approve 本 PDA
+#[account]
+#[derive(Debug)]
+pub struct Delegation {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 质押记录所有者
+    pub owner: Pubkey,
+    /// 代理地址
+    pub delegate: Pubkey,
+    /// 权限位（DELEGATE_STAKE / DELEGATE_UNSTAKE / DELEGATE_CLAIM）
+    pub permissions: u8,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Delegation {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // owner (Pubkey)
+        32 + // delegate (Pubkey)
+        1 + // permissions
+        1 // bump
+    }
+
+    /// 验证 delegate 是登记的代理且拥有 permission 对应的权限位
+    pub fn verify_permission(&self, delegate: &Pubkey, permission: u8) -> Result<()> {
+        require!(
+            self.delegate == *delegate && self.permissions & permission == permission,
+            ErrorCode::DelegateNotAuthorized
+        );
+        Ok(())
+    }
+}
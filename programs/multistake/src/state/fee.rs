@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use bytemuck::{Pod, Zeroable};
+use static_assertions::const_assert_eq;
+use std::mem::size_of;
+use crate::error::ErrorCode;
+use super::oracle::BPS_DENOMINATOR;
+use super::pool::MAX_FEE_BPS;
+
+/// 分路径的手续费配置（bps）
+/// 同时作为指令参数（Borsh）和 Pool 字段（zero_copy），两种编码的布局一致
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct FeeConfig {
+    /// stake 手续费，从存入的主币中扣除
+    pub stake_fee_bps: u16,
+    /// unstake 手续费，从赎回金额中扣除
+    pub unstake_fee_bps: u16,
+    /// 交换手续费（convert_lp 等在质押类型之间转换的路径）
+    pub swap_fee_bps: u16,
+    /// 手续费中归协议的比例（转入 fee_vault 或以 LP 形式铸造给 treasury），其余留在 vault 中归 LP 持有人
+    pub protocol_fee_bps: u16,
+}
+
+const_assert_eq!(size_of::<FeeConfig>(), 8);
+
+impl FeeConfig {
+    /// 验证费率合法：各路径费率不超过 MAX_FEE_BPS，协议分成不超过 100%
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.stake_fee_bps as u64 <= MAX_FEE_BPS
+                && self.unstake_fee_bps as u64 <= MAX_FEE_BPS
+                && self.swap_fee_bps as u64 <= MAX_FEE_BPS,
+            ErrorCode::FeeTooHigh
+        );
+        require!(
+            self.protocol_fee_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidFeeConfig
+        );
+        Ok(())
+    }
+
+    /// 检查新配置是否上调了任一路径的费率（协议分成不影响用户支付的金额）
+    pub fn is_increase_from(&self, current: &FeeConfig) -> bool {
+        self.stake_fee_bps > current.stake_fee_bps
+            || self.unstake_fee_bps > current.unstake_fee_bps
+            || self.swap_fee_bps > current.swap_fee_bps
+    }
+
+    /// 由旧版本的单一费率（fee_numerator / fee_denominator）换算
+    /// 旧费率同时作用于所有路径，stake 手续费全部归协议，换算时向下取整到 bps
+    pub fn from_legacy(fee_numerator: u64, fee_denominator: u64) -> FeeConfig {
+        let fee_bps = if fee_denominator == 0 {
+            0
+        } else {
+            ((fee_numerator as u128) * (BPS_DENOMINATOR as u128) / (fee_denominator as u128))
+                .min(MAX_FEE_BPS as u128) as u16
+        };
+        FeeConfig {
+            stake_fee_bps: fee_bps,
+            unstake_fee_bps: fee_bps,
+            swap_fee_bps: fee_bps,
+            protocol_fee_bps: BPS_DENOMINATOR as u16,
+        }
+    }
+
+    /// 按旧版本的 u64 布局读取（迁移前该位置存放 fee_numerator）
+    pub fn legacy_numerator(&self) -> u64 {
+        u64::from_le_bytes(bytemuck::cast(*self))
+    }
+}
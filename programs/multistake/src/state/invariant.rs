@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::math;
+use super::item::PoolItem;
+use super::pool::{Pool, PoolView};
+use std::ops::Deref;
+
+/// 检查 pool 记账不变量，用于尽早发现记账漂移
+/// asset_index: 被检查的主币资产
+/// vault_balance: 该主币 vault 的实际余额（指令执行后）；未传入 vault 账户时为 None，按记账余额检查
+///
+/// 检查项：
+/// 0. 缓存的总加权质押量与遍历 items 重新计算的结果一致
+/// 1. 每个 item 的 weight 非零，且 main_asset_index 指向已登记的主币资产
+/// 2. 同一主币下 items 的 mint_amount 之和不超过 u64（与 LP 总供应量一致）
+/// 3. 按赎回公式，该主币下全部 LP 可赎回金额之和不超过有效 vault 余额
+pub fn check_pool_invariants(pool: &PoolView<impl Deref<Target = Pool>, impl Deref<Target = [PoolItem]>>, asset_index: u8, vault_balance: Option<u64>) -> Result<()> {
+    let asset_count = pool.get_main_asset_count();
+    require!(
+        pool.get_asset_total_weighted(asset_index)? == pool.recompute_asset_total_weighted(asset_index)?,
+        ErrorCode::InvariantViolated
+    );
+    let mut total_mint_amount: u64 = 0;
+
+    for index in 0..pool.get_token_count() {
+        let item = pool.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        require!(item.get_weight() > 0, ErrorCode::InvariantViolated);
+        require!(
+            (item.get_main_asset_index() as usize) < asset_count,
+            ErrorCode::InvariantViolated
+        );
+        if item.get_main_asset_index() == asset_index {
+            total_mint_amount = total_mint_amount
+                .checked_add(item.get_mint_amount())
+                .ok_or(ErrorCode::InvariantViolated)?;
+        }
+    }
+
+    if total_mint_amount == 0 {
+        return Ok(());
+    }
+
+    let total_weighted = pool.calculate_asset_total_weighted_mint_amount(asset_index)?;
+    let effective_balance = match vault_balance {
+        Some(balance) => pool.effective_vault_balance(asset_index, balance)?,
+        None => pool.get_tracked_balance(asset_index)?,
+    };
+
+    let mut total_redeemable: u128 = 0;
+    for index in 0..pool.get_token_count() {
+        let item = pool.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        if item.get_main_asset_index() != asset_index || item.get_mint_amount() == 0 {
+            continue;
+        }
+        let redeemable = math::calculate_redeem_amount(
+            item.get_mint_amount(),
+            item.get_weight(),
+            total_weighted,
+            effective_balance,
+        ).ok_or(ErrorCode::InvariantViolated)?;
+        total_redeemable += redeemable as u128;
+    }
+    require!(
+        total_redeemable <= effective_balance as u128,
+        ErrorCode::InvariantViolated
+    );
+
+    Ok(())
+}
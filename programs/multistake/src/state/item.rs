@@ -2,6 +2,50 @@ use anchor_lang::prelude::*;
 use static_assertions::const_assert_eq;
 use std::mem::size_of;
 
+/// item 名称的最大字节数
+pub const ITEM_NAME_LEN: usize = 16;
+/// item 符号的最大字节数
+pub const ITEM_SYMBOL_LEN: usize = 8;
+/// 最初部署版本（v1）的 PoolItem 大小：mint_account + mint_amount + weight，用于迁移旧 pool 的 item 数组
+/// 当前 PoolItem 的前 48 字节与其一致
+pub const V1_POOL_ITEM_SIZE: usize = 32 + 8 + 8;
+
+/// weight 线性衰减计划
+/// end_ts 为 0 表示未设置；在 [start_ts, end_ts] 内 weight 从 start_weight 线性变化到 target
+#[zero_copy]
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct WeightDecay {
+    /// 计划开始时的 weight
+    pub start_weight: u64,
+    /// 计划结束时的目标 weight
+    pub target: u64,
+    /// 开始时间（unix 秒）
+    pub start_ts: i64,
+    /// 结束时间（unix 秒），0 表示未设置计划
+    pub end_ts: i64,
+}
+
+const_assert_eq!(size_of::<WeightDecay>(), 8 + 8 + 8 + 8); // 32 bytes
+
+impl WeightDecay {
+    /// 检查是否设置了衰减计划
+    pub fn is_active(&self) -> bool {
+        self.end_ts != 0
+    }
+
+    /// 计算 now 时刻的有效 weight
+    pub fn weight_at(&self, now: i64) -> Result<u64> {
+        Ok(crate::math::calculate_scheduled_weight(
+            self.start_weight,
+            self.target,
+            self.start_ts,
+            self.end_ts,
+            now,
+        ).ok_or(crate::error::ErrorCode::InvalidWeightSchedule)?)
+    }
+}
+
 /// 质押类型配置项
 /// 每个 item 记录一种质押类型的 LP mint、已发行量和权重
 /// 用于单币质押系统，不同质押类型有不同的收益权重
@@ -16,10 +60,30 @@ pub struct PoolItem {
     /// 权重 (weight) - 动态权重，由 admin 通过 oracle 修改 (8 bytes)
     /// 影响 LP 凭证兑换主币的比率，weight 越高收益越好
     pub weight: u64, // 8 bytes
+    /// 最近一次修改 weight 的 slot - 用于限制 weight 修改频率 (8 bytes)
+    pub last_weight_update_slot: u64, // 8 bytes
+    /// 主币资产索引 - 0 表示 pool_mint，其余对应 pool.extra_assets[i - 1] (1 byte)
+    pub main_asset_index: u8, // 1 byte
+    /// 是否配置了 oracle（ItemOracle PDA），1 = 已配置 (1 byte)
+    pub oracle_enabled: u8, // 1 byte
+    /// 是否接受新的质押，1 = 接受；停用后只允许赎回 (1 byte)
+    pub active: u8, // 1 byte
+    /// 是否按兑换率来源（ItemRateSource PDA）读取 weight，1 = 已配置 (1 byte)
+    pub rate_weight_enabled: u8, // 1 byte
+    /// 填充字节（确保 8 字节对齐）(4 bytes)
+    pub padding: [u8; 4], // 4 bytes
+    /// 奖励排放累加器 - 每单位 LP 累积的奖励 × REWARD_PER_SHARE_SCALE（u128 小端，避免 16 字节对齐）(16 bytes)
+    pub reward_per_share: [u8; 16], // 16 bytes
+    /// weight 衰减计划 - stake / unstake 时按计划自动更新 weight (32 bytes)
+    pub weight_decay: WeightDecay, // 32 bytes
+    /// 显示名称（UTF-8，末尾补 0）- 供 UI 标注质押类型 (16 bytes)
+    pub name: [u8; ITEM_NAME_LEN], // 16 bytes
+    /// 显示符号（UTF-8，末尾补 0）(8 bytes)
+    pub symbol: [u8; ITEM_SYMBOL_LEN], // 8 bytes
 }
 
 // 验证结构体大小和对齐（Solana 要求 8 字节对齐）
-const_assert_eq!(size_of::<PoolItem>(), 32 + 8 + 8); // 48 bytes
+const_assert_eq!(size_of::<PoolItem>(), 32 + 8 + 8 + 8 + 1 + 1 + 1 + 1 + 4 + 16 + 32 + 16 + 8); // 136 bytes
 const_assert_eq!(size_of::<PoolItem>() % 8, 0); // 必须是 8 的倍数
 
 impl PoolItem {
@@ -48,6 +112,81 @@ impl PoolItem {
         self.weight = weight;
     }
 
+    /// 获取最近一次修改 weight 的 slot
+    pub fn get_last_weight_update_slot(&self) -> u64 {
+        self.last_weight_update_slot
+    }
+
+    /// 检查距离上次修改 weight 是否已超过冷却期
+    pub fn verify_weight_cooldown(&self, current_slot: u64, cooldown_slots: u64) -> Result<()> {
+        let elapsed = current_slot.saturating_sub(self.last_weight_update_slot);
+        require!(
+            self.last_weight_update_slot == 0 || elapsed >= cooldown_slots,
+            crate::error::ErrorCode::WeightUpdateCooldown
+        );
+        Ok(())
+    }
+
+    /// 检查新 weight 相对当前 weight 的变化幅度不超过 max_change_bps（0 表示不限制）
+    pub fn verify_weight_change(&self, new_weight: u64, max_change_bps: u64) -> Result<()> {
+        require!(
+            crate::math::is_weight_change_within(self.weight, new_weight, max_change_bps),
+            crate::error::ErrorCode::WeightChangeTooLarge
+        );
+        Ok(())
+    }
+
+    /// 设置 weight 并记录修改时的 slot
+    pub fn update_weight(&mut self, weight: u64, current_slot: u64) {
+        self.weight = weight;
+        self.last_weight_update_slot = current_slot;
+    }
+
+    /// 获取主币资产索引
+    pub fn get_main_asset_index(&self) -> u8 {
+        self.main_asset_index
+    }
+
+    /// 检查是否配置了 oracle
+    pub fn has_oracle(&self) -> bool {
+        self.oracle_enabled != 0
+    }
+
+    /// 检查 weight 是否由兑换率来源决定
+    pub fn has_rate_weight(&self) -> bool {
+        self.rate_weight_enabled != 0
+    }
+
+    /// 检查是否接受新的质押
+    pub fn is_active(&self) -> bool {
+        self.active != 0
+    }
+
+    /// 设置是否接受新的质押
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active as u8;
+    }
+
+    /// 验证 item 接受新的质押
+    pub fn verify_active(&self) -> Result<()> {
+        require!(self.is_active(), crate::error::ErrorCode::ItemInactive);
+        Ok(())
+    }
+
+    /// 获取奖励排放累加器
+    pub fn get_reward_per_share(&self) -> u128 {
+        u128::from_le_bytes(self.reward_per_share)
+    }
+
+    /// 累加奖励排放
+    pub fn add_reward_per_share(&mut self, delta: u128) -> Result<()> {
+        let reward_per_share = self.get_reward_per_share()
+            .checked_add(delta)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.reward_per_share = reward_per_share.to_le_bytes();
+        Ok(())
+    }
+
     /// 设置 LP mint account
     pub fn set_mint_account(&mut self, pubkey: &Pubkey) {
         self.mint_account = *pubkey;
@@ -74,11 +213,54 @@ impl PoolItem {
         Ok(())
     }
 
+    /// 获取显示名称（去掉末尾补的 0）
+    pub fn get_name(&self) -> &str {
+        trim_padded(&self.name)
+    }
+
+    /// 获取显示符号（去掉末尾补的 0）
+    pub fn get_symbol(&self) -> &str {
+        trim_padded(&self.symbol)
+    }
+
+    /// 设置显示名称和符号，超出长度时报错
+    pub fn set_metadata(&mut self, name: &str, symbol: &str) -> Result<()> {
+        self.name = pad_bytes(name)?;
+        self.symbol = pad_bytes(symbol)?;
+        Ok(())
+    }
+
     /// 计算单个 item 所需的空间大小
     pub fn space() -> usize {
         32 + // mint_account (Pubkey)
         8 + // mint_amount
-        8 // weight
+        8 + // weight
+        8 + // last_weight_update_slot
+        1 + // main_asset_index
+        1 + // oracle_enabled
+        1 + // active
+        1 + // rate_weight_enabled
+        4 + // padding
+        16 + // reward_per_share
+        32 + // weight_decay
+        16 + // name
+        8 // symbol
     }
 }
 
+/// 将字符串写入定长字节数组，末尾补 0
+fn pad_bytes<const N: usize>(value: &str) -> Result<[u8; N]> {
+    require!(
+        value.len() <= N && !value.contains('\0'),
+        crate::error::ErrorCode::InvalidItemMetadata
+    );
+    let mut bytes = [0u8; N];
+    bytes[..value.len()].copy_from_slice(value.as_bytes());
+    Ok(bytes)
+}
+
+/// 读取末尾补 0 的字符串（由 pad_bytes 写入，必定是合法 UTF-8）
+fn trim_padded(bytes: &[u8]) -> &str {
+    let len = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+    std::str::from_utf8(&bytes[..len]).unwrap_or_default()
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::math;
+
+/// 锁仓档位：(最短锁定秒数, 加成 bps)，按锁定时长从短到长排列
+/// 加成作用于有效计量数量：积分、排放奖励和赎回都按 lp_amount × boost_bps / 10000 计算
+pub const LOCK_TIERS: [(i64, u16); 4] = [
+    (30 * 86_400, 11_000),
+    (90 * 86_400, 12_500),
+    (180 * 86_400, 15_000),
+    (365 * 86_400, 20_000),
+];
+
+/// 获取锁定 lock_seconds 对应的加成（满足的最高档位），短于最低档位时返回 None
+pub fn lock_boost_bps(lock_seconds: i64) -> Option<u16> {
+    LOCK_TIERS
+        .iter()
+        .rev()
+        .find(|(min_seconds, _)| lock_seconds >= *min_seconds)
+        .map(|(_, boost_bps)| *boost_bps)
+}
+
+/// 用户在某个质押类型下的锁仓头寸
+/// PDA seeds: ["locked_position", pool, owner, item_index (le bytes)]
+/// 锁仓不铸造 LP 代币，lp_amount 与加成部分 boost_amount 一起计入 item 的 mint_amount，
+/// 到期后通过 unstake_locked 一次性按 lp_amount + boost_amount 赎回主币
+#[account]
+#[derive(Debug)]
+pub struct LockedPosition {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 头寸所有者
+    pub owner: Pubkey,
+    /// 质押类型索引
+    pub item_index: u16,
+    /// PDA bump
+    pub bump: u8,
+    /// 按兑换率折算的 LP 数量
+    pub lp_amount: u64,
+    /// 锁仓加成的额外计量数量
+    pub boost_amount: u64,
+    /// 解锁时间戳（unix 秒）
+    pub unlock_timestamp: i64,
+}
+
+impl LockedPosition {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // owner (Pubkey)
+        2 + // item_index
+        1 + // bump
+        8 + // lp_amount
+        8 + // boost_amount
+        8 // unlock_timestamp
+    }
+
+    /// 检查头寸是否尚未初始化（init_if_needed 新建的账户）
+    pub fn is_uninitialized(&self) -> bool {
+        self.owner == Pubkey::default()
+    }
+
+    /// 获取计入 mint_amount 的有效数量（含加成）
+    pub fn effective_amount(&self) -> Result<u64> {
+        self.lp_amount
+            .checked_add(self.boost_amount)
+            .ok_or(ErrorCode::MathOverflow.into())
+    }
+
+    /// 追加锁仓：解锁时间取已有和新锁定期中较晚者，追加部分按本次档位加成
+    /// 返回本次新增的加成数量
+    pub fn add_lock(&mut self, lp_amount: u64, boost_bps: u16, unlock_timestamp: i64) -> Result<u64> {
+        let boost_amount = math::calculate_boost_amount(lp_amount, boost_bps)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.lp_amount = self.lp_amount
+            .checked_add(lp_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.boost_amount = self.boost_amount
+            .checked_add(boost_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.unlock_timestamp = self.unlock_timestamp.max(unlock_timestamp);
+        Ok(boost_amount)
+    }
+
+    /// 验证锁定期已结束
+    pub fn verify_unlocked(&self, now: i64) -> Result<()> {
+        require!(now >= self.unlock_timestamp, ErrorCode::PositionLocked);
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use super::item::PoolItem;
+use super::pool::{Pool, PoolView};
+use std::ops::Deref;
+
+/// LP mint → item 索引的映射
+/// PDA seeds: ["mint_index", pool, lp_mint]
+/// 由 add/remove token 维护，按 mint 寻址的指令无需扫描 tokens 数组
+#[account]
+#[derive(Debug)]
+pub struct MintIndex {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// LP mint
+    pub lp_mint: Pubkey,
+    /// item 在 pool.tokens 中的索引
+    pub item_index: u16,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl MintIndex {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // lp_mint (Pubkey)
+        2 + // item_index
+        1 // bump
+    }
+
+    /// O(1) 解析 item 索引，并校验映射与 pool 当前状态一致
+    pub fn resolve(&self, pool: &PoolView<impl Deref<Target = Pool>, impl Deref<Target = [PoolItem]>>) -> Result<usize> {
+        let index = self.item_index as usize;
+        let item = pool.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        require!(
+            *item.mint_pubkey() == self.lp_mint,
+            ErrorCode::InvalidTokenMint
+        );
+        Ok(index)
+    }
+}
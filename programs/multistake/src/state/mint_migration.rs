@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use primitive_types::U256;
+use crate::error::ErrorCode;
+use crate::math::rounding::{mul_div_ceil, mul_div_floor};
+use crate::math::narrow_u64;
+
+/// 主币迁移的时间锁：提议后至少等待 2 天才能执行
+pub const MINT_MIGRATION_DELAY_SECONDS: i64 = 2 * 24 * 60 * 60;
+
+/// 待执行的主币迁移
+/// PDA seeds: ["mint_migration", pool]
+/// 主币迁移到新 mint 时，管理员提议新 mint、新 vault 和兑换比例，时间锁到期后由 migrate_main_mint 执行
+#[account]
+#[derive(Debug)]
+pub struct MintMigration {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 新的主币 Mint
+    pub new_mint: Pubkey,
+    /// 新的主币 Vault - owner 为 pool authority，执行前需按兑换比例预先注资
+    pub new_vault: Pubkey,
+    /// 兑换比例分子：1 个旧主币最小单位 = ratio_numerator / ratio_denominator 个新主币最小单位
+    pub ratio_numerator: u64,
+    /// 兑换比例分母
+    pub ratio_denominator: u64,
+    /// 最早可执行的时间戳（unix 秒）
+    pub eta: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl MintMigration {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // new_mint (Pubkey)
+        32 + // new_vault (Pubkey)
+        8 + // ratio_numerator
+        8 + // ratio_denominator
+        8 + // eta
+        1 // bump
+    }
+
+    /// 验证时间锁已到期
+    pub fn verify_ready(&self, now: i64) -> Result<()> {
+        require!(now >= self.eta, ErrorCode::TimelockNotElapsed);
+        Ok(())
+    }
+
+    /// 将旧主币数量按兑换比例折算为新主币数量（向下取整，用于记账余额）
+    pub fn convert_floor(&self, amount: u64) -> Result<u64> {
+        mul_div_floor(
+            U256::from(amount),
+            U256::from(self.ratio_numerator),
+            U256::from(self.ratio_denominator),
+        )
+        .and_then(narrow_u64)
+        .ok_or(ErrorCode::MathOverflow.into())
+    }
+
+    /// 旧 vault 余额对应的新 vault 最低注资数量（向上取整）
+    pub fn required_funding(&self, old_balance: u64) -> Result<u64> {
+        mul_div_ceil(
+            U256::from(old_balance),
+            U256::from(self.ratio_numerator),
+            U256::from(self.ratio_denominator),
+        )
+        .and_then(narrow_u64)
+        .ok_or(ErrorCode::MathOverflow.into())
+    }
+}
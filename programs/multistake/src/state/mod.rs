@@ -1,12 +1,73 @@
 pub mod item;
 pub mod pool;
-// 旧的多币交换逻辑，已废弃
+pub mod naming;
+pub mod caller;
+pub mod stake_record;
+pub mod asset;
+pub mod oracle;
+pub mod referral;
+pub mod reward;
+pub mod rate_history;
+pub mod weight_oracle;
+pub mod timelock;
+pub mod mint_index;
+pub mod checkpoint;
+pub mod stake_strategy;
+pub mod priority_redeemer;
+pub mod unstake_ticket;
+pub mod invariant;
+pub mod fee;
+pub mod whitelist;
+pub mod pool_registry;
+pub mod weight_history;
+pub mod locked_position;
+pub mod delegation;
+pub mod mint_migration;
+pub mod snapshot;
+pub mod program_config;
+pub mod pool_stats;
+pub mod rate_source;
+// 旧的多币交换逻辑，已废弃；swap.rs / liquidity.rs 源文件已不在仓库中，以下声明仅作记录
 // pub mod swap;
 // pub mod liquidity;
 
-pub use item::PoolItem;
+pub use item::{PoolItem, WeightDecay};
+pub use caller::CallerAllowlist;
+pub use stake_record::StakeRecord;
+pub use asset::MainAsset;
+pub use oracle::ItemOracle;
+pub use referral::{ReferralAccount, ReferralCode};
+pub use reward::RewardConfig;
+pub use rate_history::ItemRateHistory;
+pub use weight_oracle::WeightOracle;
+pub use timelock::{AdminAction, AdminTimelock, PendingAction};
+pub use mint_index::MintIndex;
+pub use checkpoint::PoolCheckpoint;
+pub use stake_strategy::StakeStrategy;
+pub use priority_redeemer::PriorityRedeemer;
+pub use unstake_ticket::UnstakeTicket;
+pub use fee::FeeConfig;
+pub use pool_registry::PoolRegistry;
+pub use weight_history::{WeightChange, WeightHistory};
+pub use locked_position::LockedPosition;
+pub use delegation::Delegation;
+pub use mint_migration::MintMigration;
+pub use snapshot::{PoolSnapshot, SnapshotItem};
+pub use program_config::ProgramConfig;
+pub use pool_stats::{PoolStats, StatsAsset, StatsItem};
+pub use rate_source::ItemRateSource;
 pub use pool::MAX_TOKENS;
-pub use pool::Pool;
+pub use pool::INITIAL_AUTHORITY_SEED_VERSION;
+pub use pool::{POOL_VERSION, UNVERSIONED_POOL_VERSION};
+pub use pool::{authority_seed_suffix, POOL_AUTHORITY_SEED};
+pub use pool::POOL_SEED;
+pub use pool::DEFAULT_ITEM_WEIGHT;
+pub use pool::{MAX_ITEM_WEIGHT, MIN_ITEM_WEIGHT, WEIGHT_SCALE};
+pub use pool::LP_MINT_DECIMALS;
+pub use pool::VAULT_ROTATION_DELAY_SECONDS;
+pub use pool::FORCE_WEIGHT_DELAY_SECONDS;
+pub use pool::MAX_FEE_UPDATE_DELAY_SECONDS;
+pub use pool::{Pool, PoolRef, PoolRefMut, PoolSizeTier, PoolView};
 // pub use liquidity::LiquidityProtocol;
 // pub use liquidity::AddLiquidityResult;
 // pub use liquidity::RemoveLiquidityResult;
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+/// LP 名称/符号的统一前缀
+pub const LP_NAME_PREFIX: &str = "ms";
+
+/// Metaplex 元数据 name 最大长度
+pub const MAX_LP_NAME_LEN: usize = 32;
+
+/// Metaplex 元数据 symbol 最大长度
+pub const MAX_LP_SYMBOL_LEN: usize = 10;
+
+/// 未提供 pool 名称时，从主币 mint 地址截取的字符数
+const POOL_LABEL_FROM_MINT_LEN: usize = 4;
+
+/// 获取 pool 的显示标签
+/// 优先使用 admin 提供的 pool 名称，否则取主币 mint 地址（base58）的前 4 个字符
+pub fn pool_label(pool_name: Option<&str>, pool_mint: &Pubkey) -> String {
+    match pool_name {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => pool_mint
+            .to_string()
+            .chars()
+            .take(POOL_LABEL_FROM_MINT_LEN)
+            .collect(),
+    }
+}
+
+/// 生成默认的 LP 名称，例如 `msPOOLNAME-3`
+/// 超出 Metaplex 限制时截断 pool 标签，保证 `-{index}` 后缀完整
+pub fn default_lp_name(label: &str, index: usize) -> String {
+    format_with_limit(label, index, MAX_LP_NAME_LEN)
+}
+
+/// 生成默认的 LP 符号，例如 `msPOOL-3`
+pub fn default_lp_symbol(label: &str, index: usize) -> String {
+    format_with_limit(label, index, MAX_LP_SYMBOL_LEN)
+}
+
+/// 解析 LP 元数据的 name/symbol
+/// admin 提供了自定义值时直接使用，否则根据 pool 标签和 item 索引自动生成
+pub fn resolve_lp_name_and_symbol(
+    custom_name: Option<String>,
+    custom_symbol: Option<String>,
+    label: &str,
+    index: usize,
+) -> (String, String) {
+    let name = custom_name
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| default_lp_name(label, index));
+    let symbol = custom_symbol
+        .filter(|symbol| !symbol.is_empty())
+        .unwrap_or_else(|| default_lp_symbol(label, index));
+    (name, symbol)
+}
+
+/// 拼接 `{prefix}{label}-{index}`，总长度不超过 limit
+fn format_with_limit(label: &str, index: usize, limit: usize) -> String {
+    let suffix = format!("-{}", index);
+    let mut budget = limit.saturating_sub(LP_NAME_PREFIX.len() + suffix.len()).min(label.len());
+    // Metaplex 按字节限制长度，截断时退回到字符边界，避免截断多字节字符
+    while !label.is_char_boundary(budget) {
+        budget -= 1;
+    }
+    format!("{}{}{}", LP_NAME_PREFIX, &label[..budget], suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_label_is_truncated_to_limit() {
+        let name = default_lp_name("ABCDEFGHIJKLMNOPQRSTUVWXYZABCDEFGH", 12);
+        assert_eq!(name, "msABCDEFGHIJKLMNOPQRSTUVWXYZA-12");
+        assert!(name.len() <= MAX_LP_NAME_LEN);
+    }
+
+    #[test]
+    fn multi_byte_label_is_truncated_by_bytes() {
+        // 每个汉字 3 字节，symbol 预算为 10 - 2 - 2 = 6 字节，只能放下两个字
+        let symbol = default_lp_symbol("质押池测试", 3);
+        assert_eq!(symbol, "ms质押-3");
+        assert!(symbol.len() <= MAX_LP_SYMBOL_LEN);
+
+        // 预算 32 - 2 - 2 = 28 字节，9 个汉字（27 字节）后第 10 个放不下
+        let name = default_lp_name(&"质".repeat(12), 7);
+        assert_eq!(name.len(), 2 + 27 + 2);
+        assert!(name.len() <= MAX_LP_NAME_LEN);
+    }
+}
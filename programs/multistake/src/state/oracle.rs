@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use super::pool::WEIGHT_SCALE;
+
+/// weight 与价格的换算基准：weight = WEIGHT_SCALE 表示 1 单位质押资产价值 1 单位主币
+pub const WEIGHT_PRICE_SCALE: u64 = WEIGHT_SCALE;
+
+/// 基点分母
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Pyth pull oracle（Pyth Solana Receiver）程序 ID
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey =
+    pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// 脱锚时的处理方式：直接拒绝赎回
+pub const DEPEG_ACTION_HALT: u8 = 0;
+/// 脱锚时的处理方式：按 oracle 价格折价赎回
+pub const DEPEG_ACTION_PENALIZE: u8 = 1;
+
+/// PriceUpdateV2 中 verification_level = Full 的枚举标签
+const VERIFICATION_LEVEL_FULL: u8 = 1;
+
+/// 质押类型的 oracle 配置
+/// PDA seeds: ["item_oracle", pool, lp_mint]
+#[account]
+#[derive(Debug)]
+pub struct ItemOracle {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 对应质押类型的 LP mint
+    pub lp_mint: Pubkey,
+    /// Pyth PriceUpdateV2 账户 - 质押资产以主币计价的价格
+    pub price_feed: Pubkey,
+    /// 价格最大允许的延迟（秒）
+    pub max_staleness_seconds: u64,
+    /// 置信区间占价格的最大比例（bps）
+    pub max_confidence_bps: u16,
+    /// 脱锚阈值：oracle 价格与 weight 隐含价格的最大偏离（bps），0 表示不检查
+    pub depeg_threshold_bps: u16,
+    /// 脱锚时的处理方式（DEPEG_ACTION_*）
+    pub depeg_action: u8,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl ItemOracle {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // lp_mint (Pubkey)
+        32 + // price_feed (Pubkey)
+        8 + // max_staleness_seconds
+        2 + // max_confidence_bps
+        2 + // depeg_threshold_bps
+        1 + // depeg_action
+        1 // bump
+    }
+
+    /// 读取并校验 price_feed 的价格，返回按 WEIGHT_PRICE_SCALE 缩放后的价格
+    pub fn read_scaled_price(&self, price_feed: &AccountInfo, now: i64) -> Result<u64> {
+        require!(
+            price_feed.key() == self.price_feed,
+            ErrorCode::InvalidOracleAccount
+        );
+        let price = OraclePrice::load_pyth(price_feed)?;
+        price.validate(now, self.max_staleness_seconds, self.max_confidence_bps)?;
+        price.scale_to(WEIGHT_PRICE_SCALE)
+    }
+
+    /// 按 oracle 价格计算 weight：weight = price × 10^expo × WEIGHT_PRICE_SCALE
+    /// 与 check_depeg 使用同一换算基准，oracle 价格为 1 时 weight = 10^8
+    pub fn read_weight(&self, price_feed: &AccountInfo, now: i64) -> Result<u64> {
+        let weight = self.read_scaled_price(price_feed, now)?;
+        require!(weight > 0, ErrorCode::InvalidOraclePrice);
+        Ok(weight)
+    }
+
+    /// 脱锚检查
+    /// 返回赎回金额的折价比例（bps）：未脱锚时为 BPS_DENOMINATOR
+    /// - DEPEG_ACTION_HALT：偏离超过阈值直接报错
+    /// - DEPEG_ACTION_PENALIZE：oracle 价格低于隐含价格且超过阈值时，按 oracle / 隐含价格折价
+    pub fn check_depeg(&self, oracle_price: u64, weight: u64) -> Result<u64> {
+        if self.depeg_threshold_bps == 0 || weight == 0 {
+            return Ok(BPS_DENOMINATOR);
+        }
+
+        let deviation_bps = (oracle_price.abs_diff(weight) as u128)
+            .checked_mul(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / weight as u128;
+        if deviation_bps <= self.depeg_threshold_bps as u128 {
+            return Ok(BPS_DENOMINATOR);
+        }
+
+        match self.depeg_action {
+            DEPEG_ACTION_PENALIZE if oracle_price < weight => {
+                let ratio_bps = (oracle_price as u128)
+                    .checked_mul(BPS_DENOMINATOR as u128)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    / weight as u128;
+                Ok(ratio_bps as u64)
+            }
+            // 价格高于隐含价格时，按 weight 赎回已经对剩余质押者有利
+            DEPEG_ACTION_PENALIZE => Ok(BPS_DENOMINATOR),
+            _ => err!(ErrorCode::DepegDetected),
+        }
+    }
+
+    /// 按脱锚检查结果调整赎回金额
+    /// 读取 price_feed 价格，返回 redeem_amount × ratio_bps / BPS_DENOMINATOR
+    pub fn adjust_redeem_amount(
+        &self,
+        price_feed: &AccountInfo,
+        now: i64,
+        weight: u64,
+        redeem_amount: u64,
+    ) -> Result<u64> {
+        let oracle_price = self.read_scaled_price(price_feed, now)?;
+        let ratio_bps = self.check_depeg(oracle_price, weight)?;
+        let adjusted = (redeem_amount as u128)
+            .checked_mul(ratio_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        Ok(adjusted as u64)
+    }
+}
+
+/// 从 oracle 账户读取的价格
+#[derive(Debug, Clone, Copy)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// 解析 Pyth PriceUpdateV2 账户（只接受 Full 验证级别）
+    /// 布局: discriminator(8) + write_authority(32) + verification_level(1)
+    ///       + feed_id(32) + price(8) + conf(8) + exponent(4) + publish_time(8) + ...
+    pub fn load_pyth(account: &AccountInfo) -> Result<Self> {
+        require!(
+            *account.owner == PYTH_RECEIVER_PROGRAM_ID,
+            ErrorCode::InvalidOracleAccount
+        );
+        let data = account.try_borrow_data()?;
+        let level_offset = 8 + 32;
+        require!(
+            data.get(level_offset) == Some(&VERIFICATION_LEVEL_FULL),
+            ErrorCode::InvalidOracleAccount
+        );
+
+        let message = level_offset + 1 + 32;
+        let read = |offset: usize, len: usize| -> Result<&[u8]> {
+            data.get(message + offset..message + offset + len)
+                .ok_or(ErrorCode::InvalidOracleAccount.into())
+        };
+        let to_err = |_| ErrorCode::InvalidOracleAccount;
+        Ok(Self {
+            price: i64::from_le_bytes(read(0, 8)?.try_into().map_err(to_err)?),
+            conf: u64::from_le_bytes(read(8, 8)?.try_into().map_err(to_err)?),
+            expo: i32::from_le_bytes(read(16, 4)?.try_into().map_err(to_err)?),
+            publish_time: i64::from_le_bytes(read(20, 8)?.try_into().map_err(to_err)?),
+        })
+    }
+
+    /// 校验价格为正、未过期且置信区间在允许范围内
+    pub fn validate(&self, now: i64, max_staleness_seconds: u64, max_confidence_bps: u16) -> Result<()> {
+        require!(self.price > 0, ErrorCode::InvalidOraclePrice);
+        require!(
+            now.saturating_sub(self.publish_time) <= max_staleness_seconds as i64,
+            ErrorCode::StaleOraclePrice
+        );
+        let conf_bps = (self.conf as u128)
+            .checked_mul(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / self.price as u128;
+        require!(
+            conf_bps <= max_confidence_bps as u128,
+            ErrorCode::InvalidOraclePrice
+        );
+        Ok(())
+    }
+
+    /// 将价格换算到给定精度：price × 10^expo × scale
+    pub fn scale_to(&self, scale: u64) -> Result<u64> {
+        let price = self.price as u128;
+        let scaled = if self.expo >= 0 {
+            let factor = 10u128
+                .checked_pow(self.expo as u32)
+                .ok_or(ErrorCode::MathOverflow)?;
+            price
+                .checked_mul(factor)
+                .and_then(|v| v.checked_mul(scale as u128))
+                .ok_or(ErrorCode::MathOverflow)?
+        } else {
+            let divisor = 10u128
+                .checked_pow(self.expo.unsigned_abs())
+                .ok_or(ErrorCode::MathOverflow)?;
+            price
+                .checked_mul(scale as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / divisor
+        };
+        u64::try_from(scaled).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+}
@@ -1,16 +1,126 @@
 use anchor_lang::prelude::*;
 use crate::error::ErrorCode;
-use super::item::PoolItem;
+use crate::math;
+use super::item::{PoolItem, WeightDecay, ITEM_NAME_LEN, ITEM_SYMBOL_LEN, V1_POOL_ITEM_SIZE};
+use super::caller::CallerAllowlist;
+use super::fee::FeeConfig;
+use super::oracle::BPS_DENOMINATOR;
+use super::asset::{MainAsset, MAX_EXTRA_MAIN_ASSETS, PRIMARY_ASSET_INDEX};
+use super::whitelist::{verify_merkle_proof, whitelist_leaf};
+use primitive_types::U256;
 use static_assertions::const_assert_eq;
-use std::mem::size_of;
+use std::cell::{Ref, RefMut};
+use std::mem::{offset_of, size_of};
+use std::ops::{Deref, DerefMut, Range};
 
-/// 池中最多支持的质押类型数量
+/// 池中最多支持的质押类型数量（PoolSizeTier::Large 的容量）
 pub const MAX_TOKENS: usize = 512;
 
-/// 单币质押池结构
+/// Pool 账户的容量档位，create_pool 按档位分配 item 槽位并收取对应的租金
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolSizeTier {
+    /// 16 个质押类型（约 4KB）
+    Small,
+    /// 128 个质押类型（约 19KB）
+    Medium,
+    /// MAX_TOKENS（512）个质押类型（约 70KB）
+    Large,
+}
+
+impl PoolSizeTier {
+    /// 档位对应的 item 槽位数量
+    pub fn capacity(&self) -> usize {
+        match self {
+            PoolSizeTier::Small => 16,
+            PoolSizeTier::Medium => 128,
+            PoolSizeTier::Large => MAX_TOKENS,
+        }
+    }
+}
+
+/// Pool authority PDA 的 seed 前缀（沿用旧名称，authority_seed_version 为 0 时与已部署 pool 的 authority 地址一致）
+pub const POOL_AUTHORITY_SEED: &[u8] = b"anyswap_authority";
+
+/// Pool PDA 的种子前缀：["pool", main_token_mint, admin]
+pub const POOL_SEED: &[u8] = b"pool";
+
+/// 新建 pool 时 authority PDA 使用的 seed 版本号
+pub const INITIAL_AUTHORITY_SEED_VERSION: u8 = 0;
+
+/// authority seed 版本号对应的版本字节，按版本号索引
+static AUTHORITY_SEED_VERSION_BYTES: [u8; 256] = {
+    let mut bytes = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        bytes[i] = i as u8;
+        i += 1;
+    }
+    bytes
+};
+
+/// 获取 authority PDA 中版本号对应的 seed
+/// 版本 0 为已部署 pool 使用的 [POOL_AUTHORITY_SEED, pool]，返回空 seed（空 seed 不影响 PDA 推导）
+/// 版本 ≥ 1 返回单个版本字节
+pub fn authority_seed_suffix(version: u8) -> &'static [u8] {
+    match version {
+        INITIAL_AUTHORITY_SEED_VERSION => &[],
+        v => &AUTHORITY_SEED_VERSION_BYTES[v as usize..v as usize + 1],
+    }
+}
+
+/// 当前 Pool 账户布局版本
+/// 布局变更时递增，并新增对应的 migrate_pool_vN 指令把上一版本的账户迁移过来
+pub const POOL_VERSION: u8 = 2;
+
+/// 版本字段加入之前的 pool（v1 布局，即最初部署的布局）读出的版本号
+pub const UNVERSIONED_POOL_VERSION: u8 = 0;
+
+/// v1 布局的 pool 头部大小：token_count 至 fee_denominator，当前布局的前 120 字节与其一致
+pub const V1_POOL_HEADER_SIZE: usize = 2 + 2 + 4 + 32 + 32 + 32 + 8 + 8;
+
+/// 除主管理员外最多登记的共同管理员数量
+pub const MAX_ADMIN_SIGNERS: usize = 8;
+
+/// weight 的定点精度：10^8 表示 1 倍（100%）
+/// 即 1 单位 LP 价值 1 单位主币，oracle 定价和积分累积使用同一基准
+pub const WEIGHT_SCALE: u64 = 100_000_000;
+
+/// 单个质押类型允许的最小 weight：0.0001 倍，保证兑换率计算的相对精度
+pub const MIN_ITEM_WEIGHT: u64 = WEIGHT_SCALE / 10_000;
+
+/// 单个质押类型允许的最大 weight：10000 倍
+pub const MAX_ITEM_WEIGHT: u64 = WEIGHT_SCALE * 10_000;
+
+/// 新增质押类型的默认权重：1 倍
+pub const DEFAULT_ITEM_WEIGHT: u64 = WEIGHT_SCALE;
+
+/// LP mint 的精度，与 SOL 一致
+pub const LP_MINT_DECIMALS: u8 = 9;
+
+/// vault 轮换的时间锁：提议后至少等待 2 天才能执行
+pub const VAULT_ROTATION_DELAY_SECONDS: i64 = 2 * 24 * 60 * 60;
+
+/// set_fee 允许的单条路径最高费率：1000 bps（10%）
+pub const MAX_FEE_BPS: u64 = 1_000;
+
+/// 费率上调时间锁的最大值：30 天
+pub const MAX_FEE_UPDATE_DELAY_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+/// 强制修改 weight 的时间锁：提议后至少等待 2 天才能执行
+pub const FORCE_WEIGHT_DELAY_SECONDS: i64 = 2 * 24 * 60 * 60;
+
+/// 暂停状态：正常运行
+pub const POOL_ACTIVE: u8 = 0;
+/// 暂停状态：全部暂停，stake 与赎回均被拒绝
+pub const POOL_PAUSED: u8 = 1;
+/// 暂停状态：只允许赎回（withdraw-only），拒绝新的 stake
+pub const POOL_WITHDRAW_ONLY: u8 = 2;
+
+/// 单币质押池结构（账户头部）
 ///
 /// 一个 Pool 对应一种主币，支持多种质押类型（items）
-/// 使用 zero_copy 以避免栈溢出（大数组需要）
+/// 账户布局：discriminator + Pool + [PoolItem; capacity]，item 数组的长度由创建时的 PoolSizeTier 决定，
+/// 小 pool 不必为 512 个槽位支付租金；访问 items 须通过 load_versioned / load_mut_versioned 返回的 PoolView
 #[account(zero_copy)]
 #[repr(C)]
 #[derive(Debug)]
@@ -19,59 +129,1155 @@ pub struct Pool {
     pub token_count: u16,
     /// 创建计数器 - 用于生成唯一的 LP mint seed，只增不减
     pub increment_count: u16,
-    /// 填充字节（确保 8 字节对齐）
-    pub padding: [u8; 4],
+    /// 账户布局版本（POOL_VERSION），位于 v1 布局的填充字节处，v1 账户读到 0
+    pub version: u8,
+    /// pool authority PDA 的 seed 版本号
+    /// authority = PDA([POOL_AUTHORITY_SEED, pool])（版本 0），或 PDA([POOL_AUTHORITY_SEED, pool, [authority_seed_version]])（版本 ≥ 1）
+    /// 通过 migrate_pool_authority 递增，用于将来迁移 authority 方案
+    pub authority_seed_version: u8,
+    /// 主币 Vault 的代数 - 每次 rotate_pool_vault 递增
+    /// 新 vault = PDA(["pool_vault", pool, [vault_generation]])，初始 vault 不含该 seed
+    pub vault_generation: u8,
+    /// 已注册的额外主币数量（多资产模式）
+    pub extra_asset_count: u8,
     /// Pool 管理员 - 用于所有操作的权限控制
     pub admin: Pubkey,
     /// Pool 的主币 Vault 账户 - 存储所有质押的主币
     pub pool_vault: Pubkey,
     /// Pool 的主币 Mint 地址 - 该 Pool 对应的币种
     pub pool_mint: Pubkey,
-    /// 手续费分子
-    pub fee_numerator: u64,
-    /// 手续费分母
-    pub fee_denominator: u64,
-    /// 质押类型配置数组，最多支持 1024 种质押类型（固定大小）
-    /// 每个 item 记录一种质押类型的 LP mint、已发行量和权重
-    pub tokens: [PoolItem; MAX_TOKENS],
+    /// 分路径手续费配置（旧版本中该位置为 u64 fee_numerator，迁移方式见 migrate_legacy_fee）
+    pub fee_config: FeeConfig,
+    /// 旧版本的手续费分母，非 0 表示 fee_config 尚未从旧的单一费率迁移
+    pub legacy_fee_denominator: u64,
+    /// vault 轮换的生效时间戳（unix 秒），0 表示没有待执行的轮换
+    pub vault_rotation_eta: i64,
+    /// 同一 item 两次修改 weight 之间的最小 slot 间隔，0 表示不限制
+    pub weight_update_cooldown_slots: u64,
+    /// 紧急赎回的罚金比例（bps），罚金转入保险基金
+    pub emergency_penalty_bps: u16,
+    /// 退出受限标志 - 1 表示赎回受限，此时允许带罚金的 emergency_unstake
+    pub exit_restricted: u8,
+    /// 手续费以 LP 形式收取 - 1 表示 stake 手续费铸造为协议持有的 LP，存入 treasury LP 账户
+    pub fee_in_lp: u8,
+    /// stake 手续费中分给推荐人的比例（bps），0 表示不返佣
+    pub referral_share_bps: u16,
+    /// 单笔交易中直接捐赠可推高兑换率的上限（bps，相对记账余额），0 表示不限制
+    pub max_donation_bps: u16,
+    /// 存在未赎回 LP 时 vault 记账余额的最小值，防止 vault 被赎回到舍入误差可操纵的粉尘状态
+    pub min_vault_balance: u64,
+    /// 单笔 stake 的最小主币数量，防止粉尘仓位和反复小额质押的舍入套利，0 表示不限制
+    pub min_stake_amount: u64,
+    /// 主币 vault（资产索引 0）的记账余额，额外主币记录在 MainAsset.tracked_balance
+    pub tracked_vault_balance: u64,
+    /// 质押记录可转让标志 - 1 表示允许通过 transfer_position 转移给其他地址
+    pub position_transferable: u8,
+    /// 暂停状态 - POOL_ACTIVE / POOL_PAUSED / POOL_WITHDRAW_ONLY
+    pub paused: u8,
+    /// oracle weight 更新开关 - 1 表示允许任何人通过 update_weights_from_oracle 按 ItemOracle 价格刷新 weight
+    pub oracle_weights_enabled: u8,
+    /// 管理操作时间锁开关 - 1 表示费率、weight、移除质押类型、解除暂停、救援提取必须通过 queue_action 执行（启用后不可关闭）
+    pub admin_timelock_enabled: u8,
+    /// 已登记的共同管理员数量
+    pub admin_signer_count: u8,
+    /// 管理操作所需的最少管理员签名数（主管理员与共同管理员合计），0 或 1 表示单签
+    pub admin_threshold: u8,
+    /// 许可池标志 - 1 表示 stake 必须提供 whitelist_root 下的 merkle 证明
+    pub permissioned: u8,
+    /// weight 历史开关 - 1 表示已创建 WeightHistory，管理员修改 weight 时必须传入并记录
+    pub weight_history_enabled: u8,
+    /// 委托给验证者的主币数量（WSOL 池的原生质押策略），计入主币 vault 的资产
+    pub strategy_delegated_amount: u64,
+    /// 赎回限速窗口长度（slot），0 表示不限速
+    pub redemption_window_slots: u64,
+    /// 每个窗口内 unstake 可赎回的主币总量上限（含优先通道预留额度）
+    pub redemption_window_cap: u64,
+    /// 窗口上限中为优先赎回地址预留的额度，普通地址只能使用 cap - reserve
+    pub priority_reserve: u64,
+    /// 当前窗口的起始 slot
+    pub redemption_window_start: u64,
+    /// 当前窗口已赎回的主币数量
+    pub redemption_window_used: u64,
+    /// 待接受的新管理员 - 由 propose_admin 设置，新管理员签名 accept_admin 后生效，默认值表示没有待接受的提议
+    pub pending_admin: Pubkey,
+    /// weight 操作员 - 由管理员设置，只能调用 modify_token_weight，默认值表示未设置
+    /// 供 oracle 机器人等持有低权限密钥，费率、质押类型增删等仍只能由管理员执行
+    pub weight_operator: Pubkey,
+    /// 赎回冷却期（秒）- 大于 0 时必须通过 request_unstake / claim_unstake 赎回，0 表示允许即时 unstake
+    pub cooldown_seconds: i64,
+    /// 主币 vault（资产索引 0）中已销毁 LP、尚未领取的主币数量，额外主币记录在 MainAsset.pending_unstake
+    pub pending_unstake_amount: u64,
+    /// 奖励排放的奖励币 Mint，默认值表示未启用排放
+    pub emission_reward_mint: Pubkey,
+    /// 奖励排放 vault - PDA ["emission_vault", pool]，任何人可通过 fund_rewards 注资
+    pub emission_reward_vault: Pubkey,
+    /// 每秒排放的奖励数量（最小单位），按 weight × LP 在所有质押类型间分配
+    pub emission_rate: u64,
+    /// 最近一次结算排放的时间戳（unix 秒）
+    pub emission_last_update_ts: i64,
+    /// 手续费 vault - PDA ["fee_vault", pool]，create_pool 时创建，默认值表示旧 pool 未启用（手续费留在 pool_vault）
+    pub fee_vault: Pubkey,
+    /// 累计转入 fee_vault 的手续费（只增不减）
+    pub accumulated_fees: u64,
+    /// fee_vault 中属于推荐人、尚未通过 claim_referral_fees 领取的返佣，withdraw_fees 不可动用
+    pub referral_fees_owed: u64,
+    /// 费率上调的时间锁（秒）- 0 表示 set_fee 立即生效，只能增大
+    pub fee_update_delay_seconds: i64,
+    /// 待生效的手续费配置（旧版本中该位置为 u64 pending_fee_numerator）
+    pub pending_fee_config: FeeConfig,
+    /// 旧版本的待生效手续费分母，随 legacy_fee_denominator 一起迁移
+    pub legacy_pending_fee_denominator: u64,
+    /// 待生效费率的生效时间（unix 秒），0 表示没有待生效的费率
+    pub pending_fee_eta: i64,
+    /// 最近一次按 oracle 刷新 weight 的时间戳（unix 秒）
+    pub last_weight_update_ts: i64,
+    /// crank_weights 的最小调用间隔（秒）
+    pub crank_interval_seconds: i64,
+    /// 每次 crank_weights 从 fee_vault 支付给调用者的奖励，0 表示不支付
+    pub crank_reward: u64,
+    /// 单次修改 weight 的最大变化幅度（bps），0 表示不限制；设置后只能收紧
+    pub max_weight_change_bps: u64,
+    /// 待强制修改 weight 的 LP mint，默认值表示没有待执行的提议
+    pub pending_force_weight_mint: Pubkey,
+    /// 待强制设置的 weight
+    pub pending_force_weight: u64,
+    /// 强制修改 weight 的生效时间（unix 秒）
+    pub pending_force_weight_eta: i64,
+    /// 质押白名单的 merkle 根（叶子为 sha256(user)），permissioned = 1 时生效
+    pub whitelist_root: [u8; 32],
+    /// 各主币资产下 items 的总加权质押量 sum(weight × mint_amount)，按资产索引存放（U256 小端 limbs）
+    /// 随 mint_amount / weight 变化增量更新，避免每次 stake / unstake 遍历全部 items
+    pub asset_total_weighted: [[u64; 4]; 1 + MAX_EXTRA_MAIN_ASSETS],
+    /// 共同管理员列表，前 admin_signer_count 个有效
+    pub admin_signers: [Pubkey; MAX_ADMIN_SIGNERS],
+    /// 调用方程序白名单 - 限制 stake/unstake 的调用来源
+    pub caller_allowlist: CallerAllowlist,
+    /// 多资产模式下的额外主币及其 vault，item 通过 main_asset_index 关联
+    pub extra_assets: [MainAsset; MAX_EXTRA_MAIN_ASSETS],
+    /// 账户中 item 槽位的数量，items 紧跟在头部之后（见 PoolView），最多 MAX_TOKENS
+    pub capacity: u16,
+    /// 主币 Mint 的精度 - 创建时记录，stake/unstake 时校验并用于 transfer_checked
+    pub main_token_decimals: u8,
+    /// 填充字节（确保 8 字节对齐）
+    pub padding: [u8; 5],
 }
 
-// 验证结构体大小和对齐（Solana 要求 8 字节对齐）
-// 计算：2 + 2 + 4 + 32 + 32 + 32 + 8 + 8 + (48 * 512) = 24696 bytes
-const_assert_eq!(
-    size_of::<Pool>(),
-    2 + 2 + 4 + 32 + 32 + 32 + 8 + 8 + (size_of::<PoolItem>() * MAX_TOKENS)
-);
-const_assert_eq!(size_of::<Pool>(), 24696);
-const_assert_eq!(size_of::<Pool>() % 8, 0); // 必须是 8 的倍数
+// 验证结构体大小和对齐（Solana 要求 8 字节对齐）
+// 计算：2 + 2 + 1 + 1 + 1 + 1 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 2 + 1 + 1 + 2 + 2 + 8 + 8 + 8 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 32 + 8 + 8 + 32 + 32 + 8 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 8 + 32 + 256 + 256 + 136 + 616 + 2 + 1 + 5 = 1848 bytes
+const_assert_eq!(
+    size_of::<Pool>(),
+    2 + 2 + 1 + 1 + 1 + 1 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 2 + 1 + 1 + 2 + 2 + 8 + 8 + 8 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 32 + 8 + 8 + 32 + 32 + 8 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 8 + 32 + 256 + 256 + size_of::<CallerAllowlist>() + (size_of::<MainAsset>() * MAX_EXTRA_MAIN_ASSETS) + 2 + 1 + 5
+);
+const_assert_eq!(size_of::<Pool>(), 1848);
+const_assert_eq!(size_of::<Pool>() % 8, 0); // 必须是 8 的倍数
+// v1 头部字段的偏移保持不变，迁移时原样保留
+const_assert_eq!(offset_of!(Pool, legacy_fee_denominator) + 8, V1_POOL_HEADER_SIZE);
+const_assert_eq!(offset_of!(PoolItem, weight) + 8, V1_POOL_ITEM_SIZE);
+// 版本字段位于 v1 布局恒为 0 的填充字节处
+const_assert_eq!(offset_of!(Pool, version), 4);
+
+impl Pool {
+    /// 初始化新建的 pool
+    pub fn initialize(
+        &mut self,
+        admin: &Pubkey,
+        pool_vault: &Pubkey,
+        pool_mint: &Pubkey,
+        fee_config: FeeConfig,
+        capacity: usize,
+    ) {
+        self.version = POOL_VERSION;
+        self.token_count = 0;
+        self.increment_count = 0;
+        self.authority_seed_version = INITIAL_AUTHORITY_SEED_VERSION;
+        self.vault_generation = 0;
+        self.extra_asset_count = 0;
+        self.main_token_decimals = 0;
+        self.admin = *admin;
+        self.pool_vault = *pool_vault;
+        self.pool_mint = *pool_mint;
+        self.fee_config = fee_config;
+        self.legacy_fee_denominator = 0;
+        self.vault_rotation_eta = 0;
+        self.weight_update_cooldown_slots = 0;
+        self.emergency_penalty_bps = 0;
+        self.exit_restricted = 0;
+        self.fee_in_lp = 0;
+        self.referral_share_bps = 0;
+        self.max_donation_bps = 0;
+        self.min_vault_balance = 0;
+        self.min_stake_amount = 0;
+        self.tracked_vault_balance = 0;
+        self.position_transferable = 0;
+        self.paused = POOL_ACTIVE;
+        self.oracle_weights_enabled = 0;
+        self.admin_timelock_enabled = 0;
+        self.admin_signer_count = 0;
+        self.admin_threshold = 1;
+        self.permissioned = 0;
+        self.weight_history_enabled = 0;
+        self.strategy_delegated_amount = 0;
+        self.redemption_window_slots = 0;
+        self.redemption_window_cap = 0;
+        self.priority_reserve = 0;
+        self.redemption_window_start = 0;
+        self.redemption_window_used = 0;
+        self.pending_admin = Pubkey::default();
+        self.weight_operator = Pubkey::default();
+        self.cooldown_seconds = 0;
+        self.pending_unstake_amount = 0;
+        self.emission_reward_mint = Pubkey::default();
+        self.emission_reward_vault = Pubkey::default();
+        self.emission_rate = 0;
+        self.emission_last_update_ts = 0;
+        self.fee_vault = Pubkey::default();
+        self.accumulated_fees = 0;
+        self.referral_fees_owed = 0;
+        self.fee_update_delay_seconds = 0;
+        self.pending_fee_config = FeeConfig::default();
+        self.legacy_pending_fee_denominator = 0;
+        self.pending_fee_eta = 0;
+        self.last_weight_update_ts = 0;
+        self.crank_interval_seconds = 0;
+        self.crank_reward = 0;
+        self.max_weight_change_bps = 0;
+        self.pending_force_weight_mint = Pubkey::default();
+        self.pending_force_weight = 0;
+        self.pending_force_weight_eta = 0;
+        self.whitelist_root = [0u8; 32];
+        self.asset_total_weighted = [[0u64; 4]; 1 + MAX_EXTRA_MAIN_ASSETS];
+        self.admin_signers = [Pubkey::default(); MAX_ADMIN_SIGNERS];
+        // capacity 不超过 MAX_TOKENS，由 load_init 校验
+        self.capacity = capacity as u16;
+        // 质押类型 items 保持零值（zero_copy 账户创建时已清零）
+    }
+
+    /// 检查地址是否为管理员（主管理员或共同管理员）
+    pub fn is_admin_signer(&self, key: &Pubkey) -> bool {
+        *key == self.admin
+            || self.admin_signers[..self.admin_signer_count as usize].contains(key)
+    }
+
+    /// 验证管理员权限（M-of-N）
+    /// admin: 指令的 admin 签名者，必须是管理员之一
+    /// cosigners: 通常为 remaining_accounts，其中标记为 signer 的管理员计入签名数，其余账户忽略
+    /// 不同管理员的签名数（含 admin）必须达到 admin_threshold
+    pub fn verify_admins(&self, admin: &Pubkey, cosigners: &[AccountInfo]) -> Result<()> {
+        require!(self.is_admin_signer(admin), ErrorCode::InvalidAdmin);
+
+        let mut signed = vec![*admin];
+        for account in cosigners {
+            if account.is_signer && self.is_admin_signer(account.key) && !signed.contains(account.key) {
+                signed.push(*account.key);
+            }
+        }
+        require!(
+            signed.len() >= self.admin_threshold.max(1) as usize,
+            ErrorCode::InsufficientAdminSignatures
+        );
+        Ok(())
+    }
+
+    /// 管理员总数（主管理员 + 共同管理员）
+    pub fn admin_member_count(&self) -> usize {
+        1 + self.admin_signer_count as usize
+    }
+
+    /// 登记共同管理员
+    pub fn add_admin_signer(&mut self, signer: &Pubkey) -> Result<()> {
+        require!(
+            *signer != Pubkey::default() && !self.is_admin_signer(signer),
+            ErrorCode::InvalidAdminSigner
+        );
+        let count = self.admin_signer_count as usize;
+        require!(count < MAX_ADMIN_SIGNERS, ErrorCode::InvalidAdminSigner);
+        self.admin_signers[count] = *signer;
+        self.admin_signer_count += 1;
+        Ok(())
+    }
+
+    /// 移除共同管理员，最后一个移动到空位
+    pub fn remove_admin_signer(&mut self, signer: &Pubkey) -> Result<()> {
+        let count = self.admin_signer_count as usize;
+        let index = self.admin_signers[..count]
+            .iter()
+            .position(|key| key == signer)
+            .ok_or(ErrorCode::InvalidAdminSigner)?;
+        // 移除后剩余的管理员数量仍需满足签名门限
+        require!(
+            (self.admin_threshold as usize) < self.admin_member_count(),
+            ErrorCode::InvalidAdminThreshold
+        );
+        self.admin_signers[index] = self.admin_signers[count - 1];
+        self.admin_signers[count - 1] = Pubkey::default();
+        self.admin_signer_count -= 1;
+        Ok(())
+    }
+
+    /// 设置签名门限：1 ≤ threshold ≤ 管理员总数
+    pub fn set_admin_threshold(&mut self, threshold: u8) -> Result<()> {
+        require!(
+            threshold >= 1 && threshold as usize <= self.admin_member_count(),
+            ErrorCode::InvalidAdminThreshold
+        );
+        self.admin_threshold = threshold;
+        Ok(())
+    }
+
+    /// 验证可以直接执行敏感管理操作（未启用时间锁）
+    /// 启用时间锁后，这些操作必须通过 queue_action / execute_action 执行
+    pub fn verify_direct_admin_action(&self) -> Result<()> {
+        require!(self.admin_timelock_enabled == 0, ErrorCode::TimelockRequired);
+        Ok(())
+    }
+
+    /// 提议新的管理员，传入 Pubkey::default() 表示撤销提议
+    pub fn propose_admin(&mut self, new_admin: &Pubkey) {
+        self.pending_admin = *new_admin;
+    }
+
+    /// 新管理员接受提议，完成管理员交接
+    pub fn accept_admin(&mut self, new_admin: &Pubkey) -> Result<()> {
+        require!(
+            self.pending_admin != Pubkey::default() && *new_admin == self.pending_admin,
+            ErrorCode::InvalidPendingAdmin
+        );
+        self.admin = self.pending_admin;
+        self.pending_admin = Pubkey::default();
+        Ok(())
+    }
+
+    /// 设置 weight 操作员，传入 Pubkey::default() 表示撤销
+    pub fn set_weight_operator(&mut self, operator: &Pubkey) {
+        self.weight_operator = *operator;
+    }
+
+    /// 检查地址是否为已设置的 weight 操作员
+    pub fn is_weight_operator(&self, key: &Pubkey) -> bool {
+        self.weight_operator != Pubkey::default() && *key == self.weight_operator
+    }
+
+    /// 验证修改 weight 的权限：weight 操作员单签即可，否则按管理员 M-of-N 验证
+    pub fn verify_weight_authority(&self, signer: &Pubkey, cosigners: &[AccountInfo]) -> Result<()> {
+        if self.is_weight_operator(signer) {
+            return Ok(());
+        }
+        self.verify_admins(signer, cosigners)
+    }
+
+    /// 从原始账户数据中读取 admin（不要求账户大小匹配当前 Pool 布局）
+    /// 用于升级后扩容旧 pool 账户前的权限验证
+    pub fn read_admin(data: &[u8]) -> Result<Pubkey> {
+        let offset = 8 + offset_of!(Pool, admin);
+        let bytes = data
+            .get(offset..offset + 32)
+            .ok_or(ErrorCode::InvalidAccountSize)?;
+        Ok(Pubkey::try_from(bytes).map_err(|_| ErrorCode::InvalidAccountSize)?)
+    }
+
+    /// v1 布局下 pool 账户的大小（包含 discriminator）
+    pub fn v1_space() -> usize {
+        8 + V1_POOL_HEADER_SIZE + MAX_TOKENS * V1_POOL_ITEM_SIZE
+    }
+
+    /// 读取账户数据中的布局版本，v1 账户该位置为填充字节，读到 UNVERSIONED_POOL_VERSION
+    pub fn read_version(data: &[u8]) -> u8 {
+        data.get(8 + offset_of!(Pool, version))
+            .copied()
+            .unwrap_or(UNVERSIONED_POOL_VERSION)
+    }
+
+    /// 验证 pool 为当前布局版本
+    pub fn verify_version(&self) -> Result<()> {
+        require!(self.version == POOL_VERSION, ErrorCode::WrongAccountVersion);
+        Ok(())
+    }
+
+    /// 校验账户数据的 discriminator、布局版本和大小，返回 item 数组的字节范围
+    fn verify_data(data: &[u8]) -> Result<Range<usize>> {
+        require!(data.len() >= Self::header_space(), ErrorCode::InvalidAccountSize);
+        require!(
+            data[..8] == *Self::DISCRIMINATOR,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        require!(Self::read_version(data) == POOL_VERSION, ErrorCode::WrongAccountVersion);
+        let header: &Pool = bytemuck::from_bytes(&data[8..Self::header_space()]);
+        let end = Self::space_for(header.get_capacity());
+        require!(data.len() >= end, ErrorCode::InvalidAccountSize);
+        Ok(Self::header_space()..end)
+    }
+
+    /// 把账户数据（含 discriminator）拆分为头部和 item 数组，校验同 load_versioned
+    pub fn view(data: &[u8]) -> Result<PoolView<&Pool, &[PoolItem]>> {
+        let items = Self::verify_data(data)?;
+        let (header, tokens) = data.split_at(items.start);
+        Ok(PoolView {
+            header: bytemuck::from_bytes(&header[8..]),
+            tokens: bytemuck::cast_slice(&tokens[..items.len()]),
+        })
+    }
+
+    /// 以可变方式拆分账户数据（含 discriminator），校验同 load_versioned
+    pub fn view_mut(data: &mut [u8]) -> Result<PoolView<&mut Pool, &mut [PoolItem]>> {
+        let items = Self::verify_data(data)?;
+        let (header, tokens) = data.split_at_mut(items.start);
+        Ok(PoolView {
+            header: bytemuck::from_bytes_mut(&mut header[8..]),
+            tokens: bytemuck::cast_slice_mut(&mut tokens[..items.len()]),
+        })
+    }
+
+    /// 加载 pool 并验证布局版本，旧版本账户须先迁移
+    pub fn load_versioned<'a>(loader: &'a AccountLoader<Pool>) -> Result<PoolRef<'a>> {
+        let data = loader.as_ref().try_borrow_data()?;
+        let items = Self::verify_data(&data)?;
+        let (header, tokens) = Ref::map_split(data, |data| {
+            let (header, tokens) = data.split_at(items.start);
+            (
+                bytemuck::from_bytes(&header[8..]),
+                bytemuck::cast_slice(&tokens[..items.len()]),
+            )
+        });
+        Ok(PoolView { header, tokens })
+    }
+
+    /// 以可变方式加载 pool 并验证布局版本，旧版本账户须先迁移
+    pub fn load_mut_versioned<'a>(loader: &'a AccountLoader<Pool>) -> Result<PoolRefMut<'a>> {
+        require!(
+            loader.as_ref().is_writable,
+            anchor_lang::error::ErrorCode::AccountNotMutable
+        );
+        let data = loader.as_ref().try_borrow_mut_data()?;
+        let items = Self::verify_data(&data)?;
+        let (header, tokens) = RefMut::map_split(data, |data| {
+            let (header, tokens) = data.split_at_mut(items.start);
+            (
+                bytemuck::from_bytes_mut(&mut header[8..]),
+                bytemuck::cast_slice_mut(&mut tokens[..items.len()]),
+            )
+        });
+        Ok(PoolView { header, tokens })
+    }
+
+    /// 加载新分配的 pool 账户（discriminator 为零），返回的头部尚未初始化，须紧接着调用 initialize
+    /// capacity: item 槽位数量，账户须已分配 space_for(capacity)
+    pub fn load_init<'a>(loader: &'a AccountLoader<Pool>, capacity: usize) -> Result<PoolRefMut<'a>> {
+        require!(
+            loader.as_ref().is_writable,
+            anchor_lang::error::ErrorCode::AccountNotMutable
+        );
+        require!(
+            capacity <= MAX_TOKENS && loader.as_ref().data_len() >= Self::space_for(capacity),
+            ErrorCode::InvalidAccountSize
+        );
+        let data = loader.as_ref().try_borrow_mut_data()?;
+        require!(
+            data[..8] == [0u8; 8],
+            anchor_lang::error::ErrorCode::AccountDiscriminatorAlreadySet
+        );
+        let (header, tokens) = RefMut::map_split(data, |data| {
+            let (header, tokens) = data.split_at_mut(Self::header_space());
+            (
+                bytemuck::from_bytes_mut(&mut header[8..]),
+                bytemuck::cast_slice_mut(&mut tokens[..capacity * PoolItem::space()]),
+            )
+        });
+        Ok(PoolView { header, tokens })
+    }
+
+    /// 将 v1 布局的账户数据迁移到当前布局，写入 POOL_VERSION
+    /// item 容量按账户大小计算（capacity_for_space），须容纳已有的 item：
+    /// v1 账户（24704 字节）原地可容纳 168 个 item，更多时须先通过 resize_pool 扩容
+    ///
+    /// 1. v1 头部原样保留（旧费率仍由 migrate_legacy_fee 按需迁移）
+    /// 2. item 数组从 v1 位置搬移到头部之后，新增字段按 add_token 的默认值设置
+    /// 3. 新增的头部字段和未使用的 item 槽位清零（此前残留旧 item 数据），非零默认值与 initialize 一致
+    ///
+    /// 主币精度和 vault 记账余额依赖外部账户，由 migrate_pool_v2 设置
+    /// 返回迁移的 item 数量
+    pub fn migrate_from_v1(data: &mut [u8]) -> Result<usize> {
+        require!(data.len() >= Self::header_space(), ErrorCode::InvalidAccountSize);
+        let count_offset = 8 + offset_of!(Pool, token_count);
+        let token_count = u16::from_le_bytes([data[count_offset], data[count_offset + 1]]) as usize;
+        require!(token_count <= MAX_TOKENS, ErrorCode::InvalidTokenCount);
+        let capacity = Self::capacity_for_space(data.len());
+        require!(token_count <= capacity, ErrorCode::InvalidAccountSize);
+
+        let v1_base = 8 + V1_POOL_HEADER_SIZE;
+        let base = Self::header_space();
+        let item_size = PoolItem::space();
+        // 从后往前搬移，目标位置总不早于源位置，不会覆盖未搬移的 item
+        for index in (0..token_count).rev() {
+            let src = v1_base + index * V1_POOL_ITEM_SIZE;
+            let dst = base + index * item_size;
+            data.copy_within(src..src + V1_POOL_ITEM_SIZE, dst);
+            data[dst + V1_POOL_ITEM_SIZE..dst + item_size].fill(0);
+        }
+        data[v1_base..base].fill(0);
+        data[base + token_count * item_size..].fill(0);
+
+        let header: &mut Pool = bytemuck::from_bytes_mut(&mut data[8..base]);
+        header.version = POOL_VERSION;
+        header.capacity = capacity as u16;
+        header.admin_threshold = 1;
+        header.paused = POOL_ACTIVE;
+
+        let pool = &mut Self::view_mut(data)?;
+        for item in pool.tokens[..token_count].iter_mut() {
+            item.active = 1;
+        }
+        pool.sync_asset_total_weighted()?;
+        Ok(token_count)
+    }
+
+    /// 获取当前 authority seed 版本号
+    pub fn get_authority_seed_version(&self) -> u8 {
+        self.authority_seed_version
+    }
+
+    /// 获取 authority PDA 中的版本 seed（见 authority_seed_suffix）
+    pub fn get_authority_seed(&self) -> &'static [u8] {
+        authority_seed_suffix(self.authority_seed_version)
+    }
+
+    /// 获取实际使用的 token 数量
+    pub fn get_token_count(&self) -> usize {
+        self.token_count as usize
+    }
+
+    /// 账户头部的大小（discriminator + Pool）
+    pub fn header_space() -> usize {
+        8 + size_of::<Pool>()
+    }
+
+    /// 容纳 capacity 个 item 的账户大小
+    pub fn space_for(capacity: usize) -> usize {
+        Self::header_space() + capacity * PoolItem::space()
+    }
+
+    /// 账户大小可容纳的 item 数量（不超过 MAX_TOKENS）
+    pub fn capacity_for_space(space: usize) -> usize {
+        (space.saturating_sub(Self::header_space()) / PoolItem::space()).min(MAX_TOKENS)
+    }
+
+    /// 获取 item 槽位数量
+    pub fn get_capacity(&self) -> usize {
+        self.capacity as usize
+    }
+
+    /// 获取单次修改 weight 的最大变化幅度（bps）
+    pub fn get_max_weight_change_bps(&self) -> u64 {
+        self.max_weight_change_bps
+    }
+
+    /// 获取 weight 修改冷却期（slot 数）
+    pub fn get_weight_update_cooldown_slots(&self) -> u64 {
+        self.weight_update_cooldown_slots
+    }
+
+    /// 检查当前是否处于退出受限状态（允许 emergency_unstake）
+    pub fn is_exit_restricted(&self) -> bool {
+        self.exit_restricted != 0
+    }
+
+    /// 设置暂停状态
+    /// paused: 是否暂停；withdraw_only: 暂停时是否仍允许赎回
+    pub fn set_paused(&mut self, paused: bool, withdraw_only: bool) {
+        self.paused = match (paused, withdraw_only) {
+            (false, _) => POOL_ACTIVE,
+            (true, false) => POOL_PAUSED,
+            (true, true) => POOL_WITHDRAW_ONLY,
+        };
+    }
+
+    /// 设置紧急赎回配置
+    /// exit_restricted: 是否处于退出受限状态；penalty_bps: 紧急赎回罚金比例（bps）
+    pub fn set_emergency_config(&mut self, exit_restricted: bool, penalty_bps: u16) -> Result<()> {
+        require!(penalty_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidFeeConfig);
+        self.exit_restricted = exit_restricted as u8;
+        self.emergency_penalty_bps = penalty_bps;
+        Ok(())
+    }
+
+    /// 设置质押白名单，启用时 merkle 根不能为空
+    pub fn set_whitelist(&mut self, whitelist_root: [u8; 32], permissioned: bool) -> Result<()> {
+        require!(
+            !permissioned || whitelist_root != [0u8; 32],
+            ErrorCode::InvalidWhitelistRoot
+        );
+        self.whitelist_root = whitelist_root;
+        self.permissioned = permissioned as u8;
+        Ok(())
+    }
+
+    /// 验证当前允许 stake（任何暂停状态下都拒绝）
+    pub fn verify_stake_allowed(&self) -> Result<()> {
+        require!(self.paused == POOL_ACTIVE, ErrorCode::PoolPaused);
+        Ok(())
+    }
+
+    /// 检查是否启用了 weight 历史记录
+    pub fn is_weight_history_enabled(&self) -> bool {
+        self.weight_history_enabled != 0
+    }
+
+    /// 检查是否为许可池
+    pub fn is_permissioned(&self) -> bool {
+        self.permissioned != 0
+    }
+
+    /// 验证用户可以 stake：许可池要求 proof 能证明 user 在 whitelist_root 中
+    pub fn verify_whitelisted(&self, user: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+        if !self.is_permissioned() {
+            return Ok(());
+        }
+        require!(
+            verify_merkle_proof(&self.whitelist_root, whitelist_leaf(user), proof),
+            ErrorCode::NotWhitelisted
+        );
+        Ok(())
+    }
+
+    /// 验证 stake 数量不低于 min_stake_amount
+    pub fn verify_min_stake_amount(&self, stake_amount: u64) -> Result<()> {
+        require!(stake_amount >= self.min_stake_amount, ErrorCode::DustAmount);
+        Ok(())
+    }
+
+    /// 验证当前允许赎回（withdraw-only 模式下仍允许）
+    pub fn verify_unstake_allowed(&self) -> Result<()> {
+        require!(self.paused != POOL_PAUSED, ErrorCode::PoolPaused);
+        Ok(())
+    }
+
+    /// 检查是否允许按 oracle 价格刷新 weight
+    pub fn is_oracle_weights_enabled(&self) -> bool {
+        self.oracle_weights_enabled != 0
+    }
+
+    /// 验证距离上次 oracle 刷新已超过 crank 间隔
+    pub fn verify_crank_interval(&self, now: i64) -> Result<()> {
+        let next = self.last_weight_update_ts
+            .checked_add(self.crank_interval_seconds)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(now >= next, ErrorCode::CrankTooEarly);
+        Ok(())
+    }
+
+    /// 检查质押记录是否允许转让
+    pub fn is_position_transferable(&self) -> bool {
+        self.position_transferable != 0
+    }
+
+    /// 检查赎回限速是否生效
+    pub fn is_redemption_limited(&self) -> bool {
+        self.redemption_window_slots > 0
+    }
+
+    /// 占用当前窗口的赎回额度（限速未生效时直接通过）
+    /// amount: 本次赎回的主币数量
+    /// priority: 是否为优先赎回地址 - 优先地址可使用预留额度
+    pub fn consume_redemption_capacity(
+        &mut self,
+        amount: u64,
+        priority: bool,
+        current_slot: u64,
+    ) -> Result<()> {
+        if !self.is_redemption_limited() {
+            return Ok(());
+        }
+
+        // 窗口到期后重新计数
+        if current_slot >= self.redemption_window_start.saturating_add(self.redemption_window_slots) {
+            self.redemption_window_start = current_slot;
+            self.redemption_window_used = 0;
+        }
+
+        let capacity = if priority {
+            self.redemption_window_cap
+        } else {
+            self.redemption_window_cap.saturating_sub(self.priority_reserve)
+        };
+        let used = self.redemption_window_used
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(used <= capacity, ErrorCode::RedemptionRateLimited);
+
+        self.redemption_window_used = used;
+        Ok(())
+    }
+
+    /// 检查 stake 手续费是否以 LP 形式收取
+    pub fn is_fee_in_lp(&self) -> bool {
+        self.fee_in_lp != 0
+    }
+
+    /// 计算推荐返佣
+    /// fee_amount: stake 手续费
+    /// 返回: (推荐人分成, 剩余手续费)
+    pub fn calculate_referral_share(&self, fee_amount: u64) -> Result<(u64, u64)> {
+        Ok(math::calculate_share(fee_amount, self.referral_share_bps as u64, BPS_DENOMINATOR)
+            .ok_or(ErrorCode::MathOverflow)?)
+    }
+
+    /// 计算紧急赎回罚金
+    /// 返回: (罚金, 扣除罚金后的金额)
+    pub fn calculate_emergency_penalty(&self, amount: u64) -> Result<(u64, u64)> {
+        Ok(math::calculate_fee(amount, self.emergency_penalty_bps as u64, BPS_DENOMINATOR)
+            .ok_or(ErrorCode::MathOverflow)?)
+    }
+
+    /// 检查 fee_config 是否仍是旧版本的单一费率布局
+    pub fn is_legacy_fee(&self) -> bool {
+        self.legacy_fee_denominator != 0
+    }
+
+    /// 获取当前生效的手续费配置（尚未迁移的旧 pool 按旧费率换算）
+    pub fn get_fee_config(&self) -> FeeConfig {
+        if self.is_legacy_fee() {
+            FeeConfig::from_legacy(self.fee_config.legacy_numerator(), self.legacy_fee_denominator)
+        } else {
+            self.fee_config
+        }
+    }
+
+    /// 将旧版本的 fee_numerator / fee_denominator（及待生效费率）迁移为 FeeConfig
+    /// 已迁移时不做任何修改，返回是否发生了迁移
+    pub fn migrate_legacy_fee(&mut self) -> bool {
+        if !self.is_legacy_fee() {
+            return false;
+        }
+        self.fee_config = self.get_fee_config();
+        self.legacy_fee_denominator = 0;
+        self.pending_fee_config = if self.pending_fee_eta != 0 {
+            FeeConfig::from_legacy(
+                self.pending_fee_config.legacy_numerator(),
+                self.legacy_pending_fee_denominator,
+            )
+        } else {
+            FeeConfig::default()
+        };
+        self.legacy_pending_fee_denominator = 0;
+        true
+    }
+
+    /// 设置费率（旧 pool 同时完成迁移）
+    pub fn set_fee(&mut self, fee_config: FeeConfig) {
+        self.migrate_legacy_fee();
+        self.fee_config = fee_config;
+    }
+
+    /// 检查新配置是否上调了任一路径的费率
+    pub fn is_fee_increase(&self, fee_config: &FeeConfig) -> bool {
+        fee_config.is_increase_from(&self.get_fee_config())
+    }
+
+    /// 记录待生效的费率
+    pub fn queue_fee(&mut self, fee_config: FeeConfig, eta: i64) {
+        self.migrate_legacy_fee();
+        self.pending_fee_config = fee_config;
+        self.pending_fee_eta = eta;
+    }
+
+    /// 清除待生效的费率
+    pub fn clear_pending_fee(&mut self) {
+        self.queue_fee(FeeConfig::default(), 0);
+    }
+
+    /// 应用到期的待生效费率
+    pub fn apply_pending_fee(&mut self, now: i64) -> Result<()> {
+        self.migrate_legacy_fee();
+        require!(self.pending_fee_eta != 0, ErrorCode::NoPendingAction);
+        require!(now >= self.pending_fee_eta, ErrorCode::TimelockNotElapsed);
+        self.set_fee(self.pending_fee_config);
+        self.clear_pending_fee();
+        Ok(())
+    }
+
+    /// 按 bps 计算手续费
+    /// 返回: (手续费金额, 扣除手续费后的金额)
+    fn calculate_fee_bps(amount: u64, fee_bps: u16) -> Result<(u64, u64)> {
+        Ok(math::calculate_fee(amount, fee_bps as u64, BPS_DENOMINATOR)
+            .ok_or(ErrorCode::MathOverflow)?)
+    }
+
+    /// 计算 stake 手续费
+    /// 返回: (手续费金额, 扣除手续费后的金额)
+    pub fn calculate_stake_fee(&self, amount: u64) -> Result<(u64, u64)> {
+        Self::calculate_fee_bps(amount, self.get_fee_config().stake_fee_bps)
+    }
+
+    /// 计算 unstake 手续费
+    /// 返回: (手续费金额, 扣除手续费后的金额)
+    pub fn calculate_unstake_fee(&self, amount: u64) -> Result<(u64, u64)> {
+        Self::calculate_fee_bps(amount, self.get_fee_config().unstake_fee_bps)
+    }
+
+    /// 计算质押类型之间转换（convert_lp）的手续费
+    /// 返回: (手续费金额, 扣除手续费后的金额)
+    pub fn calculate_swap_fee(&self, amount: u64) -> Result<(u64, u64)> {
+        Self::calculate_fee_bps(amount, self.get_fee_config().swap_fee_bps)
+    }
+
+    /// 按 protocol_fee_bps 拆分手续费
+    /// 返回: (归协议的部分, 留在 vault 中归 LP 持有人的部分)
+    pub fn calculate_protocol_fee(&self, fee_amount: u64) -> Result<(u64, u64)> {
+        Ok(math::calculate_share(fee_amount, self.get_fee_config().protocol_fee_bps as u64, BPS_DENOMINATOR)
+            .ok_or(ErrorCode::MathOverflow)?)
+    }
+
+    /// 获取所有质押类型的总加权质押量（各主币资产缓存值之和）
+    /// 返回: 所有类型的 (weight × mint_amount) 之和
+    /// 公式: sum(weight_i × mint_amount_i)
+    pub fn calculate_total_weighted_mint_amount(&self) -> Result<U256> {
+        let total_weighted = (0..self.get_main_asset_count() as u8)
+            .try_fold(U256::zero(), |total, asset_index| {
+                total.checked_add(self.get_asset_total_weighted(asset_index)?)
+                    .ok_or(error!(ErrorCode::MathOverflow))
+            })?;
+
+        require!(!total_weighted.is_zero(), ErrorCode::InvalidTokenCount);
+        Ok(total_weighted)
+    }
+
+    /// 获取共享同一主币 vault 的质押类型的总加权质押量（缓存值）
+    /// 多资产模式下赎回只在同一主币的 items 之间分配 vault 余额
+    pub fn calculate_asset_total_weighted_mint_amount(&self, asset_index: u8) -> Result<U256> {
+        let total_weighted = self.get_asset_total_weighted(asset_index)?;
+
+        require!(!total_weighted.is_zero(), ErrorCode::InvalidTokenCount);
+        Ok(total_weighted)
+    }
+
+    /// 获取主币资产下缓存的总加权质押量（尚无 LP 时为 0）
+    pub fn get_asset_total_weighted(&self, asset_index: u8) -> Result<U256> {
+        require!(
+            (asset_index as usize) < self.get_main_asset_count(),
+            ErrorCode::InvalidAssetIndex
+        );
+        Ok(U256(self.asset_total_weighted[asset_index as usize]))
+    }
+
+    /// 按 item 加权量的变化调整其主币资产的缓存：total = total - removed + added
+    fn adjust_asset_total_weighted(&mut self, asset_index: u8, removed: U256, added: U256) -> Result<()> {
+        let total_weighted = self.get_asset_total_weighted(asset_index)?
+            .checked_sub(removed)
+            .and_then(|total| total.checked_add(added))
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.asset_total_weighted[asset_index as usize] = total_weighted.0;
+        Ok(())
+    }
+
+    /// 验证 weight 在 [MIN_ITEM_WEIGHT, MAX_ITEM_WEIGHT] 范围内
+    pub fn verify_weight(weight: u64) -> Result<()> {
+        require!(
+            (MIN_ITEM_WEIGHT..=MAX_ITEM_WEIGHT).contains(&weight),
+            ErrorCode::WeightOutOfBounds
+        );
+        Ok(())
+    }
+
+    /// 获取主币 vault 的记账余额
+    pub fn get_tracked_balance(&self, asset_index: u8) -> Result<u64> {
+        match asset_index {
+            PRIMARY_ASSET_INDEX => Ok(self.tracked_vault_balance),
+            i if (i as usize) < self.get_main_asset_count() => {
+                Ok(self.extra_assets[i as usize - 1].tracked_balance)
+            }
+            _ => err!(ErrorCode::InvalidAssetIndex),
+        }
+    }
+
+    /// 设置主币 vault 的记账余额
+    pub fn set_tracked_balance(&mut self, asset_index: u8, balance: u64) -> Result<()> {
+        match asset_index {
+            PRIMARY_ASSET_INDEX => self.tracked_vault_balance = balance,
+            i if (i as usize) < self.get_main_asset_count() => {
+                self.extra_assets[i as usize - 1].tracked_balance = balance;
+            }
+            _ => return err!(ErrorCode::InvalidAssetIndex),
+        }
+        Ok(())
+    }
+
+    /// 获取主币 vault 中待领取的赎回数量
+    pub fn get_pending_unstake(&self, asset_index: u8) -> Result<u64> {
+        match asset_index {
+            PRIMARY_ASSET_INDEX => Ok(self.pending_unstake_amount),
+            i if (i as usize) < self.get_main_asset_count() => {
+                Ok(self.extra_assets[i as usize - 1].pending_unstake)
+            }
+            _ => err!(ErrorCode::InvalidAssetIndex),
+        }
+    }
+
+    /// 设置主币 vault 中待领取的赎回数量
+    pub fn set_pending_unstake(&mut self, asset_index: u8, amount: u64) -> Result<()> {
+        match asset_index {
+            PRIMARY_ASSET_INDEX => self.pending_unstake_amount = amount,
+            i if (i as usize) < self.get_main_asset_count() => {
+                self.extra_assets[i as usize - 1].pending_unstake = amount;
+            }
+            _ => return err!(ErrorCode::InvalidAssetIndex),
+        }
+        Ok(())
+    }
+
+    /// 检查 stake 手续费是否转入 fee_vault
+    /// 仅主币资产的 item、pool 已创建 fee_vault 且未启用 fee_in_lp（该模式下手续费留在 vault 支撑 treasury LP）
+    pub fn routes_fee_to_vault(&self, asset_index: u8) -> bool {
+        self.has_fee_vault(asset_index) && !self.is_fee_in_lp()
+    }
+
+    /// 检查该主币资产是否有 fee_vault（只为主资产创建）
+    pub fn has_fee_vault(&self, asset_index: u8) -> bool {
+        self.fee_vault != Pubkey::default() && asset_index == PRIMARY_ASSET_INDEX
+    }
+
+    /// 记录转入 fee_vault 的手续费
+    pub fn add_accumulated_fees(&mut self, amount: u64) -> Result<()> {
+        self.accumulated_fees = self.accumulated_fees
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// 记录转入 fee_vault 的推荐返佣
+    pub fn add_referral_fees_owed(&mut self, amount: u64) -> Result<()> {
+        self.referral_fees_owed = self.referral_fees_owed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// 推荐人领取返佣后扣减
+    pub fn sub_referral_fees_owed(&mut self, amount: u64) -> Result<()> {
+        self.referral_fees_owed = self.referral_fees_owed
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// fee_vault 中可由管理员提取的手续费（扣除尚未领取的推荐返佣）
+    pub fn withdrawable_fees(&self, fee_vault_balance: u64) -> u64 {
+        fee_vault_balance.saturating_sub(self.referral_fees_owed)
+    }
 
-impl Pool {
-    /// 验证管理员权限
-    pub fn verify_admin(&self, admin: &Pubkey) -> Result<()> {
+    /// 检查是否已配置奖励排放
+    pub fn has_emission(&self) -> bool {
+        self.emission_reward_vault != Pubkey::default()
+    }
+
+    /// 检查是否启用了赎回冷却期
+    pub fn has_unstake_cooldown(&self) -> bool {
+        self.cooldown_seconds > 0
+    }
+
+    /// 计算主币资产的总余额：vault 实际余额 + 委托给验证者的部分（仅主币 vault）- 待领取的赎回
+    pub fn asset_total_balance(&self, asset_index: u8, vault_balance: u64) -> Result<u64> {
+        let total = if asset_index == PRIMARY_ASSET_INDEX {
+            vault_balance
+                .checked_add(self.strategy_delegated_amount)
+                .ok_or(ErrorCode::MathOverflow)?
+        } else {
+            vault_balance
+        };
+        Ok(total
+            .checked_sub(self.get_pending_unstake(asset_index)?)
+            .ok_or(ErrorCode::MathOverflow)?)
+    }
+
+    /// 比较 vault 实际余额与记账余额
+    /// 返回 (实际余额 - 记账余额)：正数为外部捐赠或误转入，负数为记账之外的短缺
+    /// 记账余额尚未建立（为 0）时返回 0
+    pub fn vault_balance_discrepancy(&self, asset_index: u8, actual_balance: u64) -> Result<i128> {
+        let tracked = self.get_tracked_balance(asset_index)?;
+        if tracked == 0 {
+            return Ok(0);
+        }
+        let total_balance = self.asset_total_balance(asset_index, actual_balance)?;
+        Ok(total_balance as i128 - tracked as i128)
+    }
+
+    /// 计算计入兑换率的 vault 余额
+    /// actual_balance: vault 实际余额
+    pub fn effective_vault_balance(&self, asset_index: u8, actual_balance: u64) -> Result<u64> {
+        let tracked = self.get_tracked_balance(asset_index)?;
+        let total_balance = self.asset_total_balance(asset_index, actual_balance)?;
+        Ok(math::calculate_effective_vault_balance(total_balance, tracked, self.max_donation_bps)
+            .ok_or(ErrorCode::MathOverflow)?)
+    }
+
+    /// 获取 pool vault 的 Pubkey
+    pub fn get_pool_vault(&self) -> &Pubkey {
+        &self.pool_vault
+    }
+
+    /// 获取 pool mint 的 Pubkey
+    pub fn get_pool_mint(&self) -> &Pubkey {
+        &self.pool_mint
+    }
+
+    /// 获取当前 vault 代数
+    pub fn get_vault_generation(&self) -> u8 {
+        self.vault_generation
+    }
+
+    /// 检查 vault 账户是否为当前记录的 pool vault
+    pub fn verify_pool_vault(&self, vault: &Pubkey) -> Result<()> {
         require!(
-            *admin == self.admin,
-            crate::error::ErrorCode::InvalidAdmin
+            *vault == self.pool_vault,
+            ErrorCode::InvalidPoolVault
         );
         Ok(())
     }
 
-    /// 获取实际使用的 token 数量
-    pub fn get_token_count(&self) -> usize {
-        self.token_count as usize
+    /// 获取主币资产数量（包括 pool_mint）
+    pub fn get_main_asset_count(&self) -> usize {
+        1 + self.extra_asset_count as usize
     }
 
-    /// 根据 mint 地址查找 token 索引
-    pub fn find_token_index(&self, mint: &Pubkey) -> Option<usize> {
-        (0..self.get_token_count()).find(|&i| self.tokens[i].mint_account == *mint)
+    /// 根据资产索引获取主币 mint
+    pub fn get_asset_mint(&self, asset_index: u8) -> Result<Pubkey> {
+        match asset_index {
+            PRIMARY_ASSET_INDEX => Ok(self.pool_mint),
+            i if (i as usize) < self.get_main_asset_count() => {
+                Ok(self.extra_assets[i as usize - 1].mint)
+            }
+            _ => err!(ErrorCode::InvalidAssetIndex),
+        }
     }
 
-    /// 根据索引获取 token item（可变引用）
-    pub fn get_token_mut(&mut self, index: usize) -> Option<&mut PoolItem> {
-        if index < self.get_token_count() {
-            Some(&mut self.tokens[index])
-        } else {
-            None
+    /// 根据资产索引获取主币精度
+    pub fn get_asset_decimals(&self, asset_index: u8) -> Result<u8> {
+        match asset_index {
+            PRIMARY_ASSET_INDEX => Ok(self.main_token_decimals),
+            i if (i as usize) < self.get_main_asset_count() => {
+                Ok(self.extra_assets[i as usize - 1].decimals)
+            }
+            _ => err!(ErrorCode::InvalidAssetIndex),
+        }
+    }
+
+    /// 验证主币 mint 的精度与记录一致，返回记录的精度
+    pub fn verify_asset_decimals(&self, asset_index: u8, mint_decimals: u8) -> Result<u8> {
+        let decimals = self.get_asset_decimals(asset_index)?;
+        require!(decimals == mint_decimals, ErrorCode::InvalidTokenMint);
+        Ok(decimals)
+    }
+
+    /// 根据资产索引获取主币 vault
+    pub fn get_asset_vault(&self, asset_index: u8) -> Result<Pubkey> {
+        match asset_index {
+            PRIMARY_ASSET_INDEX => Ok(self.pool_vault),
+            i if (i as usize) < self.get_main_asset_count() => {
+                Ok(self.extra_assets[i as usize - 1].vault)
+            }
+            _ => err!(ErrorCode::InvalidAssetIndex),
+        }
+    }
+
+    /// 获取已创建的 LP mint 数量（下一个 LP mint PDA 的 seed）
+    pub fn get_increment_count(&self) -> u16 {
+        self.increment_count
+    }
+
+    /// 获取下一个 LP mint PDA 的 seed，pool 版本不符或容量已满时报错（容量已满时先通过 grow_pool 扩容）
+    /// Anchor 先创建 init 账户再检查其他账户的 constraint，只有在 seeds 中检查才能赶在 LP mint 创建之前拒绝
+    pub fn next_lp_mint_seed(&self) -> Result<[u8; 2]> {
+        self.verify_version()?;
+        require!(
+            self.get_token_count() < self.get_capacity(),
+            ErrorCode::MaxTokensReached
+        );
+        Ok(self.increment_count.to_le_bytes())
+    }
+
+    /// 根据 mint 查找主币资产索引
+    pub fn find_asset_index(&self, mint: &Pubkey) -> Option<u8> {
+        if *mint == self.pool_mint {
+            return Some(PRIMARY_ASSET_INDEX);
         }
+        self.extra_assets[..self.extra_asset_count as usize]
+            .iter()
+            .position(|asset| asset.mint == *mint)
+            .map(|i| i as u8 + 1)
+    }
+
+    /// 注册额外主币（返回资产索引）
+    pub fn add_main_asset(&mut self, mint: &Pubkey, vault: &Pubkey, decimals: u8) -> Result<u8> {
+        require!(
+            (self.extra_asset_count as usize) < MAX_EXTRA_MAIN_ASSETS,
+            ErrorCode::MaxTokensReached
+        );
+        require!(
+            self.find_asset_index(mint).is_none(),
+            ErrorCode::InvalidTokenMint
+        );
+
+        let slot = self.extra_asset_count as usize;
+        self.extra_assets[slot] = MainAsset {
+            mint: *mint,
+            vault: *vault,
+            tracked_balance: 0,
+            pending_unstake: 0,
+            decimals,
+            padding: [0u8; 7],
+        };
+        self.extra_asset_count += 1;
+        Ok(self.extra_asset_count)
+    }
+
+    /// 设置 pool vault
+    pub fn set_pool_vault(&mut self, vault: &Pubkey) {
+        self.pool_vault = *vault;
+    }
+
+    /// 设置 pool mint
+    pub fn set_pool_mint(&mut self, mint: &Pubkey) {
+        self.pool_mint = *mint;
+    }
+}
+
+/// Pool 账户的视图：头部（Pool）+ 紧跟其后的 capacity 个 item
+///
+/// H / T 为头部和 item 数组的持有方式：链上指令使用 PoolRef / PoolRefMut（借用账户数据），
+/// 迁移和测试直接借用字节数组（Pool::view / Pool::view_mut）。
+/// 头部字段和方法通过 Deref 访问，涉及 items 的方法定义在视图上，对所有容量档位通用
+pub struct PoolView<H, T> {
+    header: H,
+    /// item 槽位，长度为 capacity，前 token_count 个有效
+    pub tokens: T,
+}
+
+/// 只读加载的 pool（见 Pool::load_versioned）
+pub type PoolRef<'a> = PoolView<Ref<'a, Pool>, Ref<'a, [PoolItem]>>;
+
+/// 可变加载的 pool（见 Pool::load_mut_versioned）
+pub type PoolRefMut<'a> = PoolView<RefMut<'a, Pool>, RefMut<'a, [PoolItem]>>;
+
+impl<H: Deref<Target = Pool>, T> Deref for PoolView<H, T> {
+    type Target = Pool;
+
+    fn deref(&self) -> &Pool {
+        &self.header
+    }
+}
+
+impl<H: DerefMut<Target = Pool>, T> DerefMut for PoolView<H, T> {
+    fn deref_mut(&mut self) -> &mut Pool {
+        &mut self.header
+    }
+}
+
+impl<H, T> PoolView<H, T>
+where
+    H: Deref<Target = Pool>,
+    T: Deref<Target = [PoolItem]>,
+{
+    /// 根据 mint 地址查找 token 索引
+    pub fn find_token_index(&self, mint: &Pubkey) -> Option<usize> {
+        (0..self.get_token_count()).find(|&i| self.tokens[i].mint_account == *mint)
     }
 
     /// 根据 mint 地址获取 token item（不可变引用）
@@ -98,156 +1304,561 @@ impl Pool {
         }
     }
 
+    /// 统计配置了 oracle 的 item 数量
+    pub fn oracle_item_count(&self) -> usize {
+        self.tokens[..self.get_token_count()]
+            .iter()
+            .filter(|item| item.oracle_enabled != 0)
+            .count()
+    }
+
+    /// 统计某个主币资产下按兑换率读取 weight 的 item 数量
+    pub fn rate_weight_item_count(&self, asset_index: u8) -> usize {
+        self.tokens[..self.get_token_count()]
+            .iter()
+            .filter(|item| item.has_rate_weight() && item.get_main_asset_index() == asset_index)
+            .count()
+    }
+
+    /// 遍历全部 items 重新计算主币资产下的总加权质押量（不使用缓存）
+    pub fn recompute_asset_total_weighted(&self, asset_index: u8) -> Result<U256> {
+        let items = self.tokens[..self.get_token_count()]
+            .iter()
+            .filter(|item| item.get_main_asset_index() == asset_index)
+            .map(|item| (item.get_weight(), item.get_mint_amount()));
+        Ok(math::calculate_total_weighted_mint_amount(items)
+            .ok_or(ErrorCode::MathOverflow)?)
+    }
+
+    /// 计算所有质押类型的权重之和
+    /// 返回: sum(weight_i)
+    pub fn calculate_total_weight(&self) -> Result<u128> {
+        let total_weight = (0..self.get_token_count())
+            .map(|i| self.tokens[i].get_weight() as u128)
+            .sum::<u128>();
+        require!(total_weight > 0, ErrorCode::InvalidTokenCount);
+        Ok(total_weight)
+    }
+
+    /// 按兑换率计算存入 amount 主币可获得的 LP 数量
+    /// 使用与 calculate_redeem_amount 相同的总加权量和有效 vault 余额（存入前），
+    /// 该主币下尚无 LP 时 1:1 铸造
+    pub fn calculate_stake_lp_amount(
+        &self,
+        amount: u64,
+        item_index: usize,
+        pool_vault_balance: u64,
+    ) -> Result<u64> {
+        let item = self.get_token(item_index)
+            .ok_or(ErrorCode::InvalidTokenIndex)?;
+        let asset_index = item.get_main_asset_index();
+
+        let total_weighted = self.get_asset_total_weighted(asset_index)?;
+        let effective_balance = self.effective_vault_balance(asset_index, pool_vault_balance)?;
+
+        Ok(math::calculate_stake_lp_amount(amount, item.get_weight(), total_weighted, effective_balance)
+            .ok_or(ErrorCode::InsufficientLiquidity)?)
+    }
+
+    /// 计算赎回 item_index 的 lp_amount 可得的主币数量（手续费和 oracle 脱锚调整前）
+    /// 公式: effective_vault_balance × lp_amount × weight / total_weighted（向下取整），
+    /// total_weighted 为共享该主币 vault 的所有 item 的 weight × mint_amount 之和，计算见 math::calculate_redeem_amount
+    /// 例：vault 10_000，item A weight 2x 持有 1_000 LP，item B weight 1x 持有 3_000 LP，
+    /// 则 1 LP(A) 可赎回 4，1 LP(B) 可赎回 2
+    pub fn calculate_redeem_amount(
+        &self,
+        lp_amount: u64,
+        item_index: usize,
+        pool_vault_balance: u64,
+    ) -> Result<u64> {
+        require!(
+            item_index < self.get_token_count(),
+            ErrorCode::InvalidTokenIndex
+        );
+
+        let item = self.get_token(item_index)
+            .ok_or(ErrorCode::InvalidTokenIndex)?;
+
+        let weight = item.get_weight();
+        let total_weighted =
+            self.calculate_asset_total_weighted_mint_amount(item.get_main_asset_index())?;
+
+        require!(weight > 0, ErrorCode::InvalidTokenCount);
+
+        // 超出记账余额的直接捐赠按 max_donation_bps 限速计入
+        let effective_balance =
+            self.effective_vault_balance(item.get_main_asset_index(), pool_vault_balance)?;
+
+        Ok(math::calculate_redeem_amount(lp_amount, weight, total_weighted, effective_balance)
+            .ok_or(ErrorCode::MathOverflow)?)
+    }
+
+    /// 检查共享该主币 vault 的质押类型是否还有未赎回的 LP
+    pub fn has_outstanding_lp(&self, asset_index: u8) -> bool {
+        self.tokens[..self.get_token_count()]
+            .iter()
+            .any(|item| item.get_main_asset_index() == asset_index && item.get_mint_amount() > 0)
+    }
+
+    /// 验证 vault 记账余额不低于最小值（仍有未赎回 LP 时）
+    pub fn verify_min_vault_balance(&self, asset_index: u8) -> Result<()> {
+        if self.has_outstanding_lp(asset_index) {
+            require!(
+                self.get_tracked_balance(asset_index)? >= self.min_vault_balance,
+                ErrorCode::VaultBalanceBelowMinimum
+            );
+        }
+        Ok(())
+    }
+
+    /// 验证 mint 可以通过 rescue_tokens 提取：不能是主币、LP 或排放奖励币
+    /// 积分奖励币记录在 RewardConfig 的 reward_vault 中，由指令另行校验
+    pub fn verify_rescuable_mint(&self, mint: &Pubkey) -> Result<()> {
+        let is_main_asset = (0..self.get_main_asset_count())
+            .any(|index| self.get_asset_mint(index as u8).ok() == Some(*mint));
+        require!(
+            !is_main_asset
+                && self.find_token_index(mint).is_none()
+                && *mint != self.emission_reward_mint,
+            ErrorCode::MintNotRescuable
+        );
+        Ok(())
+    }
+
+    /// 获取 item 对应的主币 vault
+    pub fn get_item_vault(&self, item_index: usize) -> Result<Pubkey> {
+        let item = self.get_token(item_index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        self.get_asset_vault(item.get_main_asset_index())
+    }
+
+    /// 获取 item 登记的 LP mint 地址
+    pub fn get_item_mint(&self, item_index: usize) -> Result<Pubkey> {
+        let item = self.get_token(item_index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        Ok(*item.mint_pubkey())
+    }
+}
+
+impl<H, T> PoolView<H, T>
+where
+    H: DerefMut<Target = Pool>,
+    T: DerefMut<Target = [PoolItem]>,
+{
+    /// 根据索引获取 token item（可变引用）
+    pub fn get_token_mut(&mut self, index: usize) -> Option<&mut PoolItem> {
+        if index < self.get_token_count() {
+            Some(&mut self.tokens[index])
+        } else {
+            None
+        }
+    }
+
     /// 添加新的质押类型（返回索引）
     /// lp_mint: 该质押类型的 LP 凭证 mint 地址
     /// weight: 该质押类型的初始权重
     pub fn add_token(&mut self, lp_mint: &Pubkey, weight: u64) -> Result<usize> {
         require!(
-            self.get_token_count() < MAX_TOKENS,
+            self.get_token_count() < self.tokens.len(),
             ErrorCode::MaxTokensReached
         );
-        require!(weight > 0, ErrorCode::InvalidTokenCount);
+        Pool::verify_weight(weight)?;
 
         let index = self.get_token_count();
         let token = &mut self.tokens[index];
         token.set_mint_account(lp_mint);
         token.set_mint_amount(0); // 初始发行量为 0
         token.set_weight(weight);
+        token.last_weight_update_slot = 0;
+        token.main_asset_index = PRIMARY_ASSET_INDEX;
+        token.oracle_enabled = 0;
+        token.active = 1;
+        token.rate_weight_enabled = 0;
+        token.padding = [0u8; 4];
+        token.reward_per_share = [0u8; 16];
+        token.weight_decay = WeightDecay::default();
+        token.name = [0u8; ITEM_NAME_LEN];
+        token.symbol = [0u8; ITEM_SYMBOL_LEN];
 
         self.token_count += 1;
         Ok(index)
     }
 
-    /// 计算账户所需的空间大小
-    pub fn space() -> usize {
-        8 + // discriminator
-        2 + // token_count
-        2 + // increment_count
-        4 + // padding
-        32 + // admin (Pubkey)
-        32 + // pool_vault (Pubkey)
-        32 + // pool_mint (Pubkey)
-        8 + // fee_numerator
-        8 + // fee_denominator
-        (MAX_TOKENS * PoolItem::space()) // 固定大小数组
+    /// 移除质押类型，最后一个 item 移动到空位
+    /// 要求该类型的 LP 已全部销毁（mint_amount = 0）
+    /// 返回被移动 item 原来的索引（移除的是最后一个 item 时为 None）
+    pub fn remove_token(&mut self, index: usize) -> Result<Option<usize>> {
+        let token_count = self.get_token_count();
+        let token_item = self.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+
+        // 检查该类型的 LP 是否已全部销毁
+        require!(
+            token_item.get_mint_amount() == 0,
+            ErrorCode::InsufficientTokenAmount
+        );
+
+        let last_index = token_count - 1;
+        let moved_from = if index == last_index {
+            None
+        } else {
+            // 将最后一个 token 复制到当前位置
+            self.tokens[index] = self.tokens[last_index];
+            Some(last_index)
+        };
+        self.token_count -= 1;
+        Ok(moved_from)
     }
 
-    /// 获取手续费分子
-    pub fn get_fee_numerator(&self) -> u64 {
-        self.fee_numerator
+    /// 按 items 重新计算并写入所有主币资产的总加权质押量缓存
+    /// 返回: 缓存是否发生了变化
+    pub fn sync_asset_total_weighted(&mut self) -> Result<bool> {
+        let mut changed = false;
+        for asset_index in 0..self.get_main_asset_count() as u8 {
+            let total_weighted = self.recompute_asset_total_weighted(asset_index)?;
+            changed |= total_weighted != self.get_asset_total_weighted(asset_index)?;
+            self.asset_total_weighted[asset_index as usize] = total_weighted.0;
+        }
+        Ok(changed)
     }
 
-    /// 获取手续费分母
-    pub fn get_fee_denominator(&self) -> u64 {
-        self.fee_denominator
+    /// 修改 item 的 weight（同时更新总加权质押量缓存）
+    pub fn update_item_weight(&mut self, index: usize, weight: u64, current_slot: u64) -> Result<()> {
+        Pool::verify_weight(weight)?;
+        let item = self.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        let mint_amount = U256::from(item.get_mint_amount());
+        let removed = U256::from(item.get_weight()) * mint_amount;
+        let added = U256::from(weight) * mint_amount;
+        let asset_index = item.get_main_asset_index();
+        self.adjust_asset_total_weighted(asset_index, removed, added)?;
+        self.tokens[index].update_weight(weight, current_slot);
+        // 手动修改 weight 取消衰减计划
+        self.tokens[index].weight_decay = WeightDecay::default();
+        Ok(())
     }
 
-    /// 设置费率
-    pub fn set_fee(&mut self, fee_numerator: u64, fee_denominator: u64) {
-        self.fee_numerator = fee_numerator;
-        self.fee_denominator = fee_denominator;
+    /// 按外部来源（衰减计划、兑换率）同步 item 的 weight（同时更新总加权质押量缓存）
+    /// 不记录 last_weight_update_slot，不影响管理员修改 weight 的冷却期，也不取消衰减计划
+    pub fn sync_item_weight(&mut self, index: usize, weight: u64) -> Result<()> {
+        Pool::verify_weight(weight)?;
+        let item = self.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        let mint_amount = U256::from(item.get_mint_amount());
+        let removed = U256::from(item.get_weight()) * mint_amount;
+        let added = U256::from(weight) * mint_amount;
+        let asset_index = item.get_main_asset_index();
+        self.adjust_asset_total_weighted(asset_index, removed, added)?;
+        self.tokens[index].set_weight(weight);
+        Ok(())
     }
 
-    /// 计算手续费
-    /// amount: 输入金额
-    /// 返回: (手续费金额, 扣除手续费后的金额)
-    pub fn calculate_fee(&self, amount: u64) -> Result<(u64, u64)> {
-        let amount_u128 = amount as u128;
-        let fee_amount = amount_u128
-            .checked_mul(self.fee_numerator as u128)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(self.fee_denominator as u128)
-            .ok_or(ErrorCode::MathOverflow)?;
-        let amount_after_fee = amount_u128
-            .checked_sub(fee_amount)
-            .ok_or(ErrorCode::MathOverflow)?;
-        
-        Ok((fee_amount as u64, amount_after_fee as u64))
+    /// 设置 item 的 weight 衰减计划：从当前 weight 在 [start_ts, end_ts] 内线性变化到 target
+    /// end_ts 为 0 时取消计划，weight 保持当前值
+    pub fn set_weight_schedule(
+        &mut self,
+        index: usize,
+        target: u64,
+        start_ts: i64,
+        end_ts: i64,
+        now: i64,
+    ) -> Result<()> {
+        let item = self.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        // weight 由兑换率来源决定的 item 不能设置衰减计划
+        require!(!item.has_rate_weight(), ErrorCode::InvalidWeightSchedule);
+        let start_weight = item.get_weight();
+        let weight_decay = if end_ts == 0 {
+            WeightDecay::default()
+        } else {
+            Pool::verify_weight(target)?;
+            require!(
+                start_ts >= now && end_ts > start_ts,
+                ErrorCode::InvalidWeightSchedule
+            );
+            WeightDecay { start_weight, target, start_ts, end_ts }
+        };
+        self.tokens[index].weight_decay = weight_decay;
+        Ok(())
     }
 
-    /// 计算所有质押类型的总加权质押量
-    /// 返回: 所有类型的 (weight × mint_amount) 之和
-    /// 公式: sum(weight_i × mint_amount_i)
-    pub fn calculate_total_weighted_mint_amount(&self) -> Result<u128> {
-        let token_count = self.get_token_count();
-        let mut total_weighted: u128 = 0;
-
-        for i in 0..token_count {
-            if let Some(item) = self.get_token(i) {
-                let mint_amount = item.get_mint_amount();
-                if mint_amount > 0 {
-                    let weight = item.get_weight() as u128;
-                    let mint_amount_u128 = mint_amount as u128;
-
-                    let weighted = weight
-                        .checked_mul(mint_amount_u128)
-                        .ok_or(ErrorCode::MathOverflow)?;
-
-                    total_weighted = total_weighted
-                        .checked_add(weighted)
-                        .ok_or(ErrorCode::MathOverflow)?;
+    /// 按衰减计划更新 item 所属主币下所有 item 的 weight（按差值更新总加权质押量缓存），计划结束后清除
+    /// 兑换率按该主币的总加权质押量计算，定价前须应用同一主币下的全部计划（最多 MAX_TOKENS 个 item）
+    /// 不记录 last_weight_update_slot，不影响管理员修改 weight 的冷却期
+    /// 调用前须先结算奖励排放
+    pub fn apply_weight_schedules(&mut self, index: usize, now: i64) -> Result<()> {
+        let asset_index = self.get_token(index)
+            .ok_or(ErrorCode::InvalidTokenIndex)?
+            .get_main_asset_index();
+        for i in 0..self.get_token_count() {
+            let item = &self.tokens[i];
+            if item.get_main_asset_index() == asset_index && item.weight_decay.is_active() {
+                self.apply_weight_schedule(i, now)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 按衰减计划更新单个 item 的 weight
+    fn apply_weight_schedule(&mut self, index: usize, now: i64) -> Result<()> {
+        let item = self.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        if !item.weight_decay.is_active() {
+            return Ok(());
+        }
+        let old_weight = item.get_weight();
+        let weight = item.weight_decay.weight_at(now)?;
+        let finished = now >= item.weight_decay.end_ts;
+        if weight != old_weight {
+            self.sync_item_weight(index, weight)?;
+        }
+        if finished {
+            self.tokens[index].weight_decay = WeightDecay::default();
+        }
+        Ok(())
+    }
+
+    /// 将所有 item 的 weight 按 numerator / denominator 等比缩放（用于把旧 pool 迁移到 WEIGHT_SCALE 范围内）
+    /// 每个 weight 必须被整除，缩放后仍须在范围内；各 item 的 weight 比例不变，兑换率和排放分配不受影响
+    pub fn rescale_weights(&mut self, numerator: u64, denominator: u64) -> Result<()> {
+        require!(numerator > 0 && denominator > 0, ErrorCode::InvalidWeightRescale);
+        let mut weights = Vec::with_capacity(self.get_token_count());
+        for item in &self.tokens[..self.get_token_count()] {
+            let scaled = (item.get_weight() as u128) * (numerator as u128);
+            require!(scaled % (denominator as u128) == 0, ErrorCode::InvalidWeightRescale);
+            let weight = u64::try_from(scaled / (denominator as u128))
+                .map_err(|_| ErrorCode::WeightOutOfBounds)?;
+            Pool::verify_weight(weight)?;
+            weights.push(weight);
+            // 衰减计划的起止 weight 同比缩放
+            let decay = &item.weight_decay;
+            if decay.is_active() {
+                for decay_weight in [decay.start_weight, decay.target] {
+                    let scaled = (decay_weight as u128) * (numerator as u128);
+                    require!(scaled % (denominator as u128) == 0, ErrorCode::InvalidWeightRescale);
+                    Pool::verify_weight(u64::try_from(scaled / (denominator as u128))
+                        .map_err(|_| ErrorCode::WeightOutOfBounds)?)?;
                 }
             }
         }
+        for (item, weight) in self.tokens.iter_mut().zip(weights) {
+            item.set_weight(weight);
+            let decay = &mut item.weight_decay;
+            if decay.is_active() {
+                decay.start_weight = ((decay.start_weight as u128) * (numerator as u128) / (denominator as u128)) as u64;
+                decay.target = ((decay.target as u128) * (numerator as u128) / (denominator as u128)) as u64;
+            }
+        }
+        self.sync_asset_total_weighted()?;
+        Ok(())
+    }
 
-        require!(total_weighted > 0, ErrorCode::InvalidTokenCount);
-        Ok(total_weighted)
+    /// 增加 item 的 LP 发行量（同时更新总加权质押量缓存）
+    pub fn add_item_mint_amount(&mut self, index: usize, amount: u64) -> Result<()> {
+        let item = self.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        let added = U256::from(item.get_weight()) * U256::from(amount);
+        let asset_index = item.get_main_asset_index();
+        self.adjust_asset_total_weighted(asset_index, U256::zero(), added)?;
+        self.tokens[index].add_mint_amount(amount)
     }
 
-    pub fn calculate_redeem_amount(
-        &self,
-        lp_amount: u64,
-        item_index: usize,
-        pool_vault_balance: u64,
-    ) -> Result<u64> {
-        require!(
-            item_index < self.get_token_count(),
-            ErrorCode::InvalidTokenIndex
-        );
+    /// 减少 item 的 LP 发行量（同时更新总加权质押量缓存）
+    pub fn sub_item_mint_amount(&mut self, index: usize, amount: u64) -> Result<()> {
+        let item = self.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+        let removed = U256::from(item.get_weight()) * U256::from(amount);
+        let asset_index = item.get_main_asset_index();
+        self.adjust_asset_total_weighted(asset_index, removed, U256::zero())?;
+        self.tokens[index].sub_mint_amount(amount)
+    }
 
-        let item = self.get_token(item_index)
-            .ok_or(ErrorCode::InvalidTokenIndex)?;
+    /// 结算奖励排放（惰性更新），在任何 item 的 mint_amount 或 weight 变化前调用
+    /// 自上次结算以来的排放按 weight × mint_amount 累加到各 item 的 reward_per_share
+    /// 没有任何 LP 时该时段的排放不分配
+    pub fn accrue_emission(&mut self, now: i64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.emission_last_update_ts);
+        if !self.has_emission() || elapsed <= 0 {
+            return Ok(());
+        }
+        self.emission_last_update_ts = now;
+        if self.emission_rate == 0 {
+            return Ok(());
+        }
 
-        let weight = item.get_weight();
-        let total_weighted = self.calculate_total_weighted_mint_amount()?;
+        let token_count = self.get_token_count();
+        let items = self.tokens[..token_count]
+            .iter()
+            .map(|item| (item.get_weight(), item.get_mint_amount()));
+        let total_weighted = math::calculate_total_weighted_mint_amount(items)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if total_weighted.is_zero() {
+            return Ok(());
+        }
 
-        require!(weight > 0, ErrorCode::InvalidTokenCount);
+        let emission_rate = self.emission_rate;
+        for item in self.tokens[..token_count].iter_mut() {
+            let delta = math::calculate_reward_per_share_delta(
+                elapsed as u64,
+                emission_rate,
+                item.get_weight(),
+                total_weighted,
+            ).ok_or(ErrorCode::MathOverflow)?;
+            item.add_reward_per_share(delta)?;
+        }
+        Ok(())
+    }
+}
 
-        // 使用 u128 避免溢出
-        let lp_amount_u128 = lp_amount as u128;
-        let weight_u128 = weight as u128;
-        let pool_vault_balance_u128 = pool_vault_balance as u128;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let weighted_amount = lp_amount_u128
-            .checked_mul(weight_u128)
-            .ok_or(ErrorCode::MathOverflow)?;
+    /// 按 v1 布局写入账户数据：头部 + (mint, mint_amount, weight) × items，账户大小为 v1_space()
+    fn v1_account(items: &[(Pubkey, u64, u64)], stale_slots: usize) -> (Vec<u64>, [Pubkey; 3]) {
+        let mut words = vec![0u64; Pool::v1_space() / 8];
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut words);
+        data[..8].copy_from_slice(Pool::DISCRIMINATOR);
+        data[8..10].copy_from_slice(&(items.len() as u16).to_le_bytes());
+        data[10..12].copy_from_slice(&((items.len() + stale_slots) as u16).to_le_bytes());
+        for (index, key) in keys.iter().enumerate() {
+            data[16 + index * 32..48 + index * 32].copy_from_slice(key.as_ref());
+        }
+        data[112..120].copy_from_slice(&30u64.to_le_bytes());
+        data[120..128].copy_from_slice(&10_000u64.to_le_bytes());
+        // remove_token 留下的旧 item 数据同样需要在迁移后清除
+        let stale = (Pubkey::new_unique(), 7, 7);
+        for (index, (mint, amount, weight)) in items.iter().chain(std::iter::repeat_n(&stale, stale_slots)).enumerate() {
+            let offset = 8 + V1_POOL_HEADER_SIZE + index * V1_POOL_ITEM_SIZE;
+            data[offset..offset + 32].copy_from_slice(mint.as_ref());
+            data[offset + 32..offset + 40].copy_from_slice(&amount.to_le_bytes());
+            data[offset + 40..offset + 48].copy_from_slice(&weight.to_le_bytes());
+        }
+        (words, keys)
+    }
 
-        let redeem_amount = pool_vault_balance_u128
-            .checked_mul(weighted_amount)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(total_weighted)
-            .ok_or(ErrorCode::MathOverflow)?;
+    #[test]
+    fn apply_weight_schedules_updates_all_items_of_asset() {
+        let items = [
+            (Pubkey::new_unique(), 1_000, WEIGHT_SCALE),
+            (Pubkey::new_unique(), 2_000, WEIGHT_SCALE),
+        ];
+        let (mut words, _) = v1_account(&items, 0);
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut words);
+        Pool::migrate_from_v1(data).unwrap();
+        let pool = &mut Pool::view_mut(data).unwrap();
+        for index in 0..2 {
+            pool.tokens[index].weight_decay = WeightDecay {
+                start_weight: WEIGHT_SCALE,
+                target: 3 * WEIGHT_SCALE,
+                start_ts: 0,
+                end_ts: 100,
+            };
+        }
+
+        pool.apply_weight_schedules(0, 50).unwrap();
+        assert_eq!(pool.tokens[0].get_weight(), 2 * WEIGHT_SCALE);
+        assert_eq!(pool.tokens[1].get_weight(), 2 * WEIGHT_SCALE);
+        assert_eq!(
+            pool.get_asset_total_weighted(PRIMARY_ASSET_INDEX).unwrap(),
+            pool.recompute_asset_total_weighted(PRIMARY_ASSET_INDEX).unwrap()
+        );
 
-        Ok(redeem_amount as u64)
+        // 计划结束后清除
+        pool.apply_weight_schedules(1, 100).unwrap();
+        assert_eq!(pool.tokens[0].get_weight(), 3 * WEIGHT_SCALE);
+        assert_eq!(pool.tokens[1].get_weight(), 3 * WEIGHT_SCALE);
+        assert!(!pool.tokens[0].weight_decay.is_active());
+        assert!(!pool.tokens[1].weight_decay.is_active());
     }
 
-    /// 获取 pool vault 的 Pubkey
-    pub fn get_pool_vault(&self) -> &Pubkey {
-        &self.pool_vault
+    #[test]
+    fn v1_space_matches_deployed_pool() {
+        assert_eq!(Pool::v1_space(), 24704);
     }
 
-    /// 获取 pool mint 的 Pubkey
-    pub fn get_pool_mint(&self) -> &Pubkey {
-        &self.pool_mint
+    #[test]
+    fn migrate_from_v1_restrides_items_and_keeps_header() {
+        let items: Vec<_> = (0..3u64)
+            .map(|i| (Pubkey::new_unique(), 1_000 * (i + 1), WEIGHT_SCALE * (i + 1)))
+            .collect();
+        let (mut words, [admin, vault, mint]) = v1_account(&items, 2);
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut words);
+        assert_eq!(Pool::read_version(data), UNVERSIONED_POOL_VERSION);
+
+        assert_eq!(Pool::migrate_from_v1(data).unwrap(), items.len());
+
+        assert_eq!(Pool::read_version(data), POOL_VERSION);
+        let pool = Pool::view(data).unwrap();
+        // v1 账户原地迁移：24704 字节在当前头部之后可容纳 168 个 item
+        assert_eq!(pool.get_capacity(), 168);
+        assert_eq!(pool.tokens.len(), 168);
+        assert_eq!(pool.get_token_count(), items.len());
+        assert_eq!(pool.get_increment_count(), 5);
+        assert_eq!(pool.get_authority_seed_version(), INITIAL_AUTHORITY_SEED_VERSION);
+        assert_eq!((pool.admin, pool.pool_vault, pool.pool_mint), (admin, vault, mint));
+        assert!(pool.is_legacy_fee());
+        assert_eq!(pool.get_fee_config(), FeeConfig::from_legacy(30, 10_000));
+        assert_eq!(pool.admin_threshold, 1);
+        assert_eq!(pool.whitelist_root, [0u8; 32]);
+        assert_eq!(pool.admin_signers, [Pubkey::default(); MAX_ADMIN_SIGNERS]);
+
+        for (index, (lp_mint, amount, weight)) in items.iter().enumerate() {
+            let item = pool.get_token(index).unwrap();
+            assert_eq!(item.mint_pubkey(), lp_mint);
+            assert_eq!(item.get_mint_amount(), *amount);
+            assert_eq!(item.get_weight(), *weight);
+            assert_eq!(item.get_main_asset_index(), PRIMARY_ASSET_INDEX);
+            assert_eq!(item.active, 1);
+            assert!(!item.weight_decay.is_active());
+            assert_eq!(item.name, [0u8; ITEM_NAME_LEN]);
+        }
+        assert!(pool.tokens[items.len()..].iter().all(|item| item.is_empty() && item.get_mint_amount() == 0));
+        assert_eq!(
+            pool.get_asset_total_weighted(PRIMARY_ASSET_INDEX).unwrap(),
+            pool.recompute_asset_total_weighted(PRIMARY_ASSET_INDEX).unwrap()
+        );
+        assert!(!pool.get_asset_total_weighted(PRIMARY_ASSET_INDEX).unwrap().is_zero());
     }
 
-    /// 设置 pool vault
-    pub fn set_pool_vault(&mut self, vault: &Pubkey) {
-        self.pool_vault = *vault;
+    #[test]
+    fn migrate_from_v1_requires_room_for_items() {
+        let items = [(Pubkey::new_unique(), 1, WEIGHT_SCALE), (Pubkey::new_unique(), 1, WEIGHT_SCALE)];
+        let (mut words, _) = v1_account(&items, 0);
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut words);
+        assert!(Pool::migrate_from_v1(&mut data[..Pool::space_for(1)]).is_err());
+        assert_eq!(Pool::migrate_from_v1(&mut data[..Pool::space_for(2)]).unwrap(), items.len());
+        assert_eq!(Pool::view(&data[..Pool::space_for(2)]).unwrap().get_capacity(), 2);
     }
 
-    /// 设置 pool mint
-    pub fn set_pool_mint(&mut self, mint: &Pubkey) {
-        self.pool_mint = *mint;
+    #[test]
+    fn size_tiers_match_account_space() {
+        assert_eq!(Pool::space_for(PoolSizeTier::Small.capacity()), 4032);
+        assert_eq!(Pool::space_for(PoolSizeTier::Medium.capacity()), 19264);
+        assert_eq!(Pool::space_for(PoolSizeTier::Large.capacity()), 71488);
+        for tier in [PoolSizeTier::Small, PoolSizeTier::Medium, PoolSizeTier::Large] {
+            assert_eq!(Pool::capacity_for_space(Pool::space_for(tier.capacity())), tier.capacity());
+        }
+    }
+
+    #[test]
+    fn add_token_is_bounded_by_capacity() {
+        let mut words = vec![0u64; Pool::space_for(2) / 8];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut words);
+        data[..8].copy_from_slice(Pool::DISCRIMINATOR);
+        let header: &mut Pool = bytemuck::from_bytes_mut(&mut data[8..Pool::header_space()]);
+        header.initialize(&Pubkey::new_unique(), &Pubkey::new_unique(), &Pubkey::new_unique(), FeeConfig::default(), 2);
+
+        let pool = &mut Pool::view_mut(data).unwrap();
+        assert_eq!(pool.tokens.len(), 2);
+        assert_eq!(pool.add_token(&Pubkey::new_unique(), WEIGHT_SCALE).unwrap(), 0);
+        assert_eq!(pool.add_token(&Pubkey::new_unique(), WEIGHT_SCALE).unwrap(), 1);
+        assert!(pool.add_token(&Pubkey::new_unique(), WEIGHT_SCALE).is_err());
+        assert!(pool.next_lp_mint_seed().is_err());
+    }
+
+    #[test]
+    fn initial_authority_seed_matches_deployed_pda() {
+        let pool = Pubkey::new_unique();
+        let (legacy, _) = Pubkey::find_program_address(&[POOL_AUTHORITY_SEED, pool.as_ref()], &crate::ID);
+        let (versioned, _) = Pubkey::find_program_address(
+            &[POOL_AUTHORITY_SEED, pool.as_ref(), authority_seed_suffix(INITIAL_AUTHORITY_SEED_VERSION)],
+            &crate::ID,
+        );
+        assert_eq!(versioned, legacy);
+        assert_eq!(authority_seed_suffix(1), &[1u8]);
+        assert_eq!(authority_seed_suffix(u8::MAX), &[u8::MAX]);
     }
 }
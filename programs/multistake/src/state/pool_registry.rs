@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+/// 单个注册表最多记录的 pool 数量
+pub const MAX_REGISTRY_POOLS: usize = 128;
+
+/// 主币 mint → pool 列表的注册表
+/// PDA seeds: ["pool_registry", main_token_mint]
+/// 由 create_pool / close_pool 维护，客户端按主币发现 pool 时无需 getProgramAccounts 扫描
+#[account]
+#[derive(Debug)]
+pub struct PoolRegistry {
+    /// 主币 Mint
+    pub main_token_mint: Pubkey,
+    /// 以该 mint 为主币的 pool
+    pub pools: Vec<Pubkey>,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl PoolRegistry {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // main_token_mint (Pubkey)
+        4 + 32 * MAX_REGISTRY_POOLS + // pools (Vec<Pubkey>)
+        1 // bump
+    }
+
+    /// 检查账户是否尚未初始化（init_if_needed 新建时为全零）
+    pub fn is_uninitialized(&self) -> bool {
+        self.main_token_mint == Pubkey::default()
+    }
+
+    /// 登记 pool
+    pub fn register(&mut self, pool: &Pubkey) -> Result<()> {
+        require!(!self.pools.contains(pool), ErrorCode::PoolAlreadyRegistered);
+        require!(self.pools.len() < MAX_REGISTRY_POOLS, ErrorCode::PoolRegistryFull);
+        self.pools.push(*pool);
+        Ok(())
+    }
+
+    /// 移除 pool，返回是否存在
+    pub fn unregister(&mut self, pool: &Pubkey) -> bool {
+        match self.pools.iter().position(|p| p == pool) {
+            Some(index) => {
+                self.pools.swap_remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use crate::error::ErrorCode;
+use super::item::PoolItem;
+use super::pool::{Pool, PoolView};
+use super::rate_history::RATE_SAMPLE_LP_AMOUNT;
+use std::ops::Deref;
+
+/// 统计中单个主币资产的记录
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StatsAsset {
+    /// 主币 mint
+    pub mint: Pubkey,
+    /// 计入兑换率的 vault 余额（已按 max_donation_bps 限速）
+    pub vault_balance: u64,
+    /// 共享该 vault 的所有 item 的 weight × mint_amount 之和（U256 小端 limbs）
+    pub total_weighted: [u64; 4],
+}
+
+impl StatsAsset {
+    /// 序列化后的大小
+    pub const SIZE: usize = 32 + 8 + 32;
+}
+
+/// 统计中单个质押类型的记录
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StatsItem {
+    /// 质押类型的 LP mint
+    pub lp_mint: Pubkey,
+    /// 所属主币资产索引
+    pub main_asset_index: u8,
+    /// 当前权重
+    pub weight: u64,
+    /// 当前 LP 发行量
+    pub mint_amount: u64,
+    /// RATE_SAMPLE_LP_AMOUNT 个 LP 可赎回的主币数量（手续费前），该主币下尚无 LP 时为 0
+    pub redeem_rate: u64,
+}
+
+impl StatsItem {
+    /// 序列化后的大小
+    pub const SIZE: usize = 32 + 1 + 8 + 8 + 8;
+}
+
+/// pool 的派生统计数据
+/// PDA seeds: ["pool_stats", pool]
+/// 由 refresh_stats 刷新，钱包读取这个小账户即可展示兑换率 / APY，无需拉取完整的 pool 账户
+#[account]
+#[derive(Debug)]
+pub struct PoolStats {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 最近一次刷新的 slot
+    pub slot: u64,
+    /// 最近一次刷新的时间戳（unix 秒）
+    pub unix_timestamp: i64,
+    /// PDA bump
+    pub bump: u8,
+    /// 各主币资产的记录，按资产索引排列
+    pub assets: Vec<StatsAsset>,
+    /// 各质押类型的记录，顺序与 pool.tokens 一致
+    pub items: Vec<StatsItem>,
+}
+
+impl PoolStats {
+    /// 计算 asset_count 个主币资产、token_count 个质押类型所需的空间大小
+    pub fn space(asset_count: usize, token_count: usize) -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        8 + // slot
+        8 + // unix_timestamp
+        1 + // bump
+        4 + StatsAsset::SIZE * asset_count + // assets
+        4 + StatsItem::SIZE * token_count // items
+    }
+
+    /// 按 pool 当前的资产和质押类型数量计算账户大小，超过单条指令可分配的上限时报错
+    pub fn space_for(pool: &Pool) -> Result<usize> {
+        let space = Self::space(pool.get_main_asset_count(), pool.get_token_count());
+        require!(space <= MAX_PERMITTED_DATA_INCREASE, ErrorCode::InvalidTokenCount);
+        Ok(space)
+    }
+
+    /// 按各主币 vault 的实际余额重新计算统计数据
+    /// vault_balances: 按资产索引排列的 vault 实际余额
+    pub fn refresh(&mut self, pool: &PoolView<impl Deref<Target = Pool>, impl Deref<Target = [PoolItem]>>, vault_balances: &[u64]) -> Result<()> {
+        require!(
+            vault_balances.len() == pool.get_main_asset_count(),
+            ErrorCode::InvalidAssetIndex
+        );
+        self.assets = vault_balances
+            .iter()
+            .enumerate()
+            .map(|(asset_index, &balance)| {
+                let asset_index = asset_index as u8;
+                Ok(StatsAsset {
+                    mint: pool.get_asset_mint(asset_index)?,
+                    vault_balance: pool.effective_vault_balance(asset_index, balance)?,
+                    total_weighted: pool.get_asset_total_weighted(asset_index)?.0,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.items = (0..pool.get_token_count())
+            .map(|index| {
+                let item = pool.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+                let asset_index = item.get_main_asset_index();
+                // 与 unstake 使用同一赎回计算
+                let redeem_rate = if pool.get_asset_total_weighted(asset_index)?.is_zero() {
+                    0
+                } else {
+                    let balance = *vault_balances.get(asset_index as usize)
+                        .ok_or(ErrorCode::InvalidAssetIndex)?;
+                    pool.calculate_redeem_amount(RATE_SAMPLE_LP_AMOUNT, index, balance)?
+                };
+                Ok(StatsItem {
+                    lp_mint: *item.mint_pubkey(),
+                    main_asset_index: asset_index,
+                    weight: item.get_weight(),
+                    mint_amount: item.get_mint_amount(),
+                    redeem_rate,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+/// 优先赎回地址登记
+/// PDA seeds: ["priority_redeemer", pool, redeemer]
+/// 赎回限速生效时，登记的地址（如集成借贷市场的清算机器人）可使用预留的优先额度
+#[account]
+#[derive(Debug)]
+pub struct PriorityRedeemer {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 优先赎回地址
+    pub redeemer: Pubkey,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl PriorityRedeemer {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // redeemer (Pubkey)
+        1 // bump
+    }
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::math;
+use super::oracle::BPS_DENOMINATOR;
+
+/// ProgramConfig PDA seed
+pub const PROGRAM_CONFIG_SEED: &[u8] = b"program_config";
+
+/// 全局程序配置
+/// PDA seeds: ["program_config"]
+/// 各 pool 转入 fee_vault 的协议手续费中，按 protocol_fee_share_bps 抽取给协议金库
+#[account]
+#[derive(Debug)]
+pub struct ProgramConfig {
+    /// 配置管理员
+    pub authority: Pubkey,
+    /// 协议金库（接收分成的主币账户的 owner）
+    pub protocol_treasury: Pubkey,
+    /// 协议分成比例（bps，占每个 pool 转入 fee_vault 的手续费）
+    pub protocol_fee_share_bps: u16,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl ProgramConfig {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // authority (Pubkey)
+        32 + // protocol_treasury (Pubkey)
+        2 + // protocol_fee_share_bps
+        1 // bump
+    }
+
+    /// 验证并写入分成配置
+    pub fn set(&mut self, protocol_treasury: Pubkey, protocol_fee_share_bps: u16) -> Result<()> {
+        require!(
+            protocol_fee_share_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidFeeConfig
+        );
+        require!(
+            protocol_fee_share_bps == 0 || protocol_treasury != Pubkey::default(),
+            ErrorCode::InvalidProtocolTreasury
+        );
+        self.protocol_treasury = protocol_treasury;
+        self.protocol_fee_share_bps = protocol_fee_share_bps;
+        Ok(())
+    }
+
+    /// 读取 PDA 地址上的配置，尚未初始化时返回 None
+    pub fn load_optional(info: &AccountInfo) -> Result<Option<ProgramConfig>> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(None);
+        }
+        let data = info.try_borrow_data()?;
+        Ok(Some(ProgramConfig::try_deserialize(&mut &data[..])?))
+    }
+
+    /// 拆分转入 fee_vault 的手续费
+    /// 返回: (归协议金库的部分, 仍转入 fee_vault 的部分)
+    pub fn split_fee(&self, fee_amount: u64) -> Result<(u64, u64)> {
+        Ok(math::calculate_share(fee_amount, self.protocol_fee_share_bps as u64, BPS_DENOMINATOR)
+            .ok_or(ErrorCode::MathOverflow)?)
+    }
+}
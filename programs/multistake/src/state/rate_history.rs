@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+/// 每个 item 保留的汇率样本数量（环形缓冲区）
+pub const RATE_HISTORY_LEN: usize = 32;
+
+/// 汇率的计量单位：记录 RATE_SAMPLE_LP_AMOUNT 个 LP 最小单位可赎回的主币数量
+pub const RATE_SAMPLE_LP_AMOUNT: u64 = 1_000_000_000;
+
+/// 一次汇率采样
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct RateSample {
+    /// 采样所在的 epoch
+    pub epoch: u64,
+    /// 采样时间戳（unix 秒）
+    pub unix_timestamp: i64,
+    /// RATE_SAMPLE_LP_AMOUNT 个 LP 可赎回的主币数量
+    pub rate: u64,
+}
+
+/// 质押类型的汇率历史，用于链上可验证地计算实际 APY
+/// PDA seeds: ["rate_history", pool, lp_mint]
+/// 每个 epoch 最多采样一次，超过 RATE_HISTORY_LEN 后覆盖最旧的样本
+#[account]
+#[derive(Debug)]
+pub struct ItemRateHistory {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 质押类型的 LP mint
+    pub lp_mint: Pubkey,
+    /// 下一个写入位置
+    pub head: u8,
+    /// 已写入的样本数量（不超过 RATE_HISTORY_LEN）
+    pub count: u8,
+    /// PDA bump
+    pub bump: u8,
+    /// 样本环形缓冲区
+    pub samples: [RateSample; RATE_HISTORY_LEN],
+}
+
+impl ItemRateHistory {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // lp_mint (Pubkey)
+        1 + // head
+        1 + // count
+        1 + // bump
+        (8 + 8 + 8) * RATE_HISTORY_LEN // samples
+    }
+
+    /// 获取最近一次采样
+    pub fn latest(&self) -> Option<&RateSample> {
+        if self.count == 0 {
+            return None;
+        }
+        let index = (self.head as usize + RATE_HISTORY_LEN - 1) % RATE_HISTORY_LEN;
+        self.samples.get(index)
+    }
+
+    /// 写入新的采样，同一 epoch 只能采样一次
+    pub fn push(&mut self, sample: RateSample) -> Result<()> {
+        if let Some(latest) = self.latest() {
+            require!(sample.epoch > latest.epoch, ErrorCode::RateAlreadySampled);
+        }
+        self.samples[self.head as usize] = sample;
+        self.head = ((self.head as usize + 1) % RATE_HISTORY_LEN) as u8;
+        if (self.count as usize) < RATE_HISTORY_LEN {
+            self.count += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::math;
+use super::oracle::{OraclePrice, WEIGHT_PRICE_SCALE};
+
+/// ItemRateSource PDA seed
+pub const ITEM_RATE_SOURCE_SEED: &[u8] = b"item_rate_source";
+
+/// 兑换率来源：Pyth PriceUpdateV2（如 mSOL/SOL 赎回率 feed）
+pub const RATE_SOURCE_PYTH: u8 = 0;
+/// 兑换率来源：SPL stake pool 状态账户（如 jitoSOL）
+pub const RATE_SOURCE_STAKE_POOL: u8 = 1;
+
+/// SPL stake pool 程序 ID
+pub const SPL_STAKE_POOL_PROGRAM_ID: Pubkey =
+    pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+
+/// SPL stake pool 账户类型标签：AccountType::StakePool
+const STAKE_POOL_ACCOUNT_TYPE: u8 = 1;
+/// StakePool 中 total_lamports 的偏移：account_type(1) + manager / staker / stake_deposit_authority(32 × 3)
+///   + stake_withdraw_bump_seed(1) + validator_list / reserve_stake / pool_mint / manager_fee_account / token_program_id(32 × 5)
+const STAKE_POOL_TOTAL_LAMPORTS_OFFSET: usize = 1 + 32 * 3 + 1 + 32 * 5;
+
+/// 质押类型的兑换率来源
+/// PDA seeds: ["item_rate_source", pool, lp_mint]
+/// 配置后 item 的 weight 由 stake / unstake 等指令执行时从 source 读取，无需管理员或 crank 刷新
+#[account]
+#[derive(Debug)]
+pub struct ItemRateSource {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 对应质押类型的 LP mint
+    pub lp_mint: Pubkey,
+    /// 兑换率来源账户（Pyth PriceUpdateV2 或 SPL stake pool 状态账户）
+    pub source: Pubkey,
+    /// 来源类型（RATE_SOURCE_*）
+    pub kind: u8,
+    /// 最大允许的延迟：Pyth 为秒，stake pool 为 epoch 数
+    pub max_staleness: u64,
+    /// 置信区间占价格的最大比例（bps），仅 Pyth 使用
+    pub max_confidence_bps: u16,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl ItemRateSource {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // lp_mint (Pubkey)
+        32 + // source (Pubkey)
+        1 + // kind
+        8 + // max_staleness
+        2 + // max_confidence_bps
+        1 // bump
+    }
+
+    /// 读取 remaining_accounts 中传入的配置（不依赖账户生命周期）
+    pub fn load(info: &AccountInfo) -> Result<Self> {
+        require!(info.owner == &crate::ID, ErrorCode::InvalidRateSource);
+        let data = info.try_borrow_data()?;
+        Self::try_deserialize(&mut &data[..])
+    }
+
+    /// 读取 source 的兑换率，返回 weight（1 份质押资产可兑换的主币数量 × WEIGHT_PRICE_SCALE）
+    pub fn read_weight(&self, source: &AccountInfo, clock: &Clock) -> Result<u64> {
+        require!(source.key() == self.source, ErrorCode::InvalidRateSource);
+        match self.kind {
+            RATE_SOURCE_PYTH => {
+                let price = OraclePrice::load_pyth(source)?;
+                price.validate(clock.unix_timestamp, self.max_staleness, self.max_confidence_bps)?;
+                price.scale_to(WEIGHT_PRICE_SCALE)
+            }
+            RATE_SOURCE_STAKE_POOL => {
+                require!(
+                    *source.owner == SPL_STAKE_POOL_PROGRAM_ID,
+                    ErrorCode::InvalidRateSource
+                );
+                let data = source.try_borrow_data()?;
+                require!(
+                    data.first() == Some(&STAKE_POOL_ACCOUNT_TYPE),
+                    ErrorCode::InvalidRateSource
+                );
+                let read_u64 = |offset: usize| -> Result<u64> {
+                    let start = STAKE_POOL_TOTAL_LAMPORTS_OFFSET + offset;
+                    let bytes = data.get(start..start + 8).ok_or(ErrorCode::InvalidRateSource)?;
+                    Ok(u64::from_le_bytes(bytes.try_into().map_err(|_| ErrorCode::InvalidRateSource)?))
+                };
+                let total_lamports = read_u64(0)?;
+                let pool_token_supply = read_u64(8)?;
+                let last_update_epoch = read_u64(16)?;
+                // stake pool 每个 epoch 需要 update 一次，否则 total_lamports 未计入最新的质押收益
+                require!(
+                    clock.epoch.saturating_sub(last_update_epoch) <= self.max_staleness,
+                    ErrorCode::StaleOraclePrice
+                );
+                Ok(math::calculate_rate_weight(total_lamports, pool_token_supply, WEIGHT_PRICE_SCALE)
+                    .ok_or(ErrorCode::InvalidOraclePrice)?)
+            }
+            _ => err!(ErrorCode::InvalidRateSource),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+/// 推荐码最大长度（字节）
+pub const MAX_REFERRAL_CODE_LEN: usize = 16;
+
+/// 推荐码注册表项：可读的短推荐码 → 推荐人的手续费收款账户
+/// PDA seeds: ["referral", code]
+#[account]
+#[derive(Debug)]
+pub struct ReferralCode {
+    /// 推荐人（注册者）
+    pub referrer: Pubkey,
+    /// 推荐返佣的收款 token 账户
+    pub fee_account: Pubkey,
+    /// 推荐码原文（ASCII 字母、数字、'-'、'_'）
+    pub code: Vec<u8>,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl ReferralCode {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // referrer (Pubkey)
+        32 + // fee_account (Pubkey)
+        4 + MAX_REFERRAL_CODE_LEN + // code (Vec<u8>)
+        1 // bump
+    }
+
+    /// 校验推荐码格式：非空、不超过 MAX_REFERRAL_CODE_LEN、只包含可读字符
+    pub fn validate_code(code: &[u8]) -> Result<()> {
+        require!(
+            !code.is_empty() && code.len() <= MAX_REFERRAL_CODE_LEN,
+            ErrorCode::InvalidReferralCode
+        );
+        require!(
+            code.iter().all(|c| c.is_ascii_alphanumeric() || *c == b'-' || *c == b'_'),
+            ErrorCode::InvalidReferralCode
+        );
+        Ok(())
+    }
+}
+
+/// 推荐人在某个 pool 中的返佣账户
+/// stake 时传入 referrer 参数，返佣转入 fee_vault 并记入该账户，推荐人通过 claim_referral_fees 领取
+/// PDA seeds: ["referral_account", pool, referrer]
+#[account]
+#[derive(Debug)]
+pub struct ReferralAccount {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 推荐人
+    pub referrer: Pubkey,
+    /// 尚未领取的返佣（主币最小单位）
+    pub unclaimed_fees: u64,
+    /// 累计获得的返佣（只增不减）
+    pub total_earned: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl ReferralAccount {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // referrer (Pubkey)
+        8 + // unclaimed_fees
+        8 + // total_earned
+        1 // bump
+    }
+
+    /// 检查账户是否尚未初始化（init_if_needed 新建时为全零）
+    pub fn is_uninitialized(&self) -> bool {
+        self.referrer == Pubkey::default()
+    }
+
+    /// 记入一笔返佣
+    pub fn accrue(&mut self, amount: u64) -> Result<()> {
+        self.unclaimed_fees = self.unclaimed_fees
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_earned = self.total_earned
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
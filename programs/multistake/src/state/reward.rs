@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use super::oracle::BPS_DENOMINATOR;
+
+/// 奖励兑换比例的精度：每 REWARD_RATE_SCALE 积分兑换 reward_rate 个主币最小单位
+pub const REWARD_RATE_SCALE: u128 = 1_000_000_000_000;
+
+/// Pool 的积分奖励配置
+/// PDA seeds: ["reward_config", pool]
+/// 奖励主币存放在 reward_vault（PDA ["reward_vault", pool]），任何人都可以直接转账注资
+#[account]
+#[derive(Debug)]
+pub struct RewardConfig {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 奖励 vault
+    pub reward_vault: Pubkey,
+    /// 兑换比例：reward = points × reward_rate / REWARD_RATE_SCALE
+    pub reward_rate: u64,
+    /// keeper 代为收割时抽取的比例（bps）
+    pub keeper_fee_bps: u16,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl RewardConfig {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // reward_vault (Pubkey)
+        8 + // reward_rate
+        2 + // keeper_fee_bps
+        1 // bump
+    }
+
+    /// 校验配置参数
+    pub fn validate(keeper_fee_bps: u16) -> Result<()> {
+        require!(keeper_fee_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidFeeConfig);
+        Ok(())
+    }
+
+    /// 积分兑换为奖励数量（向下取整）
+    pub fn points_to_reward(&self, points: u128) -> Result<u64> {
+        let reward = points
+            .checked_mul(self.reward_rate as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / REWARD_RATE_SCALE;
+        u64::try_from(reward).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+
+    /// 奖励数量折算回积分（向上取整），用于奖励 vault 余额不足时只消耗对应的积分
+    pub fn reward_to_points(&self, reward: u64) -> Result<u128> {
+        require!(self.reward_rate > 0, ErrorCode::InvalidFeeConfig);
+        let points = (reward as u128)
+            .checked_mul(REWARD_RATE_SCALE)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(points.div_ceil(self.reward_rate as u128))
+    }
+
+    /// 计算 keeper 抽成
+    /// 返回: (keeper 抽成, 用户所得)
+    pub fn split_keeper_fee(&self, reward: u64) -> Result<(u64, u64)> {
+        Ok(crate::math::calculate_share(reward, self.keeper_fee_bps as u64, BPS_DENOMINATOR)
+            .ok_or(ErrorCode::MathOverflow)?)
+    }
+}
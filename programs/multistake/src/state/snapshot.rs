@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use crate::error::ErrorCode;
+use super::item::PoolItem;
+use super::pool::{Pool, PoolView};
+use std::ops::Deref;
+
+/// 快照中单个质押类型的记录
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SnapshotItem {
+    /// 质押类型的 LP mint
+    pub lp_mint: Pubkey,
+    /// 快照时的 LP 发行量（mint_amount）
+    pub mint_amount: u64,
+    /// 快照时的权重
+    pub weight: u64,
+}
+
+impl SnapshotItem {
+    /// 序列化后的大小
+    pub const SIZE: usize = 32 + 8 + 8;
+}
+
+/// pool 在某个 epoch 的 LP 发行量和权重快照
+/// PDA seeds: ["snapshot", pool, epoch (le bytes)]，每个 epoch 只能创建一次
+/// 外部治理程序按 mint_amount × weight 计算投票权重，不受快照之后 stake / unstake / 修改 weight 的影响
+#[account]
+#[derive(Debug)]
+pub struct PoolSnapshot {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 快照所在的 epoch
+    pub epoch: u64,
+    /// 生成快照的 slot
+    pub slot: u64,
+    /// PDA bump
+    pub bump: u8,
+    /// 各质押类型的记录，顺序与快照时的 pool.tokens 一致
+    pub items: Vec<SnapshotItem>,
+}
+
+impl PoolSnapshot {
+    /// 计算 token_count 个质押类型所需的空间大小
+    pub fn space(token_count: usize) -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        8 + // epoch
+        8 + // slot
+        1 + // bump
+        4 + SnapshotItem::SIZE * token_count // items
+    }
+
+    /// 计算快照账户大小，超过单条指令可创建的账户上限时报错
+    pub fn space_for(pool: &Pool) -> Result<usize> {
+        let space = Self::space(pool.get_token_count());
+        require!(space <= MAX_PERMITTED_DATA_INCREASE, ErrorCode::InvalidTokenCount);
+        Ok(space)
+    }
+
+    /// 记录 pool 当前所有质押类型的 LP 发行量和权重
+    pub fn capture(&mut self, pool: &PoolView<impl Deref<Target = Pool>, impl Deref<Target = [PoolItem]>>) -> Result<()> {
+        self.items = (0..pool.get_token_count())
+            .map(|index| {
+                let item = pool.get_token(index).ok_or(ErrorCode::InvalidTokenIndex)?;
+                Ok(SnapshotItem {
+                    lp_mint: *item.mint_pubkey(),
+                    mint_amount: item.get_mint_amount(),
+                    weight: item.get_weight(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use super::pool::WEIGHT_SCALE;
+
+/// 积分计算的权重基准：weight = WEIGHT_SCALE（默认权重）时，每 slot 每单位 LP 累积 1 积分
+pub const POINTS_WEIGHT_SCALE: u128 = WEIGHT_SCALE as u128;
+
+/// 用户在某个质押类型下的质押记录
+/// PDA seeds: ["stake_record", pool, owner, lp_mint]
+/// 按 LP mint 而非 item 索引绑定：remove_token 把最后一个 item 移到空位后，记录仍对应原质押类型
+///
+/// 所有为 item 铸造或销毁 LP 的用户路径都会结算记录（不存在时创建）：铸造时累加 staked_amount，销毁时扣减
+/// LP 可以自由转账，记录只用于积分和排放奖励的累积，不限制赎回：通过转账获得的 LP 可以正常赎回，
+/// 但只有通过 transfer_position 迁移记录后才按新持有人累积奖励
+#[account]
+#[derive(Debug)]
+pub struct StakeRecord {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 记录所有者
+    pub owner: Pubkey,
+    /// 质押类型的 LP mint
+    pub lp_mint: Pubkey,
+    /// PDA bump
+    pub bump: u8,
+    /// 当前记录的 LP 数量 - 积分按该数量累积
+    pub staked_amount: u64,
+    /// 已结算的积分
+    pub points: u128,
+    /// 最近一次结算积分的 slot
+    pub last_update_slot: u64,
+    /// 已兑换为奖励的积分
+    pub claimed_points: u128,
+    /// 自动复投偏好 - 1 表示 keeper 收割的奖励直接复投为 LP
+    pub auto_compound: u8,
+    /// 最近一次结算奖励排放时 item 的 reward_per_share
+    pub reward_per_share_paid: u128,
+    /// 已结算、尚未领取的排放奖励
+    pub rewards_owed: u64,
+    /// 最近一次通过 stake 增加记录数量的时间戳（unix 秒），0 表示尚未 stake
+    pub stake_timestamp: i64,
+}
+
+impl StakeRecord {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // owner (Pubkey)
+        32 + // lp_mint (Pubkey)
+        1 + // bump
+        8 + // staked_amount
+        16 + // points
+        8 + // last_update_slot
+        16 + // claimed_points
+        1 + // auto_compound
+        16 + // reward_per_share_paid
+        8 + // rewards_owed
+        8 // stake_timestamp
+    }
+
+    /// 初始化新建的质押记录
+    pub fn initialize(
+        &mut self,
+        pool: &Pubkey,
+        owner: &Pubkey,
+        lp_mint: &Pubkey,
+        bump: u8,
+        current_slot: u64,
+    ) {
+        self.pool = *pool;
+        self.owner = *owner;
+        self.lp_mint = *lp_mint;
+        self.bump = bump;
+        self.staked_amount = 0;
+        self.points = 0;
+        self.last_update_slot = current_slot;
+        self.claimed_points = 0;
+        self.auto_compound = 0;
+        self.reward_per_share_paid = 0;
+        self.rewards_owed = 0;
+        self.stake_timestamp = 0;
+    }
+
+    /// 检查记录是否尚未初始化（init_if_needed 新建的账户）
+    pub fn is_uninitialized(&self) -> bool {
+        self.owner == Pubkey::default()
+    }
+
+    /// 计算自上次结算以来新增的积分（不修改状态）
+    /// 公式: staked_amount × weight × (current_slot - last_update_slot) / POINTS_WEIGHT_SCALE
+    pub fn pending_points(&self, weight: u64, current_slot: u64) -> Result<u128> {
+        let elapsed = current_slot.saturating_sub(self.last_update_slot) as u128;
+        let pending = (self.staked_amount as u128)
+            .checked_mul(weight as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_mul(elapsed)
+            .ok_or(ErrorCode::MathOverflow)?
+            / POINTS_WEIGHT_SCALE;
+        Ok(pending)
+    }
+
+    /// 获取截至 current_slot 的总积分（不修改状态）
+    pub fn total_points(&self, weight: u64, current_slot: u64) -> Result<u128> {
+        self.points
+            .checked_add(self.pending_points(weight, current_slot)?)
+            .ok_or(ErrorCode::MathOverflow.into())
+    }
+
+    /// 结算积分（惰性更新），在修改 staked_amount 之前调用
+    pub fn accrue_points(&mut self, weight: u64, current_slot: u64) -> Result<()> {
+        self.points = self.total_points(weight, current_slot)?;
+        self.last_update_slot = current_slot;
+        Ok(())
+    }
+
+    /// 结算奖励排放，在修改 staked_amount 之前调用（pool 需先 accrue_emission）
+    /// reward_per_share: item 当前的累加器
+    pub fn settle_rewards(&mut self, reward_per_share: u128) -> Result<()> {
+        // item 被移除后重新添加时累加器从 0 开始，旧记录的 paid 可能更大
+        let paid = self.reward_per_share_paid.min(reward_per_share);
+        let pending = crate::math::calculate_pending_reward(self.staked_amount, reward_per_share, paid)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.rewards_owed = self.rewards_owed
+            .checked_add(pending)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.reward_per_share_paid = reward_per_share;
+        Ok(())
+    }
+
+    /// 获取尚未兑换为奖励的积分（需先调用 accrue_points）
+    pub fn unclaimed_points(&self) -> u128 {
+        self.points.saturating_sub(self.claimed_points)
+    }
+
+    /// 检查是否开启自动复投
+    pub fn is_auto_compound(&self) -> bool {
+        self.auto_compound != 0
+    }
+
+    /// 合并另一条质押记录的 LP 数量、积分和排放奖励（两条记录需先结算到同一 slot 和 reward_per_share）
+    pub fn absorb(&mut self, other: &StakeRecord) -> Result<()> {
+        self.add_staked_amount(other.staked_amount)?;
+        self.points = self.points
+            .checked_add(other.points)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.claimed_points = self.claimed_points
+            .checked_add(other.claimed_points)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.rewards_owed = self.rewards_owed
+            .checked_add(other.rewards_owed)
+            .ok_or(ErrorCode::MathOverflow)?;
+        // 取较晚的 stake 时间，合并后的锁定期不会早于任一来源
+        self.stake_timestamp = self.stake_timestamp.max(other.stake_timestamp);
+        Ok(())
+    }
+
+    /// 增加记录的 LP 数量
+    pub fn add_staked_amount(&mut self, amount: u64) -> Result<()> {
+        self.staked_amount = self.staked_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// 减少记录的 LP 数量
+    /// 通过转账获得的 LP 在记录中没有数量，销毁时扣减到 0 为止
+    pub fn sub_staked_amount(&mut self, amount: u64) {
+        self.staked_amount = self.staked_amount.saturating_sub(amount);
+    }
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use super::oracle::BPS_DENOMINATOR;
+
+/// 原生质押账户的大小（StakeStateV2）
+pub const STAKE_ACCOUNT_SPACE: usize = 200;
+
+/// WSOL 池的原生质押策略
+/// PDA seeds: ["stake_strategy", pool]
+/// 策略使用单个质押账户 PDA ["strategy_stake", pool]，staker / withdrawer 均为 pool authority
+#[account]
+#[derive(Debug)]
+pub struct StakeStrategy {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 委托的验证者投票账户
+    pub vote_account: Pubkey,
+    /// 最多可委托的比例（bps，相对 vault 余额 + 已委托数量）
+    pub max_delegation_bps: u16,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl StakeStrategy {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // vote_account (Pubkey)
+        2 + // max_delegation_bps
+        1 // bump
+    }
+
+    /// 验证委托后的数量不超过上限
+    /// delegated_amount: 委托后的总数量
+    /// total_balance: vault 余额 + 已委托数量
+    pub fn verify_delegation_limit(&self, delegated_amount: u64, total_balance: u64) -> Result<()> {
+        let limit = (total_balance as u128)
+            .checked_mul(self.max_delegation_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        require!(
+            delegated_amount as u128 <= limit,
+            ErrorCode::StrategyDelegationExceeded
+        );
+        Ok(())
+    }
+}
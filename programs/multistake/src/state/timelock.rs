@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use super::caller::MAX_ALLOWED_CALLERS;
+use super::fee::FeeConfig;
+use super::oracle::BPS_DENOMINATOR;
+
+/// Pool 的管理操作时间锁配置
+/// PDA seeds: ["timelock", pool]
+#[account]
+#[derive(Debug)]
+pub struct AdminTimelock {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// guardian - 可以取消待执行的操作
+    pub guardian: Pubkey,
+    /// 操作入队后至少等待的 slot 数
+    pub delay_slots: u64,
+    /// 下一个操作的 ID（只增不减，用作 PendingAction 的 seed）
+    pub next_action_id: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl AdminTimelock {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // guardian (Pubkey)
+        8 + // delay_slots
+        8 + // next_action_id
+        1 // bump
+    }
+}
+
+/// 可排队的管理操作
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AdminAction {
+    /// 修改手续费率
+    SetFee { fee_config: FeeConfig },
+    /// 修改所有 item 的 weight，顺序与 pool.tokens 一致
+    SetWeights { weights: Vec<u64> },
+    /// 从主币资产 vault（asset_index 0 为 pool_vault，其余为额外主币 vault）紧急提取主币到指定 token 账户
+    EmergencyWithdraw { asset_index: u8, amount: u64, destination: Pubkey },
+    /// 移除质押类型（LP 必须已全部销毁）
+    RemoveToken { lp_mint: Pubkey },
+    /// 修改暂停状态
+    SetPause { paused: bool, withdraw_only: bool },
+    /// 从 pool authority 持有的账户提取误转入的其他代币（见 rescue_tokens）
+    RescueTokens { source: Pubkey, amount: u64, destination: Pubkey },
+    /// 修改紧急赎回配置
+    SetEmergencyConfig { exit_restricted: bool, penalty_bps: u16 },
+    /// 修改调用方策略
+    SetCallerPolicy { mode: u8, programs: Vec<Pubkey> },
+    /// 修改质押白名单
+    SetWhitelist { whitelist_root: [u8; 32], permissioned: bool },
+    /// 更换时间锁 guardian（启用时间锁后不能通过 configure_timelock 直接更换）
+    SetGuardian { guardian: Pubkey },
+}
+
+impl AdminAction {
+    /// 序列化后的大小
+    pub fn serialized_len(&self) -> usize {
+        1 + match self {
+            AdminAction::SetFee { .. } => 2 + 2 + 2 + 2,
+            AdminAction::SetWeights { weights } => 4 + 8 * weights.len(),
+            AdminAction::EmergencyWithdraw { .. } => 1 + 8 + 32,
+            AdminAction::RemoveToken { .. } => 32,
+            AdminAction::SetPause { .. } => 1 + 1,
+            AdminAction::RescueTokens { .. } => 32 + 8 + 32,
+            AdminAction::SetEmergencyConfig { .. } => 1 + 2,
+            AdminAction::SetCallerPolicy { programs, .. } => 1 + 4 + 32 * programs.len(),
+            AdminAction::SetWhitelist { .. } => 32 + 1,
+            AdminAction::SetGuardian { .. } => 32,
+        }
+    }
+
+    /// 校验操作参数
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            AdminAction::SetFee { fee_config } => fee_config.validate()?,
+            AdminAction::SetEmergencyConfig { penalty_bps, .. } => require!(
+                *penalty_bps as u64 <= BPS_DENOMINATOR,
+                ErrorCode::InvalidFeeConfig
+            ),
+            AdminAction::SetCallerPolicy { programs, .. } => require!(
+                programs.len() <= MAX_ALLOWED_CALLERS,
+                ErrorCode::InvalidCallerPolicy
+            ),
+            AdminAction::SetWhitelist { whitelist_root, permissioned } => require!(
+                !*permissioned || *whitelist_root != [0u8; 32],
+                ErrorCode::InvalidWhitelistRoot
+            ),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// 待执行的管理操作
+/// PDA seeds: ["pending_action", pool, action_id (le bytes)]
+#[account]
+#[derive(Debug)]
+pub struct PendingAction {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 操作 ID
+    pub action_id: u64,
+    /// 最早可执行的 slot
+    pub execute_after_slot: u64,
+    /// PDA bump
+    pub bump: u8,
+    /// 操作内容
+    pub action: AdminAction,
+}
+
+impl PendingAction {
+    /// 计算账户所需的空间大小
+    pub fn space(action: &AdminAction) -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        8 + // action_id
+        8 + // execute_after_slot
+        1 + // bump
+        action.serialized_len() // action
+    }
+
+    /// 验证时间锁已到期
+    pub fn verify_ready(&self, current_slot: u64) -> Result<()> {
+        require!(
+            current_slot >= self.execute_after_slot,
+            ErrorCode::TimelockNotElapsed
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialized_len_matches_borsh() {
+        let actions = [
+            AdminAction::SetEmergencyConfig { exit_restricted: true, penalty_bps: 500 },
+            AdminAction::SetCallerPolicy { mode: 2, programs: vec![Pubkey::new_unique(); 3] },
+            AdminAction::SetWhitelist { whitelist_root: [7u8; 32], permissioned: true },
+            AdminAction::EmergencyWithdraw { asset_index: 1, amount: 10, destination: Pubkey::new_unique() },
+            AdminAction::SetGuardian { guardian: Pubkey::new_unique() },
+        ];
+        for action in actions.iter() {
+            assert_eq!(action.try_to_vec().unwrap().len(), action.serialized_len());
+        }
+    }
+
+    #[test]
+    fn validate_rejects_invalid_admin_settings() {
+        assert!(AdminAction::SetEmergencyConfig { exit_restricted: false, penalty_bps: 10_001 }
+            .validate().is_err());
+        assert!(AdminAction::SetWhitelist { whitelist_root: [0u8; 32], permissioned: true }
+            .validate().is_err());
+        assert!(AdminAction::SetCallerPolicy { mode: 2, programs: vec![Pubkey::default(); 5] }
+            .validate().is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+/// 赎回冷却凭证
+/// PDA seeds: ["unstake_ticket", pool, owner, ticket_id (le bytes)]
+/// request_unstake 销毁 LP 时创建，记录应得主币数量；冷却期结束后 claim_unstake 领取并关闭
+#[account]
+#[derive(Debug)]
+pub struct UnstakeTicket {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 凭证所有者
+    pub owner: Pubkey,
+    /// 用户自选的凭证编号，同一用户可同时持有多张凭证
+    pub ticket_id: u64,
+    /// 主币资产索引 - 决定从哪个 vault 领取
+    pub asset_index: u8,
+    /// 可领取的主币数量（已扣除手续费）
+    pub amount: u64,
+    /// 可领取的时间戳（unix 秒）
+    pub unlock_timestamp: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl UnstakeTicket {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // owner (Pubkey)
+        8 + // ticket_id
+        1 + // asset_index
+        8 + // amount
+        8 + // unlock_timestamp
+        1 // bump
+    }
+
+    /// 验证冷却期已结束
+    pub fn verify_unlocked(&self, now: i64) -> Result<()> {
+        require!(now >= self.unlock_timestamp, ErrorCode::UnstakeTicketLocked);
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+/// 每个 pool 保留的 weight 修改记录数量（环形缓冲区）
+pub const WEIGHT_HISTORY_LEN: usize = 32;
+
+/// 一次 weight 修改记录
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct WeightChange {
+    /// 修改时间戳（unix 秒）
+    pub unix_timestamp: i64,
+    /// 质押类型索引
+    pub item_index: u16,
+    /// 修改前的 weight
+    pub old_weight: u64,
+    /// 修改后的 weight
+    pub new_weight: u64,
+    /// 发起修改的管理员
+    pub authority: Pubkey,
+}
+
+/// Pool 的 weight 修改历史，供用户和审计方在链上核对 weight 调整是否符合策略
+/// PDA seeds: ["weight_history", pool]
+/// 创建后 pool 的管理员 weight 修改必须传入该账户，超过 WEIGHT_HISTORY_LEN 后覆盖最旧的记录
+#[account]
+#[derive(Debug)]
+pub struct WeightHistory {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// 下一个写入位置
+    pub head: u8,
+    /// 已写入的记录数量（不超过 WEIGHT_HISTORY_LEN）
+    pub count: u8,
+    /// PDA bump
+    pub bump: u8,
+    /// 记录环形缓冲区
+    pub records: [WeightChange; WEIGHT_HISTORY_LEN],
+}
+
+impl WeightHistory {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        1 + // head
+        1 + // count
+        1 + // bump
+        (8 + 2 + 8 + 8 + 32) * WEIGHT_HISTORY_LEN // records
+    }
+
+    /// 获取最近一次记录
+    pub fn latest(&self) -> Option<&WeightChange> {
+        if self.count == 0 {
+            return None;
+        }
+        let index = (self.head as usize + WEIGHT_HISTORY_LEN - 1) % WEIGHT_HISTORY_LEN;
+        self.records.get(index)
+    }
+
+    /// 写入新的记录
+    pub fn push(&mut self, record: WeightChange) {
+        self.records[self.head as usize] = record;
+        self.head = ((self.head as usize + 1) % WEIGHT_HISTORY_LEN) as u8;
+        if (self.count as usize) < WEIGHT_HISTORY_LEN {
+            self.count += 1;
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use crate::error::ErrorCode;
+
+/// ed25519 原生验签程序 ID
+pub const ED25519_PROGRAM_ID: Pubkey =
+    pubkey!("Ed25519SigVerify111111111111111111111111111");
+
+/// ed25519 验签指令中 Ed25519SignatureOffsets 结构的起始位置（num_signatures + padding）
+const ED25519_OFFSETS_START: usize = 2;
+/// Ed25519SignatureOffsets 结构大小：7 个 u16
+const ED25519_OFFSETS_SIZE: usize = 14;
+/// 签名数据位于 ed25519 指令自身时，instruction_index 字段的取值
+const ED25519_SAME_INSTRUCTION: u16 = u16::MAX;
+
+/// 链下 weight oracle 配置
+/// PDA seeds: ["weight_oracle", pool]
+/// oracle 签名者只能提交 weight，不具备 admin 的其他权限
+#[account]
+#[derive(Debug)]
+pub struct WeightOracle {
+    /// 所属 Pool
+    pub pool: Pubkey,
+    /// oracle 的 ed25519 签名公钥
+    pub signer: Pubkey,
+    /// 最近一次使用的 nonce，新提交必须严格递增（防重放）
+    pub last_nonce: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl WeightOracle {
+    /// 计算账户所需的空间大小
+    pub fn space() -> usize {
+        8 + // discriminator
+        32 + // pool (Pubkey)
+        32 + // signer (Pubkey)
+        8 + // last_nonce
+        1 // bump
+    }
+
+    /// 构造 oracle 签名的消息
+    /// 格式: pool (32) ‖ nonce (u64 le) ‖ expiry (i64 le) ‖ weights (u64 le × N)
+    pub fn build_message(pool: &Pubkey, nonce: u64, expiry: i64, weights: &[u64]) -> Vec<u8> {
+        let mut message = Vec::with_capacity(32 + 8 + 8 + 8 * weights.len());
+        message.extend_from_slice(pool.as_ref());
+        message.extend_from_slice(&nonce.to_le_bytes());
+        message.extend_from_slice(&expiry.to_le_bytes());
+        for weight in weights {
+            message.extend_from_slice(&weight.to_le_bytes());
+        }
+        message
+    }
+
+    /// 验证并消费 nonce
+    pub fn consume_nonce(&mut self, nonce: u64) -> Result<()> {
+        require!(nonce > self.last_nonce, ErrorCode::InvalidOracleSignature);
+        self.last_nonce = nonce;
+        Ok(())
+    }
+
+    /// 通过指令内省验证：当前指令的前一条指令是 ed25519 验签指令，
+    /// 且验证的是 signer 对 message 的签名
+    pub fn verify_signature(&self, instructions_sysvar: &AccountInfo, message: &[u8]) -> Result<()> {
+        let current_index = load_current_index_checked(instructions_sysvar)?;
+        require!(current_index > 0, ErrorCode::InvalidOracleSignature);
+        let instruction = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+        require!(
+            instruction.program_id == ED25519_PROGRAM_ID,
+            ErrorCode::InvalidOracleSignature
+        );
+
+        let data = &instruction.data;
+        require!(
+            data.len() >= ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE && data[0] == 1,
+            ErrorCode::InvalidOracleSignature
+        );
+        let read_u16 = |offset: usize| -> u16 {
+            let start = ED25519_OFFSETS_START + offset * 2;
+            u16::from_le_bytes([data[start], data[start + 1]])
+        };
+        let signature_instruction_index = read_u16(1);
+        let public_key_offset = read_u16(2) as usize;
+        let public_key_instruction_index = read_u16(3);
+        let message_offset = read_u16(4) as usize;
+        let message_size = read_u16(5) as usize;
+        let message_instruction_index = read_u16(6);
+
+        // 签名、公钥、消息都必须位于 ed25519 指令自身
+        require!(
+            signature_instruction_index == ED25519_SAME_INSTRUCTION
+                && public_key_instruction_index == ED25519_SAME_INSTRUCTION
+                && message_instruction_index == ED25519_SAME_INSTRUCTION,
+            ErrorCode::InvalidOracleSignature
+        );
+
+        let public_key = data
+            .get(public_key_offset..public_key_offset + 32)
+            .ok_or(ErrorCode::InvalidOracleSignature)?;
+        require!(
+            public_key == self.signer.as_ref(),
+            ErrorCode::InvalidOracleSignature
+        );
+
+        let signed_message = data
+            .get(message_offset..message_offset + message_size)
+            .ok_or(ErrorCode::InvalidOracleSignature)?;
+        require!(signed_message == message, ErrorCode::InvalidOracleSignature);
+
+        Ok(())
+    }
+}
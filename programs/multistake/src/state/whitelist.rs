@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+/// 白名单证明的最大深度（可覆盖 2^24 个地址），限制验证的计算量
+pub const MAX_WHITELIST_PROOF_LEN: usize = 24;
+
+/// 计算白名单叶子节点：sha256(user)
+pub fn whitelist_leaf(user: &Pubkey) -> [u8; 32] {
+    hashv(&[user.as_ref()]).to_bytes()
+}
+
+/// 验证 merkle 证明
+/// 每一层按字节序对两个子节点排序后拼接哈希，证明中无需携带左右位置
+pub fn verify_merkle_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    if proof.len() > MAX_WHITELIST_PROOF_LEN {
+        return false;
+    }
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == *root
+}
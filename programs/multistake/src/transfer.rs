@@ -0,0 +1,84 @@
+//! 主币转账（兼容 Token 与 Token-2022）
+//!
+//! 统一使用 `transfer_checked`：主币 mint 启用 TransferHook 扩展时，
+//! 从 `additional_accounts`（通常为 remaining_accounts）中解析 hook 需要的额外账户并一起转发。
+//! 主币 mint 启用 TransferFee 扩展时，接收方实际到账 = amount - transfer_fee_amount(amount)。
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+};
+use crate::error::ErrorCode;
+
+/// 转移主币
+/// token_program: 主币所属的 token 程序（Token 或 Token-2022）
+/// additional_accounts: TransferHook 的额外账户（hook 程序、ExtraAccountMetaList 及其声明的账户）
+/// signer_seeds: 从 PDA 转出时的签名 seeds，用户签名时传空
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_main_token<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    additional_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        token_program.key,
+        from.clone(),
+        mint.clone(),
+        to.clone(),
+        authority.clone(),
+        additional_accounts,
+        amount,
+        decimals,
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// 计算转账 amount 主币时 TransferFee 扩展扣除的手续费
+/// 主币为 SPL Token 或 Token-2022 mint 未启用 TransferFee 扩展时返回 0
+pub fn transfer_fee_amount(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| ErrorCode::MathOverflow.into()),
+        Err(_) => Ok(0),
+    }
+}
+
+/// 计算转账 amount 主币后接收方实际到账的数量（扣除 TransferFee 扩展手续费）
+pub fn received_amount(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    amount
+        .checked_sub(transfer_fee_amount(mint, amount)?)
+        .ok_or_else(|| ErrorCode::MathOverflow.into())
+}
+
+/// 验证转入后 vault 余额的实际增量等于记账的到账数量
+/// 主币启用 TransferFee 扩展时 expected 应为扣除转账手续费后的数量
+pub fn verify_vault_inflow(balance_before: u64, balance_after: u64, expected: u64) -> Result<()> {
+    require!(
+        balance_after.checked_sub(balance_before) == Some(expected),
+        ErrorCode::VaultBalanceMismatch
+    );
+    Ok(())
+}
+
+/// 验证转出后 vault 余额的实际减少量等于记账的转出数量
+pub fn verify_vault_outflow(balance_before: u64, balance_after: u64, expected: u64) -> Result<()> {
+    require!(
+        balance_before.checked_sub(balance_after) == Some(expected),
+        ErrorCode::VaultBalanceMismatch
+    );
+    Ok(())
+}
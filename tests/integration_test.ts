@@ -0,0 +1,1320 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  getMint,
+  mintTo,
+  approve,
+  createMint,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import { setupPool, PoolSetup, deriveLpMint, fetchPool } from "./pool_setup";
+
+/**
+ * 指令级集成测试：覆盖 create_pool、add/remove token、weight 修改、stake/unstake 及失败路径
+ */
+describe("Integration Tests", () => {
+  let setup: PoolSetup;
+  let user: Keypair;
+  let lpMint1: PublicKey;
+  let lpMint2: PublicKey;
+  let userMainToken: PublicKey;
+  let userLp1: PublicKey;
+  let userLp2: PublicKey;
+
+  const STAKE_AMOUNT = new anchor.BN(100_000_000_000); // 100 tokens
+  const LP_AFTER_FEE = new anchor.BN(99_700_000_000); // 扣除 0.3% 手续费
+
+  async function addToken(): Promise<PublicKey> {
+    const { incrementCount } = await fetchPool(setup.program, setup.pool);
+    const lpMint = deriveLpMint(setup.program.programId, setup.pool, incrementCount);
+    await setup.program.methods
+      .addTokenToPool()
+      .accounts({
+        pool: setup.pool,
+        poolAuthority: setup.poolAuthority,
+        lpMint,
+        admin: setup.admin.publicKey,
+        payer: setup.payer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([setup.admin, setup.payer])
+      .rpc();
+    return lpMint;
+  }
+
+  function stakeMethod(
+    itemIndex: number,
+    amount: anchor.BN,
+    lpMint: PublicKey,
+    userLp: PublicKey,
+    protocolTreasuryToken: PublicKey | null = null
+  ) {
+    return setup.program.methods
+      .stake(itemIndex, amount, null, null, [])
+      .accountsPartial({
+        pool: setup.pool,
+        poolAuthority: setup.poolAuthority,
+        poolVault: setup.poolVault,
+        feeVault: setup.feeVault,
+        protocolTreasuryToken,
+        mainTokenMint: setup.mainTokenMint,
+        lpMint,
+        userMainToken,
+        userLpToken: userLp,
+        user: user.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mainTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user]);
+  }
+
+  function stake(itemIndex: number, amount: anchor.BN, lpMint: PublicKey, userLp: PublicKey) {
+    return stakeMethod(itemIndex, amount, lpMint, userLp).rpc();
+  }
+
+  function unstakeMethod(
+    itemIndex: number,
+    lpAmount: anchor.BN,
+    lpMint: PublicKey,
+    userLp: PublicKey,
+    minRedeemAmount: anchor.BN = new anchor.BN(0)
+  ) {
+    return setup.program.methods
+      .unstake(itemIndex, lpAmount, minRedeemAmount, null)
+      .accountsPartial({
+        pool: setup.pool,
+        poolAuthority: setup.poolAuthority,
+        poolVault: setup.poolVault,
+        feeVault: setup.feeVault,
+        mainTokenMint: setup.mainTokenMint,
+        lpMint,
+        userLpToken: userLp,
+        userMainToken,
+        user: user.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mainTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user]);
+  }
+
+  function unstake(
+    itemIndex: number,
+    lpAmount: anchor.BN,
+    lpMint: PublicKey,
+    userLp: PublicKey,
+    minRedeemAmount: anchor.BN = new anchor.BN(0)
+  ) {
+    return unstakeMethod(itemIndex, lpAmount, lpMint, userLp, minRedeemAmount).rpc();
+  }
+
+  // 从模拟日志中解析本程序 return data 中第 index 个小端 u64
+  function returnedU64(logs: readonly string[], index = 0): anchor.BN {
+    const prefix = `Program return: ${setup.program.programId.toBase58()} `;
+    const line = logs.find((log) => log.startsWith(prefix));
+    assert.isDefined(line, "No return data in simulation logs");
+    const data = Buffer.from(line.slice(prefix.length), "base64");
+    return new anchor.BN(data.subarray(index * 8, index * 8 + 8), "le");
+  }
+
+  function donate(amount: anchor.BN) {
+    return setup.program.methods
+      .donateToPool(0, amount)
+      .accountsPartial({
+        pool: setup.pool,
+        poolVault: setup.poolVault,
+        mainTokenMint: setup.mainTokenMint,
+        donorToken: userMainToken,
+        donor: user.publicKey,
+        mainTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+  }
+
+  function modifyWeights(weights: anchor.BN[], lpMints: PublicKey[]) {
+    return setup.program.methods
+      .modifyTokenWeight(weights)
+      .accounts({
+        pool: setup.pool,
+        admin: setup.admin.publicKey,
+      })
+      .remainingAccounts(
+        lpMints.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+      )
+      .signers([setup.admin])
+      .rpc();
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      assert.fail(`Should have failed with ${code}`);
+    } catch (error) {
+      assert.include(error.toString(), code);
+    }
+  }
+
+  before(async () => {
+    setup = await setupPool();
+    user = Keypair.generate();
+
+    await setup.provider.connection.confirmTransaction(
+      await setup.provider.connection.requestAirdrop(
+        user.publicKey,
+        10 * anchor.web3.LAMPORTS_PER_SOL
+      )
+    );
+
+    userMainToken = (
+      await getOrCreateAssociatedTokenAccount(
+        setup.provider.connection,
+        setup.payer,
+        setup.mainTokenMint,
+        user.publicKey
+      )
+    ).address;
+    await mintTo(
+      setup.provider.connection,
+      setup.payer,
+      setup.mainTokenMint,
+      userMainToken,
+      setup.admin,
+      1000_000_000_000 // 1000 tokens
+    );
+  });
+
+  describe("create_pool", () => {
+    it("Initializes pool state", async () => {
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.admin.toBase58(), setup.admin.publicKey.toBase58());
+      assert.equal(pool.poolVault.toBase58(), setup.poolVault.toBase58());
+      assert.equal(pool.poolMint.toBase58(), setup.mainTokenMint.toBase58());
+      assert.equal(pool.feeConfig.stakeFeeBps, 30);
+      assert.equal(pool.feeConfig.unstakeFeeBps, 30);
+      assert.equal(pool.feeConfig.swapFeeBps, 30);
+      assert.equal(pool.feeConfig.protocolFeeBps, 10_000);
+      assert.equal(pool.legacyFeeDenominator.toNumber(), 0);
+      assert.equal(pool.tokenCount, 0);
+      assert.equal(pool.authoritySeedVersion, 0);
+      assert.equal(pool.mainTokenDecimals, 9);
+
+      const vault = await getAccount(setup.provider.connection, setup.poolVault);
+      assert.equal(vault.owner.toBase58(), setup.poolAuthority.toBase58());
+      assert.equal(vault.amount.toString(), "0");
+
+      assert.equal(pool.feeVault.toBase58(), setup.feeVault.toBase58());
+      const feeVault = await getAccount(setup.provider.connection, setup.feeVault);
+      assert.equal(feeVault.owner.toBase58(), setup.poolAuthority.toBase58());
+    });
+  });
+
+  describe("add / remove token", () => {
+    it("Adds staking types with pool authority as LP mint authority", async () => {
+      lpMint1 = await addToken();
+      lpMint2 = await addToken();
+
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.tokenCount, 2);
+      assert.equal(pool.tokens[0].mintAccount.toBase58(), lpMint1.toBase58());
+      assert.equal(pool.tokens[1].mintAccount.toBase58(), lpMint2.toBase58());
+      assert.equal(pool.tokens[0].weight.toNumber(), 100_000_000);
+
+      const mint = await getMint(setup.provider.connection, lpMint1);
+      assert.equal(mint.mintAuthority.toBase58(), setup.poolAuthority.toBase58());
+      assert.equal(mint.supply.toString(), "0");
+
+      userLp1 = (
+        await getOrCreateAssociatedTokenAccount(
+          setup.provider.connection,
+          setup.payer,
+          lpMint1,
+          user.publicKey
+        )
+      ).address;
+      userLp2 = (
+        await getOrCreateAssociatedTokenAccount(
+          setup.provider.connection,
+          setup.payer,
+          lpMint2,
+          user.publicKey
+        )
+      ).address;
+    });
+
+    it("Rejects removing a staking type that is not in the pool", async () => {
+      await expectError(
+        setup.program.methods
+          .removeTokenFromPool()
+          .accounts({
+            pool: setup.pool,
+            poolAuthority: setup.poolAuthority,
+            lpMint: setup.mainTokenMint,
+            admin: setup.admin.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([setup.admin])
+          .rpc(),
+        "InvalidTokenMint"
+      );
+    });
+
+    it("Stores item name and symbol on-chain", async () => {
+      const setItemMetadata = (name: string, symbol: string) =>
+        setup.program.methods
+          .setItemMetadata(1, name, symbol)
+          .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+          .signers([setup.admin])
+          .rpc();
+      const decode = (bytes: number[]) =>
+        Buffer.from(bytes).toString("utf8").replace(/\0+$/, "");
+
+      await setItemMetadata("Jito Staked SOL", "jitoSOL");
+      const item = (await fetchPool(setup.program, setup.pool)).tokens[1];
+      assert.equal(decode(item.name), "Jito Staked SOL");
+      assert.equal(decode(item.symbol), "jitoSOL");
+
+      await expectError(setItemMetadata("a name longer than 16", "SYM"), "InvalidItemMetadata");
+      await expectError(setItemMetadata("name", "TOOLONGSYM"), "InvalidItemMetadata");
+    });
+
+    it("Records the layout version and rejects migrating a current pool", async () => {
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.version, 2);
+
+      await expectError(
+        setup.program.methods
+          .migratePoolV2()
+          .accounts({
+            pool: setup.pool,
+            admin: setup.admin.publicKey,
+            poolVault: setup.poolVault,
+            mainTokenMint: setup.mainTokenMint,
+          })
+          .signers([setup.admin])
+          .rpc(),
+        "WrongAccountVersion"
+      );
+    });
+  });
+
+  describe("weights", () => {
+    it("Modifies weights", async () => {
+      await modifyWeights(
+        [new anchor.BN(200_000_000), new anchor.BN(50_000_000)],
+        [lpMint1, lpMint2]
+      );
+
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.tokens[0].weight.toNumber(), 200_000_000);
+      assert.equal(pool.tokens[1].weight.toNumber(), 50_000_000);
+    });
+
+    it("Rejects mismatched weights and accounts", async () => {
+      await expectError(
+        modifyWeights([new anchor.BN(1), new anchor.BN(2)], [lpMint1]),
+        "InvalidTokenCount"
+      );
+    });
+
+    it("Rejects weights outside the allowed range", async () => {
+      await expectError(modifyWeights([new anchor.BN(1)], [lpMint1]), "WeightOutOfBounds");
+    });
+
+    it("Rescales all weights proportionally", async () => {
+      const rescale = (numerator: number, denominator: number) =>
+        setup.program.methods
+          .rescaleWeights(new anchor.BN(numerator), new anchor.BN(denominator))
+          .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+          .signers([setup.admin])
+          .rpc();
+
+      await expectError(rescale(1, 3), "InvalidWeightRescale");
+      await rescale(2, 1);
+      let pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.tokens[0].weight.toNumber(), 400_000_000);
+      assert.equal(pool.tokens[1].weight.toNumber(), 100_000_000);
+
+      await rescale(1, 2);
+      pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.tokens[0].weight.toNumber(), 200_000_000);
+      assert.equal(pool.tokens[1].weight.toNumber(), 50_000_000);
+    });
+
+    it("Enforces the weight update cooldown", async () => {
+      await setup.program.methods
+        .setWeightCooldown(new anchor.BN(1_000_000))
+        .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+        .signers([setup.admin])
+        .rpc();
+
+      await expectError(
+        modifyWeights([new anchor.BN(100_000_000)], [lpMint1]),
+        "WeightUpdateCooldown"
+      );
+
+      await setup.program.methods
+        .setWeightCooldown(new anchor.BN(0))
+        .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+        .signers([setup.admin])
+        .rpc();
+    });
+
+    it("Rejects weight changes above max_weight_change_bps", async () => {
+      await setup.program.methods
+        .setMaxWeightChange(new anchor.BN(5_000))
+        .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+        .signers([setup.admin])
+        .rpc();
+
+      // 200_000_000 → 400_000_000 为 +100%，超过 50% 上限
+      await expectError(
+        modifyWeights([new anchor.BN(400_000_000)], [lpMint1]),
+        "WeightChangeTooLarge"
+      );
+
+      // 上限只能收紧
+      await expectError(
+        setup.program.methods
+          .setMaxWeightChange(new anchor.BN(10_000))
+          .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+          .signers([setup.admin])
+          .rpc(),
+        "InvalidWeightChangeLimit"
+      );
+    });
+
+    it("Lets the weight operator modify weights but nothing else", async () => {
+      const operator = Keypair.generate();
+      const setWeightOperator = (key: PublicKey) =>
+        setup.program.methods
+          .setWeightOperator(key)
+          .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+          .signers([setup.admin])
+          .rpc();
+      const operatorModify = () =>
+        setup.program.methods
+          .modifyTokenWeight([new anchor.BN(200_000_000)])
+          .accounts({ pool: setup.pool, admin: operator.publicKey })
+          .remainingAccounts([{ pubkey: lpMint1, isSigner: false, isWritable: false }])
+          .signers([operator])
+          .rpc();
+
+      await setWeightOperator(operator.publicKey);
+      await operatorModify();
+
+      // 操作员不能执行其他管理操作
+      await expectError(
+        setup.program.methods
+          .setWeightCooldown(new anchor.BN(0))
+          .accounts({ pool: setup.pool, admin: operator.publicKey })
+          .signers([operator])
+          .rpc(),
+        "InvalidAdmin"
+      );
+
+      await setWeightOperator(PublicKey.default);
+      await expectError(operatorModify(), "InvalidAdmin");
+    });
+
+    it("Records admin weight changes in the weight history", async () => {
+      const [weightHistory] = PublicKey.findProgramAddressSync(
+        [Buffer.from("weight_history"), setup.pool.toBuffer()],
+        setup.program.programId
+      );
+      await setup.program.methods
+        .initWeightHistory()
+        .accounts({
+          pool: setup.pool,
+          admin: setup.admin.publicKey,
+          payer: setup.payer.publicKey,
+        })
+        .signers([setup.admin, setup.payer])
+        .rpc();
+
+      // 启用后不传入历史账户的修改被拒绝
+      await expectError(
+        modifyWeights([new anchor.BN(200_000_000)], [lpMint1]),
+        "WeightHistoryRequired"
+      );
+
+      await setup.program.methods
+        .modifyTokenWeight([new anchor.BN(200_000_000)])
+        .accountsPartial({
+          pool: setup.pool,
+          admin: setup.admin.publicKey,
+          weightHistory,
+        })
+        .remainingAccounts([{ pubkey: lpMint1, isSigner: false, isWritable: false }])
+        .signers([setup.admin])
+        .rpc();
+
+      const history = await setup.program.account.weightHistory.fetch(weightHistory);
+      assert.equal(history.count, 1);
+      assert.equal(history.records[0].itemIndex, 0);
+      assert.equal(history.records[0].oldWeight.toNumber(), 200_000_000);
+      assert.equal(history.records[0].newWeight.toNumber(), 200_000_000);
+      assert.equal(history.records[0].authority.toBase58(), setup.admin.publicKey.toBase58());
+    });
+
+    it("Sets and cancels a weight decay schedule", async () => {
+      const setWeightSchedule = (target: number, startTs: number, endTs: number) =>
+        setup.program.methods
+          .setWeightSchedule(0, new anchor.BN(target), new anchor.BN(startTs), new anchor.BN(endTs))
+          .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+          .signers([setup.admin])
+          .rpc();
+      const now = Math.floor(Date.now() / 1000);
+
+      await expectError(setWeightSchedule(50_000_000, now + 100, now + 100), "InvalidWeightSchedule");
+      await expectError(setWeightSchedule(0, now + 100, now + 200), "WeightOutOfBounds");
+
+      await setWeightSchedule(50_000_000, now + 3_600, now + 7_200);
+      let item = (await fetchPool(setup.program, setup.pool)).tokens[0];
+      assert.equal(item.weightDecay.startWeight.toNumber(), item.weight.toNumber());
+      assert.equal(item.weightDecay.target.toNumber(), 50_000_000);
+      assert.equal(item.weightDecay.endTs.toNumber(), now + 7_200);
+
+      await setWeightSchedule(0, 0, 0);
+      item = (await fetchPool(setup.program, setup.pool)).tokens[0];
+      assert.equal(item.weightDecay.endTs.toNumber(), 0);
+    });
+
+    it("Rejects an unknown rate source kind or an invalid source account", async () => {
+      const setItemRateSource = (kind: number, source: PublicKey) =>
+        setup.program.methods
+          .setItemRateSource(kind, new anchor.BN(1), 100)
+          .accountsPartial({
+            pool: setup.pool,
+            lpMint: lpMint1,
+            source,
+            admin: setup.admin.publicKey,
+            payer: setup.payer.publicKey,
+          })
+          .signers([setup.admin, setup.payer])
+          .rpc();
+
+      await expectError(setItemRateSource(2, setup.poolVault), "InvalidOracleConfig");
+      // 不是 SPL stake pool 程序拥有的账户
+      await expectError(setItemRateSource(1, setup.poolVault), "InvalidRateSource");
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.tokens[0].rateWeightEnabled, 0);
+    });
+  });
+
+  describe("fee config", () => {
+    const feeConfig = (feeBps: number, protocolFeeBps = 10_000) => ({
+      stakeFeeBps: feeBps,
+      unstakeFeeBps: feeBps,
+      swapFeeBps: feeBps,
+      protocolFeeBps,
+    });
+
+    it("Rejects a fee above 10%", async () => {
+      await expectError(
+        setup.program.methods
+          .setFee({ ...feeConfig(30), unstakeFeeBps: 1_010 })
+          .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+          .signers([setup.admin])
+          .rpc(),
+        "FeeTooHigh"
+      );
+    });
+
+    it("Rejects a protocol share above 100%", async () => {
+      await expectError(
+        setup.program.methods
+          .setFee(feeConfig(30, 10_001))
+          .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+          .signers([setup.admin])
+          .rpc(),
+        "InvalidFeeConfig"
+      );
+    });
+
+    it("Rejects migrating a pool that already uses FeeConfig", async () => {
+      await expectError(
+        setup.program.methods.migrateFeeConfig().accounts({ pool: setup.pool }).rpc(),
+        "InvalidFeeConfig"
+      );
+    });
+
+    it("Queues fee increases behind the delay", async () => {
+      await setup.program.methods
+        .setFeeUpdateDelay(new anchor.BN(86_400))
+        .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+        .signers([setup.admin])
+        .rpc();
+      await setup.program.methods
+        .setFee(feeConfig(50))
+        .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+        .signers([setup.admin])
+        .rpc();
+
+      let pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.feeConfig.stakeFeeBps, 30);
+      assert.equal(pool.pendingFeeConfig.stakeFeeBps, 50);
+      await expectError(
+        setup.program.methods.applyFee().accounts({ pool: setup.pool }).rpc(),
+        "TimelockNotElapsed"
+      );
+
+      // 不上调费率时立即生效，并清除待生效费率
+      await setup.program.methods
+        .setFee(feeConfig(30))
+        .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+        .signers([setup.admin])
+        .rpc();
+      pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.feeConfig.stakeFeeBps, 30);
+      assert.equal(pool.pendingFeeEta.toNumber(), 0);
+    });
+  });
+
+  describe("stake / unstake", () => {
+    it("Rejects zero stake", async () => {
+      await expectError(
+        stake(0, new anchor.BN(0), lpMint1, userLp1),
+        "InvalidTokenCount"
+      );
+    });
+
+    it("Rejects stakes below the pool's minimum stake amount", async () => {
+      const setMinStakeAmount = (amount: anchor.BN) =>
+        setup.program.methods
+          .setMinStakeAmount(amount)
+          .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+          .signers([setup.admin])
+          .rpc();
+
+      await setMinStakeAmount(STAKE_AMOUNT.addn(1));
+      await expectError(stake(0, STAKE_AMOUNT, lpMint1, userLp1), "DustAmount");
+      await setMinStakeAmount(new anchor.BN(0));
+    });
+
+    it("Rejects LP mint that does not match the item", async () => {
+      await expectError(
+        stake(0, STAKE_AMOUNT, lpMint2, userLp2),
+        "InvalidTokenMint"
+      );
+    });
+
+    it("Rejects a user LP account of a different mint", async () => {
+      await expectError(
+        stake(0, STAKE_AMOUNT, lpMint1, userLp2),
+        "ConstraintTokenMint"
+      );
+    });
+
+    it("Rejects out-of-range item index", async () => {
+      await expectError(
+        stake(7, STAKE_AMOUNT, lpMint1, userLp1),
+        "InvalidTokenIndex"
+      );
+    });
+
+    it("Stakes into both items", async () => {
+      await stake(0, STAKE_AMOUNT, lpMint1, userLp1);
+      await stake(1, STAKE_AMOUNT, lpMint2, userLp2);
+
+      const lp1 = await getAccount(setup.provider.connection, userLp1);
+      const vault = await getAccount(setup.provider.connection, setup.poolVault);
+      assert.equal(lp1.amount.toString(), LP_AFTER_FEE.toString());
+      // 0.3% 手续费进入 fee_vault
+      assert.equal(vault.amount.toString(), "199400000000");
+      const feeVault = await getAccount(setup.provider.connection, setup.feeVault);
+      assert.equal(feeVault.amount.toString(), "600000000");
+
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.tokens[0].mintAmount.toString(), LP_AFTER_FEE.toString());
+      assert.equal(pool.accumulatedFees.toString(), "600000000");
+    });
+
+    it("Returns the minted LP and redeemed amounts as return data", async () => {
+      const staked = await stakeMethod(0, STAKE_AMOUNT, lpMint1, userLp1).simulate();
+      assert.equal(returnedU64(staked.raw).toString(), LP_AFTER_FEE.toString());
+
+      const lpAmount = new anchor.BN(1_000_000_000);
+      const unstaked = await unstakeMethod(0, lpAmount, lpMint1, userLp1).simulate();
+      const quoted = await setup.program.methods
+        .quoteUnstake(0, lpAmount)
+        .accountsPartial({ pool: setup.pool, poolVault: setup.poolVault, itemOracle: null, priceFeed: null })
+        .simulate();
+      // UnstakePreview 的第三个字段为 amount_after_fee
+      assert.equal(returnedU64(unstaked.raw).toString(), returnedU64(quoted.raw, 2).toString());
+    });
+
+    it("Rejects staking into an inactive item", async () => {
+      const setActive = (active: boolean) =>
+        setup.program.methods
+          .setItemActive(0, active)
+          .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+          .signers([setup.admin])
+          .rpc();
+
+      await setActive(false);
+      await expectError(stake(0, STAKE_AMOUNT, lpMint1, userLp1), "ItemInactive");
+      await setActive(true);
+    });
+
+    it("Rejects stakers outside the whitelist of a permissioned pool", async () => {
+      const setWhitelist = (root: number[], permissioned: boolean) =>
+        setup.program.methods
+          .setWhitelist(root, permissioned)
+          .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+          .signers([setup.admin])
+          .rpc();
+
+      // 单叶子的白名单：根即为 sha256(其他地址)
+      const otherLeaf = createHash("sha256").update(Keypair.generate().publicKey.toBuffer()).digest();
+      await expectError(setWhitelist(new Array(32).fill(0), true), "InvalidWhitelistRoot");
+      await setWhitelist(Array.from(otherLeaf), true);
+      await expectError(stake(0, STAKE_AMOUNT, lpMint1, userLp1), "NotWhitelisted");
+      await setWhitelist(new Array(32).fill(0), false);
+    });
+
+    it("Rejects removing a staking type with outstanding LP", async () => {
+      await expectError(
+        setup.program.methods
+          .removeTokenFromPool()
+          .accounts({
+            pool: setup.pool,
+            poolAuthority: setup.poolAuthority,
+            lpMint: lpMint2,
+            admin: setup.admin.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([setup.admin])
+          .rpc(),
+        "InsufficientTokenAmount"
+      );
+    });
+
+    it("Rejects unstaking more LP than held", async () => {
+      await expectError(
+        unstake(0, LP_AFTER_FEE.addn(1), lpMint1, userLp1),
+        "insufficient funds"
+      );
+    });
+
+    it("Rejects unstake below the minimum redeem amount", async () => {
+      await expectError(
+        unstake(0, LP_AFTER_FEE, lpMint1, userLp1, new anchor.BN("1000000000000")),
+        "SlippageExceeded"
+      );
+    });
+
+    it("Rejects restake below the minimum LP out", async () => {
+      await expectError(
+        setup.program.methods
+          .restake(0, 1, new anchor.BN(1_000), new anchor.BN("1000000000000"))
+          .accountsPartial({
+            pool: setup.pool,
+            poolAuthority: setup.poolAuthority,
+            poolVault: setup.poolVault,
+            fromLpMint: lpMint1,
+            toLpMint: lpMint2,
+            userFromLpToken: userLp1,
+            userToLpToken: userLp2,
+            user: user.publicKey,
+            itemOracle: null,
+            priceFeed: null,
+            instructionsSysvar: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([user])
+          .rpc(),
+        "SlippageExceeded"
+      );
+    });
+
+    it("Counts donations into the tracked vault balance", async () => {
+      const donation = new anchor.BN(1_000_000_000);
+      const vaultBefore = await getAccount(setup.provider.connection, setup.poolVault);
+      const poolBefore = await fetchPool(setup.program, setup.pool);
+      await donate(donation);
+      const vaultAfter = await getAccount(setup.provider.connection, setup.poolVault);
+      const poolAfter = await fetchPool(setup.program, setup.pool);
+
+      assert.equal((vaultAfter.amount - vaultBefore.amount).toString(), donation.toString());
+      assert.equal(
+        poolAfter.trackedVaultBalance.sub(poolBefore.trackedVaultBalance).toString(),
+        donation.toString()
+      );
+    });
+
+    it("Unstakes the heavier item for more than its share of principal", async () => {
+      const mainBefore = await getAccount(setup.provider.connection, userMainToken);
+      const feeBefore = await getAccount(setup.provider.connection, setup.feeVault);
+      await unstake(0, LP_AFTER_FEE, lpMint1, userLp1);
+      const mainAfter = await getAccount(setup.provider.connection, userMainToken);
+      const feeAfter = await getAccount(setup.provider.connection, setup.feeVault);
+
+      const received = mainAfter.amount - mainBefore.amount;
+      // weight 2x vs 0.5x：item 0 占 vault 的 80%，扣除 0.3% 手续费
+      assert.isTrue(received > BigInt(STAKE_AMOUNT.toString()));
+      // unstake 手续费全部归协议，转入 fee_vault：fee = ceil((received + fee) × 30 / 10000)
+      const unstakeFee = feeAfter.amount - feeBefore.amount;
+      assert.equal(((received + unstakeFee) * 30n + 9_999n) / 10_000n, unstakeFee);
+
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.tokens[0].mintAmount.toString(), "0");
+    });
+
+    it("Unstakes the remaining item and removes it", async () => {
+      await unstake(1, LP_AFTER_FEE, lpMint2, userLp2);
+
+      await setup.program.methods
+        .removeTokenFromPool()
+        .accounts({
+          pool: setup.pool,
+          poolAuthority: setup.poolAuthority,
+          lpMint: lpMint2,
+          admin: setup.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([setup.admin])
+        .rpc();
+
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.tokenCount, 1);
+      // 移除后 LP mint 不能再被铸造
+      const mint = await getMint(setup.provider.connection, lpMint2);
+      assert.isNull(mint.mintAuthority);
+    });
+
+    it("Rejects donations once no LP is outstanding", async () => {
+      await expectError(donate(new anchor.BN(1_000_000_000)), "NoOutstandingLp");
+    });
+
+    it("Locks a stake with a tier boost until expiry", async () => {
+      const DAY = 86_400;
+      const [lockedPosition] = PublicKey.findProgramAddressSync(
+        [Buffer.from("locked_position"), setup.pool.toBuffer(), user.publicKey.toBuffer(), Buffer.from([0, 0])],
+        setup.program.programId
+      );
+      const stakeLocked = (lockSeconds: number) =>
+        setup.program.methods
+          .stakeLocked(0, STAKE_AMOUNT, new anchor.BN(lockSeconds), [])
+          .accountsPartial({
+            pool: setup.pool,
+            poolAuthority: setup.poolAuthority,
+            poolVault: setup.poolVault,
+            feeVault: setup.feeVault,
+            mainTokenMint: setup.mainTokenMint,
+            lpMint: lpMint1,
+            userMainToken,
+            user: user.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            mainTokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([user])
+          .rpc();
+
+      await expectError(stakeLocked(DAY), "InvalidLockDuration");
+      await stakeLocked(30 * DAY);
+
+      // 该主币下已无 LP，按 1:1 折算；30 天档位加成 10%
+      const position = await setup.program.account.lockedPosition.fetch(lockedPosition);
+      assert.equal(position.lpAmount.toString(), LP_AFTER_FEE.toString());
+      assert.equal(position.boostAmount.toString(), LP_AFTER_FEE.divn(10).toString());
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(
+        pool.tokens[0].mintAmount.toString(),
+        position.lpAmount.add(position.boostAmount).toString()
+      );
+
+      await expectError(
+        setup.program.methods
+          .unstakeLocked(0, new anchor.BN(0))
+          .accountsPartial({
+            pool: setup.pool,
+            poolAuthority: setup.poolAuthority,
+            poolVault: setup.poolVault,
+            feeVault: setup.feeVault,
+            mainTokenMint: setup.mainTokenMint,
+            userMainToken,
+            user: user.publicKey,
+            mainTokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([user])
+          .rpc(),
+        "PositionLocked"
+      );
+    });
+
+    it("Lets a delegate stake on the owner's behalf within its permissions", async () => {
+      const delegate = Keypair.generate();
+      const [delegation] = PublicKey.findProgramAddressSync(
+        [Buffer.from("delegation"), setup.pool.toBuffer(), user.publicKey.toBuffer()],
+        setup.program.programId
+      );
+      // 只授予 stake 权限（DELEGATE_STAKE = 1）
+      await setup.program.methods
+        .setDelegate(delegate.publicKey, 1)
+        .accountsPartial({ pool: setup.pool, delegation, owner: user.publicKey })
+        .signers([user])
+        .rpc();
+      await approve(
+        setup.provider.connection,
+        setup.payer,
+        userMainToken,
+        delegation,
+        user,
+        BigInt(STAKE_AMOUNT.toString())
+      );
+
+      const lpBefore = await getAccount(setup.provider.connection, userLp1);
+      await setup.program.methods
+        .stake(0, STAKE_AMOUNT, null, null, [])
+        .accountsPartial({
+          pool: setup.pool,
+          poolAuthority: setup.poolAuthority,
+          poolVault: setup.poolVault,
+          feeVault: setup.feeVault,
+          mainTokenMint: setup.mainTokenMint,
+          lpMint: lpMint1,
+          userMainToken,
+          userLpToken: userLp1,
+          user: user.publicKey,
+          delegate: delegate.publicKey,
+          delegation,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          mainTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([delegate])
+        .rpc();
+      const lpAfter = await getAccount(setup.provider.connection, userLp1);
+      assert.isTrue(lpAfter.amount > lpBefore.amount);
+
+      await expectError(
+        setup.program.methods
+          .unstake(0, new anchor.BN(1), new anchor.BN(0), null)
+          .accountsPartial({
+            pool: setup.pool,
+            poolAuthority: setup.poolAuthority,
+            poolVault: setup.poolVault,
+            feeVault: setup.feeVault,
+            mainTokenMint: setup.mainTokenMint,
+            lpMint: lpMint1,
+            userLpToken: userLp1,
+            userMainToken,
+            user: user.publicKey,
+            delegate: delegate.publicKey,
+            delegation,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            mainTokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([delegate])
+          .rpc(),
+        "DelegateNotAuthorized"
+      );
+
+      await setup.program.methods
+        .revokeDelegate()
+        .accountsPartial({ pool: setup.pool, delegation, owner: user.publicKey })
+        .signers([user])
+        .rpc();
+    });
+  });
+
+  describe("program config", () => {
+    function initializeConfig(authority: Keypair, treasury: PublicKey, shareBps: number) {
+      const [programData] = PublicKey.findProgramAddressSync(
+        [setup.program.programId.toBuffer()],
+        new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+      );
+      return setup.program.methods
+        .initializeConfig(treasury, shareBps)
+        .accountsPartial({
+          program: setup.program.programId,
+          programData,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    }
+
+    function updateConfig(treasury: PublicKey, shareBps: number, authority?: Keypair) {
+      const builder = setup.program.methods
+        .updateConfig(treasury, shareBps, null)
+        .accountsPartial({
+          authority: authority ? authority.publicKey : setup.provider.publicKey,
+        });
+      return authority ? builder.signers([authority]).rpc() : builder.rpc();
+    }
+
+    it("Rejects initialization by an account other than the upgrade authority", async () => {
+      await expectError(
+        initializeConfig(user, setup.admin.publicKey, 5_000),
+        "InvalidConfigAuthority"
+      );
+    });
+
+    it("Skims the protocol share of stake fees to the protocol treasury", async () => {
+      const wallet = (setup.provider.wallet as anchor.Wallet).payer;
+      await initializeConfig(wallet, setup.admin.publicKey, 5_000);
+      await expectError(
+        updateConfig(setup.admin.publicKey, 5_000, user),
+        "InvalidConfigAuthority"
+      );
+
+      const treasuryToken = (
+        await getOrCreateAssociatedTokenAccount(
+          setup.provider.connection,
+          setup.payer,
+          setup.mainTokenMint,
+          setup.admin.publicKey
+        )
+      ).address;
+      await expectError(stake(0, STAKE_AMOUNT, lpMint1, userLp1), "InvalidProtocolTreasury");
+
+      const feeBefore = (await getAccount(setup.provider.connection, setup.feeVault)).amount;
+      await stakeMethod(0, STAKE_AMOUNT, lpMint1, userLp1, treasuryToken).rpc();
+      const feeAfter = (await getAccount(setup.provider.connection, setup.feeVault)).amount;
+      const treasury = await getAccount(setup.provider.connection, treasuryToken);
+
+      // 0.3% 手续费 300000000，其中 50% 归协议金库
+      assert.equal(treasury.amount.toString(), "150000000");
+      assert.equal((feeAfter - feeBefore).toString(), "150000000");
+
+      await updateConfig(setup.admin.publicKey, 0);
+    });
+  });
+
+  describe("fee vault", () => {
+    function withdrawFees(amount: anchor.BN, admin: Keypair) {
+      return setup.program.methods
+        .withdrawFees(amount)
+        .accountsPartial({
+          pool: setup.pool,
+          poolAuthority: setup.poolAuthority,
+          feeVault: setup.feeVault,
+          mainTokenMint: setup.mainTokenMint,
+          destination: userMainToken,
+          admin: admin.publicKey,
+          mainTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([admin])
+        .rpc();
+    }
+
+    it("Rejects fee withdrawal by non-admin", async () => {
+      await expectError(withdrawFees(new anchor.BN(1), user), "InvalidAdmin");
+    });
+
+    it("Withdraws accumulated fees to the admin's destination", async () => {
+      // stake 手续费 600000000 加上两次 unstake 的手续费
+      const feeBalance = (await getAccount(setup.provider.connection, setup.feeVault)).amount;
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.accumulatedFees.toString(), feeBalance.toString());
+      assert.isTrue(feeBalance > 600_000_000n);
+
+      const mainBefore = await getAccount(setup.provider.connection, userMainToken);
+      await withdrawFees(new anchor.BN(feeBalance.toString()), setup.admin);
+      const mainAfter = await getAccount(setup.provider.connection, userMainToken);
+
+      assert.equal((mainAfter.amount - mainBefore.amount).toString(), feeBalance.toString());
+      const feeVault = await getAccount(setup.provider.connection, setup.feeVault);
+      assert.equal(feeVault.amount.toString(), "0");
+    });
+  });
+
+  describe("rescue tokens", () => {
+    function rescue(source: PublicKey, mint: PublicKey, destination: PublicKey, amount: anchor.BN) {
+      return setup.program.methods
+        .rescueTokens(amount)
+        .accountsPartial({
+          pool: setup.pool,
+          poolAuthority: setup.poolAuthority,
+          source,
+          mint,
+          destination,
+          admin: setup.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([setup.admin])
+        .rpc();
+    }
+
+    it("Rescues stray tokens held by the pool authority", async () => {
+      const strayMint = await createMint(
+        setup.provider.connection,
+        setup.payer,
+        setup.admin.publicKey,
+        null,
+        6
+      );
+      const strayAccount = await getOrCreateAssociatedTokenAccount(
+        setup.provider.connection,
+        setup.payer,
+        strayMint,
+        setup.poolAuthority,
+        true
+      );
+      const userStray = await getOrCreateAssociatedTokenAccount(
+        setup.provider.connection,
+        setup.payer,
+        strayMint,
+        user.publicKey
+      );
+      await mintTo(
+        setup.provider.connection,
+        setup.payer,
+        strayMint,
+        strayAccount.address,
+        setup.admin,
+        1_000_000
+      );
+
+      await rescue(strayAccount.address, strayMint, userStray.address, new anchor.BN(1_000_000));
+      const rescued = await getAccount(setup.provider.connection, userStray.address);
+      assert.equal(rescued.amount.toString(), "1000000");
+    });
+
+    it("Refuses to rescue the main token", async () => {
+      await expectError(
+        rescue(setup.poolVault, setup.mainTokenMint, userMainToken, new anchor.BN(1)),
+        "MintNotRescuable"
+      );
+    });
+  });
+
+  describe("main mint migration", () => {
+    it("Holds a proposed migration behind the timelock", async () => {
+      const newMint = await createMint(
+        setup.provider.connection,
+        setup.payer,
+        setup.admin.publicKey,
+        null,
+        9
+      );
+      const newVault = await getOrCreateAssociatedTokenAccount(
+        setup.provider.connection,
+        setup.payer,
+        newMint,
+        setup.poolAuthority,
+        true
+      );
+      const [mintMigration] = PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_migration"), setup.pool.toBuffer()],
+        setup.program.programId
+      );
+
+      await expectError(
+        setup.program.methods
+          .proposeMainMintMigration(new anchor.BN(0), new anchor.BN(1))
+          .accountsPartial({
+            pool: setup.pool,
+            poolAuthority: setup.poolAuthority,
+            mintMigration,
+            newMint,
+            newVault: newVault.address,
+            admin: setup.admin.publicKey,
+          })
+          .signers([setup.admin])
+          .rpc(),
+        "InvalidMintMigration"
+      );
+      await setup.program.methods
+        .proposeMainMintMigration(new anchor.BN(1), new anchor.BN(1))
+        .accountsPartial({
+          pool: setup.pool,
+          poolAuthority: setup.poolAuthority,
+          mintMigration,
+          newMint,
+          newVault: newVault.address,
+          admin: setup.admin.publicKey,
+        })
+        .signers([setup.admin])
+        .rpc();
+
+      await expectError(
+        setup.program.methods
+          .migrateMainMint()
+          .accountsPartial({
+            pool: setup.pool,
+            poolAuthority: setup.poolAuthority,
+            mintMigration,
+            oldMint: setup.mainTokenMint,
+            oldVault: setup.poolVault,
+            oldVaultDestination: userMainToken,
+            newMint,
+            newVault: newVault.address,
+            feeVault: setup.feeVault,
+            admin: setup.admin.publicKey,
+            oldTokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([setup.admin])
+          .rpc(),
+        "TimelockNotElapsed"
+      );
+    });
+  });
+
+  describe("snapshot", () => {
+    it("Records item mint amounts and weights once per epoch", async () => {
+      const { epoch } = await setup.provider.connection.getEpochInfo();
+      const epochSeed = new anchor.BN(epoch).toArrayLike(Buffer, "le", 8);
+      const [snapshot] = PublicKey.findProgramAddressSync(
+        [Buffer.from("snapshot"), setup.pool.toBuffer(), epochSeed],
+        setup.program.programId
+      );
+      const takeSnapshot = () =>
+        setup.program.methods
+          .snapshot(new anchor.BN(epoch))
+          .accountsPartial({ pool: setup.pool, snapshot, payer: setup.payer.publicKey })
+          .signers([setup.payer])
+          .rpc();
+
+      await takeSnapshot();
+      const recorded = await setup.program.account.poolSnapshot.fetch(snapshot);
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(recorded.items.length, pool.tokenCount);
+      recorded.items.forEach((item, index) => {
+        assert.equal(item.lpMint.toBase58(), pool.tokens[index].mintAccount.toBase58());
+        assert.equal(item.mintAmount.toString(), pool.tokens[index].mintAmount.toString());
+        assert.equal(item.weight.toString(), pool.tokens[index].weight.toString());
+      });
+
+      // 同一 epoch 只能生成一次
+      try {
+        await takeSnapshot();
+        assert.fail("Should have rejected a second snapshot in the same epoch");
+      } catch (error) {
+        assert.notInclude(error.toString(), "Should have rejected");
+      }
+    });
+  });
+
+  describe("pool stats", () => {
+    it("Stores per-item redeem rates in a small account", async () => {
+      const [poolStats] = PublicKey.findProgramAddressSync(
+        [Buffer.from("pool_stats"), setup.pool.toBuffer()],
+        setup.program.programId
+      );
+      await setup.program.methods
+        .initPoolStats()
+        .accountsPartial({ pool: setup.pool, poolStats, payer: setup.payer.publicKey })
+        .signers([setup.payer])
+        .rpc();
+
+      const pool = await fetchPool(setup.program, setup.pool);
+      await setup.program.methods
+        .refreshStats()
+        .accountsPartial({
+          pool: setup.pool,
+          poolVault: pool.poolVault,
+          poolStats,
+          payer: setup.payer.publicKey,
+        })
+        .signers([setup.payer])
+        .rpc();
+
+      const stats = await setup.program.account.poolStats.fetch(poolStats);
+      assert.equal(stats.assets.length, 1);
+      assert.equal(stats.assets[0].mint.toBase58(), pool.poolMint.toBase58());
+      assert.equal(stats.items.length, pool.tokenCount);
+      stats.items.forEach((item, index) => {
+        assert.equal(item.lpMint.toBase58(), pool.tokens[index].mintAccount.toBase58());
+        assert.equal(item.mintAmount.toString(), pool.tokens[index].mintAmount.toString());
+        assert.equal(item.weight.toString(), pool.tokens[index].weight.toString());
+      });
+      assert.isTrue(stats.items.some((item) => item.redeemRate.gtn(0)));
+    });
+  });
+
+  describe("admin signers", () => {
+    function manage(method: "addAdminSigner" | "removeAdminSigner", signer: PublicKey, cosigners: Keypair[] = []) {
+      return setup.program.methods[method](signer)
+        .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+        .remainingAccounts(cosigners.map((k) => ({ pubkey: k.publicKey, isSigner: true, isWritable: false })))
+        .signers([setup.admin, ...cosigners])
+        .rpc();
+    }
+
+    function setThreshold(threshold: number, cosigners: Keypair[] = []) {
+      return setup.program.methods
+        .setAdminThreshold(threshold)
+        .accounts({ pool: setup.pool, admin: setup.admin.publicKey })
+        .remainingAccounts(cosigners.map((k) => ({ pubkey: k.publicKey, isSigner: true, isWritable: false })))
+        .signers([setup.admin, ...cosigners])
+        .rpc();
+    }
+
+    it("Requires co-admin signatures once the threshold is raised", async () => {
+      const coAdmin = Keypair.generate();
+      await manage("addAdminSigner", coAdmin.publicKey);
+      await expectError(setThreshold(3), "InvalidAdminThreshold");
+      await setThreshold(2);
+
+      // 仅主管理员签名不足 2-of-2
+      await expectError(setThreshold(1), "InsufficientAdminSignatures");
+      // 非管理员的签名不计入门限
+      await expectError(setThreshold(1, [user]), "InsufficientAdminSignatures");
+
+      await setThreshold(1, [coAdmin]);
+      await manage("removeAdminSigner", coAdmin.publicKey);
+
+      const pool = await fetchPool(setup.program, setup.pool);
+      assert.equal(pool.adminSignerCount, 0);
+      assert.equal(pool.adminThreshold, 1);
+    });
+  });
+
+  describe("close pool", () => {
+    function derivePoolRegistry(target: PoolSetup) {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("pool_registry"), target.mainTokenMint.toBuffer()],
+        target.program.programId
+      )[0];
+    }
+
+    function closePool(target: PoolSetup) {
+      return target.program.methods
+        .closePool()
+        .accountsPartial({
+          pool: target.pool,
+          poolAuthority: target.poolAuthority,
+          poolVault: target.poolVault,
+          feeVault: target.feeVault,
+          poolRegistry: derivePoolRegistry(target),
+          recipient: target.admin.publicKey,
+          admin: target.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([target.admin])
+        .rpc();
+    }
+
+    it("Rejects closing a pool that still has staking types", async () => {
+      await expectError(closePool(setup), "PoolNotEmpty");
+    });
+
+    it("Closes an empty pool and its vaults", async () => {
+      const empty = await setupPool();
+      const poolRegistry = derivePoolRegistry(empty);
+      let registry = await empty.program.account.poolRegistry.fetch(poolRegistry);
+      assert.isTrue(registry.pools.some((p) => p.equals(empty.pool)));
+
+      await closePool(empty);
+      registry = await empty.program.account.poolRegistry.fetch(poolRegistry);
+      assert.isFalse(registry.pools.some((p) => p.equals(empty.pool)));
+
+      const connection = empty.provider.connection;
+      assert.isNull(await connection.getAccountInfo(empty.pool));
+      assert.isNull(await connection.getAccountInfo(empty.poolVault));
+      assert.isNull(await connection.getAccountInfo(empty.feeVault));
+    });
+  });
+});
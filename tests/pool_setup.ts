@@ -6,21 +6,109 @@ import {
   Keypair,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
+  TransactionInstruction,
 } from "@solana/web3.js";
 import {
   TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   createMint,
+  getAssociatedTokenAddressSync,
 } from "@solana/spl-token";
+import { decodePoolItem } from "../app/src/types";
 
 export interface PoolSetup {
   program: Program<Multistake>;
   provider: anchor.AnchorProvider;
   admin: Keypair;
   payer: Keypair;
-  pool: Keypair;
+  pool: PublicKey;
   mainTokenMint: PublicKey;
   poolAuthority: PublicKey;
   poolVault: PublicKey;
+  feeVault: PublicKey;
+}
+
+/** Pool 账户头部大小（8 discriminator + 1848），item 数组紧跟其后 */
+export const POOL_HEADER_SIZE = 1856;
+
+/** 单个 item 的大小 */
+export const POOL_ITEM_SIZE = 136;
+
+/** 测试 pool 使用的容量档位 */
+export const POOL_SIZE_TIER = { large: {} };
+
+/** Large 档位（512 个 item）的 Pool 账户大小 */
+export const POOL_ACCOUNT_SIZE = POOL_HEADER_SIZE + 512 * POOL_ITEM_SIZE;
+
+/** 单条指令内账户最多可扩展的字节数（MAX_PERMITTED_DATA_INCREASE） */
+const MAX_PERMITTED_DATA_INCREASE = 10240;
+
+/**
+ * 派生 Pool PDA ["pool", main_token_mint, admin]
+ */
+export function derivePool(
+  programId: PublicKey,
+  mainTokenMint: PublicKey,
+  admin: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [new TextEncoder().encode("pool"), mainTokenMint.toBytes(), admin.toBytes()],
+    programId
+  )[0];
+}
+
+/**
+ * 读取 Pool 账户：头部字段 + 紧跟其后的 capacity 个 item（tokens）
+ */
+export async function fetchPool(program: Program<Multistake>, pool: PublicKey) {
+  const info = await program.provider.connection.getAccountInfo(pool);
+  const header = program.coder.accounts.decode("pool", info!.data);
+  const tokens = Array.from({ length: header.capacity }, (_, i) => {
+    const offset = POOL_HEADER_SIZE + i * POOL_ITEM_SIZE;
+    return decodePoolItem(info!.data.subarray(offset, offset + POOL_ITEM_SIZE));
+  });
+  return { ...header, tokens };
+}
+
+/**
+ * 构造分配 Pool PDA 空间所需的 allocate_pool 指令（每条最多扩展 10KB）
+ */
+export async function allocatePoolIxs(
+  program: Program<Multistake>,
+  mainTokenMint: PublicKey,
+  admin: PublicKey,
+  payer: PublicKey,
+  sizeTier: any = POOL_SIZE_TIER,
+  accountSize: number = POOL_ACCOUNT_SIZE
+): Promise<TransactionInstruction[]> {
+  const count = Math.ceil(accountSize / MAX_PERMITTED_DATA_INCREASE);
+  const ix = await program.methods
+    .allocatePool(sizeTier)
+    .accounts({
+      pool: derivePool(program.programId, mainTokenMint, admin),
+      mainTokenMint,
+      admin,
+      payer,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction();
+  return Array.from({ length: count }, () => ix);
+}
+
+/**
+ * 派生 LP mint PDA ["lp_mint", pool, increment_count (u16 le)]
+ */
+export function deriveLpMint(
+  programId: PublicKey,
+  pool: PublicKey,
+  incrementCount: number
+): PublicKey {
+  const countSeed = new Uint8Array(2);
+  new DataView(countSeed.buffer).setUint16(0, incrementCount, true);
+  return PublicKey.findProgramAddressSync(
+    [new TextEncoder().encode("lp_mint"), pool.toBytes(), countSeed],
+    programId
+  )[0];
 }
 
 /**
@@ -34,7 +122,6 @@ export async function setupPool(): Promise<PoolSetup> {
   // 生成账户
   const admin = Keypair.generate();
   const payer = Keypair.generate();
-  const pool = Keypair.generate();
 
   // 空投 SOL
   const airdropAmount = 10 * anchor.web3.LAMPORTS_PER_SOL;
@@ -55,42 +142,150 @@ export async function setupPool(): Promise<PoolSetup> {
   );
 
   // 派生 PDA
+  const pool = derivePool(program.programId, mainTokenMint, admin.publicKey);
   const [poolAuthority] = PublicKey.findProgramAddressSync(
-    [new TextEncoder().encode("anyswap_authority"), pool.publicKey.toBytes()],
+    [new TextEncoder().encode("anyswap_authority"), pool.toBytes()],
     program.programId
   );
 
   const [poolVault] = PublicKey.findProgramAddressSync(
-    [new TextEncoder().encode("pool_vault"), pool.publicKey.toBytes()],
+    [new TextEncoder().encode("pool_vault"), pool.toBytes()],
+    program.programId
+  );
+
+  const [feeVault] = PublicKey.findProgramAddressSync(
+    [new TextEncoder().encode("fee_vault"), pool.toBytes()],
     program.programId
   );
 
-  // 创建 pool 账户
-  const poolSize = 24704;
-  const lamports = await provider.connection.getMinimumBalanceForRentExemption(poolSize);
+  // 分配 pool PDA 空间
+  const allocateIxs = await allocatePoolIxs(
+    program,
+    mainTokenMint,
+    admin.publicKey,
+    payer.publicKey
+  );
+
+  // 初始化 pool
+  await program.methods
+    .createPool({ stakeFeeBps: 30, unstakeFeeBps: 30, swapFeeBps: 30, protocolFeeBps: 10_000 }, POOL_SIZE_TIER)
+    .accounts({
+      pool,
+      poolAuthority: poolAuthority,
+      mainTokenMint: mainTokenMint,
+      poolVault: poolVault,
+      feeVault: feeVault,
+      admin: admin.publicKey,
+      payer: payer.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .preInstructions(allocateIxs)
+    .signers([payer, admin])
+    .rpc();
 
+  return {
+    program,
+    provider,
+    admin,
+    payer,
+    pool,
+    mainTokenMint,
+    poolAuthority,
+    poolVault,
+    feeVault,
+  };
+}
+
+export interface BootstrapSetup extends PoolSetup {
+  lpMints: PublicKey[];
+  users: Keypair[];
+  userMainTokens: PublicKey[];
+}
+
+/**
+ * 通过 bootstrap_test_pool 一次性创建 pool、item 和已注资的用户
+ * 需要使用 `anchor build -- --features localnet-bootstrap` 构建程序
+ */
+export async function bootstrapTestPool(
+  itemCount: number,
+  userCount: number,
+  fundAmount: anchor.BN
+): Promise<BootstrapSetup> {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Multistake as Program<Multistake>;
+
+  const admin = Keypair.generate();
+  const payer = Keypair.generate();
+  const pool = Keypair.generate();
+  const users = Array.from({ length: userCount }, () => Keypair.generate());
+
+  const airdropAmount = 10 * anchor.web3.LAMPORTS_PER_SOL;
+  await provider.connection.confirmTransaction(
+    await provider.connection.requestAirdrop(payer.publicKey, airdropAmount)
+  );
+
+  const encoder = new TextEncoder();
+  const [poolAuthority] = PublicKey.findProgramAddressSync(
+    [encoder.encode("anyswap_authority"), pool.publicKey.toBytes()],
+    program.programId
+  );
+  const [poolVault] = PublicKey.findProgramAddressSync(
+    [encoder.encode("pool_vault"), pool.publicKey.toBytes()],
+    program.programId
+  );
+  const [mainTokenMint] = PublicKey.findProgramAddressSync(
+    [encoder.encode("bootstrap_mint"), pool.publicKey.toBytes()],
+    program.programId
+  );
+  const lpMints = Array.from({ length: itemCount }, (_, i) =>
+    PublicKey.findProgramAddressSync(
+      [encoder.encode("bootstrap_lp"), pool.publicKey.toBytes(), Uint8Array.of(i)],
+      program.programId
+    )[0]
+  );
+  const userMainTokens = users.map((user) =>
+    getAssociatedTokenAddressSync(mainTokenMint, user.publicKey)
+  );
+
+  const lamports = await provider.connection.getMinimumBalanceForRentExemption(POOL_ACCOUNT_SIZE);
   const createPoolAccountIx = SystemProgram.createAccount({
     fromPubkey: payer.publicKey,
     newAccountPubkey: pool.publicKey,
     lamports,
-    space: poolSize,
+    space: POOL_ACCOUNT_SIZE,
     programId: program.programId,
   });
 
-  // 初始化 pool
+  const remainingAccounts = [
+    ...lpMints.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })),
+    ...users.flatMap((user, i) => [
+      { pubkey: user.publicKey, isSigner: false, isWritable: false },
+      { pubkey: userMainTokens[i], isSigner: false, isWritable: true },
+    ]),
+  ];
+
   await program.methods
-    .createPool(new anchor.BN(3), new anchor.BN(1000))
+    .bootstrapTestPool(
+      { stakeFeeBps: 30, unstakeFeeBps: 30, swapFeeBps: 30, protocolFeeBps: 10_000 },
+      itemCount,
+      fundAmount
+    )
     .accounts({
       pool: pool.publicKey,
-      poolAuthority: poolAuthority,
-      mainTokenMint: mainTokenMint,
-      poolVault: poolVault,
+      poolAuthority,
+      mainTokenMint,
+      poolVault,
       admin: admin.publicKey,
       payer: payer.publicKey,
       systemProgram: SystemProgram.programId,
       tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       rent: SYSVAR_RENT_PUBKEY,
     })
+    .remainingAccounts(remainingAccounts)
     .preInstructions([createPoolAccountIx])
     .signers([payer, pool, admin])
     .rpc();
@@ -100,9 +295,13 @@ export async function setupPool(): Promise<PoolSetup> {
     provider,
     admin,
     payer,
-    pool,
+    pool: pool.publicKey,
     mainTokenMint,
     poolAuthority,
     poolVault,
+    feeVault: PublicKey.default,
+    lpMints,
+    users,
+    userMainTokens,
   };
 }
@@ -14,6 +14,7 @@ import {
   mintTo,
 } from "@solana/spl-token";
 import { assert } from "chai";
+import { allocatePoolIxs, deriveLpMint, derivePool, POOL_HEADER_SIZE, POOL_ITEM_SIZE } from "./pool_setup";
 
 describe("Pool Operations Tests", () => {
   // Configure the client to use the local cluster
@@ -25,7 +26,7 @@ describe("Pool Operations Tests", () => {
   // Test accounts
   let admin: Keypair;
   let payer: Keypair;
-  let pool: Keypair;
+  let pool: PublicKey;
   let mainTokenMint: PublicKey;
   let poolAuthority: PublicKey;
   let poolVault: PublicKey;
@@ -35,22 +36,16 @@ describe("Pool Operations Tests", () => {
   let userMainTokenAccount: PublicKey;
 
   // LP mints for testing
-  let lpMint1: Keypair;
-  let lpMint2: Keypair;
-  let lpMint3: Keypair;
+  let lpMint1: PublicKey;
+  let lpMint2: PublicKey;
+  let lpMint3: PublicKey;
 
   before(async () => {
     // Initialize test accounts
     admin = Keypair.generate();
     payer = Keypair.generate();
-    pool = Keypair.generate();
     user = Keypair.generate();
 
-    // Initialize LP mint keypairs
-    lpMint1 = Keypair.generate();
-    lpMint2 = Keypair.generate();
-    lpMint3 = Keypair.generate();
-
     // Airdrop SOL to test accounts
     const airdropAmount = 10 * anchor.web3.LAMPORTS_PER_SOL;
 
@@ -75,6 +70,12 @@ describe("Pool Operations Tests", () => {
       9
     );
 
+    // 派生 pool PDA 和 LP mint PDA（按 increment_count 顺序）
+    pool = derivePool(program.programId, mainTokenMint, admin.publicKey);
+    lpMint1 = deriveLpMint(program.programId, pool, 0);
+    lpMint2 = deriveLpMint(program.programId, pool, 1);
+    lpMint3 = deriveLpMint(program.programId, pool, 2);
+
     console.log("Main token mint created:", mainTokenMint.toBase58());
 
     // Create user's main token account and mint some tokens
@@ -101,39 +102,40 @@ describe("Pool Operations Tests", () => {
   it("Creates a staking pool", async () => {
     // Derive PDAs
     [poolAuthority] = PublicKey.findProgramAddressSync(
-      [Buffer.from("anyswap_authority"), pool.publicKey.toBuffer()],
+      [Buffer.from("anyswap_authority"), pool.toBuffer()],
       program.programId
     );
 
     [poolVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("pool_vault"), pool.publicKey.toBuffer()],
+      [Buffer.from("pool_vault"), pool.toBuffer()],
       program.programId
     );
 
-    console.log("Pool:", pool.publicKey.toBase58());
+    console.log("Pool:", pool.toBase58());
     console.log("Pool Authority:", poolAuthority.toBase58());
     console.log("Pool Vault:", poolVault.toBase58());
 
-    // Create pool account
-    const poolSize = 24704; // Pool account size (24696 + 8 discriminator)
-    const lamports = await provider.connection.getMinimumBalanceForRentExemption(poolSize);
-
-    const createPoolAccountIx = SystemProgram.createAccount({
-      fromPubkey: payer.publicKey,
-      newAccountPubkey: pool.publicKey,
-      lamports,
-      space: poolSize,
-      programId: program.programId,
-    });
+    // Small 档位：16 个 item 槽位
+    const smallPoolSize = POOL_HEADER_SIZE + 16 * POOL_ITEM_SIZE;
+    const allocateIxs = await allocatePoolIxs(
+      program,
+      mainTokenMint,
+      admin.publicKey,
+      payer.publicKey,
+      { small: {} },
+      smallPoolSize
+    );
 
     // Create pool
     const tx = await program.methods
-      .createPool(
-        new anchor.BN(3), // fee_numerator: 0.3%
-        new anchor.BN(1000) // fee_denominator
-      )
+      .createPool({
+        stakeFeeBps: 30, // 0.3%
+        unstakeFeeBps: 30,
+        swapFeeBps: 30,
+        protocolFeeBps: 10_000, // stake 手续费全部转入 fee_vault
+      }, { small: {} })
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         poolAuthority: poolAuthority,
         mainTokenMint: mainTokenMint,
         poolVault: poolVault,
@@ -143,48 +145,72 @@ describe("Pool Operations Tests", () => {
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .preInstructions([createPoolAccountIx])
-      .signers([payer, pool, admin])
+      .preInstructions(allocateIxs)
+      .signers([payer, admin])
       .rpc();
 
     console.log("Create pool transaction:", tx);
 
     // Verify pool was created
-    const poolAccount = await program.account.pool.fetch(pool.publicKey);
+    const poolAccount = await program.account.pool.fetch(pool);
     assert.equal(poolAccount.tokenCount, 0);
     assert.equal(poolAccount.incrementCount, 0);
     assert.equal(poolAccount.admin.toBase58(), admin.publicKey.toBase58());
     assert.equal(poolAccount.poolVault.toBase58(), poolVault.toBase58());
     assert.equal(poolAccount.poolMint.toBase58(), mainTokenMint.toBase58());
-    assert.equal(poolAccount.feeNumerator.toNumber(), 3);
-    assert.equal(poolAccount.feeDenominator.toNumber(), 1000);
+    assert.equal(poolAccount.feeConfig.stakeFeeBps, 30);
+    assert.equal(poolAccount.feeConfig.unstakeFeeBps, 30);
+    assert.equal(poolAccount.legacyFeeDenominator.toNumber(), 0);
+    assert.equal(poolAccount.capacity, 16);
+    const poolInfo = await provider.connection.getAccountInfo(pool);
+    assert.equal(poolInfo.data.length, smallPoolSize);
 
     console.log("✅ Pool created successfully");
   });
 
+  it("Grows pool item capacity", async () => {
+    await program.methods
+      .growPool(16)
+      .accounts({
+        pool: pool,
+        admin: admin.publicKey,
+        payer: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([payer, admin])
+      .rpc();
+
+    const poolAccount = await program.account.pool.fetch(pool);
+    assert.equal(poolAccount.capacity, 32);
+    const poolInfo = await provider.connection.getAccountInfo(pool);
+    assert.equal(poolInfo.data.length, POOL_HEADER_SIZE + 32 * POOL_ITEM_SIZE);
+
+    console.log("✅ Pool capacity grown to 32");
+  });
+
   it("Adds first staking type to pool", async () => {
-    console.log("LP Mint 1:", lpMint1.publicKey.toBase58());
+    console.log("LP Mint 1:", lpMint1.toBase58());
 
     // Add token to pool
     const tx = await program.methods
       .addTokenToPool()
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         poolAuthority: poolAuthority,
-        lpMint: lpMint1.publicKey,
+        lpMint: lpMint1,
         admin: admin.publicKey,
         payer: payer.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([admin, payer, lpMint1])
+      .signers([admin, payer])
       .rpc();
 
     console.log("Add token transaction:", tx);
 
     // Verify token was added
-    const poolAccount = await program.account.pool.fetch(pool.publicKey);
+    const poolAccount = await program.account.pool.fetch(pool);
     assert.equal(poolAccount.tokenCount, 1);
     assert.equal(poolAccount.incrementCount, 1);
 
@@ -192,26 +218,26 @@ describe("Pool Operations Tests", () => {
   });
 
   it("Adds second staking type to pool", async () => {
-    console.log("LP Mint 2:", lpMint2.publicKey.toBase58());
+    console.log("LP Mint 2:", lpMint2.toBase58());
 
     const tx = await program.methods
       .addTokenToPool()
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         poolAuthority: poolAuthority,
-        lpMint: lpMint2.publicKey,
+        lpMint: lpMint2,
         admin: admin.publicKey,
         payer: payer.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([admin, payer, lpMint2])
+      .signers([admin, payer])
       .rpc();
 
     console.log("Add second token transaction:", tx);
 
-    const poolAccount = await program.account.pool.fetch(pool.publicKey);
+    const poolAccount = await program.account.pool.fetch(pool);
     assert.equal(poolAccount.tokenCount, 2);
     assert.equal(poolAccount.incrementCount, 2);
 
@@ -219,21 +245,23 @@ describe("Pool Operations Tests", () => {
   });
 
   it("Removes first staking type from pool", async () => {
-    console.log("Removing LP Mint 1:", lpMint1.publicKey.toBase58());
+    console.log("Removing LP Mint 1:", lpMint1.toBase58());
 
     const tx = await program.methods
       .removeTokenFromPool()
       .accounts({
-        pool: pool.publicKey,
-        lpMint: lpMint1.publicKey,
+        pool: pool,
+        poolAuthority: poolAuthority,
+        lpMint: lpMint1,
         admin: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([admin])
       .rpc();
 
     console.log("Remove token transaction:", tx);
 
-    const poolAccount = await program.account.pool.fetch(pool.publicKey);
+    const poolAccount = await program.account.pool.fetch(pool);
     assert.equal(poolAccount.tokenCount, 1);
     assert.equal(poolAccount.incrementCount, 2); // Should NOT change
 
@@ -241,26 +269,26 @@ describe("Pool Operations Tests", () => {
   });
 
   it("Adds third staking type after removal (no address conflict)", async () => {
-    console.log("LP Mint 3:", lpMint3.publicKey.toBase58());
+    console.log("LP Mint 3:", lpMint3.toBase58());
 
     const tx = await program.methods
       .addTokenToPool()
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         poolAuthority: poolAuthority,
-        lpMint: lpMint3.publicKey,
+        lpMint: lpMint3,
         admin: admin.publicKey,
         payer: payer.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([admin, payer, lpMint3])
+      .signers([admin, payer])
       .rpc();
 
     console.log("Add third token transaction:", tx);
 
-    const poolAccount = await program.account.pool.fetch(pool.publicKey);
+    const poolAccount = await program.account.pool.fetch(pool);
     assert.equal(poolAccount.tokenCount, 2);
     assert.equal(poolAccount.incrementCount, 3);
 
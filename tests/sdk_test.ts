@@ -54,7 +54,7 @@ describe("SDK Tests", () => {
     it("Creates a pool using SDK", async () => {
       const result = await sdk.createPool(
         mainTokenMint,
-        { feeNumerator: 3, feeDenominator: 1000 }
+        { stakeFeeBps: 30, unstakeFeeBps: 30, swapFeeBps: 30, protocolFeeBps: 10_000 }
       );
 
       pool = result.pool;
@@ -10,13 +10,13 @@ import {
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { assert } from "chai";
-import { setupPool } from "./pool_setup";
+import { setupPool, deriveLpMint } from "./pool_setup";
 
 describe("Security Tests", () => {
   let setup: Awaited<ReturnType<typeof setupPool>>;
   let user1: Keypair;
-  let lpMint1: Keypair;
-  let lpMintForTest: Keypair;
+  let lpMint1: PublicKey;
+  let lpMintForTest: PublicKey;
 
   before(async () => {
     // 创建 pool
@@ -24,7 +24,7 @@ describe("Security Tests", () => {
 
     // 创建 user1（非 admin）
     user1 = Keypair.generate();
-    lpMint1 = Keypair.generate();
+    lpMint1 = deriveLpMint(setup.program.programId, setup.pool, 0);
 
     // 给 user1 空投 SOL
     const airdropAmount = 10 * anchor.web3.LAMPORTS_PER_SOL;
@@ -36,7 +36,7 @@ describe("Security Tests", () => {
   });
 
   it("Pool created successfully", async () => {
-    const poolAccount = await setup.program.account.anySwapPool.fetch(setup.pool.publicKey);
+    const poolAccount = await setup.program.account.pool.fetch(setup.pool);
     assert.equal(poolAccount.admin.toBase58(), setup.admin.publicKey.toBase58());
     console.log("✅ Pool admin verified");
   });
@@ -46,16 +46,16 @@ describe("Security Tests", () => {
       await setup.program.methods
         .addTokenToPool()
         .accounts({
-          pool: setup.pool.publicKey,
+          pool: setup.pool,
           poolAuthority: setup.poolAuthority,
-          lpMint: lpMint1.publicKey,
+          lpMint: lpMint1,
           admin: user1.publicKey, // user1 尝试冒充 admin
           payer: user1.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: SYSVAR_RENT_PUBKEY,
         })
-        .signers([user1, lpMint1])
+        .signers([user1])
         .rpc();
 
       assert.fail("Should have failed with unauthorized error");
@@ -66,20 +66,20 @@ describe("Security Tests", () => {
   });
 
   it("Admin adds a token for testing", async () => {
-    lpMintForTest = Keypair.generate();
+    lpMintForTest = deriveLpMint(setup.program.programId, setup.pool, 0);
     await setup.program.methods
       .addTokenToPool()
       .accounts({
-        pool: setup.pool.publicKey,
+        pool: setup.pool,
         poolAuthority: setup.poolAuthority,
-        lpMint: lpMintForTest.publicKey,
+        lpMint: lpMintForTest,
         admin: setup.admin.publicKey,
         payer: setup.payer.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([setup.admin, setup.payer, lpMintForTest])
+      .signers([setup.admin, setup.payer])
       .rpc();
 
     console.log("✅ Admin added token for testing");
@@ -90,11 +90,11 @@ describe("Security Tests", () => {
       await setup.program.methods
         .modifyTokenWeight([new anchor.BN(100_000_000)])
         .accounts({
-          pool: setup.pool.publicKey,
+          pool: setup.pool,
           admin: user1.publicKey, // user1 尝试冒充 admin
         })
         .remainingAccounts([
-          { pubkey: lpMintForTest.publicKey, isSigner: false, isWritable: false }
+          { pubkey: lpMintForTest, isSigner: false, isWritable: false }
         ])
         .signers([user1])
         .rpc();
@@ -15,6 +15,7 @@ import {
   getAccount,
 } from "@solana/spl-token";
 import { assert } from "chai";
+import { allocatePoolIxs, deriveLpMint, derivePool } from "./pool_setup";
 
 describe("Stake and Unstake Tests", () => {
   const provider = anchor.AnchorProvider.env();
@@ -25,10 +26,11 @@ describe("Stake and Unstake Tests", () => {
   // Test accounts
   let admin: Keypair;
   let payer: Keypair;
-  let pool: Keypair;
+  let pool: PublicKey;
   let mainTokenMint: PublicKey;
   let poolAuthority: PublicKey;
   let poolVault: PublicKey;
+  let feeVault: PublicKey;
 
   // Users
   let user1: Keypair;
@@ -37,8 +39,8 @@ describe("Stake and Unstake Tests", () => {
   let user2MainTokenAccount: PublicKey;
 
   // LP mints
-  let lpMint1: Keypair;
-  let lpMint2: Keypair;
+  let lpMint1: PublicKey;
+  let lpMint2: PublicKey;
   let user1LpTokenAccount: PublicKey;
   let user2LpTokenAccount: PublicKey;
 
@@ -46,11 +48,8 @@ describe("Stake and Unstake Tests", () => {
     // Initialize accounts
     admin = Keypair.generate();
     payer = Keypair.generate();
-    pool = Keypair.generate();
     user1 = Keypair.generate();
     user2 = Keypair.generate();
-    lpMint1 = Keypair.generate();
-    lpMint2 = Keypair.generate();
 
     // Airdrop SOL
     const airdropAmount = 10 * anchor.web3.LAMPORTS_PER_SOL;
@@ -76,46 +75,53 @@ describe("Stake and Unstake Tests", () => {
       9
     );
 
+    // 派生 pool PDA 和 LP mint PDA（按 increment_count 顺序）
+    pool = derivePool(program.programId, mainTokenMint, admin.publicKey);
+    lpMint1 = deriveLpMint(program.programId, pool, 0);
+    lpMint2 = deriveLpMint(program.programId, pool, 1);
+
     console.log("Main token mint:", mainTokenMint.toBase58());
   });
 
   it("Creates pool", async () => {
     [poolAuthority] = PublicKey.findProgramAddressSync(
-      [Buffer.from("anyswap_authority"), pool.publicKey.toBuffer()],
+      [Buffer.from("anyswap_authority"), pool.toBuffer()],
       program.programId
     );
 
     [poolVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("pool_vault"), pool.publicKey.toBuffer()],
+      [Buffer.from("pool_vault"), pool.toBuffer()],
       program.programId
     );
 
-    const poolSize = 24704;
-    const lamports = await provider.connection.getMinimumBalanceForRentExemption(poolSize);
+    [feeVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_vault"), pool.toBuffer()],
+      program.programId
+    );
 
-    const createPoolAccountIx = SystemProgram.createAccount({
-      fromPubkey: payer.publicKey,
-      newAccountPubkey: pool.publicKey,
-      lamports,
-      space: poolSize,
-      programId: program.programId,
-    });
+    const allocateIxs = await allocatePoolIxs(
+      program,
+      mainTokenMint,
+      admin.publicKey,
+      payer.publicKey
+    );
 
     await program.methods
-      .createPool(new anchor.BN(3), new anchor.BN(1000))
+      .createPool({ stakeFeeBps: 30, unstakeFeeBps: 30, swapFeeBps: 30, protocolFeeBps: 10_000 }, { large: {} })
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         poolAuthority: poolAuthority,
         mainTokenMint: mainTokenMint,
         poolVault: poolVault,
+        feeVault: feeVault,
         admin: admin.publicKey,
         payer: payer.publicKey,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .preInstructions([createPoolAccountIx])
-      .signers([payer, pool, admin])
+      .preInstructions(allocateIxs)
+      .signers([payer, admin])
       .rpc();
 
     console.log("✅ Pool created");
@@ -125,16 +131,16 @@ describe("Stake and Unstake Tests", () => {
     await program.methods
       .addTokenToPool()
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         poolAuthority: poolAuthority,
-        lpMint: lpMint1.publicKey,
+        lpMint: lpMint1,
         admin: admin.publicKey,
         payer: payer.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([admin, payer, lpMint1])
+      .signers([admin, payer])
       .rpc();
 
     console.log("✅ User1 LP token type added");
@@ -164,7 +170,7 @@ describe("Stake and Unstake Tests", () => {
     const user1LpToken = await getOrCreateAssociatedTokenAccount(
       provider.connection,
       payer,
-      lpMint1.publicKey,
+      lpMint1,
       user1.publicKey
     );
     user1LpTokenAccount = user1LpToken.address;
@@ -173,16 +179,19 @@ describe("Stake and Unstake Tests", () => {
 
     // Stake
     await program.methods
-      .stake(0, new anchor.BN(100_000_000_000)) // 100 tokens
+      .stake(0, new anchor.BN(100_000_000_000), null, null, []) // 100 tokens
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         poolAuthority: poolAuthority,
         poolVault: poolVault,
-        lpMint: lpMint1.publicKey,
+        feeVault: feeVault,
+        lpMint: lpMint1,
         userMainToken: user1MainTokenAccount,
         userLpToken: user1LpTokenAccount,
         user: user1.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
+        mainTokenMint: mainTokenMint,
+        mainTokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([user1])
       .rpc();
@@ -199,7 +208,7 @@ describe("Stake and Unstake Tests", () => {
     // With 0.3% fee: 100 tokens staked, 99.7 LP minted, 0.3 fee stays in vault
     assert.equal(user1MainAfter.amount.toString(), "900000000000"); // 1000 - 100
     assert.equal(user1LpAfter.amount.toString(), "99700000000"); // 100 * 0.997 = 99.7 LP
-    assert.equal(poolVaultAfter.amount.toString(), "100000000000"); // 100 in vault (including 0.3 fee)
+    assert.equal(poolVaultAfter.amount.toString(), "99700000000"); // 99.7 in vault (0.3 fee to fee vault)
 
     console.log("✅ User1 staked 100 tokens");
   });
@@ -208,16 +217,16 @@ describe("Stake and Unstake Tests", () => {
     await program.methods
       .addTokenToPool()
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         poolAuthority: poolAuthority,
-        lpMint: lpMint2.publicKey,
+        lpMint: lpMint2,
         admin: admin.publicKey,
         payer: payer.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([admin, payer, lpMint2])
+      .signers([admin, payer])
       .rpc();
 
     console.log("✅ User2 LP token type added");
@@ -247,23 +256,26 @@ describe("Stake and Unstake Tests", () => {
     const user2LpToken = await getOrCreateAssociatedTokenAccount(
       provider.connection,
       payer,
-      lpMint2.publicKey,
+      lpMint2,
       user2.publicKey
     );
     user2LpTokenAccount = user2LpToken.address;
 
     // Stake
     await program.methods
-      .stake(1, new anchor.BN(200_000_000_000)) // 200 tokens
+      .stake(1, new anchor.BN(200_000_000_000), null, null, []) // 200 tokens
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         poolAuthority: poolAuthority,
         poolVault: poolVault,
-        lpMint: lpMint2.publicKey,
+        feeVault: feeVault,
+        lpMint: lpMint2,
         userMainToken: user2MainTokenAccount,
         userLpToken: user2LpTokenAccount,
         user: user2.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
+        mainTokenMint: mainTokenMint,
+        mainTokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([user2])
       .rpc();
@@ -276,7 +288,7 @@ describe("Stake and Unstake Tests", () => {
     // With 0.3% fee: 200 tokens staked, 199.4 LP minted, 0.6 fee stays in vault
     assert.equal(user2MainAfter.amount.toString(), "800000000000"); // 1000 - 200
     assert.equal(user2LpAfter.amount.toString(), "199400000000"); // 200 * 0.997 = 199.4 LP
-    assert.equal(poolVaultAfter.amount.toString(), "300000000000"); // 100 + 200 (including fees)
+    assert.equal(poolVaultAfter.amount.toString(), "299100000000"); // 99.7 + 199.4 (fees to fee vault)
 
     console.log("✅ User2 staked 200 tokens");
   });
@@ -289,12 +301,12 @@ describe("Stake and Unstake Tests", () => {
         new anchor.BN(50_000_000),  // user2: 0.5x
       ])
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         admin: admin.publicKey,
       })
       .remainingAccounts([
-        { pubkey: lpMint1.publicKey, isSigner: false, isWritable: false },
-        { pubkey: lpMint2.publicKey, isSigner: false, isWritable: false },
+        { pubkey: lpMint1, isSigner: false, isWritable: false },
+        { pubkey: lpMint2, isSigner: false, isWritable: false },
       ])
       .signers([admin])
       .rpc();
@@ -311,16 +323,19 @@ describe("Stake and Unstake Tests", () => {
 
     // Unstake all LP tokens (199.4 tokens, not 200)
     await program.methods
-      .unstake(1, new anchor.BN(199_400_000_000))
+      .unstake(1, new anchor.BN(199_400_000_000), new anchor.BN(0), null)
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         poolAuthority: poolAuthority,
         poolVault: poolVault,
-        lpMint: lpMint2.publicKey,
+        feeVault: feeVault,
+        lpMint: lpMint2,
         userLpToken: user2LpTokenAccount,
         userMainToken: user2MainTokenAccount,
         user: user2.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
+        mainTokenMint: mainTokenMint,
+        mainTokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([user2])
       .rpc();
@@ -347,16 +362,19 @@ describe("Stake and Unstake Tests", () => {
 
     // Unstake all LP tokens (99.7 tokens, not 100)
     await program.methods
-      .unstake(0, new anchor.BN(99_700_000_000))
+      .unstake(0, new anchor.BN(99_700_000_000), new anchor.BN(0), null)
       .accounts({
-        pool: pool.publicKey,
+        pool: pool,
         poolAuthority: poolAuthority,
         poolVault: poolVault,
-        lpMint: lpMint1.publicKey,
+        feeVault: feeVault,
+        lpMint: lpMint1,
         userLpToken: user1LpTokenAccount,
         userMainToken: user1MainTokenAccount,
         user: user1.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
+        mainTokenMint: mainTokenMint,
+        mainTokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([user1])
       .rpc();